@@ -0,0 +1 @@
+ALTER TABLE track ADD COLUMN skip_on_shuffle INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE track ADD COLUMN rating INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE album ADD COLUMN rating INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE album ADD COLUMN image_path TEXT;
+ALTER TABLE album ADD COLUMN thumb_path TEXT;
@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS idx_album_image_path ON album (image_path);
+CREATE INDEX IF NOT EXISTS idx_album_thumb_path ON album (thumb_path);
@@ -0,0 +1,2 @@
+ALTER TABLE track ADD COLUMN codec TEXT;
+ALTER TABLE track ADD COLUMN bitrate INTEGER;
@@ -0,0 +1,3 @@
+ALTER TABLE track ADD COLUMN sample_rate INTEGER;
+ALTER TABLE track ADD COLUMN bit_depth INTEGER;
+ALTER TABLE track ADD COLUMN channels INTEGER;
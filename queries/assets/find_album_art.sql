@@ -1 +1 @@
-SELECT image FROM album WHERE id = $1;
+SELECT image, image_path FROM album WHERE id = $1;
@@ -1 +1 @@
-SELECT thumb FROM album WHERE id = $1;
+SELECT COALESCE(thumb, image), COALESCE(thumb_path, image_path) FROM album WHERE id = $1;
@@ -0,0 +1,7 @@
+SELECT date(ph.played_at, 'localtime') AS day, COUNT(*) AS play_count,
+    COALESCE(SUM(t.duration), 0) AS total_duration
+FROM play_history ph
+JOIN track t ON ph.track_id = t.id
+WHERE ph.played_at >= $1
+GROUP BY day
+ORDER BY day ASC;
@@ -0,0 +1,4 @@
+SELECT ph.id AS history_id, ph.played_at, t.* FROM play_history ph
+JOIN track t ON ph.track_id = t.id
+ORDER BY ph.id DESC
+LIMIT $1 OFFSET $2;
@@ -0,0 +1,4 @@
+SELECT ph.id AS history_id, ph.played_at, t.* FROM play_history ph
+JOIN track t ON ph.track_id = t.id
+WHERE ph.id > $1
+ORDER BY ph.id DESC;
@@ -0,0 +1,3 @@
+SELECT t.* FROM play_history ph
+JOIN track t ON ph.track_id = t.id
+ORDER BY ph.id DESC;
@@ -0,0 +1,4 @@
+SELECT COUNT(*) AS play_count, COALESCE(SUM(t.duration), 0) AS total_duration
+FROM play_history ph
+JOIN track t ON ph.track_id = t.id
+WHERE $1 IS NULL OR ph.played_at >= $1;
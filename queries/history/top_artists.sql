@@ -0,0 +1,9 @@
+SELECT ar.id, ar.name, NULL AS subtitle, COUNT(*) AS play_count
+FROM play_history ph
+JOIN track t ON ph.track_id = t.id
+JOIN album al ON t.album_id = al.id
+JOIN artist ar ON al.artist_id = ar.id
+WHERE $1 IS NULL OR ph.played_at >= $1
+GROUP BY ar.id
+ORDER BY play_count DESC, MAX(ph.id) DESC
+LIMIT $2;
@@ -0,0 +1,7 @@
+SELECT t.id, t.title AS name, t.artist_names AS subtitle, COUNT(*) AS play_count
+FROM play_history ph
+JOIN track t ON ph.track_id = t.id
+WHERE $1 IS NULL OR ph.played_at >= $1
+GROUP BY t.id
+ORDER BY play_count DESC, MAX(ph.id) DESC
+LIMIT $2;
@@ -1,14 +1,17 @@
 SELECT
-    id,
-    title,
-    title_sortable,
-    artist_id,
-    release_date,
-    date_precision,
-    created_at,
-    label,
-    catalog_number,
-    isrc,
-    vinyl_numbering
+    album.id,
+    album.title,
+    album.title_sortable,
+    album.artist_id,
+    album.release_date,
+    album.date_precision,
+    album.created_at,
+    album.label,
+    album.catalog_number,
+    album.isrc,
+    album.vinyl_numbering,
+    album.rating,
+    artist.name AS artist_name
 FROM album
-WHERE id = $1;
+LEFT JOIN artist ON artist.id = album.artist_id
+WHERE album.id = $1;
@@ -10,6 +10,7 @@ SELECT
     label,
     catalog_number,
     isrc,
-    vinyl_numbering
+    vinyl_numbering,
+    rating
 FROM album
 WHERE id = $1;
@@ -4,6 +4,17 @@ SELECT
 FROM
     album p
     JOIN artist a ON p.artist_id = a.id
+WHERE
+    (
+        $1 IS NULL
+        OR EXISTS (
+            SELECT 1 FROM track g WHERE g.album_id = p.id AND g.genres = $1 COLLATE NOCASE
+        )
+    )
+    AND (
+        $2 IS NULL
+        OR CAST(substr(p.release_date, 1, 4) AS INTEGER) BETWEEN $2 AND $3
+    )
 ORDER BY
     a.name_sortable COLLATE NOCASE ASC,
     p.release_date ASC;
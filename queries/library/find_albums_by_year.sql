@@ -0,0 +1,10 @@
+SELECT
+    id,
+    title_sortable,
+    CAST(substr(release_date, 1, 4) AS INTEGER) AS year
+FROM
+    album
+ORDER BY
+    release_date IS NULL ASC,
+    release_date ASC,
+    title_sortable COLLATE NOCASE ASC;
@@ -0,0 +1,19 @@
+SELECT
+    id,
+    title_sortable
+FROM
+    album
+WHERE
+    (
+        $1 IS NULL
+        OR EXISTS (
+            SELECT 1 FROM track g WHERE g.album_id = album.id AND g.genres = $1 COLLATE NOCASE
+        )
+    )
+    AND (
+        $2 IS NULL
+        OR CAST(substr(album.release_date, 1, 4) AS INTEGER) BETWEEN $2 AND $3
+    )
+ORDER BY
+    rating DESC,
+    title_sortable COLLATE NOCASE ASC;
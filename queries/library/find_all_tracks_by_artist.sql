@@ -1,5 +1,5 @@
 SELECT t.* FROM track t
 JOIN album al ON t.album_id = al.id
 WHERE al.artist_id = $1
-ORDER BY al.release_date ASC,
+ORDER BY al.release_date IS NULL, al.release_date ASC,
          al.id ASC, t.disc_number ASC, t.track_number ASC;
@@ -0,0 +1,2 @@
+SELECT name_sortable FROM artist
+ORDER BY name_sortable COLLATE NOCASE ASC;
@@ -0,0 +1,5 @@
+SELECT t.* FROM track t
+JOIN album al ON t.album_id = al.id
+LEFT JOIN playlist_item pi ON pi.track_id = t.id AND pi.playlist_id = 1
+WHERE al.artist_id = $1 AND ($2 = 0 OR pi.id IS NOT NULL)
+ORDER BY CASE WHEN $2 = 0 THEN t.created_at ELSE pi.created_at END DESC;
@@ -1,5 +1,5 @@
 SELECT t.* FROM track t
 JOIN album al ON t.album_id = al.id
-JOIN playlist_item pi ON pi.track_id = t.id
-WHERE al.artist_id = $1 AND pi.playlist_id = 1
+LEFT JOIN playlist_item pi ON pi.track_id = t.id AND pi.playlist_id = 1
+WHERE al.artist_id = $1 AND ($2 = 0 OR pi.id IS NOT NULL)
 ORDER BY al.release_date ASC, al.id ASC, t.disc_number ASC, t.track_number ASC;
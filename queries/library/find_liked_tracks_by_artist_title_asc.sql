@@ -1,5 +0,0 @@
-SELECT t.* FROM track t
-JOIN album al ON t.album_id = al.id
-JOIN playlist_item pi ON pi.track_id = t.id
-WHERE al.artist_id = $1 AND pi.playlist_id = 1
-ORDER BY t.title_sortable ASC;
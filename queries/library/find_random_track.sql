@@ -0,0 +1,4 @@
+SELECT location, id, album_id FROM track
+WHERE skip_on_shuffle = 0
+ORDER BY RANDOM()
+LIMIT 1;
@@ -0,0 +1,2 @@
+SELECT id FROM track
+WHERE skip_on_shuffle = 1;
@@ -0,0 +1,2 @@
+SELECT codec, bitrate, sample_rate, bit_depth, channels FROM track
+WHERE id = $1;
@@ -1,2 +1,6 @@
-SELECT * FROM track
-WHERE id = $1;
+SELECT
+    t.*,
+    (SELECT COUNT(*) FROM play_history ph WHERE ph.track_id = t.id) AS play_count,
+    (SELECT MAX(ph.played_at) FROM play_history ph WHERE ph.track_id = t.id) AS last_played
+FROM track t
+WHERE t.id = $1;
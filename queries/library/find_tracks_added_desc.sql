@@ -0,0 +1,19 @@
+SELECT
+    t.id,
+    t.title_sortable,
+    t.album_id,
+    t.location
+FROM
+    track t
+WHERE
+    ($1 IS NULL OR t.genres = $1 COLLATE NOCASE)
+    AND (
+        $2 IS NULL
+        OR (
+            SELECT CAST(substr(y.release_date, 1, 4) AS INTEGER) FROM album y WHERE y.id = t.album_id
+        ) BETWEEN $2 AND $3
+    )
+ORDER BY
+    t.created_at DESC,
+    t.title_sortable,
+    t.location COLLATE NOCASE ASC;
@@ -7,6 +7,14 @@ FROM
     track t
     LEFT JOIN album al ON t.album_id = al.id
     LEFT JOIN artist ar ON al.artist_id = ar.id
+WHERE
+    ($1 IS NULL OR t.genres = $1 COLLATE NOCASE)
+    AND (
+        $2 IS NULL
+        OR (
+            SELECT CAST(substr(y.release_date, 1, 4) AS INTEGER) FROM album y WHERE y.id = t.album_id
+        ) BETWEEN $2 AND $3
+    )
 ORDER BY
     ar.name_sortable COLLATE NOCASE ASC,
     al.title_sortable COLLATE NOCASE ASC,
@@ -0,0 +1,24 @@
+SELECT
+    t.id,
+    t.title_sortable,
+    t.album_id,
+    t.location
+FROM
+    track t
+LEFT JOIN (
+    SELECT track_id, COUNT(*) AS plays, MAX(played_at) AS last_played
+    FROM play_history
+    GROUP BY track_id
+) p ON p.track_id = t.id
+WHERE
+    ($1 IS NULL OR t.genres = $1 COLLATE NOCASE)
+    AND (
+        $2 IS NULL
+        OR (
+            SELECT CAST(substr(y.release_date, 1, 4) AS INTEGER) FROM album y WHERE y.id = t.album_id
+        ) BETWEEN $2 AND $3
+    )
+ORDER BY
+    COALESCE(p.plays, 0) ASC,
+    t.title_sortable,
+    t.location COLLATE NOCASE ASC;
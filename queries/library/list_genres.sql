@@ -0,0 +1,11 @@
+SELECT
+    genres
+FROM
+    track
+WHERE
+    genres IS NOT NULL
+    AND genres != ''
+GROUP BY
+    genres COLLATE NOCASE
+ORDER BY
+    genres COLLATE NOCASE ASC;
@@ -0,0 +1,8 @@
+SELECT DISTINCT
+    CAST(substr(release_date, 1, 3) AS INTEGER) * 10 AS decade
+FROM
+    album
+WHERE
+    release_date IS NOT NULL
+ORDER BY
+    decade ASC;
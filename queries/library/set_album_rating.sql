@@ -0,0 +1 @@
+UPDATE album SET rating = $1 WHERE id = $2;
@@ -0,0 +1 @@
+UPDATE track SET rating = $1 WHERE id = $2;
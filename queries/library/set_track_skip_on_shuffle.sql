@@ -0,0 +1 @@
+UPDATE track SET skip_on_shuffle = $1 WHERE id = $2;
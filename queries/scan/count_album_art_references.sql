@@ -0,0 +1 @@
+SELECT COUNT(*) FROM album WHERE image_path = $1 OR thumb_path = $1;
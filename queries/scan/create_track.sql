@@ -1,5 +1,5 @@
-INSERT INTO track (title, title_sortable, album_id, track_number, disc_number, duration, location, genres, artist_names, folder, rg_track_gain, rg_track_peak, rg_album_gain, rg_album_peak, disc_subtitle)
-    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+INSERT INTO track (title, title_sortable, album_id, track_number, disc_number, duration, location, genres, artist_names, folder, rg_track_gain, rg_track_peak, rg_album_gain, rg_album_peak, disc_subtitle, rating, codec, bitrate, sample_rate, bit_depth, channels)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
     ON CONFLICT (location) DO UPDATE SET
         title = EXCLUDED.title,
         title_sortable = EXCLUDED.title_sortable,
@@ -15,5 +15,10 @@ INSERT INTO track (title, title_sortable, album_id, track_number, disc_number, d
         rg_track_peak = EXCLUDED.rg_track_peak,
         rg_album_gain = EXCLUDED.rg_album_gain,
         rg_album_peak = EXCLUDED.rg_album_peak,
-        disc_subtitle = EXCLUDED.disc_subtitle
+        disc_subtitle = EXCLUDED.disc_subtitle,
+        codec = EXCLUDED.codec,
+        bitrate = EXCLUDED.bitrate,
+        sample_rate = EXCLUDED.sample_rate,
+        bit_depth = EXCLUDED.bit_depth,
+        channels = EXCLUDED.channels
     RETURNING id;
@@ -0,0 +1,4 @@
+DELETE FROM play_history
+WHERE track_id IN (
+    SELECT id FROM track WHERE location = $1
+);
@@ -0,0 +1 @@
+SELECT image_path, thumb_path FROM album WHERE id = $1;
@@ -0,0 +1,3 @@
+SELECT image_path FROM album WHERE image_path IS NOT NULL
+UNION
+SELECT thumb_path FROM album WHERE thumb_path IS NOT NULL;
@@ -0,0 +1,3 @@
+SELECT id, image, thumb FROM album
+WHERE image IS NOT NULL OR thumb IS NOT NULL
+LIMIT $1;
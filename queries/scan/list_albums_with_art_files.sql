@@ -0,0 +1,3 @@
+SELECT id, image_path, thumb_path FROM album
+WHERE image_path IS NOT NULL OR thumb_path IS NOT NULL
+LIMIT $1;
@@ -0,0 +1 @@
+UPDATE album SET image = $2, thumb = $3, image_path = $4, thumb_path = $5 WHERE id = $1;
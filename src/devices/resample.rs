@@ -172,15 +172,24 @@ pub struct Resampler {
     channels: usize,
     source_rate: u32,
     target_rate: u32,
+    sub_chunks: usize,
     eof: bool,
 }
 
 impl Resampler {
-    pub fn new(orig_rate: u32, target_rate: u32, duration: u64, channels: u16) -> Self {
+    /// Creates a resampler. `sub_chunks` controls the filter length: each chunk of `duration`
+    /// frames is split into this many FFTs, so fewer sub-chunks give a higher quality.
+    pub fn new(
+        orig_rate: u32,
+        target_rate: u32,
+        duration: u64,
+        channels: u16,
+        sub_chunks: usize,
+    ) -> Self {
         if orig_rate != target_rate {
             info!(
-                "Resampling required, resampling from {:?} to {:?} (duration {:?})",
-                orig_rate, target_rate, duration
+                "Resampling required, resampling from {:?} to {:?} (duration {:?}, sub-chunks {:?})",
+                orig_rate, target_rate, duration, sub_chunks
             );
         }
 
@@ -188,7 +197,7 @@ impl Resampler {
             orig_rate as usize,
             target_rate as usize,
             duration as usize,
-            2,
+            sub_chunks,
             channels as usize,
             FixedSync::Input,
         )
@@ -215,6 +224,7 @@ impl Resampler {
             channels: channels_usize,
             source_rate: orig_rate,
             target_rate,
+            sub_chunks,
             eof: false,
         }
     }
@@ -229,11 +239,13 @@ impl Resampler {
         target_rate: u32,
         duration: u64,
         channels: usize,
+        sub_chunks: usize,
     ) -> bool {
         self.source_rate == source_rate
             && self.target_rate == target_rate
             && self.duration == duration
             && self.channels == channels
+            && self.sub_chunks == sub_chunks
     }
 
     fn input_available(&self) -> usize {
@@ -1,3 +1,4 @@
+pub mod art;
 pub mod db;
 pub mod playlist;
 pub mod scan;
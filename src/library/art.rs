@@ -0,0 +1,296 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use rustc_hash::FxHashSet;
+use sqlx::{SqliteExecutor, SqlitePool};
+use tracing::{debug, info, warn};
+
+use crate::{paths, settings::scan::ArtStorage};
+
+/// Number of albums moved between storage locations at once.
+const MOVE_BATCH_SIZE: i64 = 50;
+
+/// Returns the name of the file art is stored in. Files are named after a hash of their contents,
+/// so albums with identical art share a single file. The extension is picked from the image data
+/// so the files can be opened by other programs.
+pub fn art_file_name(data: &[u8]) -> String {
+    let extension = image::guess_format(data)
+        .ok()
+        .and_then(|format| format.extensions_str().first().copied())
+        .unwrap_or("img");
+
+    format!("{:x}.{extension}", md5::compute(data))
+}
+
+fn art_path_in(dir: &Path, name: &str) -> PathBuf {
+    // names come from the database, never let them point outside of the art directory
+    dir.join(Path::new(name).file_name().unwrap_or_default())
+}
+
+/// Returns the art stored for an album, either directly from the database or from the file the
+/// database refers to. Missing files are treated as missing art.
+pub fn read_art(data: Option<Vec<u8>>, file_name: Option<String>) -> io::Result<Option<Vec<u8>>> {
+    read_art_in(&paths::art_dir(), data, file_name)
+}
+
+fn read_art_in(
+    dir: &Path,
+    data: Option<Vec<u8>>,
+    file_name: Option<String>,
+) -> io::Result<Option<Vec<u8>>> {
+    if let Some(data) = data
+        && !data.is_empty()
+    {
+        return Ok(Some(data));
+    }
+
+    let Some(file_name) = file_name else {
+        return Ok(None);
+    };
+
+    match std::fs::read(art_path_in(dir, &file_name)) {
+        Ok(data) if !data.is_empty() => Ok(Some(data)),
+        Ok(_) => Ok(None),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Writes art to the art directory, returning the name of the file. If the same art is already
+/// stored, the existing file is reused.
+pub async fn write_art(data: &[u8]) -> io::Result<String> {
+    let dir = paths::art_dir();
+    let name = art_file_name(data);
+    let path = art_path_in(&dir, &name);
+
+    if tokio::fs::try_exists(&path).await? {
+        return Ok(name);
+    }
+
+    tokio::fs::create_dir_all(&dir).await?;
+
+    // written under a temporary name first so a partial file is never mistaken for the art
+    let partial = path.with_extension("partial");
+    tokio::fs::write(&partial, data).await?;
+    tokio::fs::rename(&partial, &path).await?;
+
+    Ok(name)
+}
+
+async fn remove_art_file(file_name: &str) {
+    let path = art_path_in(&paths::art_dir(), file_name);
+
+    if let Err(err) = tokio::fs::remove_file(&path).await
+        && err.kind() != io::ErrorKind::NotFound
+    {
+        warn!(?err, "Failed to remove album art file {path:?}: {err}");
+    }
+}
+
+/// Removes an art file if no album uses it anymore.
+pub async fn release_art<'c>(
+    executor: impl SqliteExecutor<'c>,
+    file_name: &str,
+) -> anyhow::Result<()> {
+    let (references,): (i64,) = sqlx::query_as(include_str!(
+        "../../queries/scan/count_album_art_references.sql"
+    ))
+    .bind(file_name)
+    .fetch_one(executor)
+    .await?;
+
+    if references == 0 {
+        remove_art_file(file_name).await;
+    }
+
+    Ok(())
+}
+
+/// Removes files in the art directory that no album refers to. These are left behind if a scan is
+/// interrupted, or after art is moved into the database.
+async fn remove_unreferenced_art(pool: &SqlitePool) -> anyhow::Result<()> {
+    let dir = paths::art_dir();
+    let mut entries = match tokio::fs::read_dir(&dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let referenced: FxHashSet<String> =
+        sqlx::query_scalar(include_str!("../../queries/scan/list_album_art_files.sql"))
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+    let mut removed: u64 = 0;
+    while let Some(entry) = entries.next_entry().await? {
+        let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+
+        if !referenced.contains(&name) {
+            debug!("Removing unreferenced album art file {name}");
+            remove_art_file(&name).await;
+            removed += 1;
+        }
+    }
+
+    if removed > 0 {
+        info!("Removed {removed} unused album art files");
+    }
+
+    Ok(())
+}
+
+/// Moves album art stored in the wrong place for `storage`, so the library matches the current
+/// setting, and removes art files that are no longer used.
+pub async fn sync_art_storage(pool: &SqlitePool, storage: ArtStorage) -> anyhow::Result<()> {
+    match storage {
+        ArtStorage::Database => move_art_to_database(pool).await?,
+        ArtStorage::Files => move_art_to_files(pool).await?,
+    }
+
+    remove_unreferenced_art(pool).await
+}
+
+async fn move_art_to_files(pool: &SqlitePool) -> anyhow::Result<()> {
+    let mut moved: u64 = 0;
+
+    loop {
+        let albums: Vec<(i64, Option<Vec<u8>>, Option<Vec<u8>>)> = sqlx::query_as(include_str!(
+            "../../queries/scan/list_albums_with_art_blobs.sql"
+        ))
+        .bind(MOVE_BATCH_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+        if albums.is_empty() {
+            break;
+        }
+
+        for (id, image, thumb) in albums {
+            let image_path = match image.filter(|data| !data.is_empty()) {
+                Some(data) => Some(write_art(&data).await?),
+                None => None,
+            };
+            let thumb_path = match thumb.filter(|data| !data.is_empty()) {
+                Some(data) => Some(write_art(&data).await?),
+                None => None,
+            };
+
+            sqlx::query(include_str!("../../queries/scan/set_album_art.sql"))
+                .bind(id)
+                .bind(None::<Vec<u8>>)
+                .bind(None::<Vec<u8>>)
+                .bind(image_path)
+                .bind(thumb_path)
+                .execute(pool)
+                .await?;
+
+            moved += 1;
+        }
+    }
+
+    if moved > 0 {
+        info!("Moved art for {moved} albums out of the library database");
+
+        // the space used by the art is only given back to the file system after a vacuum
+        sqlx::query("VACUUM").execute(pool).await?;
+    }
+
+    Ok(())
+}
+
+async fn move_art_to_database(pool: &SqlitePool) -> anyhow::Result<()> {
+    let mut moved: u64 = 0;
+
+    loop {
+        let albums: Vec<(i64, Option<String>, Option<String>)> = sqlx::query_as(include_str!(
+            "../../queries/scan/list_albums_with_art_files.sql"
+        ))
+        .bind(MOVE_BATCH_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+        if albums.is_empty() {
+            break;
+        }
+
+        for (id, image_path, thumb_path) in albums {
+            let image = read_art(None, image_path.clone())?;
+            let thumb = read_art(None, thumb_path.clone())?;
+
+            sqlx::query(include_str!("../../queries/scan/set_album_art.sql"))
+                .bind(id)
+                .bind(image)
+                .bind(thumb)
+                .bind(None::<String>)
+                .bind(None::<String>)
+                .execute(pool)
+                .await?;
+
+            moved += 1;
+        }
+    }
+
+    if moved > 0 {
+        info!("Moved art for {moved} albums into the library database");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestDir;
+
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    #[test]
+    fn file_names_use_detected_extension() {
+        assert!(art_file_name(PNG_HEADER).ends_with(".png"));
+        assert!(art_file_name(b"not an image").ends_with(".img"));
+    }
+
+    #[test]
+    fn identical_art_shares_a_file_name() {
+        assert_eq!(art_file_name(b"cover"), art_file_name(b"cover"));
+        assert_ne!(art_file_name(b"cover"), art_file_name(b"other cover"));
+    }
+
+    #[test]
+    fn read_art_prefers_database_data() {
+        let dir = TestDir::new("hummingbird-art-test");
+        std::fs::write(dir.join("1_full.png"), b"file").unwrap();
+
+        let data = read_art_in(
+            dir.path(),
+            Some(b"database".to_vec()),
+            Some("1_full.png".to_string()),
+        )
+        .unwrap();
+        assert_eq!(data.as_deref(), Some(&b"database"[..]));
+
+        let data = read_art_in(dir.path(), None, Some("1_full.png".to_string())).unwrap();
+        assert_eq!(data.as_deref(), Some(&b"file"[..]));
+    }
+
+    #[test]
+    fn read_art_treats_missing_files_as_missing_art() {
+        let dir = TestDir::new("hummingbird-art-test");
+
+        let data = read_art_in(dir.path(), None, Some("2_full.png".to_string())).unwrap();
+        assert_eq!(data, None);
+    }
+
+    #[test]
+    fn art_paths_stay_in_art_directory() {
+        let dir = Path::new("/data/art");
+
+        assert_eq!(art_path_in(dir, "../../library.db"), dir.join("library.db"));
+    }
+}
@@ -1,5 +1,6 @@
 use std::{path::Path, sync::Arc};
 
+use chrono::{DateTime, Utc};
 use gpui::App;
 use serde::{Deserialize, Serialize};
 use sqlx::{
@@ -9,11 +10,15 @@ use sqlx::{
 use tracing::debug;
 
 use crate::{
-    library::types::{ArtistWithCounts, Playlist, PlaylistItem, PlaylistWithCount, TrackStats},
+    library::types::{
+        ArtistWithCounts, DailyPlays, ListeningStats, ListeningTotals, PlayHistoryEntry, Playlist,
+        PlaylistItem, PlaylistWithCount, TopPlayed, TrackStats,
+    },
+    media::metadata::AudioProperties,
     ui::app::Pool,
 };
 
-use super::types::{Album, Artist, Track};
+use super::types::{Album, Artist, MAX_RATING, Track};
 
 pub async fn create_pool(path: impl AsRef<Path>) -> sqlx::Result<SqlitePool> {
     debug!("Creating database pool at {:?}", path.as_ref());
@@ -60,6 +65,21 @@ pub async fn create_pool(path: impl AsRef<Path>) -> sqlx::Result<SqlitePool> {
     Ok(pool)
 }
 
+/// Narrows the albums and tracks listed in the library down to a genre and a range of release
+/// years. Facets that are `None` don't filter anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LibraryFilter {
+    pub genre: Option<String>,
+    /// The first and last release year to include.
+    pub years: Option<(i32, i32)>,
+}
+
+impl LibraryFilter {
+    pub fn is_empty(&self) -> bool {
+        self.genre.is_none() && self.years.is_none()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AlbumSortMethod {
     TitleAsc,
@@ -72,6 +92,8 @@ pub enum AlbumSortMethod {
     LabelDesc,
     CatalogAsc,
     CatalogDesc,
+    RatingAsc,
+    RatingDesc,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -86,6 +108,16 @@ pub enum TrackSortMethod {
     DurationDesc,
     TrackNumberAsc,
     TrackNumberDesc,
+    RatingAsc,
+    RatingDesc,
+    FormatAsc,
+    FormatDesc,
+    PlayCountAsc,
+    PlayCountDesc,
+    DateAddedAsc,
+    DateAddedDesc,
+    LastPlayedAsc,
+    LastPlayedDesc,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -133,6 +165,7 @@ pub enum AlbumMethod {
 pub async fn list_albums(
     pool: &SqlitePool,
     sort_method: AlbumSortMethod,
+    filter: &LibraryFilter,
 ) -> sqlx::Result<Vec<(u32, String)>> {
     let query = match sort_method {
         AlbumSortMethod::TitleAsc => {
@@ -165,9 +198,18 @@ pub async fn list_albums(
         AlbumSortMethod::CatalogDesc => {
             include_str!("../../queries/library/find_albums_catnum_desc.sql")
         }
+        AlbumSortMethod::RatingAsc => {
+            include_str!("../../queries/library/find_albums_rating_asc.sql")
+        }
+        AlbumSortMethod::RatingDesc => {
+            include_str!("../../queries/library/find_albums_rating_desc.sql")
+        }
     };
 
     let albums = sqlx::query_as::<_, (u32, String)>(query)
+        .bind(filter.genre.as_deref())
+        .bind(filter.years.map(|years| years.0))
+        .bind(filter.years.map(|years| years.1))
         .fetch_all(pool)
         .await?;
 
@@ -177,6 +219,7 @@ pub async fn list_albums(
 pub async fn list_tracks(
     pool: &SqlitePool,
     sort_method: TrackSortMethod,
+    filter: &LibraryFilter,
 ) -> sqlx::Result<Vec<(i64, String, Option<i64>, String)>> {
     let query = match sort_method {
         TrackSortMethod::TitleAsc => {
@@ -209,9 +252,42 @@ pub async fn list_tracks(
         TrackSortMethod::TrackNumberDesc => {
             include_str!("../../queries/library/find_tracks_number_desc.sql")
         }
+        TrackSortMethod::RatingAsc => {
+            include_str!("../../queries/library/find_tracks_rating_asc.sql")
+        }
+        TrackSortMethod::RatingDesc => {
+            include_str!("../../queries/library/find_tracks_rating_desc.sql")
+        }
+        TrackSortMethod::FormatAsc => {
+            include_str!("../../queries/library/find_tracks_format_asc.sql")
+        }
+        TrackSortMethod::FormatDesc => {
+            include_str!("../../queries/library/find_tracks_format_desc.sql")
+        }
+        TrackSortMethod::PlayCountAsc => {
+            include_str!("../../queries/library/find_tracks_plays_asc.sql")
+        }
+        TrackSortMethod::PlayCountDesc => {
+            include_str!("../../queries/library/find_tracks_plays_desc.sql")
+        }
+        TrackSortMethod::DateAddedAsc => {
+            include_str!("../../queries/library/find_tracks_added_asc.sql")
+        }
+        TrackSortMethod::DateAddedDesc => {
+            include_str!("../../queries/library/find_tracks_added_desc.sql")
+        }
+        TrackSortMethod::LastPlayedAsc => {
+            include_str!("../../queries/library/find_tracks_last_played_asc.sql")
+        }
+        TrackSortMethod::LastPlayedDesc => {
+            include_str!("../../queries/library/find_tracks_last_played_desc.sql")
+        }
     };
 
     let tracks = sqlx::query_as::<_, (i64, String, Option<i64>, String)>(query)
+        .bind(filter.genre.as_deref())
+        .bind(filter.years.map(|years| years.0))
+        .bind(filter.years.map(|years| years.1))
         .fetch_all(pool)
         .await?;
 
@@ -312,6 +388,45 @@ pub async fn list_artists(
     Ok(artists.into_iter().map(|r| r.0).collect())
 }
 
+/// Returns the sortable name of every artist, in ascending name order.
+pub async fn list_artist_sort_names(pool: &SqlitePool) -> sqlx::Result<Vec<String>> {
+    let query = include_str!("../../queries/library/find_artist_sort_names.sql");
+
+    let names: Vec<(String,)> = sqlx::query_as(query).fetch_all(pool).await?;
+
+    Ok(names.into_iter().map(|r| r.0).collect())
+}
+
+pub async fn list_genres(pool: &SqlitePool) -> sqlx::Result<Vec<String>> {
+    let query = include_str!("../../queries/library/list_genres.sql");
+
+    let genres: Vec<(String,)> = sqlx::query_as(query).fetch_all(pool).await?;
+
+    Ok(genres.into_iter().map(|r| r.0).collect())
+}
+
+pub async fn list_release_decades(pool: &SqlitePool) -> sqlx::Result<Vec<i32>> {
+    let query = include_str!("../../queries/library/list_release_decades.sql");
+
+    let decades: Vec<(i32,)> = sqlx::query_as(query).fetch_all(pool).await?;
+
+    Ok(decades.into_iter().map(|r| r.0).collect())
+}
+
+/// Lists every album in release order, along with the year it was released in. Albums without a
+/// release date come last.
+pub async fn list_albums_by_year(
+    pool: &SqlitePool,
+) -> sqlx::Result<Vec<(u32, String, Option<i32>)>> {
+    let query = include_str!("../../queries/library/find_albums_by_year.sql");
+
+    let albums = sqlx::query_as::<_, (u32, String, Option<i32>)>(query)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(albums)
+}
+
 pub async fn list_albums_by_artist(
     pool: &SqlitePool,
     artist_id: i64,
@@ -340,35 +455,39 @@ pub async fn get_artist_with_counts(
     Ok(Arc::new(artist))
 }
 
-pub async fn get_liked_tracks_by_artist(
+/// Lists the tracks by an artist in the given order. If `liked_only` is set, only the tracks in
+/// the liked songs playlist are listed, and recently added means recently liked.
+pub async fn get_artist_tracks(
     pool: &SqlitePool,
     artist_id: i64,
     sort_method: LikedTrackSortMethod,
+    liked_only: bool,
 ) -> sqlx::Result<Arc<Vec<Track>>> {
     let query = match sort_method {
         LikedTrackSortMethod::TitleAsc => {
-            include_str!("../../queries/library/find_liked_tracks_by_artist_title_asc.sql")
+            include_str!("../../queries/library/find_artist_tracks_title_asc.sql")
         }
         LikedTrackSortMethod::TitleDesc => {
-            include_str!("../../queries/library/find_liked_tracks_by_artist_title_desc.sql")
+            include_str!("../../queries/library/find_artist_tracks_title_desc.sql")
         }
         LikedTrackSortMethod::ReleaseOrder => {
-            include_str!("../../queries/library/find_liked_tracks_by_artist_release_asc.sql")
+            include_str!("../../queries/library/find_artist_tracks_release_asc.sql")
         }
         LikedTrackSortMethod::ReleaseOrderDesc => {
-            include_str!("../../queries/library/find_liked_tracks_by_artist_release_desc.sql")
+            include_str!("../../queries/library/find_artist_tracks_release_desc.sql")
         }
         LikedTrackSortMethod::RecentlyAdded => {
-            include_str!("../../queries/library/find_liked_tracks_by_artist_recent_desc.sql")
+            include_str!("../../queries/library/find_artist_tracks_recent_desc.sql")
         }
         LikedTrackSortMethod::RecentlyAddedAsc => {
-            include_str!("../../queries/library/find_liked_tracks_by_artist_recent_asc.sql")
+            include_str!("../../queries/library/find_artist_tracks_recent_asc.sql")
         }
     };
 
     let tracks = Arc::new(
         sqlx::query_as::<_, Track>(query)
             .bind(artist_id)
+            .bind(liked_only)
             .fetch_all(pool)
             .await?,
     );
@@ -689,6 +808,16 @@ pub async fn get_all_tracks(pool: &SqlitePool) -> sqlx::Result<Vec<(String, i64,
     Ok(tracks)
 }
 
+/// Picks a random track from the library, leaving out tracks that are skipped when shuffling.
+pub async fn random_track(pool: &SqlitePool) -> sqlx::Result<Option<(String, i64, Option<i64>)>> {
+    let query = include_str!("../../queries/library/find_random_track.sql");
+
+    let track: Option<(String, i64, Option<i64>)> =
+        sqlx::query_as(query).fetch_optional(pool).await?;
+
+    Ok(track)
+}
+
 pub async fn lyrics_for_track(pool: &SqlitePool, track_id: i64) -> sqlx::Result<Option<String>> {
     let query = include_str!("../../queries/library/get_lyrics_by_track_id.sql");
 
@@ -700,13 +829,206 @@ pub async fn lyrics_for_track(pool: &SqlitePool, track_id: i64) -> sqlx::Result<
     Ok(row.map(|(content,)| content))
 }
 
+/// Returns the technical details recorded for a track when it was last scanned.
+pub async fn get_track_audio_properties(
+    pool: &SqlitePool,
+    track_id: i64,
+) -> sqlx::Result<AudioProperties> {
+    let query = include_str!("../../queries/library/find_track_audio_properties.sql");
+
+    #[allow(clippy::type_complexity)]
+    let (codec, bitrate, sample_rate, bit_depth, channels): (
+        Option<String>,
+        Option<i64>,
+        Option<i64>,
+        Option<i64>,
+        Option<i64>,
+    ) = sqlx::query_as(query).bind(track_id).fetch_one(pool).await?;
+
+    let to_u32 = |value: Option<i64>| value.and_then(|value| u32::try_from(value).ok());
+
+    Ok(AudioProperties {
+        codec,
+        bitrate: to_u32(bitrate),
+        sample_rate: to_u32(sample_rate),
+        bit_depth: to_u32(bit_depth),
+        channels: to_u32(channels),
+    })
+}
+
+pub async fn set_track_skip(pool: &SqlitePool, track_id: i64, skip: bool) -> sqlx::Result<()> {
+    let query = include_str!("../../queries/library/set_track_skip_on_shuffle.sql");
+
+    sqlx::query(query)
+        .bind(skip)
+        .bind(track_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Sets the star rating of a track. A rating of 0 clears it.
+pub async fn set_track_rating(pool: &SqlitePool, track_id: i64, rating: i32) -> sqlx::Result<()> {
+    let query = include_str!("../../queries/library/set_track_rating.sql");
+
+    sqlx::query(query)
+        .bind(rating.clamp(0, MAX_RATING))
+        .bind(track_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Sets the star rating of an album. A rating of 0 clears it.
+pub async fn set_album_rating(pool: &SqlitePool, album_id: i64, rating: i32) -> sqlx::Result<()> {
+    let query = include_str!("../../queries/library/set_album_rating.sql");
+
+    sqlx::query(query)
+        .bind(rating.clamp(0, MAX_RATING))
+        .bind(album_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Lists the IDs of all tracks that should be left out when shuffling.
+pub async fn get_shuffle_skipped_tracks(pool: &SqlitePool) -> sqlx::Result<Vec<i64>> {
+    let query = include_str!("../../queries/library/find_shuffle_skipped_tracks.sql");
+
+    let tracks: Vec<i64> = sqlx::query_scalar(query).fetch_all(pool).await?;
+
+    Ok(tracks)
+}
+
+/// Records a play of the track at the given path in the play history. Returns false if the path
+/// is not in the library.
+pub async fn record_play(pool: &SqlitePool, path: &Path) -> sqlx::Result<bool> {
+    let query = include_str!("../../queries/history/insert_play.sql");
+
+    let result = sqlx::query(query)
+        .bind(path.to_string_lossy().as_ref())
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Lists played tracks, most recent first. Returns at most `limit` entries, skipping the first
+/// `offset`.
+pub async fn get_play_history(
+    pool: &SqlitePool,
+    limit: i64,
+    offset: i64,
+) -> sqlx::Result<Arc<Vec<PlayHistoryEntry>>> {
+    let query = include_str!("../../queries/history/find_play_history.sql");
+
+    let entries = sqlx::query_as::<_, PlayHistoryEntry>(query)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(Arc::new(entries))
+}
+
+/// Lists the plays added after the history entry `history_id`, most recent first.
+pub async fn get_play_history_after(
+    pool: &SqlitePool,
+    history_id: i64,
+) -> sqlx::Result<Arc<Vec<PlayHistoryEntry>>> {
+    let query = include_str!("../../queries/history/find_play_history_after.sql");
+
+    let entries = sqlx::query_as::<_, PlayHistoryEntry>(query)
+        .bind(history_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(Arc::new(entries))
+}
+
+/// Lists the track of every play in the history, most recent first.
+pub async fn get_play_history_tracks(pool: &SqlitePool) -> sqlx::Result<Arc<Vec<Track>>> {
+    let query = include_str!("../../queries/history/find_play_history_tracks.sql");
+
+    let tracks = sqlx::query_as::<_, Track>(query).fetch_all(pool).await?;
+
+    Ok(Arc::new(tracks))
+}
+
+/// Formats a timestamp the same way SQLite's `CURRENT_TIMESTAMP` does, so it can be compared
+/// against `played_at`.
+fn history_timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Summarizes the play history. Totals and top lists only count plays since `since` (or all plays
+/// if it is `None`), and contain at most `limit` entries each. Daily activity is listed for every
+/// day since `activity_since`.
+pub async fn get_listening_stats(
+    pool: &SqlitePool,
+    since: Option<DateTime<Utc>>,
+    activity_since: DateTime<Utc>,
+    limit: i64,
+) -> sqlx::Result<Arc<ListeningStats>> {
+    let since = since.map(history_timestamp);
+
+    let totals: ListeningTotals =
+        sqlx::query_as(include_str!("../../queries/history/listening_totals.sql"))
+            .bind(&since)
+            .fetch_one(pool)
+            .await?;
+
+    let top_tracks: Vec<TopPlayed> =
+        sqlx::query_as(include_str!("../../queries/history/top_tracks.sql"))
+            .bind(&since)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+
+    let top_albums: Vec<TopPlayed> =
+        sqlx::query_as(include_str!("../../queries/history/top_albums.sql"))
+            .bind(&since)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+
+    let top_artists: Vec<TopPlayed> =
+        sqlx::query_as(include_str!("../../queries/history/top_artists.sql"))
+            .bind(&since)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?;
+
+    let daily: Vec<DailyPlays> =
+        sqlx::query_as(include_str!("../../queries/history/daily_plays.sql"))
+            .bind(history_timestamp(activity_since))
+            .fetch_all(pool)
+            .await?;
+
+    Ok(Arc::new(ListeningStats {
+        totals,
+        top_tracks,
+        top_albums,
+        top_artists,
+        daily,
+    }))
+}
+
 pub trait LibraryAccess {
-    fn list_albums(&self, sort_method: AlbumSortMethod) -> sqlx::Result<Vec<(u32, String)>>;
+    fn list_albums(
+        &self,
+        sort_method: AlbumSortMethod,
+        filter: &LibraryFilter,
+    ) -> sqlx::Result<Vec<(u32, String)>>;
     // TODO: handle this better
     #[allow(clippy::type_complexity)]
     fn list_tracks(
         &self,
         sort_method: TrackSortMethod,
+        filter: &LibraryFilter,
     ) -> sqlx::Result<Vec<(i64, String, Option<i64>, String)>>;
     fn list_tracks_in_album(&self, album_id: i64) -> sqlx::Result<Arc<Vec<Track>>>;
     fn get_album_by_id(&self, album_id: i64, method: AlbumMethod) -> sqlx::Result<Arc<Album>>;
@@ -734,32 +1056,60 @@ pub trait LibraryAccess {
     fn get_playlist_item(&self, item_id: i64) -> sqlx::Result<PlaylistItem>;
     fn get_track_stats(&self) -> sqlx::Result<Arc<TrackStats>>;
     fn playlist_has_track(&self, playlist_id: i64, track_id: i64) -> sqlx::Result<Option<i64>>;
+    fn add_playlist_item(&self, playlist_id: i64, track_id: i64) -> sqlx::Result<i64>;
+    fn remove_playlist_item(&self, item_id: i64) -> sqlx::Result<()>;
     fn list_artists(&self, sort_method: ArtistSortMethod) -> sqlx::Result<Vec<i64>>;
+    fn list_artist_sort_names(&self) -> sqlx::Result<Vec<String>>;
+    fn list_genres(&self) -> sqlx::Result<Vec<String>>;
+    fn list_release_decades(&self) -> sqlx::Result<Vec<i32>>;
     fn list_albums_by_artist(&self, artist_id: i64) -> sqlx::Result<Vec<(u32, String)>>;
+    fn list_albums_by_year(&self) -> sqlx::Result<Vec<(u32, String, Option<i32>)>>;
     fn get_artist_with_counts(&self, artist_id: i64) -> sqlx::Result<Arc<ArtistWithCounts>>;
-    fn get_liked_tracks_by_artist(
+    fn get_artist_tracks(
         &self,
         artist_id: i64,
         sort_method: LikedTrackSortMethod,
+        liked_only: bool,
     ) -> sqlx::Result<Arc<Vec<Track>>>;
     fn get_all_tracks_by_artist(&self, artist_id: i64) -> sqlx::Result<Arc<Vec<Track>>>;
     fn artist_id_for_album(&self, album_id: i64) -> sqlx::Result<i64>;
     fn get_all_tracks(&self) -> sqlx::Result<Vec<(String, i64, i64)>>;
+    fn random_track(&self) -> sqlx::Result<Option<(String, i64, Option<i64>)>>;
     fn lyrics_for_track(&self, track_id: i64) -> sqlx::Result<Option<String>>;
+    fn get_track_audio_properties(&self, track_id: i64) -> sqlx::Result<AudioProperties>;
+    fn set_track_skip(&self, track_id: i64, skip: bool) -> sqlx::Result<()>;
+    fn set_track_rating(&self, track_id: i64, rating: i32) -> sqlx::Result<()>;
+    fn set_album_rating(&self, album_id: i64, rating: i32) -> sqlx::Result<()>;
+    fn get_shuffle_skipped_tracks(&self) -> sqlx::Result<Vec<i64>>;
+    fn get_play_history(&self, limit: i64, offset: i64)
+    -> sqlx::Result<Arc<Vec<PlayHistoryEntry>>>;
+    fn get_play_history_after(&self, history_id: i64) -> sqlx::Result<Arc<Vec<PlayHistoryEntry>>>;
+    fn get_play_history_tracks(&self) -> sqlx::Result<Arc<Vec<Track>>>;
+    fn get_listening_stats(
+        &self,
+        since: Option<DateTime<Utc>>,
+        activity_since: DateTime<Utc>,
+        limit: i64,
+    ) -> sqlx::Result<Arc<ListeningStats>>;
 }
 
 impl LibraryAccess for App {
-    fn list_albums(&self, sort_method: AlbumSortMethod) -> sqlx::Result<Vec<(u32, String)>> {
+    fn list_albums(
+        &self,
+        sort_method: AlbumSortMethod,
+        filter: &LibraryFilter,
+    ) -> sqlx::Result<Vec<(u32, String)>> {
         let pool: &Pool = self.global();
-        crate::RUNTIME.block_on(list_albums(&pool.0, sort_method))
+        crate::RUNTIME.block_on(list_albums(&pool.0, sort_method, filter))
     }
 
     fn list_tracks(
         &self,
         sort_method: TrackSortMethod,
+        filter: &LibraryFilter,
     ) -> sqlx::Result<Vec<(i64, String, Option<i64>, String)>> {
         let pool: &Pool = self.global();
-        crate::RUNTIME.block_on(list_tracks(&pool.0, sort_method))
+        crate::RUNTIME.block_on(list_tracks(&pool.0, sort_method, filter))
     }
 
     fn list_tracks_in_album(&self, album_id: i64) -> sqlx::Result<Arc<Vec<Track>>> {
@@ -877,28 +1227,64 @@ impl LibraryAccess for App {
         crate::RUNTIME.block_on(playlist_has_track(&pool.0, playlist_id, track_id))
     }
 
+    fn add_playlist_item(&self, playlist_id: i64, track_id: i64) -> sqlx::Result<i64> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(add_playlist_item(&pool.0, playlist_id, track_id))
+    }
+
+    fn remove_playlist_item(&self, item_id: i64) -> sqlx::Result<()> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(remove_playlist_item(&pool.0, item_id))
+    }
+
     fn list_artists(&self, sort_method: ArtistSortMethod) -> sqlx::Result<Vec<i64>> {
         let pool: &Pool = self.global();
         crate::RUNTIME.block_on(list_artists(&pool.0, sort_method))
     }
 
+    fn list_artist_sort_names(&self) -> sqlx::Result<Vec<String>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(list_artist_sort_names(&pool.0))
+    }
+
+    fn list_genres(&self) -> sqlx::Result<Vec<String>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(list_genres(&pool.0))
+    }
+
+    fn list_release_decades(&self) -> sqlx::Result<Vec<i32>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(list_release_decades(&pool.0))
+    }
+
     fn list_albums_by_artist(&self, artist_id: i64) -> sqlx::Result<Vec<(u32, String)>> {
         let pool: &Pool = self.global();
         crate::RUNTIME.block_on(list_albums_by_artist(&pool.0, artist_id))
     }
 
+    fn list_albums_by_year(&self) -> sqlx::Result<Vec<(u32, String, Option<i32>)>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(list_albums_by_year(&pool.0))
+    }
+
     fn get_artist_with_counts(&self, artist_id: i64) -> sqlx::Result<Arc<ArtistWithCounts>> {
         let pool: &Pool = self.global();
         crate::RUNTIME.block_on(get_artist_with_counts(&pool.0, artist_id))
     }
 
-    fn get_liked_tracks_by_artist(
+    fn get_artist_tracks(
         &self,
         artist_id: i64,
         sort_method: LikedTrackSortMethod,
+        liked_only: bool,
     ) -> sqlx::Result<Arc<Vec<Track>>> {
         let pool: &Pool = self.global();
-        crate::RUNTIME.block_on(get_liked_tracks_by_artist(&pool.0, artist_id, sort_method))
+        crate::RUNTIME.block_on(get_artist_tracks(
+            &pool.0,
+            artist_id,
+            sort_method,
+            liked_only,
+        ))
     }
 
     fn get_all_tracks_by_artist(&self, artist_id: i64) -> sqlx::Result<Arc<Vec<Track>>> {
@@ -916,8 +1302,67 @@ impl LibraryAccess for App {
         crate::RUNTIME.block_on(get_all_tracks(&pool.0))
     }
 
+    fn random_track(&self) -> sqlx::Result<Option<(String, i64, Option<i64>)>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(random_track(&pool.0))
+    }
+
     fn lyrics_for_track(&self, track_id: i64) -> sqlx::Result<Option<String>> {
         let pool: &Pool = self.global();
         crate::RUNTIME.block_on(lyrics_for_track(&pool.0, track_id))
     }
+
+    fn get_track_audio_properties(&self, track_id: i64) -> sqlx::Result<AudioProperties> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(get_track_audio_properties(&pool.0, track_id))
+    }
+
+    fn set_track_skip(&self, track_id: i64, skip: bool) -> sqlx::Result<()> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(set_track_skip(&pool.0, track_id, skip))
+    }
+
+    fn set_track_rating(&self, track_id: i64, rating: i32) -> sqlx::Result<()> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(set_track_rating(&pool.0, track_id, rating))
+    }
+
+    fn set_album_rating(&self, album_id: i64, rating: i32) -> sqlx::Result<()> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(set_album_rating(&pool.0, album_id, rating))
+    }
+
+    fn get_shuffle_skipped_tracks(&self) -> sqlx::Result<Vec<i64>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(get_shuffle_skipped_tracks(&pool.0))
+    }
+
+    fn get_play_history(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> sqlx::Result<Arc<Vec<PlayHistoryEntry>>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(get_play_history(&pool.0, limit, offset))
+    }
+
+    fn get_play_history_after(&self, history_id: i64) -> sqlx::Result<Arc<Vec<PlayHistoryEntry>>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(get_play_history_after(&pool.0, history_id))
+    }
+
+    fn get_play_history_tracks(&self) -> sqlx::Result<Arc<Vec<Track>>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(get_play_history_tracks(&pool.0))
+    }
+
+    fn get_listening_stats(
+        &self,
+        since: Option<DateTime<Utc>>,
+        activity_since: DateTime<Utc>,
+        limit: i64,
+    ) -> sqlx::Result<Arc<ListeningStats>> {
+        let pool: &Pool = self.global();
+        crate::RUNTIME.block_on(get_listening_stats(&pool.0, since, activity_since, limit))
+    }
 }
@@ -4,6 +4,7 @@ mod discover;
 mod record;
 
 use std::{
+    mem::take,
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
@@ -21,20 +22,28 @@ use tokio::{
     sync::{
         Mutex,
         mpsc::{Receiver, Sender, UnboundedReceiver, UnboundedSender, channel, unbounded_channel},
+        oneshot,
     },
     task::spawn_blocking,
+    time::sleep_until,
 };
 use tracing::{error, info, warn};
 
 use crate::{
-    library::scan::{
-        database::{AlbumCacheKey, AlbumPathCacheKey, update_metadata},
-        decode::{FileInformation, read_metadata_for_path},
-        discover::{cleanup_removed_directories, cleanup_with_exclusions, discover},
-        record::{SCAN_VERSION, ScanRecord, load_scan_record, write_checkpoint, write_scan_record},
+    library::{
+        art::sync_art_storage,
+        scan::{
+            database::{AlbumCacheKey, AlbumPathCacheKey, update_metadata},
+            decode::{FileInformation, TimedScanner, read_metadata_for_path},
+            discover::{cleanup_removed_directories, cleanup_with_exclusions, discover},
+            record::{
+                RecordWriter, SCAN_VERSION, ScanRecord, load_checkpoint, load_scan_record,
+                write_checkpoint,
+            },
+        },
     },
     paths,
-    settings::scan::{MissingFolderPolicy, ScanSettings},
+    settings::scan::{ArtPriority, MissingFolderPolicy, ScanSettings},
     ui::models::{Models, PlaylistEvent},
 };
 
@@ -44,9 +53,23 @@ const BATCH_SIZE: usize = 50;
 #[derive(Debug, PartialEq, Clone)]
 pub enum ScanEvent {
     Cleaning,
+    /// Progress through the cleanup stage: `checked` of the `total` previously scanned files have
+    /// been checked, and `removed` of the `to_remove` tracks found missing have been removed from
+    /// the library.
+    CleaningProgress {
+        checked: u64,
+        total: u64,
+        removed: u64,
+        to_remove: u64,
+    },
     PlaylistsUpdated(Vec<i64>),
-    WaitingForMissingFolderDecision { paths: Vec<Utf8PathBuf> },
-    ScanProgress { current: u64, total: u64 },
+    WaitingForMissingFolderDecision {
+        paths: Vec<Utf8PathBuf>,
+    },
+    ScanProgress {
+        current: u64,
+        total: u64,
+    },
     ScanCompleteWatching,
     ScanCompleteIdle,
 }
@@ -57,7 +80,7 @@ pub enum MissingFolderAction {
     DeleteFromLibrary,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 enum ScanCommand {
     Scan,
     /// A force-scan is different to a regular scan in that it will ignore all previous data and
@@ -65,9 +88,14 @@ enum ScanCommand {
     /// database schema has been changed, or a bug has been fixed with in the scanning proccess,
     /// and is usually triggered by the scan version changing (see [SCAN_VERSION]).
     ForceScan,
+    /// Starts a scan that re-reads the given files even if they haven't changed, and recreates
+    /// the albums they belong to, like a force-scan limited to those files.
+    RefreshPaths(Vec<Utf8PathBuf>),
     ResolveMissingFolders(MissingFolderAction),
     UpdateSettings(ScanSettings),
     Stop,
+    /// Writes the scan record to disk if it has unsaved changes, then signals the sender.
+    Flush(oneshot::Sender<()>),
 }
 
 pub struct ScanInterface {
@@ -95,6 +123,12 @@ impl ScanInterface {
             .expect("could not send force re-scan start command");
     }
 
+    pub fn refresh_paths(&self, paths: Vec<Utf8PathBuf>) {
+        self.cmd_tx
+            .blocking_send(ScanCommand::RefreshPaths(paths))
+            .expect("could not send refresh command");
+    }
+
     pub fn stop(&self) {
         self.cmd_tx
             .blocking_send(ScanCommand::Stop)
@@ -107,6 +141,21 @@ impl ScanInterface {
             .expect("could not send scan settings update command");
     }
 
+    /// Asks the scanner to write any unsaved changes to the scan record. The returned future
+    /// resolves once the record has been written, or immediately if the scanner isn't running.
+    ///
+    /// Nothing is sent until the future is polled, so it doesn't block the calling thread.
+    pub fn flush(&self) -> impl Future<Output = ()> + use<> {
+        let cmd_tx = self.cmd_tx.clone();
+
+        async move {
+            let (done_tx, done_rx) = oneshot::channel();
+            if cmd_tx.send(ScanCommand::Flush(done_tx)).await.is_ok() {
+                let _ = done_rx.await;
+            }
+        }
+    }
+
     pub fn resolve_missing_folders(&self, action: MissingFolderAction) {
         self.cmd_tx
             .blocking_send(ScanCommand::ResolveMissingFolders(action))
@@ -155,6 +204,8 @@ async fn resolve_missing_folder_action(
     event_tx: &UnboundedSender<ScanEvent>,
     scan_settings: &mut ScanSettings,
     missing_paths: Vec<Utf8PathBuf>,
+    scan_record: &ScanRecord,
+    record_writer: &mut RecordWriter,
 ) -> MissingFolderAction {
     match scan_settings.missing_folder_policy {
         MissingFolderPolicy::KeepInLibrary => MissingFolderAction::KeepInLibrary,
@@ -180,7 +231,13 @@ async fn resolve_missing_folder_action(
                         }
                     }
                     Some(ScanCommand::Stop) => break MissingFolderAction::KeepInLibrary,
-                    Some(ScanCommand::Scan) | Some(ScanCommand::ForceScan) => {}
+                    Some(ScanCommand::Scan)
+                    | Some(ScanCommand::ForceScan)
+                    | Some(ScanCommand::RefreshPaths(_)) => {}
+                    Some(ScanCommand::Flush(done)) => {
+                        record_writer.flush(scan_record).await;
+                        let _ = done.send(());
+                    }
                     None => break MissingFolderAction::KeepInLibrary,
                 }
             }
@@ -223,6 +280,7 @@ async fn run_scanner(
                             .map(|(k, v)| (k, UNIX_EPOCH + Duration::from_secs(v)))
                             .collect(),
                         directories: scan_settings.paths.clone(),
+                        art_priority: ArtPriority::default(),
                     })
                 }
                 Err(e) => {
@@ -255,19 +313,15 @@ async fn run_scanner(
     };
 
     // attempt to recover checkpoint data from a previous crashed scan
-    if try_exists(&checkpoint_path).await.unwrap_or(false) {
-        let checkpoint = load_scan_record(&checkpoint_path).await;
-        let base_dirs: FxHashSet<Utf8PathBuf> =
-            scan_record_state.directories.iter().cloned().collect();
-        for dir in checkpoint.directories {
-            if !base_dirs.contains(&dir) {
-                scan_record_state.directories.push(dir);
+    let recovered_checkpoint = try_exists(&checkpoint_path).await.unwrap_or(false);
+    if recovered_checkpoint {
+        let (added, removed) = match load_checkpoint(&checkpoint_path).await {
+            Some(checkpoint) => {
+                let removed = checkpoint.removed.len();
+                (checkpoint.merge_into(&mut scan_record_state), removed)
             }
-        }
-        let added = checkpoint.records.len();
-        for (path, timestamp) in checkpoint.records {
-            scan_record_state.records.insert(path, timestamp);
-        }
+            None => (0, 0),
+        };
         if let Err(e) = tokio::fs::remove_file(&checkpoint_path).await {
             warn!(
                 "Failed to delete scan record checkpoint after merging: {:?}",
@@ -275,14 +329,24 @@ async fn run_scanner(
             );
         }
         info!(
-            "Merged scan record checkpoint ({} entries, {} total)",
+            "Merged scan record checkpoint ({} entries, {} removed, {} total)",
             added,
+            removed,
             scan_record_state.records.len()
         );
     }
 
     let mut scan_record_slot = Some(scan_record_state);
     let mut pending_start: Option<bool> = None;
+    let mut pending_refresh: Vec<Utf8PathBuf> = Vec::new();
+    let mut record_writer = RecordWriter::new(
+        scan_record_path,
+        checkpoint_path.clone(),
+        scan_settings.record_write_interval(),
+    );
+    if recovered_checkpoint {
+        record_writer.mark_dirty();
+    }
 
     loop {
         let mut scan_record = scan_record_slot
@@ -292,19 +356,45 @@ async fn run_scanner(
             force
         } else {
             loop {
-                match command_rx.recv().await {
-                    Some(ScanCommand::Scan) => break false,
-                    Some(ScanCommand::ForceScan) => break true,
-                    Some(ScanCommand::ResolveMissingFolders(_)) => {}
-                    Some(ScanCommand::UpdateSettings(s)) => {
-                        scan_settings = s;
+                let write_due = record_writer.pending_deadline();
+
+                tokio::select! {
+                    cmd = command_rx.recv() => match cmd {
+                        Some(ScanCommand::Scan) => break false,
+                        Some(ScanCommand::ForceScan) => break true,
+                        Some(ScanCommand::RefreshPaths(paths)) => {
+                            pending_refresh.extend(paths);
+                            break false;
+                        }
+                        Some(ScanCommand::ResolveMissingFolders(_)) => {}
+                        Some(ScanCommand::UpdateSettings(s)) => {
+                            record_writer.set_interval(s.record_write_interval());
+                            scan_settings = s;
+                        }
+                        Some(ScanCommand::Stop) => continue,
+                        Some(ScanCommand::Flush(done)) => {
+                            record_writer.flush(&scan_record).await;
+                            let _ = done.send(());
+                        }
+                        None => {
+                            // channel closed, shut down
+                            record_writer.flush(&scan_record).await;
+                            return;
+                        }
+                    },
+
+                    // write a deferred scan record once its interval has passed
+                    _ = sleep_until(write_due.unwrap_or_else(std::time::Instant::now).into()),
+                        if write_due.is_some() =>
+                    {
+                        record_writer.flush(&scan_record).await;
                     }
-                    Some(ScanCommand::Stop) => continue,
-                    None => return, // channel closed, shut down
                 }
             }
         };
 
+        record_writer.set_interval(scan_settings.record_write_interval());
+
         if scan_record.is_version_mismatch() {
             info!(
                 "Scan record version mismatch (found {}, expected {}), forcing full scan",
@@ -313,7 +403,24 @@ async fn run_scanner(
             is_force = true;
         }
 
+        // the record remembers which art priority its files were read with, so a change made
+        // just before quitting still applies on the next start
+        if scan_record.art_priority != scan_settings.art_priority {
+            info!(
+                "Album art priority changed ({:?} to {:?}), forcing full scan",
+                scan_record.art_priority, scan_settings.art_priority
+            );
+            is_force = true;
+        }
+
         scan_record.version = SCAN_VERSION;
+        scan_record.art_priority = scan_settings.art_priority;
+
+        // forgetting refreshed files makes discovery pick them up again
+        let refreshed: FxHashSet<Utf8PathBuf> = pending_refresh.drain(..).collect();
+        for path in &refreshed {
+            scan_record.records.remove(path);
+        }
 
         info!(
             "Starting scan (force: {}) with settings: {:?}",
@@ -334,6 +441,8 @@ async fn run_scanner(
                 &event_tx,
                 &mut scan_settings,
                 missing_paths.clone(),
+                &scan_record,
+                &mut record_writer,
             )
             .await
         };
@@ -348,11 +457,26 @@ async fn run_scanner(
         let cleanup_start = std::time::Instant::now();
 
         let _ = event_tx.send(ScanEvent::Cleaning);
-
-        let mut updated_playlists =
-            cleanup_removed_directories(&pool, &mut scan_record, &scan_settings.paths).await;
-        updated_playlists
-            .extend(cleanup_with_exclusions(&pool, &mut scan_record, excluded_missing_roots).await);
+        let records_before_cleanup = scan_record.records.len();
+
+        let mut updated_playlists = cleanup_removed_directories(
+            &pool,
+            &mut scan_record,
+            &mut record_writer,
+            &scan_settings.paths,
+            &event_tx,
+        )
+        .await;
+        updated_playlists.extend(
+            cleanup_with_exclusions(
+                &pool,
+                &mut scan_record,
+                &mut record_writer,
+                excluded_missing_roots,
+                &event_tx,
+            )
+            .await,
+        );
         if !updated_playlists.is_empty() {
             let _ = event_tx.send(ScanEvent::PlaylistsUpdated(
                 updated_playlists.into_iter().collect(),
@@ -362,6 +486,17 @@ async fn run_scanner(
         let cleanup_duration = std::time::Instant::now() - cleanup_start;
         info!("Cleanup took {:?}", cleanup_duration);
 
+        if let Err(err) = sync_art_storage(&pool, scan_settings.art_storage).await {
+            error!(?err, "Failed to move album art: {err}");
+        }
+
+        if is_force
+            || scan_record.records.len() != records_before_cleanup
+            || scan_record.directories != scan_settings.paths
+        {
+            record_writer.mark_dirty();
+        }
+
         scan_record.directories = scan_settings.paths.clone();
         let checkpoint_dirs = scan_record.directories.clone();
 
@@ -407,6 +542,10 @@ async fn run_scanner(
         });
 
         let path_rx_shared = Arc::new(Mutex::new(path_rx));
+        let file_read_timeout = scan_settings.file_read_timeout();
+        let generate_thumbnails = scan_settings.generate_thumbnails;
+        let art_storage = scan_settings.art_storage;
+        let art_priority = scan_settings.art_priority;
 
         for _ in 0..num_workers {
             let path_rx = Arc::clone(&path_rx_shared);
@@ -415,6 +554,7 @@ async fn run_scanner(
             let cancel_flag = Arc::clone(&cancel_flag);
             spawn_blocking(move || {
                 let mut art_cache: FxHashMap<Utf8PathBuf, Option<Arc<[u8]>>> = FxHashMap::default();
+                let mut scanner = TimedScanner::new(file_read_timeout);
                 loop {
                     if cancel_flag.load(Ordering::Relaxed) {
                         break;
@@ -432,7 +572,9 @@ async fn run_scanner(
                         break;
                     }
 
-                    if let Some(info) = read_metadata_for_path(&path, &mut art_cache) {
+                    if let Some(info) =
+                        read_metadata_for_path(&path, &mut art_cache, &mut scanner, art_priority)
+                    {
                         if cancel_flag.load(Ordering::Relaxed) {
                             break;
                         }
@@ -470,8 +612,13 @@ async fn run_scanner(
         let mut discovery_complete = false;
         let mut discovered_total: u64 = 0;
         let mut pending_commit: Vec<(Utf8PathBuf, SystemTime)> = Vec::with_capacity(BATCH_SIZE);
+        // start from the changes that haven't been written yet, so that checkpoints written
+        // during this scan don't drop them
         let scan_checkpoint: Arc<Mutex<FxHashMap<Utf8PathBuf, SystemTime>>> =
-            Arc::new(Mutex::new(FxHashMap::default()));
+            Arc::new(Mutex::new(record_writer.unsaved_changes().clone()));
+        // nothing is removed while files are being read, so the removals made by cleanup are
+        // saved with every checkpoint as they are
+        let checkpoint_removed = Arc::new(record_writer.unsaved_removals().clone());
         let mut checkpoint_handle: Option<tokio::task::JoinHandle<()>> = None;
 
         let mut discover_handle = discover_handle;
@@ -493,10 +640,21 @@ async fn run_scanner(
                         Some(ScanCommand::ForceScan) => {
                             pending_start = Some(true);
                         }
+                        Some(ScanCommand::RefreshPaths(paths)) => {
+                            pending_refresh.extend(paths);
+                            pending_start.get_or_insert(false);
+                        }
                         Some(ScanCommand::UpdateSettings(s)) => {
                             scan_settings = s;
                         }
                         Some(ScanCommand::ResolveMissingFolders(_)) => {}
+                        Some(ScanCommand::Flush(done)) => {
+                            // the scan is still running, so write what has been committed so far
+                            let sr = scan_record_shared.lock().await;
+                            record_writer.flush(&sr).await;
+                            record_writer.mark_dirty();
+                            let _ = done.send(());
+                        }
                         None => return,
                     }
                 }
@@ -518,10 +676,11 @@ async fn run_scanner(
                     scan_checkpoint.lock().await.insert(path.clone(), timestamp);
                     let mut sr = scan_record_shared.lock().await;
                     sr.records.insert(path, timestamp);
+                    record_writer.mark_dirty();
                 }
 
                 item = meta_rx.recv() => {
-                    let Some((path, timestamp, (metadata, length, image))) = item else {
+                    let Some((path, timestamp, (metadata, length, image, properties))) = item else {
                         if items_in_tx > 0 {
                             if let Err(e) = tx
                                 .take()
@@ -532,6 +691,12 @@ async fn run_scanner(
                                 error!("Failed to commit final scan transaction: {:?}", e);
                                 pending_commit.clear();
                             } else {
+                                let mut ckpt = scan_checkpoint.lock().await;
+                                for (p, ts) in &pending_commit {
+                                    ckpt.insert(p.clone(), *ts);
+                                }
+                                drop(ckpt);
+
                                 let mut sr = scan_record_shared.lock().await;
                                 for (p, ts) in pending_commit.drain(..) {
                                     sr.records.insert(p, ts);
@@ -548,7 +713,10 @@ async fn run_scanner(
                         &path,
                         length,
                         &image,
-                        is_force,
+                        &properties,
+                        generate_thumbnails,
+                        art_storage,
+                        is_force || refreshed.contains(&path),
                         &mut force_encountered_albums,
                         &mut artist_cache,
                         &mut album_cache,
@@ -599,10 +767,11 @@ async fn run_scanner(
                                 let _ = handle.await;
                             }
                             let checkpoint_arc = Arc::clone(&scan_checkpoint);
+                            let removed = Arc::clone(&checkpoint_removed);
                             let dirs = checkpoint_dirs.clone();
                             let path = checkpoint_path.clone();
                             checkpoint_handle = Some(tokio::spawn(async move {
-                                write_checkpoint(checkpoint_arc, dirs, &path).await;
+                                write_checkpoint(checkpoint_arc, removed, dirs, &path).await;
                             }));
                         }
                         tx = Some(
@@ -640,6 +809,11 @@ async fn run_scanner(
             scan_checkpoint.lock().await.insert(path.clone(), timestamp);
             let mut sr = scan_record_shared.lock().await;
             sr.records.insert(path, timestamp);
+            record_writer.mark_dirty();
+        }
+
+        if scanned > 0 {
+            record_writer.mark_dirty();
         }
 
         let time_end = std::time::Instant::now();
@@ -675,10 +849,12 @@ async fn run_scanner(
             }
             write_checkpoint(
                 Arc::clone(&scan_checkpoint),
+                Arc::clone(&checkpoint_removed),
                 checkpoint_dirs.clone(),
                 &checkpoint_path,
             )
             .await;
+            record_writer.add_changes(take(&mut *scan_checkpoint.lock().await));
 
             scan_record_slot = Some(
                 Arc::try_unwrap(scan_record_shared)
@@ -691,7 +867,7 @@ async fn run_scanner(
         }
 
         info!(
-            "Scan complete, {} files scanned in {} seconds, writing record.",
+            "Scan complete, {} files scanned in {} seconds.",
             scanned,
             duration.as_secs_f32()
         );
@@ -699,6 +875,7 @@ async fn run_scanner(
         if let Some(handle) = checkpoint_handle.take() {
             let _ = handle.await;
         }
+        record_writer.add_changes(take(&mut *scan_checkpoint.lock().await));
 
         scan_record_slot = Some(
             Arc::try_unwrap(scan_record_shared)
@@ -706,20 +883,13 @@ async fn run_scanner(
                 .into_inner(),
         );
 
-        write_scan_record(
-            scan_record_slot
-                .as_ref()
-                .expect("scan record should be restored before writing"),
-            &scan_record_path,
-        )
-        .await;
-
-        // remove checkpoint file after successful write
-        if let Err(e) = tokio::fs::remove_file(&checkpoint_path).await
-            && e.kind() != std::io::ErrorKind::NotFound
-        {
-            warn!("Failed to delete scan record checkpoint: {:?}", e);
-        }
+        record_writer
+            .scan_complete(
+                scan_record_slot
+                    .as_ref()
+                    .expect("scan record should be restored before writing"),
+            )
+            .await;
 
         let _ = event_tx.send(ScanEvent::ScanCompleteIdle);
     }
@@ -5,10 +5,12 @@ use tracing::{debug, warn};
 
 use crate::{
     library::{
+        art::{release_art, write_art},
         scan::decode::process_album_art,
         types::{DATE_PRECISION_FULL_DATE, DATE_PRECISION_YEAR, DATE_PRECISION_YEAR_MONTH},
     },
-    media::metadata::Metadata,
+    media::metadata::{AudioProperties, Metadata},
+    settings::scan::ArtStorage,
 };
 
 async fn insert_artist(
@@ -80,11 +82,70 @@ fn bind_release_date(metadata: &Metadata) -> (Option<String>, Option<i32>) {
     (None, None)
 }
 
+/// Converts a track length in seconds to the value stored in the `duration` column.
+///
+/// SQLite integers are 64-bit, so this only saturates for lengths no real file can have.
+fn bind_duration(length: u64) -> i64 {
+    i64::try_from(length).unwrap_or(i64::MAX)
+}
+
+/// Stores art for an album as files, replacing the files it had before. If `storage` isn't
+/// [`ArtStorage::Files`], or the album has no art, the album stops using its previous files
+/// instead. Files that are no longer used by any album are removed.
+async fn store_album_art_files(
+    conn: &mut SqliteConnection,
+    album_id: i64,
+    storage: ArtStorage,
+    image: Option<&[u8]>,
+    thumb: Option<&[u8]>,
+) -> anyhow::Result<()> {
+    let (old_image_path, old_thumb_path): (Option<String>, Option<String>) = sqlx::query_as(
+        include_str!("../../../queries/scan/get_album_art_paths.sql"),
+    )
+    .bind(album_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let (image_path, thumb_path) = match (storage, image) {
+        (ArtStorage::Files, Some(image)) => {
+            let image_path = write_art(image).await?;
+            let thumb_path = match thumb {
+                Some(thumb) => Some(write_art(thumb).await?),
+                None => None,
+            };
+            (Some(image_path), thumb_path)
+        }
+        _ if old_image_path.is_none() && old_thumb_path.is_none() => return Ok(()),
+        _ => (None, None),
+    };
+
+    let in_database = storage == ArtStorage::Database;
+    sqlx::query(include_str!("../../../queries/scan/set_album_art.sql"))
+        .bind(album_id)
+        .bind(image.filter(|_| in_database))
+        .bind(thumb.filter(|_| in_database))
+        .bind(&image_path)
+        .bind(&thumb_path)
+        .execute(&mut *conn)
+        .await?;
+
+    for old in [old_image_path, old_thumb_path].into_iter().flatten() {
+        if Some(&old) != image_path.as_ref() && Some(&old) != thumb_path.as_ref() {
+            release_art(&mut *conn, &old).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn insert_album(
     conn: &mut SqliteConnection,
     metadata: &Metadata,
     artist_id: Option<i64>,
     image: &Option<Box<[u8]>>,
+    generate_thumbnails: bool,
+    art_storage: ArtStorage,
     is_force: bool,
     force_encountered_albums: &mut FxHashSet<i64>,
     album_cache: &mut FxHashMap<AlbumCacheKey, i64>,
@@ -131,8 +192,8 @@ async fn insert_album(
         (Err(sqlx::Error::RowNotFound), _) | (Ok(_), _) => {
             let (resized_image, thumb) = match image {
                 Some(image) => {
-                    match process_album_art(image) {
-                        Ok((resized, thumb)) => (Some(resized), Some(thumb)),
+                    match process_album_art(image, generate_thumbnails) {
+                        Ok((resized, thumb)) => (Some(resized), thumb),
                         Err(e) => {
                             // if there is a decode error, just ignore it and pretend there is no image
                             warn!("Failed to process album art: {:?}", e);
@@ -144,14 +205,15 @@ async fn insert_album(
             };
 
             let (release_date, date_precision) = bind_release_date(metadata);
+            let in_database = art_storage == ArtStorage::Database;
 
             let result: (i64,) =
                 sqlx::query_as(include_str!("../../../queries/scan/create_album.sql"))
                     .bind(album)
                     .bind(metadata.sort_album.as_ref().unwrap_or(album))
                     .bind(artist_id)
-                    .bind(resized_image.as_deref())
-                    .bind(thumb.as_deref())
+                    .bind(resized_image.as_deref().filter(|_| in_database))
+                    .bind(thumb.as_deref().filter(|_| in_database))
                     .bind(release_date)
                     .bind(date_precision)
                     .bind(&metadata.label)
@@ -162,6 +224,15 @@ async fn insert_album(
                     .fetch_one(&mut *conn)
                     .await?;
 
+            store_album_art_files(
+                conn,
+                result.0,
+                art_storage,
+                resized_image.as_deref(),
+                thumb.as_deref(),
+            )
+            .await?;
+
             album_cache.insert(cache_key, result.0);
             Ok(Some(result.0))
         }
@@ -196,6 +267,7 @@ async fn delete_lyrics(conn: &mut SqliteConnection, track_id: i64) -> anyhow::Re
 async fn insert_track(
     conn: &mut SqliteConnection,
     metadata: &Metadata,
+    properties: &AudioProperties,
     album_id: Option<i64>,
     path: &Utf8Path,
     length: u64,
@@ -257,7 +329,7 @@ async fn insert_track(
             .bind(album_id)
             .bind(metadata.track_current.map(|x| x as i32))
             .bind(metadata.disc_current.map(|x| x as i32))
-            .bind(length as i32)
+            .bind(bind_duration(length))
             .bind(path.as_str())
             .bind(&metadata.genre)
             .bind(&metadata.artist)
@@ -267,6 +339,13 @@ async fn insert_track(
             .bind(metadata.replaygain_album_gain)
             .bind(metadata.replaygain_album_peak)
             .bind(&metadata.disc_subtitle)
+            // only used when the track is first added, so ratings changed in Hummingbird stick
+            .bind(metadata.rating.unwrap_or(0) as i32)
+            .bind(&properties.codec)
+            .bind(properties.bitrate.map(i64::from))
+            .bind(properties.sample_rate.map(i64::from))
+            .bind(properties.bit_depth.map(i64::from))
+            .bind(properties.channels.map(i64::from))
             .fetch_one(&mut *conn)
             .await;
 
@@ -284,6 +363,9 @@ pub async fn update_metadata(
     path: &Utf8Path,
     length: u64,
     image: &Option<Box<[u8]>>,
+    properties: &AudioProperties,
+    generate_thumbnails: bool,
+    art_storage: ArtStorage,
     is_force: bool,
     force_encountered_albums: &mut FxHashSet<i64>,
     artist_cache: &mut FxHashMap<String, i64>,
@@ -314,12 +396,23 @@ pub async fn update_metadata(
         metadata,
         artist_id,
         album_image,
+        generate_thumbnails,
+        art_storage,
         is_force,
         force_encountered_albums,
         album_cache,
     )
     .await?;
-    let track_id = insert_track(conn, metadata, album_id, path, length, album_path_cache).await?;
+    let track_id = insert_track(
+        conn,
+        metadata,
+        properties,
+        album_id,
+        path,
+        length,
+        album_path_cache,
+    )
+    .await?;
 
     if let Some(track_id) = track_id {
         if let Some(lyrics) = &metadata.lyrics {
@@ -334,7 +427,7 @@ pub async fn update_metadata(
 
 #[cfg(test)]
 mod tests {
-    use super::bind_release_date;
+    use super::{bind_duration, bind_release_date};
     use crate::{
         library::types::{
             DATE_PRECISION_FULL_DATE, DATE_PRECISION_YEAR, DATE_PRECISION_YEAR_MONTH,
@@ -387,4 +480,12 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn binds_long_durations() {
+        // a 12 hour mix
+        assert_eq!(bind_duration(12 * 60 * 60), 43_200);
+        assert_eq!(bind_duration(i32::MAX as u64 + 1), i32::MAX as i64 + 1);
+        assert_eq!(bind_duration(u64::MAX), i64::MAX);
+    }
 }
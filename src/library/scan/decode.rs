@@ -1,22 +1,50 @@
 use crate::library::scan::discover::sidecar_lyrics_path;
-use std::{io::Cursor, sync::Arc};
+use std::{
+    io::Cursor,
+    sync::{
+        Arc,
+        mpsc::{Receiver, RecvTimeoutError, Sender, channel},
+    },
+    time::Duration,
+};
 
 use camino::{Utf8Path, Utf8PathBuf};
 use globwalk::GlobWalkerBuilder;
 use image::{DynamicImage, EncodableLayout, codecs::jpeg::JpegEncoder, imageops};
 use rustc_hash::FxHashMap;
+use tracing::warn;
 
-use crate::media::{
-    lookup_table::try_open_media, metadata::Metadata, traits::MediaProviderFeatures,
+use crate::{
+    media::{
+        lookup_table::try_open_media,
+        metadata::{AudioProperties, Metadata},
+        traits::MediaProviderFeatures,
+    },
+    settings::scan::ArtPriority,
 };
 
 /// Information extracted from a media file during the metadata reading stage.
 /// Raw image bytes are passed through the pipeline; image processing (resize + thumbnail) only
 /// happens in `insert_album` when a new album is actually created.
-pub type FileInformation = (Metadata, u64, Option<Box<[u8]>>);
+pub type FileInformation = (Metadata, u64, Option<Box<[u8]>>, AudioProperties);
+
+/// Estimates the average bitrate of a file in kbps from its size and duration. Embedded art is
+/// left out, since it can make up a large part of a small file.
+///
+/// The exact duration is used rather than whole seconds, since the dropped fraction of a second
+/// would noticeably inflate the bitrate of shorter tracks.
+fn average_bitrate(file_size: u64, image_size: u64, duration_ms: u64) -> Option<u32> {
+    if duration_ms == 0 {
+        return None;
+    }
+
+    // bits per millisecond are kilobits per second
+    let audio_size = file_size.saturating_sub(image_size);
+    u32::try_from((audio_size * 8 + duration_ms / 2) / duration_ms).ok()
+}
 
-/// Read metadata, duration, and embedded image from a file using the global provider lookup table.
-/// Returns raw (unprocessed) image bytes.
+/// Read metadata, duration, embedded image and audio properties from a file using the global
+/// provider lookup table. Returns raw (unprocessed) image bytes.
 fn scan_path(path: &Utf8Path) -> Result<FileInformation, ()> {
     let mut stream = try_open_media(
         path.as_std_path(),
@@ -28,8 +56,105 @@ fn scan_path(path: &Utf8Path) -> Result<FileInformation, ()> {
     let metadata = stream.read_metadata().cloned().map_err(|_| ())?;
     let image = stream.read_image().map_err(|_| ())?;
     let len = stream.duration_secs().map_err(|_| ())?;
+    let len_ms = stream.duration_ms().ok();
+    let codec = stream.codec_name().ok().map(str::to_owned);
+    let sample_rate = stream.sample_rate().ok();
+    let bit_depth = stream.bits_per_sample().ok().flatten();
+    // channels() guesses stereo for containers that don't record the channel count, which would be
+    // wrong for mono files, so only the stored count is used
+    let channels = stream.stored_channel_count().ok().flatten().map(u32::from);
     stream.close().map_err(|_| ())?;
-    Ok((metadata, len, image))
+
+    let image_size = image.as_ref().map_or(0, |image| image.len() as u64);
+    let bitrate = std::fs::metadata(path)
+        .ok()
+        .zip(len_ms)
+        .and_then(|(file, len_ms)| average_bitrate(file.len(), image_size, len_ms));
+
+    let properties = AudioProperties {
+        codec,
+        bitrate,
+        sample_rate,
+        bit_depth,
+        channels,
+    };
+
+    Ok((metadata, len, image, properties))
+}
+
+/// Reads file metadata with an optional time limit.
+///
+/// Provider calls block and can't be interrupted, so when a time limit is set, files are read on a
+/// helper thread. If a read times out, the helper is abandoned (it exits once the provider
+/// returns, if ever) and a new one is started for the next file.
+pub struct TimedScanner {
+    timeout: Option<Duration>,
+    helper: Option<ScanHelper>,
+}
+
+struct ScanHelper {
+    path_tx: Sender<Utf8PathBuf>,
+    result_rx: Receiver<Result<FileInformation, ()>>,
+}
+
+impl ScanHelper {
+    fn start() -> Option<Self> {
+        let (path_tx, path_rx) = channel::<Utf8PathBuf>();
+        let (result_tx, result_rx) = channel();
+
+        std::thread::Builder::new()
+            .name("metadata-reader".to_string())
+            .spawn(move || {
+                while let Ok(path) = path_rx.recv() {
+                    if result_tx.send(scan_path(&path)).is_err() {
+                        break;
+                    }
+                }
+            })
+            .inspect_err(|err| warn!(?err, "Could not start metadata reader thread: {err}"))
+            .ok()?;
+
+        Some(Self { path_tx, result_rx })
+    }
+}
+
+impl TimedScanner {
+    /// Creates a scanner that gives up on a file after `timeout`, or never if `None`.
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            helper: None,
+        }
+    }
+
+    fn scan(&mut self, path: &Utf8Path) -> Result<FileInformation, ()> {
+        let Some(timeout) = self.timeout else {
+            return scan_path(path);
+        };
+
+        if self.helper.is_none() {
+            self.helper = ScanHelper::start();
+        }
+        let helper = self.helper.as_ref().ok_or(())?;
+
+        if helper.path_tx.send(path.to_path_buf()).is_err() {
+            self.helper = None;
+            return Err(());
+        }
+
+        match helper.result_rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                warn!("Timed out after {timeout:?} reading metadata for file: {path:?}");
+                self.helper = None;
+                Err(())
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                self.helper = None;
+                Err(())
+            }
+        }
+    }
 }
 
 /// Returns the first image (cover/front/folder.jpeg/png/jpg) in the track's containing folder.
@@ -74,20 +199,41 @@ fn resolve_lyrics(path: &Utf8Path, embedded_lyrics: Option<String>) -> Option<St
 
 /// Process album art into a (resized_full_image, thumbnail_bmp) pair.
 ///
-/// The thumbnail is always a 70×70 BMP. The full-size image is passed through if both dimensions
+/// The thumbnail is a 70×70 BMP. The full-size image is passed through if both dimensions
 /// are ≤ 1024, otherwise it is downscaled to 1024×1024 and re-encoded as JPEG.
-pub fn process_album_art(image: &[u8]) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+///
+/// When `generate_thumbnail` is false no thumbnail is returned, and images that don't need to be
+/// resized are passed through without being decoded at all.
+pub fn process_album_art(
+    image: &[u8],
+    generate_thumbnail: bool,
+) -> anyhow::Result<(Vec<u8>, Option<Vec<u8>>)> {
+    if !generate_thumbnail {
+        let (w, h) = image::ImageReader::new(Cursor::new(image))
+            .with_guessed_format()?
+            .into_dimensions()?;
+
+        if w <= 1024 && h <= 1024 {
+            return Ok((image.to_vec(), None));
+        }
+    }
+
     let decoded = image::ImageReader::new(Cursor::new(image))
         .with_guessed_format()?
         .decode()?
         .into_rgb8();
 
     // thumbnail
-    let thumb_rgb = imageops::thumbnail(&decoded, 70, 70);
-    let thumb_rgba = DynamicImage::ImageRgb8(thumb_rgb).into_rgba8();
+    let thumb_buf = if generate_thumbnail {
+        let thumb_rgb = imageops::thumbnail(&decoded, 70, 70);
+        let thumb_rgba = DynamicImage::ImageRgb8(thumb_rgb).into_rgba8();
 
-    let mut thumb_buf: Vec<u8> = Vec::new();
-    thumb_rgba.write_to(&mut Cursor::new(&mut thumb_buf), image::ImageFormat::Bmp)?;
+        let mut thumb_buf: Vec<u8> = Vec::new();
+        thumb_rgba.write_to(&mut Cursor::new(&mut thumb_buf), image::ImageFormat::Bmp)?;
+        Some(thumb_buf)
+    } else {
+        None
+    };
 
     // full-size image (resized if necessary)
     let resized = if decoded.dimensions().0 <= 1024 && decoded.dimensions().1 <= 1024 {
@@ -122,16 +268,22 @@ pub fn process_album_art(image: &[u8]) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
     Ok((resized, thumb_buf))
 }
 
-/// Read metadata from a file, resolve album art (embedded or from directory).
+/// Read metadata from a file, resolve album art (embedded or from directory). `art_priority`
+/// decides which of the two is used when both are available.
 ///
 /// Each metadata reader thread maintains its own `art_cache` to avoid redundant directory scans
-/// for files in the same folder.
+/// for files in the same folder, and its own `scanner` so that a file which takes too long to read
+/// only holds up that file.
 pub fn read_metadata_for_path(
     path: &Utf8Path,
     art_cache: &mut FxHashMap<Utf8PathBuf, Option<Arc<[u8]>>>,
+    scanner: &mut TimedScanner,
+    art_priority: ArtPriority,
 ) -> Option<FileInformation> {
-    if let Ok(mut metadata) = scan_path(path) {
-        if metadata.2.is_none()
+    if let Ok(mut metadata) = scanner.scan(path) {
+        let prefer_folder = art_priority == ArtPriority::FolderFirst;
+
+        if (metadata.2.is_none() || prefer_folder)
             && let Some(art) = scan_path_for_album_art(path, art_cache)
         {
             metadata.2 = Some(art.to_vec().into_boxed_slice());
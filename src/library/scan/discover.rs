@@ -9,15 +9,24 @@ use std::{
 use camino::{Utf8Path, Utf8PathBuf};
 use rustc_hash::{FxHashMap, FxHashSet};
 use sqlx::SqlitePool;
-use tokio::sync::{Mutex, mpsc::Sender};
+use tokio::sync::{
+    Mutex,
+    mpsc::{Sender, UnboundedSender},
+};
 use tracing::{debug, error, info};
 
 use crate::{
-    library::scan::record::ScanRecord,
+    library::scan::{
+        BATCH_SIZE, ScanEvent,
+        record::{RecordWriter, ScanRecord},
+    },
     media::{lookup_table::can_be_read, traits::MediaProviderFeatures},
     settings::scan::ScanSettings,
 };
 
+/// Number of recorded files to check for removal between cleanup progress updates.
+const CLEANUP_PROGRESS_INTERVAL: usize = 500;
+
 pub fn sidecar_lyrics_path(path: &Utf8Path) -> Option<Utf8PathBuf> {
     let stem = path.file_stem()?;
     let parent = path.parent()?;
@@ -80,9 +89,10 @@ fn file_is_scannable(
 pub async fn cleanup_removed_directories(
     pool: &SqlitePool,
     scan_record: &mut ScanRecord,
+    record_writer: &mut RecordWriter,
     current_directories: &[Utf8PathBuf],
+    event_tx: &UnboundedSender<ScanEvent>,
 ) -> FxHashSet<i64> {
-    let mut updated_playlists: FxHashSet<i64> = FxHashSet::default();
     let current_set: FxHashSet<Utf8PathBuf> = current_directories.iter().cloned().collect();
     let old_set: FxHashSet<Utf8PathBuf> = scan_record.directories.iter().cloned().collect();
 
@@ -93,7 +103,7 @@ pub async fn cleanup_removed_directories(
         .collect();
 
     if removed_dirs.is_empty() {
-        return updated_playlists;
+        return FxHashSet::default();
     }
 
     info!(
@@ -101,14 +111,6 @@ pub async fn cleanup_removed_directories(
         removed_dirs.len()
     );
 
-    let mut tx = match pool.begin().await {
-        Ok(tx) => tx,
-        Err(e) => {
-            error!("Could not begin directory cleanup transaction: {:?}", e);
-            return updated_playlists;
-        }
-    };
-
     let to_remove: Vec<Utf8PathBuf> = scan_record
         .records
         .keys()
@@ -120,29 +122,89 @@ pub async fn cleanup_removed_directories(
         .cloned()
         .collect();
 
-    let mut deleted: Vec<Utf8PathBuf> = Vec::with_capacity(to_remove.len());
-    for path in &to_remove {
-        debug!("removing track from removed directory: {:?}", path);
-        if cleanup_track(&mut tx, path, &mut updated_playlists).await {
-            deleted.push(path.clone());
-        }
-    }
+    // nothing needs to be checked on disk, only removed
+    let total = scan_record.records.len() as u64;
+    let (deleted, updated_playlists) = delete_tracks(
+        pool,
+        scan_record,
+        record_writer,
+        &to_remove,
+        (total, total),
+        event_tx,
+    )
+    .await;
 
-    if let Err(e) = tx.commit().await {
-        error!("Failed to commit directory cleanup transaction: {:?}", e);
-        return FxHashSet::default();
-    }
+    info!("Cleaned up {} track(s) from removed directories", deleted);
+
+    updated_playlists
+}
 
-    for path in &deleted {
-        scan_record.records.remove(path);
+/// Deletes the given tracks from the database, committing every [`BATCH_SIZE`] tracks and
+/// removing each committed batch from the scan record. Removals are also given to
+/// `record_writer`, so they're saved in checkpoints. A batch that fails to commit is left in place
+/// so that it can be retried on the next scan.
+///
+/// `checked` is the `(checked, total)` file count reported alongside deletion progress. Returns the
+/// number of tracks deleted and the playlists that contained them.
+async fn delete_tracks(
+    pool: &SqlitePool,
+    scan_record: &mut ScanRecord,
+    record_writer: &mut RecordWriter,
+    paths: &[Utf8PathBuf],
+    checked: (u64, u64),
+    event_tx: &UnboundedSender<ScanEvent>,
+) -> (u64, FxHashSet<i64>) {
+    let mut updated_playlists: FxHashSet<i64> = FxHashSet::default();
+    let mut removed: u64 = 0;
+    let to_remove = paths.len() as u64;
+
+    let send_progress = |removed: u64| {
+        let _ = event_tx.send(ScanEvent::CleaningProgress {
+            checked: checked.0,
+            total: checked.1,
+            removed,
+            to_remove,
+        });
+    };
+
+    if !paths.is_empty() {
+        send_progress(0);
     }
 
-    info!(
-        "Cleaned up {} track(s) from removed directories",
-        deleted.len()
-    );
+    for batch in paths.chunks(BATCH_SIZE) {
+        let mut tx = match pool.begin().await {
+            Ok(tx) => tx,
+            Err(e) => {
+                error!("Could not begin cleanup transaction: {:?}", e);
+                break;
+            }
+        };
 
-    updated_playlists
+        let mut batch_playlists: FxHashSet<i64> = FxHashSet::default();
+        let mut deleted: Vec<&Utf8PathBuf> = Vec::with_capacity(batch.len());
+        for path in batch {
+            debug!("removing track from library: {:?}", path);
+            if cleanup_track(&mut tx, path, &mut batch_playlists).await {
+                deleted.push(path);
+            }
+        }
+
+        if let Err(e) = tx.commit().await {
+            error!("Failed to commit cleanup transaction: {:?}", e);
+            continue;
+        }
+
+        for path in &deleted {
+            scan_record.records.remove(*path);
+            removed += 1;
+        }
+        record_writer.add_removals(deleted.into_iter().cloned());
+        updated_playlists.extend(batch_playlists);
+
+        send_progress(removed);
+    }
+
+    (removed, updated_playlists)
 }
 
 async fn cleanup_track(
@@ -196,6 +258,21 @@ async fn cleanup_track(
         return false;
     }
 
+    let history_result = sqlx::query(include_str!(
+        "../../../queries/scan/delete_play_history_for_track.sql"
+    ))
+    .bind(path.as_str())
+    .execute(&mut **tx)
+    .await;
+
+    if let Err(e) = history_result {
+        error!(
+            "Database error while deleting play history for track: {:?}",
+            e
+        );
+        return false;
+    }
+
     let track_result = sqlx::query(include_str!("../../../queries/scan/delete_track.sql"))
         .bind(path.as_str())
         .execute(&mut **tx)
@@ -214,54 +291,51 @@ async fn cleanup_track(
 pub async fn cleanup_with_exclusions(
     pool: &SqlitePool,
     scan_record: &mut ScanRecord,
+    record_writer: &mut RecordWriter,
     excluded_roots: &[Utf8PathBuf],
+    event_tx: &UnboundedSender<ScanEvent>,
 ) -> FxHashSet<i64> {
-    let mut updated_playlists: FxHashSet<i64> = FxHashSet::default();
-
     let canonicalized_roots: Vec<Utf8PathBuf> = excluded_roots
         .iter()
         .map(|root| root.canonicalize_utf8().unwrap_or(root.clone()))
         .collect();
 
-    let to_delete: Vec<Utf8PathBuf> = scan_record
-        .records
-        .keys()
-        .filter(|path| {
-            !(path.exists())
-                && !canonicalized_roots
-                    .iter()
-                    .any(|excluded_root| path.starts_with(excluded_root))
-        })
-        .cloned()
-        .collect();
-
-    let mut tx = match pool.begin().await {
-        Ok(tx) => tx,
-        Err(e) => {
-            error!("Could not begin cleanup transaction: {:?}", e);
-            return updated_playlists;
+    let total = scan_record.records.len() as u64;
+    let mut to_delete: Vec<Utf8PathBuf> = Vec::new();
+
+    for (checked, path) in scan_record.records.keys().enumerate() {
+        if checked % CLEANUP_PROGRESS_INTERVAL == 0 {
+            let _ = event_tx.send(ScanEvent::CleaningProgress {
+                checked: checked as u64,
+                total,
+                removed: 0,
+                to_remove: to_delete.len() as u64,
+            });
         }
-    };
 
-    let mut deleted: Vec<Utf8PathBuf> = Vec::with_capacity(to_delete.len());
-    for path in &to_delete {
-        debug!("track deleted or moved: {:?}", path);
-        if cleanup_track(&mut tx, path, &mut updated_playlists).await {
-            deleted.push(path.clone());
+        if !(path.exists())
+            && !canonicalized_roots
+                .iter()
+                .any(|excluded_root| path.starts_with(excluded_root))
+        {
+            debug!("track deleted or moved: {:?}", path);
+            to_delete.push(path.clone());
         }
     }
 
-    if let Err(e) = tx.commit().await {
-        error!("Failed to commit cleanup transaction: {:?}", e);
-        return FxHashSet::default();
-    }
-
-    for path in &deleted {
-        scan_record.records.remove(path);
-    }
+    let (_, updated_playlists) = delete_tracks(
+        pool,
+        scan_record,
+        record_writer,
+        &to_delete,
+        (total, total),
+        event_tx,
+    )
+    .await;
 
     updated_playlists
 }
+
 /// Performs a full recursive directory walk, streaming discovered file paths through `path_tx`
 /// as they are found so that downstream pipeline stages can begin processing immediately.
 ///
@@ -1,25 +1,55 @@
-use std::{io::ErrorKind, path::Path, sync::Arc, time::SystemTime};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
 
 use async_compression::tokio::bufread::ZlibDecoder;
 use async_compression::tokio::write::ZlibEncoder;
 use camino::Utf8PathBuf;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt, BufReader},
     sync::Mutex,
 };
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+use crate::settings::scan::ArtPriority;
 
 /// The version of the scanning process. If this version number is incremented, a re-scan of all
 /// files will be forced (see [ScanCommand::ForceScan]).
-pub const SCAN_VERSION: u16 = 2;
+pub const SCAN_VERSION: u16 = 4;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanRecord {
     pub version: u16,
     pub records: FxHashMap<Utf8PathBuf, SystemTime>,
     pub directories: Vec<Utf8PathBuf>,
+    /// The album art priority the recorded files were read with. If the setting no longer
+    /// matches, every file has to be read again.
+    pub art_priority: ArtPriority,
+}
+
+/// A scan record written before the art priority was recorded. These were all read with the
+/// default priority.
+#[derive(Deserialize)]
+struct LegacyScanRecord {
+    version: u16,
+    records: FxHashMap<Utf8PathBuf, SystemTime>,
+    directories: Vec<Utf8PathBuf>,
+}
+
+impl From<LegacyScanRecord> for ScanRecord {
+    fn from(legacy: LegacyScanRecord) -> Self {
+        Self {
+            version: legacy.version,
+            records: legacy.records,
+            directories: legacy.directories,
+            art_priority: ArtPriority::default(),
+        }
+    }
 }
 
 impl ScanRecord {
@@ -28,6 +58,7 @@ impl ScanRecord {
             version: SCAN_VERSION,
             records: FxHashMap::default(),
             directories: Vec::new(),
+            art_priority: ArtPriority::default(),
         }
     }
 
@@ -56,7 +87,13 @@ pub async fn load_scan_record(path: &Path) -> ScanRecord {
     let mut bytes = Vec::new();
     file.read_to_end(&mut bytes).await.unwrap_or_default();
 
-    match postcard::from_bytes(&bytes) {
+    let scan_record = postcard::from_bytes::<ScanRecord>(&bytes).or_else(|e| {
+        postcard::from_bytes::<LegacyScanRecord>(&bytes)
+            .map(ScanRecord::from)
+            .map_err(|_| e)
+    });
+
+    match scan_record {
         Ok(scan_record) => scan_record,
         Err(e) => {
             error!("Could not read scan record: {:?}", e);
@@ -66,15 +103,74 @@ pub async fn load_scan_record(path: &Path) -> ScanRecord {
     }
 }
 
+/// The changes made to the scan record since it was last written. While a write is deferred or a
+/// scan is running, these are saved as a checkpoint and merged back into the record on the next
+/// start, so that a crash doesn't lose them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    pub version: u16,
+    pub records: FxHashMap<Utf8PathBuf, SystemTime>,
+    pub directories: Vec<Utf8PathBuf>,
+    /// Files that were removed from the library, and so from the record.
+    pub removed: FxHashSet<Utf8PathBuf>,
+}
+
+impl ScanCheckpoint {
+    /// Applies the checkpoint's changes to `record`. Returns the number of entries added.
+    pub fn merge_into(self, record: &mut ScanRecord) -> usize {
+        for dir in self.directories {
+            if !record.directories.contains(&dir) {
+                record.directories.push(dir);
+            }
+        }
+
+        for path in &self.removed {
+            record.records.remove(path);
+        }
+
+        let added = self.records.len();
+        record.records.extend(self.records);
+
+        added
+    }
+}
+
+/// Reads the checkpoint left behind by a previous run, if there is one.
+pub async fn load_checkpoint(path: &Path) -> Option<ScanCheckpoint> {
+    let mut file = match tokio::fs::File::open(path)
+        .await
+        .map(BufReader::new)
+        .map(ZlibDecoder::new)
+    {
+        Ok(f) => f,
+        Err(e) => {
+            if e.kind() != ErrorKind::NotFound {
+                error!("Could not open scan record checkpoint: {:?}", e);
+            }
+
+            return None;
+        }
+    };
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).await.unwrap_or_default();
+
+    postcard::from_bytes(&bytes)
+        .inspect_err(|e| error!("Could not read scan record checkpoint: {:?}", e))
+        .ok()
+}
+
 #[derive(Serialize)]
-struct ScanRecordForWrite<'a> {
+struct ScanCheckpointForWrite<'a> {
     version: u16,
     records: &'a FxHashMap<Utf8PathBuf, SystemTime>,
     directories: &'a [Utf8PathBuf],
+    removed: &'a FxHashSet<Utf8PathBuf>,
 }
 
 pub async fn write_checkpoint(
     checkpoint: Arc<Mutex<FxHashMap<Utf8PathBuf, SystemTime>>>,
+    removed: Arc<FxHashSet<Utf8PathBuf>>,
     directories: Vec<Utf8PathBuf>,
     path: &Path,
 ) {
@@ -82,10 +178,11 @@ pub async fn write_checkpoint(
 
     let serialized = {
         let guard = checkpoint.lock().await;
-        let view = ScanRecordForWrite {
+        let view = ScanCheckpointForWrite {
             version: SCAN_VERSION,
             records: &guard,
             directories: &directories,
+            removed: &removed,
         };
         postcard::to_allocvec(&view)
     };
@@ -128,7 +225,8 @@ pub async fn write_checkpoint(
     }
 }
 
-pub async fn write_scan_record(scan_record: &ScanRecord, path: &Path) {
+/// Returns whether the record was written successfully.
+pub async fn write_scan_record(scan_record: &ScanRecord, path: &Path) -> bool {
     let tmp_path = path.with_extension("hsr.tmp");
 
     let mut file = match tokio::fs::File::create(&tmp_path)
@@ -139,7 +237,7 @@ pub async fn write_scan_record(scan_record: &ScanRecord, path: &Path) {
         Err(e) => {
             error!("Could not create temporary scan record file: {:?}", e);
             error!("Scan record will not be saved, this may cause rescans on restart");
-            return;
+            return false;
         }
     };
 
@@ -149,29 +247,163 @@ pub async fn write_scan_record(scan_record: &ScanRecord, path: &Path) {
                 error!("Could not write scan record: {:?}", e);
                 error!("Scan record will not be saved, this may cause rescans on restart");
                 let _ = tokio::fs::remove_file(&tmp_path).await;
-                return;
+                return false;
             }
 
             if let Err(e) = file.shutdown().await {
                 error!("Could not close scan record: {:?}", e);
                 error!("Scan record will not be saved, this may cause rescans on restart");
                 let _ = tokio::fs::remove_file(&tmp_path).await;
-                return;
+                return false;
             }
 
             if let Err(e) = tokio::fs::rename(&tmp_path, path).await {
                 error!("Could not rename scan record into place: {:?}", e);
                 error!("Scan record will not be saved, this may cause rescans on restart");
                 let _ = tokio::fs::remove_file(&tmp_path).await;
-                return;
+                return false;
             }
 
             info!("Scan record saved successfully");
+            true
         }
         Err(e) => {
             error!("Could not serialize scan record: {:?}", e);
             error!("Scan record will not be saved, this may cause rescans on restart");
             let _ = tokio::fs::remove_file(&tmp_path).await;
+            false
+        }
+    }
+}
+
+/// Decides when the scan record is written to disk.
+///
+/// Without a write interval, the record is written after every scan that changed it. With an
+/// interval, writes are spaced at least that far apart and the record is kept in memory in
+/// between; the scanner writes it once the interval has passed, or when asked to flush on
+/// shutdown. Scans that don't change the record never write it.
+///
+/// While a write is deferred, only the entries added and removed since the last write are saved,
+/// as a checkpoint that is merged back into the record on the next start.
+pub struct RecordWriter {
+    path: PathBuf,
+    checkpoint_path: PathBuf,
+    interval: Option<Duration>,
+    dirty: bool,
+    last_write: Option<Instant>,
+    changes: FxHashMap<Utf8PathBuf, SystemTime>,
+    removed: FxHashSet<Utf8PathBuf>,
+}
+
+impl RecordWriter {
+    pub fn new(path: PathBuf, checkpoint_path: PathBuf, interval: Option<Duration>) -> Self {
+        Self {
+            path,
+            checkpoint_path,
+            interval,
+            dirty: false,
+            last_write: None,
+            changes: FxHashMap::default(),
+            removed: FxHashSet::default(),
+        }
+    }
+
+    pub fn set_interval(&mut self, interval: Option<Duration>) {
+        self.interval = interval;
+    }
+
+    /// Marks the in-memory record as having changes that haven't been written yet.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Returns the entries added to the record since it was last written.
+    pub fn unsaved_changes(&self) -> &FxHashMap<Utf8PathBuf, SystemTime> {
+        &self.changes
+    }
+
+    /// Returns the entries removed from the record since it was last written.
+    pub fn unsaved_removals(&self) -> &FxHashSet<Utf8PathBuf> {
+        &self.removed
+    }
+
+    /// Remembers entries added to the record by a scan, so that they can be saved incrementally
+    /// until the whole record is written.
+    pub fn add_changes(&mut self, changes: FxHashMap<Utf8PathBuf, SystemTime>) {
+        for path in changes.keys() {
+            self.removed.remove(path);
+        }
+
+        self.changes.extend(changes);
+    }
+
+    /// Remembers entries removed from the record, the same way as [`RecordWriter::add_changes`].
+    pub fn add_removals(&mut self, removed: impl IntoIterator<Item = Utf8PathBuf>) {
+        for path in removed {
+            self.changes.remove(&path);
+            self.removed.insert(path);
+        }
+    }
+
+    /// Returns when the pending write is due, or `None` if there is nothing to write.
+    pub fn pending_deadline(&self) -> Option<Instant> {
+        if !self.dirty {
+            return None;
+        }
+
+        match (self.interval, self.last_write) {
+            (Some(interval), Some(last_write)) => Some(last_write + interval),
+            _ => Some(Instant::now()),
+        }
+    }
+
+    /// Called when a scan has finished. Writes the record if the write interval has passed,
+    /// otherwise leaves it to be written later.
+    pub async fn scan_complete(&mut self, record: &ScanRecord) {
+        match self.pending_deadline() {
+            Some(deadline) if deadline <= Instant::now() => self.flush(record).await,
+            Some(deadline) => {
+                info!(
+                    "Scan record write deferred for {:?}, saving {} changed and {} removed entries",
+                    deadline.saturating_duration_since(Instant::now()),
+                    self.changes.len(),
+                    self.removed.len()
+                );
+
+                write_checkpoint(
+                    Arc::new(Mutex::new(self.changes.clone())),
+                    Arc::new(self.removed.clone()),
+                    record.directories.clone(),
+                    &self.checkpoint_path,
+                )
+                .await;
+            }
+            None => remove_checkpoint(&self.checkpoint_path).await,
         }
     }
+
+    /// Writes the record if it has unsaved changes.
+    pub async fn flush(&mut self, record: &ScanRecord) {
+        if !self.dirty {
+            return;
+        }
+
+        if write_scan_record(record, &self.path).await {
+            self.dirty = false;
+            self.last_write = Some(Instant::now());
+            self.changes.clear();
+            self.removed.clear();
+
+            // the checkpoint only holds entries that are now in the record
+            remove_checkpoint(&self.checkpoint_path).await;
+        }
+    }
+}
+
+async fn remove_checkpoint(path: &Path) {
+    if let Err(e) = tokio::fs::remove_file(path).await
+        && e.kind() != ErrorKind::NotFound
+    {
+        warn!("Failed to delete scan record checkpoint: {:?}", e);
+    }
 }
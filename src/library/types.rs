@@ -3,7 +3,7 @@ pub mod table;
 
 use std::{path::PathBuf, sync::Arc};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use gpui::{IntoElement, RenderImage, SharedString};
 use image::{Frame, RgbaImage};
 use smallvec::SmallVec;
@@ -188,6 +188,9 @@ pub const DATE_PRECISION_YEAR: i32 = 0;
 pub const DATE_PRECISION_FULL_DATE: i32 = 1;
 pub const DATE_PRECISION_YEAR_MONTH: i32 = 2;
 
+/// The highest star rating a track or album can be given.
+pub const MAX_RATING: i32 = 5;
+
 #[derive(sqlx::FromRow, Clone)]
 pub struct Album {
     pub id: i64,
@@ -218,6 +221,12 @@ pub struct Album {
     /// Whether this album uses vinyl-style track numbering (A1, A2, B1, B2, etc.)
     /// When true, disc numbers should be displayed as "SIDE A", "SIDE B", etc.
     pub vinyl_numbering: bool,
+    #[sqlx(default)]
+    /// Star rating from 1 to [`MAX_RATING`], or 0 if the album hasn't been rated.
+    pub rating: i32,
+    /// The name of the album's artist. Only loaded with the album's metadata.
+    #[sqlx(default)]
+    pub artist_name: Option<DBString>,
 }
 
 #[derive(sqlx::FromRow, Clone, Debug)]
@@ -250,6 +259,23 @@ pub struct Track {
     pub rg_album_peak: Option<f64>,
     #[sqlx(default)]
     pub disc_subtitle: Option<DBString>,
+    #[sqlx(default)]
+    pub skip_on_shuffle: bool,
+    #[sqlx(default)]
+    /// Star rating from 1 to [`MAX_RATING`], or 0 if the track hasn't been rated.
+    pub rating: i32,
+    /// Short name of the codec, such as `flac` or `mp3`.
+    #[sqlx(default)]
+    pub codec: Option<DBString>,
+    /// Average bitrate in kbps.
+    #[sqlx(default)]
+    pub bitrate: Option<i64>,
+    /// How many times the track has been played. Only loaded when a single track is looked up.
+    #[sqlx(default)]
+    pub play_count: i64,
+    /// When the track was last played. Only loaded when a single track is looked up.
+    #[sqlx(default)]
+    pub last_played: Option<DateTime<Utc>>,
 }
 
 #[derive(sqlx::Type, Clone, Copy, Debug, PartialEq)]
@@ -299,6 +325,50 @@ pub struct PlaylistItem {
     pub position: i64,
 }
 
+/// A single play of a track, as recorded in the play history.
+#[derive(sqlx::FromRow, Clone, Debug)]
+pub struct PlayHistoryEntry {
+    pub history_id: i64,
+    pub played_at: DateTime<Utc>,
+    #[sqlx(flatten)]
+    pub track: Track,
+}
+
+/// The number of plays over a period, and the combined length of the tracks played. Plays count
+/// the whole track, however much of it was listened to.
+#[derive(sqlx::FromRow, Clone, Debug)]
+pub struct ListeningTotals {
+    pub play_count: i64,
+    pub total_duration: i64,
+}
+
+/// A track, album or artist ranked by how often it was played.
+#[derive(sqlx::FromRow, Clone, Debug)]
+pub struct TopPlayed {
+    pub id: i64,
+    pub name: DBString,
+    pub subtitle: Option<DBString>,
+    pub play_count: i64,
+}
+
+/// Listening activity on a single (local) day.
+#[derive(sqlx::FromRow, Clone, Debug)]
+pub struct DailyPlays {
+    pub day: NaiveDate,
+    pub play_count: i64,
+    pub total_duration: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ListeningStats {
+    pub totals: ListeningTotals,
+    pub top_tracks: Vec<TopPlayed>,
+    pub top_albums: Vec<TopPlayed>,
+    pub top_artists: Vec<TopPlayed>,
+    /// Days without any plays are left out.
+    pub daily: Vec<DailyPlays>,
+}
+
 #[derive(sqlx::FromRow, Clone)]
 pub struct TrackStats {
     pub track_count: i64,
@@ -1,17 +1,21 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use chrono::{DateTime, NaiveDate, Utc};
-use cntp_i18n::{Date, I18N_MANAGER, StringModifier, tr};
+use cntp_i18n::tr;
 use gpui::{App, SharedString};
 use indexmap::IndexMap;
 use rustc_hash::FxBuildHasher;
 
 use super::{
     Album, ArtistWithCounts, DATE_PRECISION_FULL_DATE, DATE_PRECISION_YEAR,
-    DATE_PRECISION_YEAR_MONTH, DBString, Track,
+    DATE_PRECISION_YEAR_MONTH, DBString, MAX_RATING, Track,
 };
 use crate::{
-    library::db::{AlbumMethod, AlbumSortMethod, ArtistSortMethod, LibraryAccess, TrackSortMethod},
+    library::db::{
+        AlbumMethod, AlbumSortMethod, ArtistSortMethod, LibraryAccess, LibraryFilter,
+        TrackSortMethod,
+    },
+    settings::SettingsGlobal,
     ui::{
         availability::{
             album_has_available_tracks, artist_has_available_tracks, is_track_available,
@@ -19,8 +23,11 @@ use crate::{
         components::{
             drag_drop::{AlbumDragData, TrackDragData},
             managed_image::ManagedImageKey,
-            table::table_data::{Column, GridContext, TableData, TableDragData, TableSort},
+            table::table_data::{
+                Column, FilterOptions, GridContext, TableData, TableDragData, TableSort,
+            },
         },
+        format::{format_badge, format_date, format_duration, format_number, format_relative_date},
         library::context_menus::{
             AlbumContextMenuContext, TrackContextMenuContext, album_menu_for_table,
             play_album_next, play_track_next, track_menu_for_table,
@@ -51,11 +58,7 @@ fn format_album_release_date_with(
     length: &'static str,
 ) -> Option<SharedString> {
     let release_date = parse_album_release_date(release_date?)?;
-    let format_var = (None, format);
-    let length_var = (Some("length"), length);
-    let variables = [&format_var, &length_var];
-    let locale = &I18N_MANAGER.read().unwrap().locale;
-    Some(Date.transform(locale, &release_date, &variables).into())
+    Some(format_date(&release_date, format, length))
 }
 
 fn format_album_release_date(
@@ -66,6 +69,25 @@ fn format_album_release_date(
     format_album_release_date_with(release_date, format, length)
 }
 
+/// Formats a star rating as a row of filled and empty stars. Unrated items have no text.
+pub fn format_rating(rating: i32) -> Option<SharedString> {
+    let filled = rating.clamp(0, MAX_RATING) as usize;
+    if filled == 0 {
+        return None;
+    }
+
+    let empty = MAX_RATING as usize - filled;
+    Some(format!("{}{}", "★".repeat(filled), "☆".repeat(empty)).into())
+}
+
+/// The genres and decades found in the library, which the albums and tracks can be filtered by.
+fn library_filter_options(cx: &mut App) -> Option<FilterOptions> {
+    Some(FilterOptions {
+        genres: cx.list_genres().unwrap_or_default(),
+        decades: cx.list_release_decades().unwrap_or_default(),
+    })
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub enum AlbumColumn {
     Title,
@@ -73,6 +95,7 @@ pub enum AlbumColumn {
     Date,
     Label,
     CatalogNumber,
+    Rating,
 }
 
 impl Column for AlbumColumn {
@@ -83,6 +106,7 @@ impl Column for AlbumColumn {
             AlbumColumn::Date => tr!("COLUMN_DATE", "Date").into(),
             AlbumColumn::Label => tr!("COLUMN_LABEL", "Label").into(),
             AlbumColumn::CatalogNumber => tr!("COLUMN_CATALOG_NUMBER", "Catalog Number").into(),
+            AlbumColumn::Rating => tr!("COLUMN_RATING", "Rating").into(),
         }
     }
 
@@ -97,6 +121,7 @@ impl Column for AlbumColumn {
             AlbumColumn::Date,
             AlbumColumn::Label,
             AlbumColumn::CatalogNumber,
+            AlbumColumn::Rating,
         ]
     }
 }
@@ -112,6 +137,7 @@ impl TableData<AlbumColumn> for Album {
     fn get_rows(
         cx: &mut gpui::App,
         sort: Option<TableSort<AlbumColumn>>,
+        filter: &LibraryFilter,
     ) -> anyhow::Result<Vec<Self::Identifier>> {
         let sort_method = match sort {
             Some(TableSort {
@@ -154,28 +180,57 @@ impl TableData<AlbumColumn> for Album {
                 column: AlbumColumn::CatalogNumber,
                 ascending: false,
             }) => AlbumSortMethod::CatalogDesc,
+            Some(TableSort {
+                column: AlbumColumn::Rating,
+                ascending: true,
+            }) => AlbumSortMethod::RatingAsc,
+            Some(TableSort {
+                column: AlbumColumn::Rating,
+                ascending: false,
+            }) => AlbumSortMethod::RatingDesc,
             _ => AlbumSortMethod::ArtistAsc,
         };
 
-        Ok(cx.list_albums(sort_method)?)
+        Ok(cx.list_albums(sort_method, filter)?)
+    }
+
+    fn get_filter_options(cx: &mut App) -> Option<FilterOptions> {
+        library_filter_options(cx)
+    }
+
+    fn get_sort_text(
+        _cx: &mut App,
+        sort: Option<TableSort<AlbumColumn>>,
+        rows: &[Self::Identifier],
+    ) -> Option<Vec<String>> {
+        match sort {
+            Some(TableSort {
+                column: AlbumColumn::Title,
+                ..
+            }) => Some(rows.iter().map(|row| row.1.clone()).collect()),
+            _ => None,
+        }
     }
 
     fn get_row(cx: &mut gpui::App, id: Self::Identifier) -> anyhow::Result<Option<Arc<Self>>> {
         Ok(cx.get_album_by_id(id.0 as i64, AlbumMethod::Metadata).ok())
     }
 
-    fn get_column(&self, cx: &mut App, column: AlbumColumn) -> Option<SharedString> {
+    fn get_column(&self, _cx: &mut App, column: AlbumColumn) -> Option<SharedString> {
         match column {
             AlbumColumn::Title => Some(self.title.0.clone()),
-            AlbumColumn::Artist => cx
-                .get_artist_name_by_id(self.artist_id)
-                .ok()
-                .map(|v| (*v).clone().into()),
+            AlbumColumn::Artist => Some(
+                self.artist_name
+                    .as_ref()
+                    .map(|name| name.0.clone())
+                    .unwrap_or_else(|| tr!("UNKNOWN_ARTIST").into()),
+            ),
             AlbumColumn::Date => {
                 format_album_release_date(self.release_date.as_ref(), self.date_precision)
             }
             AlbumColumn::Label => self.label.as_ref().map(|v| v.0.clone()),
             AlbumColumn::CatalogNumber => self.catalog_number.as_ref().map(|v| v.0.clone()),
+            AlbumColumn::Rating => format_rating(self.rating),
         }
     }
 
@@ -206,15 +261,20 @@ impl TableData<AlbumColumn> for Album {
     fn default_columns() -> IndexMap<AlbumColumn, f32, FxBuildHasher> {
         let s = FxBuildHasher;
         let mut columns: IndexMap<AlbumColumn, f32, FxBuildHasher> = IndexMap::with_hasher(s);
-        columns.insert(AlbumColumn::Title, 300.0);
-        columns.insert(AlbumColumn::Artist, 200.0);
+        columns.insert(AlbumColumn::Title, 250.0);
+        columns.insert(AlbumColumn::Artist, 175.0);
         columns.insert(AlbumColumn::Date, 125.0);
-        columns.insert(AlbumColumn::Label, 150.0);
+        columns.insert(AlbumColumn::Label, 125.0);
         // length is weird because the image column is 47.0
-        columns.insert(AlbumColumn::CatalogNumber, 178.0);
+        columns.insert(AlbumColumn::CatalogNumber, 153.0);
+        columns.insert(AlbumColumn::Rating, 125.0);
         columns
     }
 
+    fn get_album_id(&self) -> Option<i64> {
+        Some(self.id)
+    }
+
     fn get_drag_data(&self) -> Option<TableDragData> {
         Some(TableDragData::Album(AlbumDragData::new(
             self.id,
@@ -245,12 +305,9 @@ impl TableData<AlbumColumn> for Album {
         true
     }
 
-    fn get_grid_content(&self, cx: &mut App) -> Option<(SharedString, Option<SharedString>)> {
+    fn get_grid_content(&self, _cx: &mut App) -> Option<(SharedString, Option<SharedString>)> {
         let title = self.title.0.clone();
-        let artist = cx
-            .get_artist_name_by_id(self.artist_id)
-            .ok()
-            .map(|v| (*v).clone().into());
+        let artist = self.artist_name.as_ref().map(|name| name.0.clone());
         Some((title, artist))
     }
 
@@ -259,23 +316,36 @@ impl TableData<AlbumColumn> for Album {
         cx: &mut App,
         context: GridContext,
     ) -> Option<(SharedString, Option<SharedString>)> {
-        let title = self.title.0.clone();
+        let metadata = cx
+            .global::<SettingsGlobal>()
+            .model
+            .read(cx)
+            .interface
+            .grid_metadata;
+
+        if !metadata.shows_title() {
+            return None;
+        }
 
-        let artist_part: Option<String> = match context {
-            GridContext::Table => cx
-                .get_artist_name_by_id(self.artist_id)
-                .ok()
-                .map(|v| (*v).to_string()),
-            GridContext::Standalone => None,
-        };
+        let title = self.title.0.clone();
 
-        let secondary = match artist_part {
-            Some(artist) => {
-                format_album_release_date_with(self.release_date.as_ref(), "Y", "medium")
-                    .map(|year| format!("{artist} • {year}").into())
-                    .or(Some(SharedString::from(artist)))
-            }
-            None => format_album_release_date(self.release_date.as_ref(), self.date_precision),
+        // on artist pages every album is by the same artist
+        let artist = (metadata.shows_artist() && context == GridContext::Table).then(|| {
+            self.artist_name
+                .as_ref()
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| tr!("UNKNOWN_ARTIST").to_string())
+        });
+        let year = metadata
+            .shows_year()
+            .then(|| format_album_release_date_with(self.release_date.as_ref(), "Y", "medium"))
+            .flatten();
+
+        let secondary = match (artist, year) {
+            (Some(artist), Some(year)) => Some(format!("{artist} • {year}").into()),
+            (Some(artist), None) => Some(artist.into()),
+            (None, Some(year)) => Some(year),
+            (None, None) => None,
         };
 
         Some((title, secondary))
@@ -293,6 +363,11 @@ pub enum TrackColumn {
     Album,
     Artist,
     Length,
+    Rating,
+    Format,
+    PlayCount,
+    DateAdded,
+    LastPlayed,
 }
 
 impl Column for TrackColumn {
@@ -303,6 +378,11 @@ impl Column for TrackColumn {
             TrackColumn::Album => tr!("COLUMN_ALBUM", "Album").into(),
             TrackColumn::Artist => tr!("COLUMN_ARTIST").into(),
             TrackColumn::Length => tr!("COLUMN_LENGTH", "Length").into(),
+            TrackColumn::Rating => tr!("COLUMN_RATING").into(),
+            TrackColumn::Format => tr!("COLUMN_FORMAT", "Format").into(),
+            TrackColumn::PlayCount => tr!("COLUMN_PLAY_COUNT", "Plays").into(),
+            TrackColumn::DateAdded => tr!("COLUMN_DATE_ADDED", "Date Added").into(),
+            TrackColumn::LastPlayed => tr!("COLUMN_LAST_PLAYED", "Last Played").into(),
         }
     }
 
@@ -310,6 +390,16 @@ impl Column for TrackColumn {
         !matches!(self, TrackColumn::Title)
     }
 
+    fn is_shown_by_default(&self) -> bool {
+        !matches!(
+            self,
+            TrackColumn::Format
+                | TrackColumn::PlayCount
+                | TrackColumn::DateAdded
+                | TrackColumn::LastPlayed
+        )
+    }
+
     fn all_columns() -> &'static [Self] {
         &[
             TrackColumn::TrackNumber,
@@ -317,6 +407,11 @@ impl Column for TrackColumn {
             TrackColumn::Album,
             TrackColumn::Artist,
             TrackColumn::Length,
+            TrackColumn::Rating,
+            TrackColumn::Format,
+            TrackColumn::PlayCount,
+            TrackColumn::DateAdded,
+            TrackColumn::LastPlayed,
         ]
     }
 }
@@ -332,6 +427,7 @@ impl TableData<TrackColumn> for Track {
     fn get_rows(
         cx: &mut gpui::App,
         sort: Option<TableSort<TrackColumn>>,
+        filter: &LibraryFilter,
     ) -> anyhow::Result<Vec<Self::Identifier>> {
         let sort_method = match sort {
             Some(TableSort {
@@ -374,10 +470,68 @@ impl TableData<TrackColumn> for Track {
                 column: TrackColumn::TrackNumber,
                 ascending: false,
             }) => TrackSortMethod::TrackNumberDesc,
+            Some(TableSort {
+                column: TrackColumn::Rating,
+                ascending: true,
+            }) => TrackSortMethod::RatingAsc,
+            Some(TableSort {
+                column: TrackColumn::Rating,
+                ascending: false,
+            }) => TrackSortMethod::RatingDesc,
+            Some(TableSort {
+                column: TrackColumn::Format,
+                ascending: true,
+            }) => TrackSortMethod::FormatAsc,
+            Some(TableSort {
+                column: TrackColumn::Format,
+                ascending: false,
+            }) => TrackSortMethod::FormatDesc,
+            Some(TableSort {
+                column: TrackColumn::PlayCount,
+                ascending: true,
+            }) => TrackSortMethod::PlayCountAsc,
+            Some(TableSort {
+                column: TrackColumn::PlayCount,
+                ascending: false,
+            }) => TrackSortMethod::PlayCountDesc,
+            Some(TableSort {
+                column: TrackColumn::DateAdded,
+                ascending: true,
+            }) => TrackSortMethod::DateAddedAsc,
+            Some(TableSort {
+                column: TrackColumn::DateAdded,
+                ascending: false,
+            }) => TrackSortMethod::DateAddedDesc,
+            Some(TableSort {
+                column: TrackColumn::LastPlayed,
+                ascending: true,
+            }) => TrackSortMethod::LastPlayedAsc,
+            Some(TableSort {
+                column: TrackColumn::LastPlayed,
+                ascending: false,
+            }) => TrackSortMethod::LastPlayedDesc,
             _ => TrackSortMethod::ArtistAsc,
         };
 
-        Ok(cx.list_tracks(sort_method)?)
+        Ok(cx.list_tracks(sort_method, filter)?)
+    }
+
+    fn get_filter_options(cx: &mut App) -> Option<FilterOptions> {
+        library_filter_options(cx)
+    }
+
+    fn get_sort_text(
+        _cx: &mut App,
+        sort: Option<TableSort<TrackColumn>>,
+        rows: &[Self::Identifier],
+    ) -> Option<Vec<String>> {
+        match sort {
+            Some(TableSort {
+                column: TrackColumn::Title,
+                ..
+            }) => Some(rows.iter().map(|row| row.1.clone()).collect()),
+            _ => None,
+        }
     }
 
     fn get_row(cx: &mut gpui::App, id: Self::Identifier) -> anyhow::Result<Option<Arc<Self>>> {
@@ -407,17 +561,14 @@ impl TableData<TrackColumn> for Track {
                 }
             }
             TrackColumn::Title => Some(self.title.0.clone()),
-            TrackColumn::Album => {
-                if let Some(album_id) = self.album_id {
-                    cx.get_album_by_id(album_id, AlbumMethod::Metadata)
-                        .ok()
-                        .map(|v| v.title.0.clone())
-                } else {
-                    None
-                }
-            }
+            TrackColumn::Album => Some(
+                self.album_id
+                    .and_then(|album_id| cx.get_album_by_id(album_id, AlbumMethod::Metadata).ok())
+                    .map(|v| v.title.0.clone())
+                    .unwrap_or_else(|| tr!("UNKNOWN_ALBUM", "Unknown Album").into()),
+            ),
             TrackColumn::Artist => {
-                if let Some(artist) = &self.artist_names {
+                let artist = if let Some(artist) = &self.artist_names {
                     Some(artist.0.clone())
                 } else if let Some(album_id) = self.album_id {
                     cx.get_album_by_id(album_id, AlbumMethod::Metadata)
@@ -429,13 +580,20 @@ impl TableData<TrackColumn> for Track {
                         })
                 } else {
                     None
-                }
-            }
-            TrackColumn::Length => {
-                let minutes = self.duration / 60;
-                let seconds = self.duration % 60;
-                Some(format!("{:02}:{:02}", minutes, seconds).into())
+                };
+
+                Some(artist.unwrap_or_else(|| tr!("UNKNOWN_ARTIST").into()))
             }
+            TrackColumn::Length => Some(format_duration(self.duration.max(0) as u64).into()),
+            TrackColumn::Rating => format_rating(self.rating),
+            TrackColumn::Format => format_badge(self),
+            TrackColumn::PlayCount => Some(format_number(self.play_count)),
+            TrackColumn::DateAdded => Some(format_relative_date(&self.created_at)),
+            TrackColumn::LastPlayed => Some(
+                self.last_played
+                    .map(|played_at| format_relative_date(&played_at))
+                    .unwrap_or_else(|| tr!("NEVER_PLAYED", "Never").into()),
+            ),
         }
     }
 
@@ -473,13 +631,26 @@ impl TableData<TrackColumn> for Track {
         let s = FxBuildHasher;
         let mut columns: IndexMap<TrackColumn, f32, FxBuildHasher> = IndexMap::with_hasher(s);
         columns.insert(TrackColumn::TrackNumber, 75.0);
-        columns.insert(TrackColumn::Title, 350.0);
-        columns.insert(TrackColumn::Album, 250.0);
-        columns.insert(TrackColumn::Artist, 225.0);
+        columns.insert(TrackColumn::Title, 300.0);
+        columns.insert(TrackColumn::Album, 225.0);
+        columns.insert(TrackColumn::Artist, 200.0);
         columns.insert(TrackColumn::Length, 100.0);
+        columns.insert(TrackColumn::Rating, 100.0);
+        columns.insert(TrackColumn::Format, 100.0);
+        columns.insert(TrackColumn::PlayCount, 80.0);
+        columns.insert(TrackColumn::DateAdded, 130.0);
+        columns.insert(TrackColumn::LastPlayed, 130.0);
         columns
     }
 
+    fn get_location(&self) -> Option<&Path> {
+        Some(&self.location)
+    }
+
+    fn get_track_id(&self) -> Option<i64> {
+        Some(self.id)
+    }
+
     fn get_drag_data(&self) -> Option<TableDragData> {
         Some(TableDragData::Track(TrackDragData::from_track(
             self.id,
@@ -559,6 +730,7 @@ impl TableData<ArtistColumn> for ArtistWithCounts {
     fn get_rows(
         cx: &mut gpui::App,
         sort: Option<TableSort<ArtistColumn>>,
+        _filter: &LibraryFilter,
     ) -> anyhow::Result<Vec<Self::Identifier>> {
         let sort_method = match sort {
             Some(TableSort {
@@ -591,15 +763,43 @@ impl TableData<ArtistColumn> for ArtistWithCounts {
         Ok(cx.list_artists(sort_method)?)
     }
 
+    fn get_sort_text(
+        cx: &mut App,
+        sort: Option<TableSort<ArtistColumn>>,
+        rows: &[Self::Identifier],
+    ) -> Option<Vec<String>> {
+        let ascending = match sort {
+            None => true,
+            Some(TableSort {
+                column: ArtistColumn::Name,
+                ascending,
+            }) => ascending,
+            _ => return None,
+        };
+
+        let mut names = cx.list_artist_sort_names().ok()?;
+        if !ascending {
+            names.reverse();
+        }
+
+        // the artist list may have changed since the rows were loaded
+        (names.len() == rows.len()).then_some(names)
+    }
+
     fn get_row(cx: &mut gpui::App, id: Self::Identifier) -> anyhow::Result<Option<Arc<Self>>> {
         Ok(cx.get_artist_with_counts(id).ok())
     }
 
     fn get_column(&self, _cx: &mut App, column: ArtistColumn) -> Option<SharedString> {
         match column {
-            ArtistColumn::Name => self.name.as_ref().map(|v| v.0.clone()),
-            ArtistColumn::Albums => Some(self.album_count.to_string().into()),
-            ArtistColumn::Tracks => Some(self.track_count.to_string().into()),
+            ArtistColumn::Name => Some(
+                self.name
+                    .as_ref()
+                    .map(|v| v.0.clone())
+                    .unwrap_or_else(|| tr!("UNKNOWN_ARTIST").into()),
+            ),
+            ArtistColumn::Albums => Some(format_number(self.album_count)),
+            ArtistColumn::Tracks => Some(format_number(self.track_count)),
         }
     }
 
@@ -643,7 +843,7 @@ impl TableData<ArtistColumn> for ArtistWithCounts {
 
 #[cfg(test)]
 mod tests {
-    use super::{album_release_date_format, parse_album_release_date};
+    use super::{album_release_date_format, format_rating, parse_album_release_date};
     use crate::library::types::{
         DATE_PRECISION_FULL_DATE, DATE_PRECISION_YEAR, DATE_PRECISION_YEAR_MONTH, DBString,
     };
@@ -672,4 +872,11 @@ mod tests {
             Some(Utc.with_ymd_and_hms(1995, 6, 1, 0, 0, 0).single().unwrap())
         );
     }
+
+    #[test]
+    fn formats_ratings_as_stars() {
+        assert_eq!(format_rating(0), None);
+        assert_eq!(format_rating(3).as_deref(), Some("★★★☆☆"));
+        assert_eq!(format_rating(9).as_deref(), Some("★★★★★"));
+    }
 }
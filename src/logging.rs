@@ -61,7 +61,11 @@ pub fn init() -> anyhow::Result<()> {
         .with(file_layer);
 
     #[cfg(feature = "console")]
-    let subscriber = subscriber.with(console_subscriber::spawn());
+    let subscriber = subscriber.with(console_enabled().then(|| {
+        console_subscriber::ConsoleLayer::builder()
+            .with_default_env()
+            .spawn()
+    }));
 
     subscriber.init();
     Ok(())
@@ -103,6 +107,22 @@ fn filter_value() -> String {
         .unwrap_or_else(|| DEFAULT_LOG_FILTER.to_owned())
 }
 
+/// Whether the tokio-console subscriber should be started. Builds with the `console` feature only
+/// start it when `HUMMINGBIRD_CONSOLE` is set, since it keeps a record of every task and listens on
+/// a local port.
+#[cfg(feature = "console")]
+fn console_enabled() -> bool {
+    std::env::var("HUMMINGBIRD_CONSOLE").is_ok_and(|value| is_enabled_value(&value))
+}
+
+#[cfg(feature = "console")]
+fn is_enabled_value(value: &str) -> bool {
+    !matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "" | "0" | "false" | "no" | "off"
+    )
+}
+
 fn open_file_make_writer(log_path: &Path) -> Option<FileMakeWriter> {
     fs::create_dir_all(log_path.parent()?).ok()?;
 
@@ -385,4 +405,16 @@ mod tests {
         let file = fs::read_to_string(&active_path).unwrap();
         assert!(file.contains("integration log test"));
     }
+
+    #[cfg(feature = "console")]
+    #[test]
+    fn console_env_values() {
+        assert!(is_enabled_value("1"));
+        assert!(is_enabled_value("true"));
+        assert!(is_enabled_value("yes"));
+        assert!(!is_enabled_value(""));
+        assert!(!is_enabled_value("0"));
+        assert!(!is_enabled_value(" False "));
+        assert!(!is_enabled_value("off"));
+    }
 }
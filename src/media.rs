@@ -5,3 +5,4 @@ pub mod metadata;
 pub mod pipeline;
 pub mod playback;
 pub mod traits;
+pub mod waveform;
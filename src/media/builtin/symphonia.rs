@@ -36,6 +36,7 @@ use symphonia::{
 };
 
 use symphonia_adapter_libopus::OpusDecoder;
+use tracing::warn;
 
 use crate::{
     devices::format::{ChannelSpec, SampleFormat},
@@ -79,6 +80,49 @@ fn parse_rg_gain(value: &Value) -> Option<f64> {
     }
 }
 
+/// Converts a POPM (popularimeter) rating from 0-255 to 0-5 stars, using the same ranges as most
+/// other players.
+fn popm_to_stars(rating: u8) -> u8 {
+    match rating {
+        0 => 0,
+        1..=31 => 1,
+        32..=95 => 2,
+        96..=159 => 3,
+        160..=223 => 4,
+        _ => 5,
+    }
+}
+
+/// Parse a rating tag to 0-5 stars. ID3 POPM frames always use a 0-255 scale, while text ratings
+/// (such as Vorbis `RATING` comments) can be on a 0-5, 0-100 or 0-255 scale.
+fn parse_rating(key: &str, value: &Value) -> Option<u8> {
+    let rating = match value {
+        Value::UnsignedInt(v) => *v as f64,
+        Value::SignedInt(v) => *v as f64,
+        Value::Float(v) => *v,
+        Value::String(s) => s.trim().parse().ok()?,
+        _ => return None,
+    };
+
+    if !rating.is_finite() || rating < 0.0 {
+        return None;
+    }
+
+    if key
+        .get(..4)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("POPM"))
+    {
+        return Some(popm_to_stars(rating.min(255.0) as u8));
+    }
+
+    match rating {
+        r if r <= 5.0 => Some(r.round() as u8),
+        r if r <= 100.0 => Some((r / 20.0).round() as u8),
+        r if r <= 255.0 => Some(popm_to_stars(r as u8)),
+        _ => None,
+    }
+}
+
 /// Parse an R128 gain value (Q7.8 integer stored as string) to dB.
 fn parse_r128_gain(value: &Value) -> Option<f64> {
     match value {
@@ -92,6 +136,48 @@ fn parse_r128_gain(value: &Value) -> Option<f64> {
     }
 }
 
+/// Converts a decoded signed 24-bit sample, clamping it to the 24-bit range if the decoder
+/// produced a value that doesn't fit. Returns whether the sample had to be clamped.
+fn convert_i24(value: i32) -> (I24, bool) {
+    match I24::try_from(value) {
+        Ok(sample) => (sample, false),
+        Err(_) if value < 0 => (I24::MIN, true),
+        Err(_) => (I24::MAX, true),
+    }
+}
+
+/// Converts a decoded unsigned 24-bit sample, clamping it to the 24-bit range if the decoder
+/// produced a value that doesn't fit. Returns whether the sample had to be clamped.
+fn convert_u24(value: u32) -> (U24, bool) {
+    match U24::try_from(value) {
+        Ok(sample) => (sample, false),
+        Err(_) => (U24::MAX, true),
+    }
+}
+
+/// The number of packets in a row that can fail to be read before the stream is considered broken.
+const MAX_CONSECUTIVE_PACKET_ERRORS: usize = 32;
+
+/// What to do after the format reader failed to read the next packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PacketReadAction {
+    /// The end of the track has been reached.
+    Eof,
+    /// The packet is malformed, but the rest of the stream may still be readable.
+    Skip,
+    /// The stream can't be read any further.
+    Fatal(String),
+}
+
+fn classify_packet_error(error: Error) -> PacketReadAction {
+    match error {
+        Error::ResetRequired => PacketReadAction::Eof,
+        Error::IoError(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => PacketReadAction::Eof,
+        Error::DecodeError(_) => PacketReadAction::Skip,
+        e => PacketReadAction::Fatal(e.to_string()),
+    }
+}
+
 fn time_to_millis(time: Time) -> u64 {
     time.seconds
         .saturating_mul(1_000)
@@ -195,6 +281,7 @@ pub struct SymphoniaStream {
     current_track: u32,
     current_duration: u64,
     current_length: Option<u64>,
+    current_length_ms: Option<u64>,
     current_position_ms: u64,
     current_timebase: Option<TimeBase>,
     decoder: Option<Box<dyn Decoder>>,
@@ -202,9 +289,24 @@ pub struct SymphoniaStream {
     last_image: Option<Visual>,
     /// Pre-allocated buffer for sample format conversion, reused across decode calls
     conversion_buffer: Vec<Vec<f64>>,
+    /// Whether out-of-range 24-bit samples have already been logged for this stream
+    warned_clamped_samples: bool,
+    /// The channel count of the last decoded packet
+    decoded_channels: Option<usize>,
+    /// Short name of the codec being decoded, set when playback starts
+    codec_name: Option<&'static str>,
+    /// The most frames a single packet can decode to, set when playback starts
+    max_frames_per_packet: Option<u64>,
 }
 
 impl SymphoniaStream {
+    fn warn_clamped_samples(&mut self, clamped: bool) {
+        if clamped && !self.warned_clamped_samples {
+            warn!("Decoder produced 24-bit samples outside of the 24-bit range, clamping them");
+            self.warned_clamped_samples = true;
+        }
+    }
+
     fn break_metadata(&mut self, tags: &[Tag]) {
         let id3_position_in_set_regex = Regex::new(r"(\d+)/(\d+)").unwrap();
         let vinyl_track_regex = Regex::new(r"(?i)^([A-Z])(\d*)$").unwrap();
@@ -381,6 +483,14 @@ impl SymphoniaStream {
                 Some(StandardTagKey::DiscSubtitle) => {
                     self.current_metadata.disc_subtitle = Some(tag.value.to_string());
                 }
+                Some(StandardTagKey::Rating) => {
+                    // files can contain a POPM frame for every player that rated the track (keyed
+                    // by email), so keep the highest rating
+                    if let Some(rating) = parse_rating(&tag.key, &tag.value) {
+                        self.current_metadata.rating =
+                            self.current_metadata.rating.max(Some(rating));
+                    }
+                }
                 _ => {
                     // Handle non-standard ReplayGain tag keys and R128 tags
                     let key = tag.key.as_str();
@@ -470,12 +580,17 @@ impl MediaProvider for SymphoniaProvider {
             current_track: 0,
             current_duration: 0,
             current_length: None,
+            current_length_ms: None,
             current_position_ms: 0,
             current_timebase: None,
             decoder: None,
             pending_metadata_update: false,
             last_image: None,
             conversion_buffer: Vec::new(),
+            warned_clamped_samples: false,
+            decoded_channels: None,
+            codec_name: None,
+            max_frames_per_packet: None,
         };
 
         stream.read_base_metadata(&mut probed);
@@ -522,20 +637,24 @@ impl MediaStream for SymphoniaStream {
         if let Some(frame_count) = track.codec_params.n_frames
             && let Some(tb) = track.codec_params.time_base
         {
-            self.current_length = Some(tb.calc_time(frame_count).seconds);
+            let length = tb.calc_time(frame_count);
+            self.current_length = Some(length.seconds);
+            self.current_length_ms = Some(length.seconds * 1000 + (length.frac * 1000.0) as u64);
             self.current_timebase = Some(tb);
         }
 
         // Pre-allocate conversion buffer based on codec parameters
         let channel_count = track.codec_params.channels.map(|c| c.count()).unwrap_or(2);
         // Typical frame sizes: 1152 (MP3), 4096 (FLAC), 960-2880 (Opus)
-        let frame_capacity = track.codec_params.max_frames_per_packet.unwrap_or(8192) as usize;
+        let max_frames_per_packet = track.codec_params.max_frames_per_packet.unwrap_or(8192);
+        let frame_capacity = max_frames_per_packet as usize;
 
         self.conversion_buffer = (0..channel_count)
             .map(|_| Vec::with_capacity(frame_capacity))
             .collect();
 
         self.current_track = track.id;
+        self.max_frames_per_packet = Some(max_frames_per_packet);
 
         let dec_opts: DecoderOptions = Default::default();
         self.decoder = Some({
@@ -564,6 +683,10 @@ impl MediaStream for SymphoniaStream {
                 codecs.register_all::<symphonia_adapter_fdk_aac::AacDecoder>();
             }
 
+            self.codec_name = codecs
+                .get_codec(track.codec_params.codec)
+                .map(|codec| codec.short_name);
+
             codecs
                 .make(&track.codec_params, &dec_opts)
                 .map_err(|_| PlaybackStartError::Undecodable)?
@@ -575,6 +698,8 @@ impl MediaStream for SymphoniaStream {
     fn stop_playback(&mut self) -> Result<(), PlaybackStopError> {
         self.current_track = 0;
         self.decoder = None;
+        self.codec_name = None;
+        self.max_frames_per_packet = None;
 
         Ok(())
     }
@@ -587,6 +712,11 @@ impl MediaStream for SymphoniaStream {
         }
     }
 
+    fn max_frame_duration(&self) -> Result<u64, FrameDurationError> {
+        self.max_frames_per_packet
+            .ok_or(FrameDurationError::NeverStarted)
+    }
+
     fn read_metadata(&mut self) -> Result<&Metadata, MetadataError> {
         self.pending_metadata_update = false;
 
@@ -623,6 +753,14 @@ impl MediaStream for SymphoniaStream {
         }
     }
 
+    fn duration_ms(&self) -> Result<u64, TrackDurationError> {
+        if self.decoder.is_none() || self.current_length_ms.is_none() {
+            Err(TrackDurationError::NeverStarted)
+        } else {
+            Ok(self.current_length_ms.unwrap_or_default())
+        }
+    }
+
     fn position_ms(&self) -> Result<u64, TrackDurationError> {
         if self.decoder.is_none() || self.current_length.is_none() {
             Err(TrackDurationError::NeverStarted)
@@ -649,9 +787,10 @@ impl MediaStream for SymphoniaStream {
             )
             .map_err(|e| SeekError::Unknown(e.to_string()))?;
 
-        if let Some(timebase) = timebase {
-            self.current_position_ms = time_to_millis(timebase.calc_time(seek.actual_ts));
-        }
+        self.current_position_ms = match timebase {
+            Some(timebase) => time_to_millis(timebase.calc_time(seek.actual_ts)),
+            None => (time.max(0.0) * 1_000.0) as u64,
+        };
 
         Ok(())
     }
@@ -667,8 +806,9 @@ impl MediaStream for SymphoniaStream {
             .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
             .ok_or(ChannelRetrievalError::NothingToPlay)?;
 
-        // HACK: if the channel count isn't in the codec parameters pretend that it's stereo
-        // this "fixes" m4a container files but obviously poorly
+        // m4a files don't have the channel count in the codec parameters, so use the count from
+        // the decoded audio instead. Until something has been decoded, guess that it's stereo -
+        // decode_into reports ChannelCountChanged if the guess was wrong.
         //
         // upstream issue: https://github.com/pdeljanov/Symphonia/issues/289
         Ok(ChannelSpec::Count(
@@ -676,6 +816,7 @@ impl MediaStream for SymphoniaStream {
                 .codec_params
                 .channels
                 .map(Channels::count)
+                .or(self.decoded_channels)
                 .unwrap_or(2) as u16,
         ))
     }
@@ -766,6 +907,45 @@ impl MediaStream for SymphoniaStream {
             .ok_or(ChannelRetrievalError::NothingToPlay)
     }
 
+    fn codec_name(&self) -> Result<&str, ChannelRetrievalError> {
+        if self.format.is_none() {
+            return Err(ChannelRetrievalError::InvalidState);
+        }
+
+        self.codec_name.ok_or(ChannelRetrievalError::NeverStarted)
+    }
+
+    fn bits_per_sample(&self) -> Result<Option<u32>, ChannelRetrievalError> {
+        let Some(format) = &self.format else {
+            return Err(ChannelRetrievalError::InvalidState);
+        };
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or(ChannelRetrievalError::NothingToPlay)?;
+
+        Ok(track.codec_params.bits_per_sample)
+    }
+
+    fn stored_channel_count(&self) -> Result<Option<u16>, ChannelRetrievalError> {
+        let Some(format) = &self.format else {
+            return Err(ChannelRetrievalError::InvalidState);
+        };
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or(ChannelRetrievalError::NothingToPlay)?;
+
+        Ok(track
+            .codec_params
+            .channels
+            .map(|channels| channels.count() as u16))
+    }
+
     fn decode_into(
         &mut self,
         output: &ChannelProducers<f64>,
@@ -774,14 +954,25 @@ impl MediaStream for SymphoniaStream {
             return Err(PlaybackReadError::InvalidState);
         };
 
+        let mut packet_errors = 0;
+
         loop {
             let packet = match format.next_packet() {
                 Ok(packet) => packet,
-                Err(Error::ResetRequired) => return Ok(DecodeResult::Eof),
-                Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
-                    return Ok(DecodeResult::Eof);
-                }
-                Err(_) => return Ok(DecodeResult::Eof),
+                Err(e) => match classify_packet_error(e) {
+                    PacketReadAction::Eof => return Ok(DecodeResult::Eof),
+                    PacketReadAction::Skip if packet_errors < MAX_CONSECUTIVE_PACKET_ERRORS => {
+                        packet_errors += 1;
+                        warn!("Skipping malformed packet");
+                        continue;
+                    }
+                    PacketReadAction::Skip => {
+                        return Err(PlaybackReadError::DecodeFatal(
+                            "too many malformed packets".to_string(),
+                        ));
+                    }
+                    PacketReadAction::Fatal(e) => return Err(PlaybackReadError::DecodeFatal(e)),
+                },
             };
 
             while !format.metadata().is_latest() {
@@ -806,6 +997,11 @@ impl MediaStream for SymphoniaStream {
                         self.current_position_ms = time_to_millis(tb.calc_time(packet.ts()));
                     }
 
+                    self.decoded_channels = Some(channel_count);
+                    if channel_count != output.channel_count() {
+                        return Err(PlaybackReadError::ChannelCountChanged);
+                    }
+
                     // prepare buffers
                     while self.conversion_buffer.len() < channel_count {
                         self.conversion_buffer
@@ -833,11 +1029,15 @@ impl MediaStream for SymphoniaStream {
                             v.frames()
                         }
                         AudioBufferRef::U24(v) => {
+                            let mut clamped = false;
                             for ch in 0..channel_count {
                                 self.conversion_buffer[ch].extend(v.chan(ch).iter().map(|s| {
-                                    U24::try_from(s.0).expect("u24 overflow").sample_into()
+                                    let (sample, out_of_range) = convert_u24(s.0);
+                                    clamped |= out_of_range;
+                                    sample.sample_into()
                                 }));
                             }
+                            self.warn_clamped_samples(clamped);
                             v.frames()
                         }
                         AudioBufferRef::U32(v) => {
@@ -862,11 +1062,15 @@ impl MediaStream for SymphoniaStream {
                             v.frames()
                         }
                         AudioBufferRef::S24(v) => {
+                            let mut clamped = false;
                             for ch in 0..channel_count {
                                 self.conversion_buffer[ch].extend(v.chan(ch).iter().map(|s| {
-                                    I24::try_from(s.0).expect("i24 overflow").sample_into()
+                                    let (sample, out_of_range) = convert_i24(s.0);
+                                    clamped |= out_of_range;
+                                    sample.sample_into()
                                 }));
                             }
+                            self.warn_clamped_samples(clamped);
                             v.frames()
                         }
                         AudioBufferRef::S32(v) => {
@@ -916,16 +1120,27 @@ impl MediaStream for SymphoniaStream {
             return Err(PlaybackReadError::InvalidState);
         };
 
+        let mut packet_errors = 0;
+
         loop {
             let packet = match format.next_packet() {
                 Ok(packet) => packet,
-                Err(Error::ResetRequired) => {
-                    return Ok(F32DecodeResult::Decoded(DecodeResult::Eof));
-                }
-                Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
-                    return Ok(F32DecodeResult::Decoded(DecodeResult::Eof));
-                }
-                Err(_) => return Ok(F32DecodeResult::Decoded(DecodeResult::Eof)),
+                Err(e) => match classify_packet_error(e) {
+                    PacketReadAction::Eof => {
+                        return Ok(F32DecodeResult::Decoded(DecodeResult::Eof));
+                    }
+                    PacketReadAction::Skip if packet_errors < MAX_CONSECUTIVE_PACKET_ERRORS => {
+                        packet_errors += 1;
+                        warn!("Skipping malformed packet");
+                        continue;
+                    }
+                    PacketReadAction::Skip => {
+                        return Err(PlaybackReadError::DecodeFatal(
+                            "too many malformed packets".to_string(),
+                        ));
+                    }
+                    PacketReadAction::Fatal(e) => return Err(PlaybackReadError::DecodeFatal(e)),
+                },
             };
 
             while !format.metadata().is_latest() {
@@ -950,6 +1165,11 @@ impl MediaStream for SymphoniaStream {
                         self.current_position_ms = time_to_millis(tb.calc_time(packet.ts()));
                     }
 
+                    self.decoded_channels = Some(channel_count);
+                    if channel_count != output.channel_count() {
+                        return Err(PlaybackReadError::ChannelCountChanged);
+                    }
+
                     // Only handle F32, return NotF32 for other formats
                     let frames = match decoded {
                         AudioBufferRef::F32(v) => {
@@ -979,8 +1199,12 @@ impl MediaStream for SymphoniaStream {
 
 #[cfg(test)]
 mod tests {
-    use super::{ParsedReleaseDate, parse_release_date};
+    use super::{
+        PacketReadAction, ParsedReleaseDate, classify_packet_error, convert_i24, convert_u24,
+        parse_rating, parse_release_date,
+    };
     use chrono::{NaiveTime, TimeZone, Timelike, Utc};
+    use intx::{I24, U24};
 
     #[test]
     fn parses_year_only_release_dates() {
@@ -1029,4 +1253,55 @@ mod tests {
         assert_eq!(date.time(), NaiveTime::MIN);
         assert_eq!(date.time().nanosecond(), 0);
     }
+
+    #[test]
+    fn clamps_out_of_range_24_bit_samples() {
+        assert_eq!(convert_i24(-42), (I24::try_from(-42).unwrap(), false));
+        assert_eq!(convert_i24(i32::MAX), (I24::MAX, true));
+        assert_eq!(convert_i24(i32::MIN), (I24::MIN, true));
+
+        assert_eq!(convert_u24(42), (U24::try_from(42_u32).unwrap(), false));
+        assert_eq!(convert_u24(u32::MAX), (U24::MAX, true));
+    }
+
+    #[test]
+    fn normalizes_ratings_to_five_stars() {
+        use symphonia::core::meta::Value;
+
+        assert_eq!(
+            parse_rating("POPM:rating@winamp.com", &Value::UnsignedInt(196)),
+            Some(4)
+        );
+        assert_eq!(parse_rating("POPM", &Value::UnsignedInt(1)), Some(1));
+        assert_eq!(parse_rating("POPM", &Value::UnsignedInt(0)), Some(0));
+        assert_eq!(parse_rating("RATING", &Value::String("3".into())), Some(3));
+        assert_eq!(parse_rating("RATING", &Value::String("80".into())), Some(4));
+        assert_eq!(
+            parse_rating("RATING", &Value::String("255".into())),
+            Some(5)
+        );
+        assert_eq!(parse_rating("RATING", &Value::String("great".into())), None);
+    }
+
+    #[test]
+    fn only_treats_end_of_stream_as_eof() {
+        use symphonia::core::errors::Error;
+
+        assert_eq!(
+            classify_packet_error(Error::ResetRequired),
+            PacketReadAction::Eof
+        );
+        assert_eq!(
+            classify_packet_error(Error::IoError(std::io::ErrorKind::UnexpectedEof.into())),
+            PacketReadAction::Eof
+        );
+        assert_eq!(
+            classify_packet_error(Error::DecodeError("bad packet")),
+            PacketReadAction::Skip
+        );
+        assert!(matches!(
+            classify_packet_error(Error::IoError(std::io::ErrorKind::PermissionDenied.into())),
+            PacketReadAction::Fatal(_)
+        ));
+    }
 }
@@ -66,6 +66,11 @@ pub enum PlaybackReadError {
     Unknown(String),
     #[error("Decode error: `{0}`")]
     DecodeFatal(String),
+    /// The decoded audio has a different number of channels than the output it was decoded into.
+    /// This happens when the container doesn't report the real channel count. The decoded samples
+    /// are discarded, and the output should be recreated using the updated channel count.
+    #[error("The channel count of the media changed")]
+    ChannelCountChanged,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Error)]
@@ -41,5 +41,23 @@ pub struct Metadata {
     pub replaygain_album_gain: Option<f64>,
     pub replaygain_album_peak: Option<f64>,
 
+    /// Star rating from 0 to 5, normalized from whichever scale the file's rating tags use.
+    pub rating: Option<u8>,
+
     pub lyrics: Option<String>,
 }
+
+/// Technical details about a file's audio, as opposed to its tags.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AudioProperties {
+    /// Short name of the codec, such as `flac` or `mp3`.
+    pub codec: Option<String>,
+    /// Average bitrate in kbps.
+    pub bitrate: Option<u32>,
+    /// Sample rate in Hz.
+    pub sample_rate: Option<u32>,
+    /// Bits per sample. Only known for lossless codecs.
+    pub bit_depth: Option<u32>,
+    /// Number of channels.
+    pub channels: Option<u32>,
+}
@@ -1,9 +1,23 @@
-use rb::{Consumer, Producer, RB, RbConsumer, RbProducer, SpscRb};
+use rb::{Consumer, Producer, RB, RbConsumer, RbInspector, RbProducer, SpscRb};
 
 use crate::devices::format::SampleFormat;
 
 pub const DEFAULT_BUFFER_FRAMES: usize = 8192;
 
+/// The packet size assumed when the media can't say how large its packets are.
+pub const FALLBACK_PACKET_FRAMES: usize = 8192;
+
+/// The sizes, in frames per channel, to create a pipeline's buffers with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferSizes {
+    /// The buffer in front of the device, which is also the most the resampler reads at once.
+    pub device: usize,
+    /// The buffer between the decoder and the resampler.
+    pub decoder: usize,
+    /// The largest packet the decoder can produce.
+    pub max_packet: usize,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DecodeResult {
     Decoded { frames: usize, rate: u32 },
@@ -32,7 +46,7 @@ impl<T: Copy + Default + Send + 'static> ChannelBuffers<T> {
         let mut producers = Vec::with_capacity(self.channel_count);
         let mut consumers = Vec::with_capacity(self.channel_count);
 
-        for rb in self.buffers {
+        for rb in &self.buffers {
             producers.push(rb.producer());
             consumers.push(rb.consumer());
         }
@@ -40,6 +54,7 @@ impl<T: Copy + Default + Send + 'static> ChannelBuffers<T> {
         (
             ChannelProducers {
                 producers,
+                buffers: self.buffers,
                 channel_count: self.channel_count,
             },
             ChannelConsumers {
@@ -53,12 +68,26 @@ impl<T: Copy + Default + Send + 'static> ChannelBuffers<T> {
     }
 }
 
-pub struct ChannelProducers<T: Copy + Send + 'static> {
+pub struct ChannelProducers<T: Copy + Default + Send + 'static> {
     producers: Vec<Producer<T>>,
+    buffers: Vec<SpscRb<T>>,
     channel_count: usize,
 }
 
-impl<T: Copy + Send + 'static> ChannelProducers<T> {
+impl<T: Copy + Default + Send + 'static> ChannelProducers<T> {
+    pub fn channel_count(&self) -> usize {
+        self.channel_count
+    }
+
+    /// The number of samples that can be written to every channel without blocking.
+    pub fn slots_free(&self) -> usize {
+        self.buffers
+            .iter()
+            .map(|rb| rb.slots_free())
+            .min()
+            .unwrap_or(0)
+    }
+
     pub fn write_slices(&self, samples: &[&[T]]) {
         assert_eq!(samples.len(), self.channel_count);
 
@@ -138,6 +167,16 @@ impl<T: Copy + Default + Send + 'static> ChannelConsumers<T> {
     }
 }
 
+/// The resampler's side of a [`ConvertPipeline`]: it reads decoded samples from `input` and writes
+/// resampled samples to `output`.
+///
+/// This is split out from the pipeline so that it can be handed to a worker thread when
+/// resampling is offloaded from the playback thread.
+pub struct ResampleStage {
+    pub input: ChannelConsumers<f64>,
+    pub output: ChannelProducers<f64>,
+}
+
 /// Pipeline that converts all audio to f64 for processing (resampling, format conversion)
 ///
 /// The idea behind this is that all supported non-f32 formats fit within an f64's mantissa, so
@@ -146,37 +185,61 @@ impl<T: Copy + Default + Send + 'static> ChannelConsumers<T> {
 /// the f64 pipeline for everything except for pure f32 -> f32 output.
 pub struct ConvertPipeline {
     pub decoder_output: ChannelProducers<f64>,
-    pub resampler_input: ChannelConsumers<f64>,
-    pub device_input_producers: ChannelProducers<f64>,
+    /// The resampler's ring buffer ends. `None` while the stage is owned by a resampler worker.
+    pub resample_stage: Option<ResampleStage>,
     pub device_input: ChannelConsumers<f64>,
     pub source_rate: u32,
     pub target_rate: u32,
     pub channel_count: usize,
+    /// The most frames the resampler reads from the decoder's buffer at once.
+    pub resample_frames: usize,
+    max_packet_frames: usize,
 }
 
 impl ConvertPipeline {
+    /// Creates the pipeline's buffers.
+    ///
+    /// Decoded packets and resampled chunks are written whole, so the buffers are grown past
+    /// `sizes` when needed: the decoder buffer holds at least one packet, and the device buffer
+    /// holds everything the resampler can produce from one read plus one leftover packet.
     pub fn new(
         channel_count: usize,
         source_rate: u32,
         target_rate: u32,
-        buffer_frames: usize,
+        sizes: BufferSizes,
     ) -> Self {
+        let resample_frames = sizes.device.max(sizes.max_packet);
+        let decoder_frames = sizes.decoder.max(resample_frames);
+        let resampled_frames = (resample_frames + sizes.max_packet) as u64 * target_rate as u64;
+        let device_frames = sizes
+            .device
+            .max(resampled_frames.div_ceil(source_rate.max(1) as u64) as usize);
+
         let (decoder_output, resampler_input) =
-            ChannelBuffers::<f64>::new(channel_count, buffer_frames).split();
+            ChannelBuffers::<f64>::new(channel_count, decoder_frames).split();
 
         let (device_input_producers, device_input) =
-            ChannelBuffers::<f64>::new(channel_count, buffer_frames).split();
+            ChannelBuffers::<f64>::new(channel_count, device_frames).split();
 
         Self {
             decoder_output,
-            resampler_input,
-            device_input_producers,
+            resample_stage: Some(ResampleStage {
+                input: resampler_input,
+                output: device_input_producers,
+            }),
             device_input,
             source_rate,
             target_rate,
             channel_count,
+            resample_frames,
+            max_packet_frames: sizes.max_packet,
         }
     }
+
+    /// Whether a whole packet can be decoded into the pipeline without blocking.
+    pub fn has_room_for_packet(&self) -> bool {
+        self.decoder_output.slots_free() >= self.max_packet_frames
+    }
 }
 
 /// Pipeline for f32 passthrough - no format conversion, no resampling
@@ -187,9 +250,9 @@ pub struct F32PassthroughPipeline {
 }
 
 impl F32PassthroughPipeline {
-    pub fn new(channel_count: usize, buffer_frames: usize) -> Self {
+    pub fn new(channel_count: usize, sizes: BufferSizes) -> Self {
         let (decoder_output, device_input) =
-            ChannelBuffers::<f32>::new(channel_count, buffer_frames).split();
+            ChannelBuffers::<f32>::new(channel_count, sizes.device.max(sizes.max_packet)).split();
 
         Self {
             decoder_output,
@@ -212,19 +275,19 @@ impl AudioPipeline {
         source_rate: u32,
         device_format: SampleFormat,
         device_rate: u32,
-        buffer_frames: usize,
+        sizes: BufferSizes,
     ) -> Self {
         if source_format == SampleFormat::Float32
             && device_format == SampleFormat::Float32
             && source_rate == device_rate
         {
-            AudioPipeline::F32Passthrough(F32PassthroughPipeline::new(channel_count, buffer_frames))
+            AudioPipeline::F32Passthrough(F32PassthroughPipeline::new(channel_count, sizes))
         } else {
             AudioPipeline::Convert(ConvertPipeline::new(
                 channel_count,
                 source_rate,
                 device_rate,
-                buffer_frames,
+                sizes,
             ))
         }
     }
@@ -104,6 +104,10 @@ pub trait MediaStream {
     /// samples.
     fn duration_secs(&self) -> Result<u64, TrackDurationError>;
 
+    /// Returns the duration of the currently opened file in milliseconds. The same requirements
+    /// apply as for [`MediaStream::duration_secs`].
+    fn duration_ms(&self) -> Result<u64, TrackDurationError>;
+
     /// Returns the current playback position in milliseconds. If no file is opened, or playback
     /// has not started, this function should return an error. This function should be available
     /// immediately after playback has started, and should not require reading any samples.
@@ -129,6 +133,33 @@ pub trait MediaStream {
     /// resampling when the source rate differs from the device rate.
     fn sample_rate(&self) -> Result<u32, ChannelRetrievalError>;
 
+    /// Returns a short name for the codec used by the track being decoded, such as `flac` or
+    /// `mp3`. This function should be available immediately after playback has started, and
+    /// should not require reading any samples.
+    ///
+    /// This function is used during library indexing to record the format of each track.
+    fn codec_name(&self) -> Result<&str, ChannelRetrievalError>;
+
+    /// Returns the number of bits per sample stored in the file, if the codec has such a thing.
+    /// Lossy codecs usually don't, in which case `None` should be returned. This function should
+    /// be available immediately after playback has started.
+    fn bits_per_sample(&self) -> Result<Option<u32>, ChannelRetrievalError>;
+
+    /// Returns the number of channels stored in the file's headers, or `None` if the container
+    /// doesn't record it. Unlike [`MediaStream::channels`], this never guesses. This function
+    /// should be available immediately after playback has started.
+    ///
+    /// This function is used during library indexing to record the channel layout of each track.
+    fn stored_channel_count(&self) -> Result<Option<u16>, ChannelRetrievalError>;
+
+    /// Returns the largest number of frames a single PlaybackFrame can hold for the track being
+    /// decoded. This function should be available immediately after playback has started, and
+    /// should not require reading any samples.
+    ///
+    /// This function is used by the playback thread to make sure its buffers can hold a whole
+    /// packet.
+    fn max_frame_duration(&self) -> Result<u64, FrameDurationError>;
+
     /// Decode one packet/frame and write samples as f64 directly to the provided ring buffer producers.
     /// The decoder is responsible for converting from the native sample format to f64.
     fn decode_into(
@@ -0,0 +1,136 @@
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::Context;
+
+use super::{
+    errors::PlaybackReadError,
+    lookup_table::try_open_media,
+    pipeline::{ChannelBuffers, DEFAULT_BUFFER_FRAMES, DecodeResult, FALLBACK_PACKET_FRAMES},
+    traits::MediaProviderFeatures,
+};
+
+/// The number of points in a waveform overview.
+pub const WAVEFORM_POINTS: usize = 200;
+
+/// Decodes the file at `path` and returns its peak envelope: `points` values between 0.0 and 1.0,
+/// each the loudest sample in its slice of the track.
+///
+/// This reads the entire file, so it should only be called from a blocking thread. Decoding stops
+/// early and returns `None` once `cancelled` is set.
+pub fn compute_waveform(
+    path: &Path,
+    points: usize,
+    cancelled: &AtomicBool,
+) -> anyhow::Result<Option<Vec<f32>>> {
+    let mut stream = try_open_media(path, MediaProviderFeatures::PROVIDES_DECODER)?
+        .context("no media provider found")?;
+
+    stream.start_playback()?;
+
+    let channels = stream.channels()?.count() as usize;
+    let max_packet_frames = stream
+        .max_frame_duration()
+        .map(|frames| frames as usize)
+        .unwrap_or(FALLBACK_PACKET_FRAMES);
+
+    // writing to a full buffer blocks, so the largest packet has to fit in it
+    let (producers, mut consumers) =
+        ChannelBuffers::<f64>::new(channels, max_packet_frames.max(DEFAULT_BUFFER_FRAMES) * 2)
+            .split();
+
+    // the peak and length of every decoded packet
+    let mut packets = Vec::new();
+
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            stream.stop_playback().ok();
+            stream.close().ok();
+            return Ok(None);
+        }
+
+        match stream.decode_into(&producers) {
+            Ok(DecodeResult::Decoded { .. }) => {}
+            Ok(DecodeResult::Eof) | Err(PlaybackReadError::Eof) => break,
+            // the same as during playback, damaged packets are skipped
+            Err(PlaybackReadError::Unknown(_)) => continue,
+            Err(err) => return Err(err.into()),
+        }
+
+        let read = consumers.try_read_to_staging(consumers.potentially_available());
+        if read == 0 {
+            continue;
+        }
+
+        let peak = consumers
+            .staging()
+            .iter()
+            .flatten()
+            .fold(0.0_f64, |peak, sample| peak.max(sample.abs()));
+
+        packets.push((peak as f32, read));
+    }
+
+    stream.stop_playback().ok();
+    stream.close().ok();
+
+    Ok(Some(envelope(&packets, points)))
+}
+
+/// Spreads packet peaks over `points` evenly sized slices of the track, keeping the loudest peak
+/// in each slice. Packets longer than a slice are spread over every slice they overlap.
+fn envelope(packets: &[(f32, usize)], points: usize) -> Vec<f32> {
+    let total_frames: usize = packets.iter().map(|(_, frames)| frames).sum();
+    if total_frames == 0 || points == 0 {
+        return vec![0.0; points];
+    }
+
+    let mut peaks = vec![0.0_f32; points];
+    let mut position = 0;
+
+    for &(peak, frames) in packets {
+        let first = position * points / total_frames;
+        let last = ((position + frames) * points)
+            .div_ceil(total_frames)
+            .max(first + 1);
+
+        for slot in &mut peaks[first..last.min(points)] {
+            *slot = slot.max(peak.min(1.0));
+        }
+
+        position += frames;
+    }
+
+    peaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::envelope;
+
+    #[test]
+    fn envelope_keeps_loudest_peak_per_point() {
+        let packets = [(0.2, 10), (0.8, 10), (0.4, 10), (0.1, 10)];
+
+        assert_eq!(envelope(&packets, 2), vec![0.8, 0.4]);
+    }
+
+    #[test]
+    fn envelope_spreads_long_packets_over_points() {
+        let packets = [(0.5, 100), (0.25, 100)];
+
+        assert_eq!(envelope(&packets, 4), vec![0.5, 0.5, 0.25, 0.25]);
+    }
+
+    #[test]
+    fn envelope_clamps_clipped_samples() {
+        assert_eq!(envelope(&[(1.5, 10)], 1), vec![1.0]);
+    }
+
+    #[test]
+    fn empty_tracks_are_silent() {
+        assert_eq!(envelope(&[], 3), vec![0.0; 3]);
+    }
+}
@@ -22,6 +22,11 @@ pub fn data_dir() -> PathBuf {
     project_dirs().data_dir().to_path_buf()
 }
 
+/// The directory album art is stored in when it is kept outside the library database.
+pub fn art_dir() -> PathBuf {
+    data_dir().join("art")
+}
+
 pub fn log_dir() -> PathBuf {
     log_dir_in(
         project_dirs(),
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 
 use crate::{media::metadata::Metadata, settings::playback::PlaybackSettings};
@@ -53,6 +54,9 @@ pub enum PlaybackCommand {
     Previous,
     /// Requests that the playback thread clear the queue.
     ClearQueue,
+    /// Requests that the playback thread remove every track before the current one from the
+    /// queue.
+    ClearPlayed,
     /// Jumps to the specified position in the queue.
     Jump(usize),
     /// Jumps to the specified position in the queue. This will use the position of the track
@@ -60,8 +64,15 @@ pub enum PlaybackCommand {
     JumpUnshuffled(usize),
     /// Requests that the playback thread seek to the specified position in the current file.
     Seek(f64),
-    /// Requests that the playback thread set the volume to the specified level.
+    /// Requests that the playback thread seek to the specified position in the current file
+    /// without fading, for seeks that follow the scrubber while it's being dragged.
+    Scrub(f64),
+    /// Requests that the playback thread set the volume to the specified level. This also unmutes
+    /// playback.
     SetVolume(f64),
+    /// Requests that the playback thread mute or unmute playback. The volume level is kept, and is
+    /// restored when unmuting.
+    ToggleMute,
     /// Requests that the playback thread replace the current queue with the specified queue.
     /// This will set the current playing track to the first item in the queue.
     ReplaceQueue(Vec<QueueItemData>),
@@ -85,6 +96,9 @@ pub enum PlaybackCommand {
     /// Unlike ReplaceQueue, the playback thread will jump to the specified index in the new queue,
     /// instead of the first item.
     ReplaceQueueWithIndex(Vec<QueueItemData>, usize),
+    /// Informs the playback thread which tracks (by database ID) should be left out when
+    /// building a shuffled queue. Tracks explicitly chosen by the user are still played.
+    SetShuffleSkips(FxHashSet<i64>),
 }
 
 /// An event from the playback thread. This is used to communicate information from the playback
@@ -117,4 +131,6 @@ pub enum PlaybackEvent {
     RepeatChanged(RepeatState),
     /// Indicates that the volume has changed. The f64 is the new volume, from 0.0 to 1.0.
     VolumeChanged(f64),
+    /// Indicates that playback has been muted or unmuted.
+    MuteChanged(bool),
 }
@@ -3,6 +3,7 @@
 use std::{path::PathBuf, sync::Arc};
 
 use gpui::App;
+use rustc_hash::FxHashSet;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 use crate::{
@@ -90,6 +91,10 @@ impl PlaybackInterface {
         self.cmd_tx.send(PlaybackCommand::ClearQueue).unwrap();
     }
 
+    pub fn clear_played(&self) {
+        self.cmd_tx.send(PlaybackCommand::ClearPlayed).unwrap();
+    }
+
     pub fn jump(&self, index: usize) {
         self.cmd_tx.send(PlaybackCommand::Jump(index)).unwrap();
     }
@@ -104,12 +109,20 @@ impl PlaybackInterface {
         self.cmd_tx.send(PlaybackCommand::Seek(position)).unwrap();
     }
 
+    pub fn scrub(&self, position: f64) {
+        self.cmd_tx.send(PlaybackCommand::Scrub(position)).unwrap();
+    }
+
     pub fn set_volume(&self, volume: f64) {
         self.cmd_tx
             .send(PlaybackCommand::SetVolume(volume))
             .unwrap();
     }
 
+    pub fn toggle_mute(&self) {
+        self.cmd_tx.send(PlaybackCommand::ToggleMute).unwrap();
+    }
+
     pub fn replace_queue(&self, items: Vec<QueueItemData>) {
         self.cmd_tx
             .send(PlaybackCommand::ReplaceQueue(items))
@@ -156,6 +169,12 @@ impl PlaybackInterface {
             .unwrap();
     }
 
+    pub fn set_shuffle_skips(&self, skips: FxHashSet<i64>) {
+        self.cmd_tx
+            .send(PlaybackCommand::SetShuffleSkips(skips))
+            .unwrap();
+    }
+
     pub fn get_sender(&self) -> UnboundedSender<PlaybackCommand> {
         self.cmd_tx.clone()
     }
@@ -287,6 +306,12 @@ impl PlaybackInterface {
                                 });
                             }
                         }
+                        PlaybackEvent::MuteChanged(v) => {
+                            playback_info.muted.update(cx, |m, cx| {
+                                *m = v;
+                                cx.notify()
+                            });
+                        }
                         PlaybackEvent::QueuePositionChanged(v) => {
                             queue_model.update(cx, |m, cx| {
                                 m.position = v;
@@ -306,14 +331,3 @@ impl PlaybackInterface {
         .detach();
     }
 }
-
-// TODO: this should be in a trait for AppContext
-/// Replace the current queue with the given items.
-pub fn replace_queue(items: Vec<QueueItemData>, app: &mut App) {
-    let playback_interface = app.global::<PlaybackInterface>();
-    playback_interface.replace_queue(items);
-
-    // let data_interface = app.global::<GPUIDataInterface>();
-
-    // data_interface.evict_cache();
-}
@@ -4,7 +4,11 @@ use std::sync::{Arc, RwLock};
 use gpui::{App, AppContext, Entity, SharedString};
 use std::path::PathBuf;
 
-use crate::{library::db::LibraryAccess, ui::data::Decode};
+use crate::{
+    library::db::LibraryAccess,
+    settings::playback::{PlaybackSettings, QueueOverflow},
+    ui::data::Decode,
+};
 
 #[derive(Clone, Debug)]
 pub struct QueueItemData {
@@ -225,3 +229,32 @@ impl QueueItemData {
             .map(|e| e.entity_id().as_u64() as usize)
     }
 }
+
+/// Cuts `items`, which are about to replace the queue, down to the queue size limit. The item at
+/// `start_index` is always kept, since it was chosen to be played. With
+/// [`QueueOverflow::DropPlayed`] the items before it are dropped first, otherwise items are dropped
+/// from the end. Returns the remaining items and the new position of `start_index`.
+pub fn fit_to_limit(
+    settings: &PlaybackSettings,
+    mut items: Vec<QueueItemData>,
+    start_index: usize,
+) -> (Vec<QueueItemData>, usize) {
+    let Some(limit) = settings.max_queue_length else {
+        return (items, start_index);
+    };
+    let limit = limit.max(1);
+
+    if items.len() <= limit {
+        return (items, start_index);
+    }
+
+    let start_index = start_index.min(items.len() - 1);
+    let skip = match settings.queue_overflow {
+        QueueOverflow::DropPlayed => start_index.min(items.len() - limit),
+        QueueOverflow::Reject => (start_index + 1).saturating_sub(limit),
+    };
+
+    items.drain(..skip);
+    items.truncate(limit);
+    (items, start_index - skip)
+}
@@ -2,11 +2,13 @@ mod audio_engine;
 mod device_controller;
 mod media_controller;
 mod queue_manager;
+mod resample_worker;
 
 use std::{
     path::Path,
     sync::{Arc, RwLock},
     thread::sleep,
+    time::Instant,
 };
 
 use itertools::Itertools as _;
@@ -20,7 +22,7 @@ use crate::{
     media::errors::PlaybackStartError,
     playback::{events::RepeatState, session_storage::PlaybackSessionData},
     settings::{
-        playback::PlaybackSettings,
+        playback::{PlaybackSettings, ReplayBehavior},
         replaygain::{ReplayGainAutoHint, calculate_gain},
     },
 };
@@ -28,7 +30,7 @@ use crate::{
 use super::{
     events::{PlaybackCommand, PlaybackEvent},
     interface::PlaybackInterface,
-    queue::QueueItemData,
+    queue::{QueueItemData, fit_to_limit},
 };
 
 use audio_engine::{AudioEngine, EngineCycleResult, EngineState};
@@ -76,14 +78,20 @@ pub struct PlaybackThread {
     position_broadcast_active: bool,
     engine: AudioEngine,
     queue: QueueManager,
-    /// The volume to apply on startup (restored from persisted settings).
-    initial_volume: f64,
+    /// The volume chosen by the user, initially restored from persisted settings. This is kept
+    /// while playback is muted, so it can be restored when unmuting.
+    volume: f64,
+    /// Whether playback is muted.
+    muted: bool,
     /// Current auto-mode hint for ReplayGain.
     rg_auto_hint: ReplayGainAutoHint,
     /// Cached track gain from last metadata update.
     last_track_gain: Option<f64>,
     /// Cached album gain from last metadata update.
     last_album_gain: Option<f64>,
+    /// When playback was last stopped or paused, used to release the audio device after
+    /// [`PlaybackSettings::release_device_after`] seconds.
+    idle_since: Option<Instant>,
 }
 
 impl PlaybackThread {
@@ -104,6 +112,9 @@ impl PlaybackThread {
                 let queue_manager =
                     QueueManager::new(queue, playback_settings.clone(), session, storage_tx);
 
+                let mut engine = AudioEngine::new();
+                engine.update_settings(&playback_settings);
+
                 let mut thread = PlaybackThread {
                     playback_settings,
                     commands_rx,
@@ -111,12 +122,14 @@ impl PlaybackThread {
                     last_timestamp: u64::MAX,
                     last_broadcast_timestamp: u64::MAX,
                     position_broadcast_active: true,
-                    engine: AudioEngine::new(),
+                    engine,
                     queue: queue_manager,
-                    initial_volume: last_volume,
+                    volume: last_volume,
+                    muted: false,
                     rg_auto_hint: ReplayGainAutoHint::PreferTrack,
                     last_track_gain: None,
                     last_album_gain: None,
+                    idle_since: None,
                 };
 
                 thread.run();
@@ -133,7 +146,7 @@ impl PlaybackThread {
             error!("Failed to initialize audio engine: {:?}", e);
         }
 
-        self.set_volume(self.initial_volume);
+        self.set_volume(self.volume);
         self.send_event(PlaybackEvent::RepeatChanged(self.queue.repeat_state()));
         self.send_event(PlaybackEvent::ShuffleToggled(
             self.queue.is_shuffle_enabled(),
@@ -150,14 +163,34 @@ impl PlaybackThread {
         self.command_intake();
 
         if self.engine.state() == EngineState::Playing {
+            self.idle_since = None;
             self.play_audio();
         } else {
+            self.release_idle_device();
             sleep(std::time::Duration::from_millis(10));
         }
 
         self.broadcast_events();
     }
 
+    /// Release the audio device once playback has been stopped or paused for longer than the
+    /// user's configured idle time.
+    fn release_idle_device(&mut self) {
+        let Some(timeout) = self.playback_settings.release_device_timeout() else {
+            return;
+        };
+
+        if !self.engine.has_device() {
+            return;
+        }
+
+        let idle_since = *self.idle_since.get_or_insert_with(Instant::now);
+        if idle_since.elapsed() >= timeout {
+            self.engine.release_device();
+            self.idle_since = None;
+        }
+    }
+
     /// Check for updated metadata and album art, and broadcast it to the UI.
     pub fn broadcast_events(&mut self) {
         self.process_metadata_update();
@@ -184,10 +217,13 @@ impl PlaybackThread {
                 PlaybackCommand::Next => self.next(true),
                 PlaybackCommand::Previous => self.previous(),
                 PlaybackCommand::ClearQueue => self.clear_queue(),
+                PlaybackCommand::ClearPlayed => self.clear_played(),
                 PlaybackCommand::Jump(v) => self.jump(v),
                 PlaybackCommand::JumpUnshuffled(v) => self.jump_unshuffled(v),
                 PlaybackCommand::Seek(v) => self.seek(v),
+                PlaybackCommand::Scrub(v) => self.scrub(v),
                 PlaybackCommand::SetVolume(v) => self.set_volume(v),
+                PlaybackCommand::ToggleMute => self.toggle_mute(),
                 PlaybackCommand::ReplaceQueue(v) => self.replace_queue(v),
                 PlaybackCommand::Stop => self.stop(),
                 PlaybackCommand::ToggleShuffle => self.toggle_shuffle(),
@@ -201,6 +237,7 @@ impl PlaybackThread {
                 PlaybackCommand::ReplaceQueueWithIndex(v, idx) => {
                     self.replace_queue_with_index(v, idx)
                 }
+                PlaybackCommand::SetShuffleSkips(skips) => self.queue.set_shuffle_skips(skips),
             }
         }
     }
@@ -326,6 +363,20 @@ impl PlaybackThread {
         }
     }
 
+    /// Fade out the current track ahead of a transition the user asked for, if
+    /// [`PlaybackSettings::crossfade_on_manual_skip`] is enabled. Returns whether playback was
+    /// faded out, in which case it has to be resumed once the transition is done.
+    fn fade_for_manual_transition(&mut self) -> bool {
+        if !self.playback_settings.crossfade_on_manual_skip
+            || self.state() != PlaybackState::Playing
+        {
+            return false;
+        }
+
+        self.engine.fade_out();
+        true
+    }
+
     /// Skip to the next track in the queue.
     fn next(&mut self, user_initiated: bool) {
         match self.queue.next(user_initiated) {
@@ -336,6 +387,11 @@ impl PlaybackThread {
             } => {
                 info!("Opening next file in queue at index {}", index);
 
+                // automatic advances stay gapless
+                if user_initiated {
+                    self.fade_for_manual_transition();
+                }
+
                 if reshuffled == Reshuffled::Reshuffled {
                     self.send_event(PlaybackEvent::QueueUpdated);
                 }
@@ -348,9 +404,7 @@ impl PlaybackThread {
             }
             QueueNavigationResult::Unchanged { path } => {
                 info!("Repeating current track");
-                if let Err(err) = self.open(&path) {
-                    error!(path = %path.display(), ?err, "Unable to open file: {err}");
-                }
+                self.repeat_current(&path);
             }
             QueueNavigationResult::EndOfQueue => {
                 info!("Playback queue ended, stopping playback");
@@ -359,6 +413,24 @@ impl PlaybackThread {
         }
     }
 
+    /// Play the current track again from the beginning (RepeatOne). The existing stream is
+    /// rewound rather than reopened so that the track loops without a gap. If the stream can't be
+    /// rewound, the file is opened again instead.
+    fn repeat_current(&mut self, path: &Path) {
+        if let Err(err) = self.engine.restart() {
+            warn!(?err, "Unable to rewind current track, reopening it: {err}");
+            if let Err(err) = self.open(path) {
+                error!(path = %path.display(), ?err, "Unable to open file: {err}");
+            }
+            return;
+        }
+
+        // the track hasn't changed, but listeners (such as scrobblers) treat each repeat as a new
+        // play
+        self.send_event(PlaybackEvent::SongChanged(path.to_owned()));
+        self.update_ts(true);
+    }
+
     /// Skip to the previous track in the queue.
     fn previous(&mut self) {
         // If we're past 5 seconds, seek to start instead of going to previous track
@@ -392,6 +464,7 @@ impl PlaybackThread {
                 reshuffled: _,
             } => {
                 info!("Opening previous file in queue at index {}", index);
+                self.fade_for_manual_transition();
 
                 if let Err(err) = self.open(&path) {
                     error!(path = %path.display(), ?err, "Unable to open file: {err}");
@@ -401,6 +474,7 @@ impl PlaybackThread {
             }
             QueueNavigationResult::Unchanged { path } => {
                 info!("At beginning of queue, replaying current track");
+                self.fade_for_manual_transition();
                 if let Err(err) = self.open(&path) {
                     error!(path = %path.display(), ?err, "Unable to open file: {err}");
                 }
@@ -415,8 +489,13 @@ impl PlaybackThread {
     fn queue_item(&mut self, item: &QueueItemData) {
         info!("Adding file to queue: {}", item);
 
-        let index = self.queue.queue_item(item.clone());
+        let position = self.queue.current_position();
+        let Some(index) = self.queue.queue_item(item.clone()) else {
+            warn!("Queue is full, not adding file: {}", item);
+            return;
+        };
         self.refresh_rg_auto_hint();
+        self.notify_dropped_played(position);
 
         if self.state() == PlaybackState::Stopped {
             if !item.get_path().exists() {
@@ -450,12 +529,20 @@ impl PlaybackThread {
             .enumerate()
             .find(|(_, item)| item.get_path().exists())
             .map(|(idx, item)| (idx, item.clone()));
-        let first_index = self.queue.queue_items(items);
+        let position = self.queue.current_position();
+        let added = self.queue.queue_items(items);
+        if added.is_empty() {
+            warn!("Queue is full, not adding files");
+            return;
+        }
+        let first_index = added.start;
         self.refresh_rg_auto_hint();
+        self.notify_dropped_played(position);
 
         // If stopped, start playing the first item
         if self.state() == PlaybackState::Stopped
             && let Some((relative_idx, first)) = first
+            && first_index + relative_idx < added.end
         {
             let path = first.get_path();
 
@@ -470,6 +557,18 @@ impl PlaybackThread {
         self.send_event(PlaybackEvent::QueueUpdated);
     }
 
+    /// Emits a position change if adding to the queue removed already played tracks before the
+    /// current one.
+    fn notify_dropped_played(&mut self, previous_position: Option<usize>) {
+        let position = self.queue.current_position();
+
+        if let Some(position) = position
+            && position != previous_position.unwrap_or(position)
+        {
+            self.send_event(PlaybackEvent::QueuePositionChanged(position));
+        }
+    }
+
     /// Move an item from one position to another in the queue.
     fn move_item(&mut self, from: usize, to: usize) {
         match self.queue.move_item(from, to) {
@@ -662,6 +761,23 @@ impl PlaybackThread {
 
     /// Seek to the specified timestamp (in seconds).
     fn seek(&mut self, timestamp: f64) {
+        let faded = self.fade_for_manual_transition();
+
+        if let Err(e) = self.engine.seek(timestamp) {
+            warn!("Failed to seek: {:?}", e);
+        } else {
+            self.update_ts(true);
+        }
+
+        if faded && let Err(e) = self.engine.play() {
+            error!("Failed to resume playback after seeking: {:?}", e);
+            self.send_event(PlaybackEvent::StateChanged(PlaybackState::Paused));
+        }
+    }
+
+    /// Seek to the specified timestamp (in seconds) without fading, since scrubbing seeks many
+    /// times in quick succession.
+    fn scrub(&mut self, timestamp: f64) {
         if let Err(e) = self.engine.seek(timestamp) {
             warn!("Failed to seek: {:?}", e);
         } else {
@@ -673,6 +789,7 @@ impl PlaybackThread {
     fn jump(&mut self, index: usize) {
         match self.queue.jump(index) {
             JumpResult::Jumped { path } => {
+                self.fade_for_manual_transition();
                 if let Err(err) = self.open(&path) {
                     error!(path = %path.display(), ?err, "Unable to open file: {err}");
                 }
@@ -689,6 +806,7 @@ impl PlaybackThread {
     fn jump_unshuffled(&mut self, index: usize) {
         match self.queue.jump_unshuffled(index) {
             JumpResult::Jumped { path } => {
+                self.fade_for_manual_transition();
                 if let Err(err) = self.open(&path) {
                     error!(path = %path.display(), ?err, "Unable to open file: {err}");
                 }
@@ -705,9 +823,30 @@ impl PlaybackThread {
 
     /// Replace the current queue with the given paths.
     fn replace_queue(&mut self, paths: Vec<QueueItemData>) {
+        let (paths, _) = fit_to_limit(&self.playback_settings, paths, 0);
+
+        // playing the same tracks again shouldn't lose the current position
+        if self.state() != PlaybackState::Stopped && self.queue.has_items(&paths) {
+            match self.playback_settings.replay_behavior {
+                ReplayBehavior::Continue => {
+                    debug!("Queue already holds these tracks, continuing");
+                    self.play();
+                    return;
+                }
+                ReplayBehavior::Restart => {
+                    debug!("Queue already holds these tracks, restarting");
+                    if let Some((_, first_index)) = self.queue.first_with_index() {
+                        self.jump(first_index);
+                    }
+                    return;
+                }
+                ReplayBehavior::Replace => (),
+            }
+        }
+
         debug!("Replacing queue with: '{}'", paths.iter().format(":"));
 
-        match self.queue.replace_queue(paths) {
+        match self.queue.replace_queue(paths, None) {
             ReplaceResult::Replaced { first_item } => {
                 self.refresh_rg_auto_hint();
                 if first_item.is_some()
@@ -726,7 +865,18 @@ impl PlaybackThread {
     }
 
     fn replace_queue_with_index(&mut self, paths: Vec<QueueItemData>, idx: usize) {
-        match self.queue.replace_queue(paths) {
+        let (paths, idx) = fit_to_limit(&self.playback_settings, paths, idx);
+
+        // playing a track from the tracks already queued only has to move to it
+        if self.playback_settings.replay_behavior != ReplayBehavior::Replace
+            && self.queue.has_items(&paths)
+        {
+            debug!("Queue already holds these tracks, jumping to {idx}");
+            self.jump_unshuffled(idx);
+            return;
+        }
+
+        match self.queue.replace_queue(paths, Some(idx)) {
             ReplaceResult::Replaced { .. } => {
                 self.refresh_rg_auto_hint();
                 self.jump_unshuffled(idx);
@@ -757,6 +907,19 @@ impl PlaybackThread {
         self.send_event(PlaybackEvent::QueueUpdated);
     }
 
+    /// Remove the tracks before the current one from the queue.
+    fn clear_played(&mut self) {
+        if !self.queue.clear_played() {
+            return;
+        }
+
+        self.refresh_rg_auto_hint();
+        self.send_event(PlaybackEvent::QueuePositionChanged(
+            self.queue.current_position().unwrap_or(0),
+        ));
+        self.send_event(PlaybackEvent::QueueUpdated);
+    }
+
     /// Stop the current playback.
     fn stop(&mut self) {
         self.engine.stop();
@@ -775,6 +938,9 @@ impl PlaybackThread {
 
                 self.send_event(PlaybackEvent::ShuffleToggled(true, position));
                 self.send_event(PlaybackEvent::QueueUpdated);
+
+                // shuffling the entire queue moves the current track to the start
+                self.send_event(PlaybackEvent::QueuePositionChanged(position));
             }
             ShuffleResult::Unshuffled { new_position } => {
                 self.refresh_rg_auto_hint();
@@ -786,8 +952,15 @@ impl PlaybackThread {
         }
     }
 
-    /// Sets the volume of the playback stream.
+    /// Sets the volume of the playback stream, unmuting it if it was muted.
     fn set_volume(&mut self, volume: f64) {
+        self.volume = volume;
+
+        if self.muted {
+            self.muted = false;
+            self.send_event(PlaybackEvent::MuteChanged(false));
+        }
+
         if let Err(e) = self.engine.set_volume(volume) {
             warn!("Failed to set volume: {:?}", e);
         }
@@ -795,6 +968,18 @@ impl PlaybackThread {
         self.send_event(PlaybackEvent::VolumeChanged(volume));
     }
 
+    /// Mutes or unmutes the playback stream without changing the volume level.
+    fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+
+        let volume = if self.muted { 0.0 } else { self.volume };
+        if let Err(e) = self.engine.set_volume(volume) {
+            warn!("Failed to set volume: {:?}", e);
+        }
+
+        self.send_event(PlaybackEvent::MuteChanged(self.muted));
+    }
+
     /// Sets the repeat mode.
     fn set_repeat(&mut self, state: RepeatState) {
         self.queue.set_repeat(state);
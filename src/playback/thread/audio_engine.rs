@@ -9,15 +9,18 @@ use crate::{
     },
     media::{
         errors::{PlaybackStartError, SeekError},
-        pipeline::{AudioPipeline, DEFAULT_BUFFER_FRAMES, DecodeResult},
+        pipeline::{
+            AudioPipeline, BufferSizes, DEFAULT_BUFFER_FRAMES, DecodeResult, FALLBACK_PACKET_FRAMES,
+        },
         traits::F32DecodeResult,
     },
     playback::thread::media_controller::CompleteMetadata,
-    settings::playback::PlaybackSettings,
+    settings::playback::{PlaybackSettings, ResampleQuality, ResamplerThreading},
 };
 
 use super::device_controller::DeviceController;
 use super::media_controller::MediaController;
+use super::resample_worker::{DECODER_BUFFER_FACTOR, ResampleWorker, ResamplerParams};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
@@ -77,6 +80,13 @@ pub struct AudioEngine {
     device: DeviceController,
     pipeline: Option<AudioPipeline>,
     resampler: Option<Resampler>,
+    /// The worker resampling the current pipeline, if resampling has been offloaded. While the
+    /// worker is running it owns the resampler.
+    resample_worker: Option<ResampleWorker>,
+    resampler_threading: ResamplerThreading,
+    resample_quality: ResampleQuality,
+    /// The size of the pipeline's buffers, in frames per channel.
+    buffer_frames: usize,
     state: EngineState,
     /// Whether a stream reset is pending (e.g., after seek).
     pending_reset: bool,
@@ -89,6 +99,10 @@ impl AudioEngine {
             device: DeviceController::new(),
             pipeline: None,
             resampler: None,
+            resample_worker: None,
+            resampler_threading: ResamplerThreading::default(),
+            resample_quality: ResampleQuality::default(),
+            buffer_frames: DEFAULT_BUFFER_FRAMES,
             state: EngineState::Idle,
             pending_reset: false,
         }
@@ -118,6 +132,9 @@ impl AudioEngine {
     pub fn open(&mut self, path: &Path) -> Result<OpenInfo, PlaybackStartError> {
         info!("AudioEngine: Opening track '{}'", path.display());
 
+        // Clear the pipeline for the new track, but preserve the resampler for gapless playback
+        // The resampler will be reused if params match, or recreated in process_decode_resample if needed
+        self.release_pipeline();
         self.reset_resampler();
 
         // Handle paused state - reset device if needed
@@ -138,10 +155,6 @@ impl AudioEngine {
             recreation_required = true;
         }
 
-        // Clear the pipeline for the new track, but preserve the resampler for gapless playback
-        // The resampler will be reused if params match, or recreated in process_decode_resample if needed
-        self.pipeline = None;
-
         let media_info = self.media.open(path)?;
 
         // Check if we need to recreate the stream for different channel count
@@ -187,6 +200,8 @@ impl AudioEngine {
         match self.state {
             EngineState::Playing => Ok(()),
             EngineState::Paused => {
+                self.reacquire_device()?;
+
                 if self.device.has_stream() {
                     if self.pending_reset {
                         if let Err(err) = self.device.reset() {
@@ -231,6 +246,8 @@ impl AudioEngine {
                 Ok(())
             }
             EngineState::Ready => {
+                self.reacquire_device()?;
+
                 if self.device.has_stream()
                     && let Err(err) = self.device.play()
                 {
@@ -262,6 +279,24 @@ impl AudioEngine {
         Ok(())
     }
 
+    /// Fade out whatever is playing and drop the samples buffered for the device.
+    ///
+    /// The engine is left paused. The next call to [`Self::open`] or [`Self::play`] resets the
+    /// device stream, so the new audio fades in instead of cutting in over the old audio. Does
+    /// nothing unless playing.
+    pub fn fade_out(&mut self) {
+        if self.state != EngineState::Playing || !self.device.has_stream() {
+            return;
+        }
+
+        if let Err(e) = self.device.pause() {
+            warn!("Failed to fade out device: {:?}", e);
+        }
+
+        self.state = EngineState::Paused;
+        self.pending_reset = true;
+    }
+
     /// Stop playback and clear all state.
     pub fn stop(&mut self) {
         self.media.close();
@@ -269,6 +304,43 @@ impl AudioEngine {
         self.state = EngineState::Idle;
     }
 
+    /// Whether the device stream is currently open.
+    pub fn has_device(&self) -> bool {
+        self.device.has_stream()
+    }
+
+    /// Close the device stream so that other applications can use the device.
+    ///
+    /// Does nothing while playing. The loaded media is kept, and the stream is reopened the next
+    /// time playback is started or a track is opened. Samples that were buffered for the device
+    /// are dropped, the same as when seeking.
+    pub fn release_device(&mut self) {
+        if self.state == EngineState::Playing || !self.device.has_stream() {
+            return;
+        }
+
+        // the next device may have a different sample rate, so the resampler can't be reused
+        self.clear_pipeline();
+        self.device.close_stream();
+        self.pending_reset = false;
+        info!("Released audio device");
+    }
+
+    /// Reopen the device stream if it was released with [`Self::release_device`].
+    fn reacquire_device(&mut self) -> Result<(), EngineError> {
+        if self.device.has_stream() {
+            return Ok(());
+        }
+
+        info!("Reacquiring audio device");
+        let channels = self.media.channels().ok();
+        self.device
+            .create_stream(channels)
+            .map_err(|e| EngineError::DeviceError(format!("Failed to reopen stream: {:?}", e)))?;
+
+        Ok(())
+    }
+
     /// Seek to the specified time in seconds.
     pub fn seek(&mut self, time: f64) -> Result<(), SeekError> {
         let result = self.media.seek(time);
@@ -278,6 +350,19 @@ impl AudioEngine {
         result
     }
 
+    /// Rewind the current track to the beginning without reopening it.
+    ///
+    /// Unlike [`Self::seek`], samples that are already buffered for the device are kept, so the
+    /// end of the track flows directly into its beginning. This is used to loop a single track
+    /// without a gap.
+    pub fn restart(&mut self) -> Result<(), SeekError> {
+        if self.state != EngineState::Playing || !self.media.has_stream() {
+            return Err(SeekError::InvalidState);
+        }
+
+        self.media.seek(0.0)
+    }
+
     /// Set the playback volume (0.0 to 1.0).
     pub fn set_volume(&mut self, volume: f64) -> Result<(), EngineError> {
         self.device
@@ -310,11 +395,12 @@ impl AudioEngine {
 
     /// Update settings that affect playback.
     ///
-    /// Currently this is a placeholder for future settings that might affect
-    /// the audio engine directly (e.g., resampler quality settings).
-    pub fn update_settings(&mut self, _settings: &PlaybackSettings) {
-        // Currently no engine-specific settings to update.
-        // This method exists for future extensibility.
+    /// Settings that affect the pipeline are applied the next time a pipeline is set up. The
+    /// resample quality is applied as soon as the next packet is decoded.
+    pub fn update_settings(&mut self, settings: &PlaybackSettings) {
+        self.resampler_threading = settings.resampler_threading;
+        self.resample_quality = settings.resample_quality;
+        self.buffer_frames = settings.normalized_buffer_frames();
     }
 
     /// Process one cycle of the audio pipeline.
@@ -356,6 +442,7 @@ impl AudioEngine {
 
         match result {
             DecodeStepResult::Eof => {
+                self.flush_resample_worker();
                 info!("EOF, track finished");
                 return EngineCycleResult::Eof;
             }
@@ -363,6 +450,7 @@ impl AudioEngine {
                 error!("Fatal error in audio engine");
                 return EngineCycleResult::FatalError(msg);
             }
+            DecodeStepResult::ChannelCountChanged => return self.reconfigure_channels(),
             DecodeStepResult::Continue => {}
         }
 
@@ -462,19 +550,49 @@ impl AudioEngine {
             .sample_rate()
             .unwrap_or(device_format.sample_rate); // Fallback to device rate if unavailable
 
-        let pipeline = AudioPipeline::new(
+        let max_packet_frames = self
+            .media
+            .max_frame_duration()
+            .map(|frames| frames as usize)
+            .unwrap_or(FALLBACK_PACKET_FRAMES);
+
+        // The worker gets a larger decoder buffer so that decoding doesn't stall while it catches
+        // up, without adding latency in front of the device.
+        let use_worker = self.resampler_threading.use_worker();
+        let sizes = BufferSizes {
+            device: self.buffer_frames,
+            decoder: if use_worker {
+                self.buffer_frames * DECODER_BUFFER_FACTOR
+            } else {
+                self.buffer_frames
+            },
+            max_packet: max_packet_frames,
+        };
+
+        let mut pipeline = AudioPipeline::new(
             channel_count,
             source_format,
             source_rate,
             device_format.sample_type,
             device_format.sample_rate,
-            DEFAULT_BUFFER_FRAMES,
+            sizes,
         );
 
-        if pipeline.is_passthrough() {
-            info!("Using f32 passthrough pipeline (no conversion needed)");
-        } else {
-            info!("Using f64 conversion pipeline");
+        match &mut pipeline {
+            AudioPipeline::F32Passthrough(_) => {
+                info!("Using f32 passthrough pipeline (no conversion needed)");
+            }
+            AudioPipeline::Convert(p) => {
+                info!("Using f64 conversion pipeline");
+
+                if use_worker && let Some(stage) = p.resample_stage.take() {
+                    self.resample_worker = Some(ResampleWorker::start(
+                        stage,
+                        self.resampler.take(),
+                        p.resample_frames,
+                    ));
+                }
+            }
         }
 
         self.pipeline = Some(pipeline);
@@ -482,10 +600,88 @@ impl AudioEngine {
         Ok(())
     }
 
+    /// Rebuild the pipeline (and, if needed, the device stream) after the media reported a
+    /// different channel count than the one the pipeline was set up with.
+    ///
+    /// The pipeline is set up again on the next cycle using the updated channel count.
+    fn reconfigure_channels(&mut self) -> EngineCycleResult {
+        let channels = match self.media.channels() {
+            Ok(channels) => channels,
+            Err(e) => {
+                error!("Failed to get channels: {:?}", e);
+                return EngineCycleResult::FatalError(format!("Failed to get channels: {:?}", e));
+            }
+        };
+
+        info!(
+            "Channel count changed to {}, rebuilding pipeline",
+            channels.count()
+        );
+        self.release_pipeline();
+
+        if self.device.needs_format_change(channels) {
+            info!(
+                "Channel count mismatch, re-opening with the correct channel count (if supported)"
+            );
+
+            if let Err(e) = self.device.recreate_stream(true, Some(channels)) {
+                error!("Failed to recreate stream: {:?}", e);
+                return EngineCycleResult::FatalError(format!(
+                    "Failed to recreate stream: {:?}",
+                    e
+                ));
+            }
+
+            if let Err(e) = self.device.play() {
+                error!("Device was recreated and we still can't play: {:?}", e);
+                return EngineCycleResult::FatalError(format!("Failed to play device: {:?}", e));
+            }
+        }
+
+        EngineCycleResult::Continue
+    }
+
+    /// Drop the current pipeline, stopping the resampler worker (if any) and taking back its
+    /// resampler.
+    fn release_pipeline(&mut self) {
+        if let Some(worker) = self.resample_worker.take() {
+            // Discard whatever the worker writes so that it can't block on a full buffer
+            let result = worker.stop(|| {
+                if let Some(AudioPipeline::Convert(p)) = &mut self.pipeline {
+                    p.device_input.try_read_to_staging(p.resample_frames);
+                }
+            });
+            self.resampler = result.and_then(|(_, resampler)| resampler);
+        }
+
+        self.pipeline = None;
+    }
+
+    /// Wait for the resampler worker (if any) to process all remaining input, sending the output
+    /// to the device, then move the resampling step back onto the playback thread.
+    ///
+    /// This is used at the end of a track so that the samples still in flight aren't lost.
+    fn flush_resample_worker(&mut self) {
+        let Some(worker) = self.resample_worker.take() else {
+            return;
+        };
+
+        let Some((stage, resampler)) = worker.finish(|| {
+            self.consume_to_device();
+        }) else {
+            return;
+        };
+
+        if let Some(AudioPipeline::Convert(p)) = &mut self.pipeline {
+            p.resample_stage = Some(stage);
+        }
+        self.resampler = resampler;
+    }
+
     /// Clear the pipeline and resampler completely (e.g., on stop).
     /// For track transitions, prefer clearing only the pipeline to preserve the resampler for gapless playback.
     fn clear_pipeline(&mut self) {
-        self.pipeline = None;
+        self.release_pipeline();
         self.resampler = None;
     }
 
@@ -528,6 +724,12 @@ impl AudioEngine {
                 }
             }
             AudioPipeline::Convert(p) => {
+                // The worker frees up space in the decoder's buffer only once the device has
+                // taken its output, so decoding into a full buffer would block both threads
+                if self.resample_worker.is_some() && !p.has_room_for_packet() {
+                    return Ok(DecodeStepResult::Continue);
+                }
+
                 let decode_result = match self.media.decode_into(&p.decoder_output) {
                     Ok(result) => result,
                     Err(e) => {
@@ -541,14 +743,28 @@ impl AudioEngine {
                         return Ok(DecodeStepResult::Eof);
                     }
                     DecodeResult::Decoded { rate, .. } => {
-                        // Only recreate resampler if parameters actually changed
                         let duration = self.media.frame_duration().unwrap_or(1024);
+
+                        if let Some(worker) = &mut self.resample_worker {
+                            worker.configure(ResamplerParams {
+                                source_rate: rate,
+                                target_rate: p.target_rate,
+                                duration,
+                                channels: p.channel_count,
+                                sub_chunks: self.resample_quality.sub_chunks(),
+                            });
+                            p.source_rate = rate;
+                            return Ok(DecodeStepResult::Continue);
+                        }
+
+                        // Only recreate resampler if parameters actually changed
                         let needs_new_resampler = match &self.resampler {
                             Some(resampler) => !resampler.matches_params(
                                 rate,
                                 p.target_rate,
                                 duration,
                                 p.channel_count,
+                                self.resample_quality.sub_chunks(),
                             ),
                             None => true,
                         };
@@ -559,6 +775,7 @@ impl AudioEngine {
                                 p.target_rate,
                                 duration,
                                 p.channel_count as u16,
+                                self.resample_quality.sub_chunks(),
                             ));
                         }
 
@@ -566,11 +783,12 @@ impl AudioEngine {
                     }
                 }
 
-                if let Some(resampler) = &mut self.resampler {
+                if let (Some(resampler), Some(stage)) = (&mut self.resampler, &mut p.resample_stage)
+                {
                     let _processed = resampler.process_ring_buffers(
-                        &mut p.resampler_input,
-                        &p.device_input_producers,
-                        DEFAULT_BUFFER_FRAMES,
+                        &mut stage.input,
+                        &stage.output,
+                        p.resample_frames,
                     );
                 }
 
@@ -611,6 +829,7 @@ impl AudioEngine {
                 error!("Fatal decoding error: {}", s);
                 Ok(DecodeStepResult::FatalError(s))
             }
+            PlaybackReadError::ChannelCountChanged => Ok(DecodeStepResult::ChannelCountChanged),
         }
     }
 }
@@ -626,4 +845,6 @@ enum DecodeStepResult {
     Continue,
     Eof,
     FatalError(String),
+    /// The media's channel count doesn't match the pipeline's.
+    ChannelCountChanged,
 }
@@ -179,6 +179,13 @@ impl MediaController {
             .frame_duration()
     }
 
+    pub fn max_frame_duration(&self) -> Result<u64, FrameDurationError> {
+        self.media_stream
+            .as_ref()
+            .ok_or(FrameDurationError::NeverStarted)?
+            .max_frame_duration()
+    }
+
     pub fn sample_rate(&self) -> Result<u32, ChannelRetrievalError> {
         self.media_stream
             .as_ref()
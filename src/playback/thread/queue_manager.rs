@@ -1,14 +1,16 @@
 use std::{
     mem::take,
+    ops::Range,
     path::PathBuf,
     sync::{Arc, RwLock},
 };
 
 use rand::{rng, seq::SliceRandom};
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
     playback::{events::RepeatState, queue::QueueItemData, session_storage::PlaybackSessionData},
-    settings::playback::PlaybackSettings,
+    settings::playback::{PlaybackSettings, QueueOverflow, ShuffleScope},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -109,6 +111,8 @@ pub struct QueueManager {
     /// If queue_next == queue.len(), we're on the last track.
     queue_next: usize,
     repeat: RepeatState,
+    /// Database IDs of tracks that are left out when building a shuffled queue.
+    shuffle_skips: FxHashSet<i64>,
     storage_tx: tokio::sync::watch::Sender<PlaybackSessionData>,
 }
 
@@ -140,6 +144,100 @@ impl QueueManager {
         (start..queue.len()).find(|idx| Self::item_is_playable(&queue[*idx]))
     }
 
+    fn is_shuffle_skipped(&self, item: &QueueItemData) -> bool {
+        item.get_db_id()
+            .is_some_and(|id| self.shuffle_skips.contains(&id))
+    }
+
+    /// Returns a shuffled copy of `items`, leaving out tracks that are flagged to be skipped when
+    /// shuffling. The item at `keep` is always included, since it was explicitly chosen. If every
+    /// item would be left out, all of them are shuffled instead.
+    fn shuffled_without_skips(
+        &self,
+        items: &[QueueItemData],
+        keep: Option<usize>,
+    ) -> Vec<QueueItemData> {
+        let mut shuffled: Vec<QueueItemData> = items
+            .iter()
+            .enumerate()
+            .filter(|(idx, item)| Some(*idx) == keep || !self.is_shuffle_skipped(item))
+            .map(|(_, item)| item.clone())
+            .collect();
+
+        if shuffled.is_empty() {
+            shuffled = items.to_vec();
+        }
+
+        shuffled.shuffle(&mut rng());
+        shuffled
+    }
+
+    /// Whether `queue` could be a shuffled copy of `original`. Tracks that are skipped when
+    /// shuffling are left out of the shuffled queue, so `original` may hold more items.
+    fn is_shuffle_of(queue: &[QueueItemData], original: &[QueueItemData]) -> bool {
+        if original.len() < queue.len() {
+            return false;
+        }
+
+        let mut counts: FxHashMap<&PathBuf, usize> = FxHashMap::default();
+        for item in original {
+            *counts.entry(item.get_path()).or_default() += 1;
+        }
+
+        queue
+            .iter()
+            .all(|item| match counts.get_mut(item.get_path()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    true
+                }
+                _ => false,
+            })
+    }
+
+    /// Makes room for `incoming` new items within the configured queue size limit. If the limit
+    /// would be exceeded and the overflow behavior allows it, the oldest already played items are
+    /// removed. Returns how many of the new items fit.
+    fn make_room(&mut self, incoming: usize) -> usize {
+        let Some(limit) = self.playback_settings.max_queue_length else {
+            return incoming;
+        };
+
+        let (mut available, played) = {
+            let queue = self.queue.read().expect("poisoned queue lock");
+            (
+                limit.saturating_sub(queue.len()),
+                self.queue_next.saturating_sub(1).min(queue.len()),
+            )
+        };
+
+        if available < incoming
+            && self.playback_settings.queue_overflow == QueueOverflow::DropPlayed
+        {
+            let drop_count = (incoming - available).min(played);
+            self.drop_played(drop_count);
+            available += drop_count;
+        }
+
+        incoming.min(available)
+    }
+
+    /// Removes the first `count` items from the queue, which must all have been played already,
+    /// along with their copies in the unshuffled queue.
+    fn drop_played(&mut self, count: usize) {
+        let mut queue = self.queue.write().expect("poisoned queue lock");
+
+        for removed in queue.drain(..count) {
+            if self.shuffle
+                && let Some(pos) = self.original_queue.iter().position(|item| item == &removed)
+            {
+                self.original_queue.remove(pos);
+            }
+        }
+
+        self.queue_next -= count;
+    }
+
     fn prev_playable_before(queue: &[QueueItemData], end_exclusive: usize) -> Option<usize> {
         (0..end_exclusive)
             .rev()
@@ -162,7 +260,8 @@ impl QueueManager {
         let (queue_len, original_queue) = {
             let queue = queue.read().expect("poisoned queue lock");
             let queue_len = queue.len();
-            let original_queue = if shuffle && session_original_queue.len() == queue_len {
+            let original_queue = if shuffle && Self::is_shuffle_of(&queue, &session_original_queue)
+            {
                 session_original_queue
             } else if shuffle {
                 queue.clone()
@@ -181,6 +280,7 @@ impl QueueManager {
             original_queue,
             shuffle,
             queue_next: queue_position.map_or(0, |position| position + 1),
+            shuffle_skips: FxHashSet::default(),
             storage_tx,
         }
     }
@@ -219,6 +319,15 @@ impl QueueManager {
             .all(|item| item.get_db_album_id() == Some(first_album))
     }
 
+    /// Returns true if the queue holds exactly `items`, in the order they were queued in.
+    pub fn has_items(&self, items: &[QueueItemData]) -> bool {
+        if self.shuffle {
+            return self.original_queue == items;
+        }
+
+        *self.queue.read().expect("poisoned queue lock") == items
+    }
+
     /// Get the first playable item in the queue along with its index.
     pub fn first_with_index(&self) -> Option<(QueueItemData, usize)> {
         self.queue
@@ -258,6 +367,12 @@ impl QueueManager {
         }
     }
 
+    /// Set the tracks that are left out when building a shuffled queue. Only affects queues
+    /// shuffled after this call.
+    pub fn set_shuffle_skips(&mut self, skips: FxHashSet<i64>) {
+        self.shuffle_skips = skips;
+    }
+
     /// Advance to the next track in the queue.
     ///
     /// Returns information about what track to play next, or if playback should stop.
@@ -404,8 +519,12 @@ impl QueueManager {
 
     /// Add a single item to the end of the queue.
     ///
-    /// Returns the index where the item was added.
-    pub fn queue_item(&mut self, item: QueueItemData) -> usize {
+    /// Returns the index where the item was added, or `None` if the queue is full.
+    pub fn queue_item(&mut self, item: QueueItemData) -> Option<usize> {
+        if self.make_room(1) == 0 {
+            return None;
+        }
+
         let mut queue = self.queue.write().expect("poisoned queue lock");
 
         if self.shuffle {
@@ -419,42 +538,56 @@ impl QueueManager {
         drop(queue);
         self.persist_session_with_queue();
 
-        index
+        Some(index)
     }
 
     /// Add multiple items to the end of the queue.
     ///
-    /// If shuffle is enabled, the new items are shuffled before being added.
-    /// Returns the index of the first item added.
-    pub fn queue_items(&mut self, items: Vec<QueueItemData>) -> usize {
+    /// If shuffle is enabled, the new items are shuffled before being added, leaving out tracks
+    /// that are skipped when shuffling. Items that don't fit within the queue size limit are not
+    /// added. Returns the indices of the added items.
+    pub fn queue_items(&mut self, mut items: Vec<QueueItemData>) -> Range<usize> {
+        items.truncate(self.make_room(items.len()));
+
         if items.is_empty() {
-            return self.len();
+            let len = self.len();
+            return len..len;
         }
 
         let mut queue = self.queue.write().expect("poisoned queue lock");
         let first_index = queue.len();
 
         if self.shuffle {
-            self.original_queue.extend(items.clone());
+            // a single track was explicitly chosen, so it is never skipped
+            let keep = (items.len() == 1).then_some(0);
+            queue.extend(self.shuffled_without_skips(&items, keep));
 
-            let mut shuffled = items.clone();
-            shuffled.shuffle(&mut rng());
-            queue.extend(shuffled);
+            self.original_queue.extend(items);
         } else {
             queue.extend(items.clone());
         }
 
+        let added = first_index..queue.len();
+
         drop(queue);
         self.persist_session_with_queue();
 
-        first_index
+        added
     }
 
     /// Insert a single item at a specific position.
+    ///
+    /// If the queue is full, the item is not inserted.
     pub fn insert_item(&mut self, position: usize, item: QueueItemData) -> InsertResult {
+        let queue_next = self.queue_next;
+        if self.make_room(1) == 0 {
+            return InsertResult::Unchanged;
+        }
+        let dropped = queue_next - self.queue_next;
+
         let mut queue = self.queue.write().expect("poisoned queue lock");
 
-        let insert_pos = position.min(queue.len());
+        let insert_pos = position.saturating_sub(dropped).min(queue.len());
 
         if self.shuffle {
             self.original_queue.push(item.clone());
@@ -470,6 +603,11 @@ impl QueueManager {
                 first_index: insert_pos,
                 new_position: self.queue_next - 1,
             }
+        } else if dropped > 0 {
+            InsertResult::InsertedMovedCurrent {
+                first_index: insert_pos,
+                new_position: self.queue_next - 1,
+            }
         } else {
             InsertResult::Inserted {
                 first_index: insert_pos,
@@ -481,14 +619,20 @@ impl QueueManager {
     }
 
     /// Insert multiple items at a specific position.
-    pub fn insert_items(&mut self, position: usize, items: Vec<QueueItemData>) -> InsertResult {
+    ///
+    /// Items that don't fit within the queue size limit are not inserted.
+    pub fn insert_items(&mut self, position: usize, mut items: Vec<QueueItemData>) -> InsertResult {
+        let queue_next = self.queue_next;
+        items.truncate(self.make_room(items.len()));
+        let dropped = queue_next - self.queue_next;
+
         if items.is_empty() {
             return InsertResult::Unchanged;
         }
 
         let mut queue = self.queue.write().expect("poisoned queue lock");
 
-        let insert_pos = position.min(queue.len());
+        let insert_pos = position.saturating_sub(dropped).min(queue.len());
         let items_len = items.len();
 
         if self.shuffle {
@@ -505,6 +649,11 @@ impl QueueManager {
                 first_index: insert_pos,
                 new_position: self.queue_next - 1,
             }
+        } else if dropped > 0 {
+            InsertResult::InsertedMovedCurrent {
+                first_index: insert_pos,
+                new_position: self.queue_next - 1,
+            }
         } else {
             InsertResult::Inserted {
                 first_index: insert_pos,
@@ -601,16 +750,19 @@ impl QueueManager {
 
     /// Replace the entire queue with new items.
     ///
-    /// If shuffle is enabled, the items are shuffled (but original order is preserved).
-    pub fn replace_queue(&mut self, items: Vec<QueueItemData>) -> ReplaceResult {
+    /// If shuffle is enabled, the items are shuffled (but original order is preserved). Tracks that
+    /// are skipped when shuffling are left out of the shuffled queue, except for the item at
+    /// `start_index`, which the user explicitly chose to play.
+    pub fn replace_queue(
+        &mut self,
+        items: Vec<QueueItemData>,
+        start_index: Option<usize>,
+    ) -> ReplaceResult {
         let mut queue = self.queue.write().expect("poisoned queue lock");
 
         if self.shuffle {
-            let mut shuffled = items.clone();
-            shuffled.shuffle(&mut rng());
-
+            *queue = self.shuffled_without_skips(&items, start_index);
             self.original_queue = items.clone();
-            *queue = shuffled;
         } else {
             self.original_queue.clear();
             *queue = items.clone();
@@ -661,7 +813,29 @@ impl QueueManager {
         self.persist_session_with_queue();
     }
 
+    /// Remove every track before the current one, keeping the current track and everything after
+    /// it. Returns false if there were no played tracks to remove.
+    pub fn clear_played(&mut self) -> bool {
+        let played = {
+            let queue = self.queue.read().expect("poisoned queue lock");
+            self.queue_next.saturating_sub(1).min(queue.len())
+        };
+
+        if played == 0 {
+            return false;
+        }
+
+        self.drop_played(played);
+        self.persist_session_with_queue();
+
+        true
+    }
+
     /// Toggle shuffle mode.
+    ///
+    /// When enabling shuffle, [`PlaybackSettings::shuffle_scope`] decides whether only the
+    /// upcoming tracks or the whole queue is shuffled. In both cases the unshuffled queue is kept,
+    /// so [`Self::jump_unshuffled`] and disabling shuffle use the original order of every track.
     pub fn toggle_shuffle(&mut self) -> ShuffleResult {
         let result = {
             let mut queue = self.queue.write().expect("poisoned queue lock");
@@ -671,9 +845,33 @@ impl QueueManager {
             if self.shuffle {
                 self.original_queue = queue.clone();
 
-                let start = self.queue_next.min(queue.len());
-                if start < queue.len() {
-                    queue[start..].shuffle(&mut rng());
+                match self.playback_settings.shuffle_scope {
+                    ShuffleScope::Upcoming => {
+                        let start = self.queue_next.min(queue.len());
+                        if start < queue.len() {
+                            let upcoming = self.shuffled_without_skips(&queue[start..], None);
+                            queue.truncate(start);
+                            queue.extend(upcoming);
+                        }
+                    }
+                    ShuffleScope::Entire => {
+                        let current = (self.queue_next > 0 && self.queue_next <= queue.len())
+                            .then(|| self.queue_next - 1);
+                        let mut shuffled = self.shuffled_without_skips(&queue, current);
+
+                        // the current track keeps playing, as the first track of the new order
+                        if let Some(current_item) = current.map(|idx| &queue[idx])
+                            && let Some(pos) = shuffled.iter().position(|item| item == current_item)
+                        {
+                            let current_item = shuffled.remove(pos);
+                            shuffled.insert(0, current_item);
+                            self.queue_next = 1;
+                        } else {
+                            self.queue_next = 0;
+                        }
+
+                        *queue = shuffled;
+                    }
                 }
 
                 ShuffleResult::Shuffled
@@ -0,0 +1,203 @@
+use std::{
+    sync::mpsc::{Receiver, RecvTimeoutError, Sender, TryRecvError, channel},
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use tracing::{error, info, warn};
+
+use crate::{devices::resample::Resampler, media::pipeline::ResampleStage};
+
+/// How long the worker waits for new commands when there was no input to resample.
+const IDLE_WAIT: Duration = Duration::from_millis(2);
+
+/// How often the playback thread drains the device buffer while waiting for the worker to exit.
+const PUMP_INTERVAL: Duration = Duration::from_millis(1);
+
+/// How many times larger than the device buffer the decoder's buffer is made when resampling on
+/// a worker, so that the decoder can keep running ahead of the resampler.
+pub const DECODER_BUFFER_FACTOR: usize = 4;
+
+/// The parameters the worker's resampler must match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResamplerParams {
+    pub source_rate: u32,
+    pub target_rate: u32,
+    pub duration: u64,
+    pub channels: usize,
+    pub sub_chunks: usize,
+}
+
+enum WorkerCommand {
+    /// Ensure the resampler matches the given parameters, recreating it if necessary.
+    Configure(ResamplerParams),
+    /// Resample whatever input is left, then exit and hand the stage and resampler back.
+    Finish,
+    /// Exit without resampling the remaining input, handing the stage and resampler back.
+    Stop,
+}
+
+/// Runs the resampling step of a [`crate::media::pipeline::ConvertPipeline`] on a dedicated
+/// thread.
+///
+/// The playback thread keeps decoding into the pipeline and consuming from the device input,
+/// while the worker moves samples between the two. The resampler is handed back when the worker
+/// is finished so that it can be reused for gapless playback.
+pub struct ResampleWorker {
+    commands_tx: Sender<WorkerCommand>,
+    done_rx: Receiver<(ResampleStage, Option<Resampler>)>,
+    handle: JoinHandle<()>,
+    params: Option<ResamplerParams>,
+}
+
+impl ResampleWorker {
+    /// Spawns a worker that owns the given stage and (optionally) an existing resampler.
+    pub fn start(stage: ResampleStage, resampler: Option<Resampler>, buffer_frames: usize) -> Self {
+        let (commands_tx, commands_rx) = channel();
+        let (done_tx, done_rx) = channel();
+
+        let handle = std::thread::Builder::new()
+            .name("resampler".to_string())
+            .spawn(move || {
+                let _ = done_tx.send(run(stage, resampler, commands_rx, buffer_frames));
+            })
+            .expect("unable to spawn resampler thread");
+
+        info!("Resampling on a dedicated worker thread");
+
+        Self {
+            commands_tx,
+            done_rx,
+            handle,
+            params: None,
+        }
+    }
+
+    /// Tells the worker which parameters the resampler should use. Only sends a command if the
+    /// parameters have changed since the last call.
+    pub fn configure(&mut self, params: ResamplerParams) {
+        if self.params == Some(params) {
+            return;
+        }
+
+        if self
+            .commands_tx
+            .send(WorkerCommand::Configure(params))
+            .is_err()
+        {
+            warn!("Resampler worker exited unexpectedly");
+            return;
+        }
+
+        self.params = Some(params);
+    }
+
+    /// Resamples the remaining input, then stops the worker and returns the stage and resampler
+    /// it owned. Returns `None` if the worker panicked.
+    ///
+    /// `pump` is called repeatedly until the worker exits. It must consume from the device input
+    /// of the pipeline, otherwise the worker may block forever writing into a full buffer.
+    pub fn finish(self, pump: impl FnMut()) -> Option<(ResampleStage, Option<Resampler>)> {
+        self.shut_down(WorkerCommand::Finish, pump)
+    }
+
+    /// Stops the worker without resampling the remaining input, and returns the stage and
+    /// resampler it owned. Returns `None` if the worker panicked.
+    ///
+    /// `pump` has the same requirements as in [`ResampleWorker::finish`], since the worker may be
+    /// in the middle of writing a chunk.
+    pub fn stop(self, pump: impl FnMut()) -> Option<(ResampleStage, Option<Resampler>)> {
+        self.shut_down(WorkerCommand::Stop, pump)
+    }
+
+    fn shut_down(
+        self,
+        command: WorkerCommand,
+        mut pump: impl FnMut(),
+    ) -> Option<(ResampleStage, Option<Resampler>)> {
+        let _ = self.commands_tx.send(command);
+
+        let result = loop {
+            match self.done_rx.recv_timeout(PUMP_INTERVAL) {
+                Ok(result) => break Some(result),
+                Err(RecvTimeoutError::Timeout) => pump(),
+                Err(RecvTimeoutError::Disconnected) => break None,
+            }
+        };
+
+        if let Err(err) = self.handle.join() {
+            error!("Resampler worker panicked: {:?}", err);
+        }
+
+        result
+    }
+}
+
+fn run(
+    mut stage: ResampleStage,
+    mut resampler: Option<Resampler>,
+    commands_rx: Receiver<WorkerCommand>,
+    buffer_frames: usize,
+) -> (ResampleStage, Option<Resampler>) {
+    loop {
+        let processed = process(&mut stage, resampler.as_mut(), buffer_frames);
+
+        let command = if processed == 0 {
+            match commands_rx.recv_timeout(IDLE_WAIT) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            match commands_rx.try_recv() {
+                Ok(command) => Some(command),
+                Err(TryRecvError::Empty) => None,
+                Err(TryRecvError::Disconnected) => break,
+            }
+        };
+
+        match command {
+            Some(WorkerCommand::Configure(params)) => {
+                let matches = resampler.as_ref().is_some_and(|resampler| {
+                    resampler.matches_params(
+                        params.source_rate,
+                        params.target_rate,
+                        params.duration,
+                        params.channels,
+                        params.sub_chunks,
+                    )
+                });
+
+                if !matches {
+                    resampler = Some(Resampler::new(
+                        params.source_rate,
+                        params.target_rate,
+                        params.duration,
+                        params.channels as u16,
+                        params.sub_chunks,
+                    ));
+                }
+            }
+            Some(WorkerCommand::Finish) => {
+                while process(&mut stage, resampler.as_mut(), buffer_frames) > 0 {}
+                break;
+            }
+            Some(WorkerCommand::Stop) => break,
+            None => {}
+        }
+    }
+
+    (stage, resampler)
+}
+
+fn process(
+    stage: &mut ResampleStage,
+    resampler: Option<&mut Resampler>,
+    buffer_frames: usize,
+) -> usize {
+    let Some(resampler) = resampler else {
+        return 0;
+    };
+
+    resampler.process_ring_buffers(&mut stage.input, &stage.output, buffer_frames)
+}
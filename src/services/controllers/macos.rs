@@ -10,8 +10,8 @@ use objc2_media_player::{
     MPChangePlaybackPositionCommandEvent, MPMediaItemArtwork, MPMediaItemPropertyAlbumTitle,
     MPMediaItemPropertyArtist, MPMediaItemPropertyArtwork, MPMediaItemPropertyPlaybackDuration,
     MPMediaItemPropertyTitle, MPNowPlayingInfoCenter, MPNowPlayingInfoPropertyElapsedPlaybackTime,
-    MPNowPlayingPlaybackState, MPRemoteCommandCenter, MPRemoteCommandEvent,
-    MPRemoteCommandHandlerStatus,
+    MPNowPlayingInfoPropertyPlaybackRate, MPNowPlayingPlaybackState, MPRemoteCommandCenter,
+    MPRemoteCommandEvent, MPRemoteCommandHandlerStatus,
 };
 use raw_window_handle::RawWindowHandle;
 use tracing::{debug, error};
@@ -178,6 +178,34 @@ impl MacMediaPlayerController {
                 PlaybackState::Playing => MPNowPlayingPlaybackState::Playing,
                 PlaybackState::Paused => MPNowPlayingPlaybackState::Paused,
             });
+
+            if state == PlaybackState::Stopped {
+                // nothing is loaded anymore, so the widget shouldn't keep showing the last track
+                media_center.setNowPlayingInfo(None);
+                return;
+            }
+
+            let now_playing: Retained<NSMutableDictionary<NSString>> =
+                NSMutableDictionary::dictionary();
+
+            if let Some(prev_now_playing) = media_center.nowPlayingInfo() {
+                now_playing.addEntriesFromDictionary(&prev_now_playing);
+            }
+
+            // the widget advances the elapsed time on its own between position updates, at this
+            // rate
+            let rate = if state == PlaybackState::Playing {
+                1.0
+            } else {
+                0.0
+            };
+            let ns = NSNumber::numberWithDouble(rate);
+            now_playing.setObject_forKey(
+                &ns,
+                ProtocolObject::from_ref(MPNowPlayingInfoPropertyPlaybackRate),
+            );
+
+            media_center.setNowPlayingInfo(Some(&*now_playing));
         }
     }
 
@@ -218,6 +246,17 @@ impl MacMediaPlayerController {
             cmd.setEnabled(true);
             cmd.addTargetWithHandler(&toggle_handler);
 
+            // Stop
+            let stop_bridge = self.bridge.clone();
+            let stop_handler = RcBlock::new(move |_| {
+                stop_bridge.stop();
+                MPRemoteCommandHandlerStatus::Success
+            });
+
+            let cmd = command_center.stopCommand();
+            cmd.setEnabled(true);
+            cmd.addTargetWithHandler(&stop_handler);
+
             // Previous Track
             let prev_bridge = self.bridge.clone();
             let prev_handler = RcBlock::new(move |_| {
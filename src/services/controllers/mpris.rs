@@ -217,9 +217,8 @@ impl PlayerInterface for MprisControllerServer {
         let data = self.data.read().await;
 
         if let Some(position) = data.last_position {
-            let offset = offset.as_secs();
-            self.bridge
-                .seek(position.saturating_add_signed(offset) as f64);
+            let position = position as f64 + offset.as_micros() as f64 / 1_000_000.0;
+            self.bridge.seek(position.max(0.0));
         }
 
         Ok(())
@@ -247,8 +246,8 @@ impl PlayerInterface for MprisControllerServer {
         _track_id: mpris_server::TrackId, // TODO: handle this?
         position: Time,
     ) -> fdo::Result<()> {
-        let position = position.as_secs();
-        self.bridge.seek(position as f64);
+        let position = position.as_micros() as f64 / 1_000_000.0;
+        self.bridge.seek(position.max(0.0));
 
         Ok(())
     }
@@ -1,16 +1,21 @@
-use std::{ffi::c_void, path::Path, time::Duration};
+use std::{
+    ffi::c_void,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use raw_window_handle::RawWindowHandle;
 use windows::{
     Foundation::TypedEventHandler,
     Media::{
-        MediaPlaybackAutoRepeatMode, MediaPlaybackStatus, MediaPlaybackType,
-        SystemMediaTransportControls, SystemMediaTransportControlsButton,
-        SystemMediaTransportControlsButtonPressedEventArgs,
+        AutoRepeatModeChangeRequestedEventArgs, MediaPlaybackAutoRepeatMode, MediaPlaybackStatus,
+        MediaPlaybackType, PlaybackPositionChangeRequestedEventArgs,
+        ShuffleEnabledChangeRequestedEventArgs, SystemMediaTransportControls,
+        SystemMediaTransportControlsButton, SystemMediaTransportControlsButtonPressedEventArgs,
         SystemMediaTransportControlsDisplayUpdater, SystemMediaTransportControlsTimelineProperties,
     },
-    Storage::Streams::{DataWriter, InMemoryRandomAccessStream, RandomAccessStreamReference},
+    Storage::{StorageFile, Streams::RandomAccessStreamReference},
     Win32::{Foundation::HWND, System::WinRT::ISystemMediaTransportControlsInterop},
     core::HSTRING,
 };
@@ -30,6 +35,12 @@ pub struct WindowsController {
     bridge: ControllerBridge,
 }
 
+/// The file the current album art is written to. SMTC only reliably shows thumbnails that are
+/// backed by a file, so the art can't be handed over from memory.
+fn album_art_path() -> PathBuf {
+    std::env::temp_dir().join(format!("hummingbird-smtc-art-{}", std::process::id()))
+}
+
 impl WindowsController {
     pub fn connect_events(&mut self) -> anyhow::Result<()> {
         self.controls.SetIsEnabled(true)?;
@@ -37,6 +48,7 @@ impl WindowsController {
         self.controls.SetIsPreviousEnabled(true)?;
         self.controls.SetIsPlayEnabled(true)?;
         self.controls.SetIsPauseEnabled(true)?;
+        self.controls.SetIsStopEnabled(true)?;
 
         let bridge = self.bridge.clone();
         self.controls.ButtonPressed(&TypedEventHandler::<
@@ -48,6 +60,7 @@ impl WindowsController {
             match event {
                 SystemMediaTransportControlsButton::Play => bridge.play(),
                 SystemMediaTransportControlsButton::Pause => bridge.pause(),
+                SystemMediaTransportControlsButton::Stop => bridge.stop(),
                 SystemMediaTransportControlsButton::Next => bridge.next(),
                 SystemMediaTransportControlsButton::Previous => bridge.previous(),
                 _ => (),
@@ -56,10 +69,61 @@ impl WindowsController {
             Ok(())
         }))?;
 
+        let bridge = self.bridge.clone();
+        self.controls
+            .PlaybackPositionChangeRequested(&TypedEventHandler::<
+                SystemMediaTransportControls,
+                PlaybackPositionChangeRequestedEventArgs,
+            >::new(move |_, args| {
+                let position: Duration = args.as_ref().unwrap().RequestedPlaybackPosition()?.into();
+                bridge.seek(position.as_secs_f64());
+
+                Ok(())
+            }))?;
+
+        let bridge = self.bridge.clone();
+        self.controls
+            .AutoRepeatModeChangeRequested(&TypedEventHandler::<
+                SystemMediaTransportControls,
+                AutoRepeatModeChangeRequestedEventArgs,
+            >::new(move |_, args| {
+                let repeat = match args.as_ref().unwrap().RequestedAutoRepeatMode()? {
+                    MediaPlaybackAutoRepeatMode::Track => RepeatState::RepeatingOne,
+                    MediaPlaybackAutoRepeatMode::List => RepeatState::Repeating,
+                    _ => RepeatState::NotRepeating,
+                };
+                bridge.set_repeat(repeat);
+
+                Ok(())
+            }))?;
+
+        // the playback thread only knows how to toggle shuffle, so the flyout's requested state is
+        // only acted on when it differs from ours
+        let bridge = self.bridge.clone();
+        let controls = self.controls.clone();
+        self.controls
+            .ShuffleEnabledChangeRequested(&TypedEventHandler::<
+                SystemMediaTransportControls,
+                ShuffleEnabledChangeRequestedEventArgs,
+            >::new(move |_, args| {
+                let requested = args.as_ref().unwrap().RequestedShuffleEnabled()?;
+                if requested != controls.ShuffleEnabled()? {
+                    bridge.toggle_shuffle();
+                }
+
+                Ok(())
+            }))?;
+
         Ok(())
     }
 }
 
+impl Drop for WindowsController {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(album_art_path());
+    }
+}
+
 impl InitPlaybackController for WindowsController {
     fn init(
         bridge: ControllerBridge,
@@ -162,18 +226,11 @@ impl PlaybackController for WindowsController {
     }
 
     async fn album_art_changed(&mut self, album_art: &[u8]) -> anyhow::Result<()> {
-        let stream = InMemoryRandomAccessStream::new().expect("could not create RAS");
-        let writer = DataWriter::CreateDataWriter(&stream).unwrap();
+        let path = album_art_path();
+        tokio::fs::write(&path, album_art).await?;
 
-        writer.WriteBytes(album_art)?;
-
-        writer
-            .StoreAsync()
-            .expect("could not start store operation")
-            .await?;
-
-        writer.DetachStream()?;
-        let reference = RandomAccessStreamReference::CreateFromStream(&stream)?;
+        let file = StorageFile::GetFileFromPathAsync(&HSTRING::from(path.as_os_str()))?.await?;
+        let reference = RandomAccessStreamReference::CreateFromFile(&file)?;
 
         self.display.SetThumbnail(&reference)?;
         self.display.Update()?;
@@ -203,6 +260,11 @@ impl PlaybackController for WindowsController {
 
         self.controls.SetPlaybackStatus(playback_state)?;
 
+        if playback_state == MediaPlaybackStatus::Stopped {
+            self.display.ClearAll()?;
+            self.display.Update()?;
+        }
+
         Ok(())
     }
     async fn shuffle_state_changed(&mut self, shuffling: bool) -> anyhow::Result<()> {
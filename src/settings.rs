@@ -4,6 +4,7 @@ pub mod replaygain;
 pub mod scan;
 pub mod services;
 pub mod storage;
+pub mod transfer;
 pub mod update;
 
 use std::{
@@ -266,6 +267,24 @@ mod tests {
         assert_eq!(settings.update.auto_update, defaults.update.auto_update);
     }
 
+    #[test]
+    fn create_settings_clamps_out_of_range_buffer_frames() {
+        let dir = create_test_dir();
+        fs::write(
+            settings_path(&dir),
+            serde_json::to_vec(&json!({ "playback": { "buffer_frames": 16 } })).unwrap(),
+        )
+        .unwrap();
+
+        let settings = create_settings(&settings_path(&dir));
+
+        assert_eq!(settings.playback.buffer_frames, 16);
+        assert_eq!(
+            settings.playback.normalized_buffer_frames(),
+            crate::settings::playback::MIN_BUFFER_FRAMES
+        );
+    }
+
     #[test]
     fn create_settings_deserializes_valid_json() {
         let dir = create_test_dir();
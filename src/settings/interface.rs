@@ -1,9 +1,14 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
 pub const DEFAULT_GRID_MIN_ITEM_WIDTH: f32 = 192.0;
 pub const MIN_GRID_MIN_ITEM_WIDTH: f32 = 128.0;
 pub const MAX_GRID_MIN_ITEM_WIDTH: f32 = 384.0;
 
+/// Windows narrower than this use the compact playback controls when the layout is automatic.
+pub const COMPACT_CONTROLS_WINDOW_WIDTH: f32 = 1000.0;
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum StartupLibraryView {
@@ -14,10 +19,213 @@ pub enum StartupLibraryView {
     LikedSongs,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ListDensity {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+impl ListDensity {
+    /// The height of a table row, including its bottom border.
+    pub fn table_row_height(self) -> f32 {
+        match self {
+            ListDensity::Comfortable => 36.0,
+            ListDensity::Compact => 28.0,
+        }
+    }
+
+    /// The height of a track row in a track listing, including its bottom border.
+    pub fn track_row_height(self) -> f32 {
+        match self {
+            ListDensity::Comfortable => 39.0,
+            ListDensity::Compact => 31.0,
+        }
+    }
+
+    /// The vertical padding applied to the contents of a row.
+    pub fn row_padding(self) -> f32 {
+        match self {
+            ListDensity::Comfortable => 6.0,
+            ListDensity::Compact => 3.0,
+        }
+    }
+}
+
+/// Which lines of text are shown under album covers in grids.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GridMetadata {
+    /// Only show the covers.
+    None,
+    Title,
+    TitleArtist,
+    #[default]
+    TitleArtistYear,
+}
+
+impl GridMetadata {
+    pub fn shows_title(self) -> bool {
+        self != GridMetadata::None
+    }
+
+    pub fn shows_artist(self) -> bool {
+        matches!(
+            self,
+            GridMetadata::TitleArtist | GridMetadata::TitleArtistYear
+        )
+    }
+
+    pub fn shows_year(self) -> bool {
+        self == GridMetadata::TitleArtistYear
+    }
+}
+
+/// What happens when a track on an album, playlist or artist page is clicked.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackClickAction {
+    /// Replace the queue with the surrounding album, playlist or table, starting at the track.
+    #[default]
+    PlayFromHere,
+    /// Add only the clicked track to the queue and play it.
+    PlayTrack,
+}
+
+/// What is queued alongside a track that is clicked in the Tracks table.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SingleTrackContext {
+    /// Add only the clicked track to the queue and play it.
+    JustThis,
+    /// Replace the queue with every track currently shown, in the current sort order.
+    #[default]
+    AllVisible,
+    /// Replace the queue with the clicked track's album.
+    Album,
+}
+
+/// What happens when the main window is closed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseBehavior {
+    /// Quit Hummingbird.
+    #[default]
+    Quit,
+    /// Show an icon in the system tray and minimize the window, keeping playback going.
+    MinimizeToTray,
+}
+
+/// How the playback controls at the bottom of the window are laid out.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlsLayout {
+    /// Use the compact layout when the window is narrow.
+    #[default]
+    Automatic,
+    /// Always show every control.
+    Full,
+    /// Always use the compact layout, which moves the less used controls into a menu.
+    Compact,
+}
+
+impl ControlsLayout {
+    pub fn is_compact(self, window_width: f32) -> bool {
+        match self {
+            ControlsLayout::Automatic => window_width < COMPACT_CONTROLS_WINDOW_WIDTH,
+            ControlsLayout::Full => false,
+            ControlsLayout::Compact => true,
+        }
+    }
+}
+
+/// What is shown in place of album art for albums that don't have any.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingArt {
+    /// Leave the space empty.
+    Blank,
+    /// Show a music note.
+    #[default]
+    Placeholder,
+    /// Show the image at `missing_art_path`.
+    Custom,
+}
+
+/// Which tracks are listed below the albums on an artist's page.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtistTracks {
+    /// List the artist's liked tracks.
+    #[default]
+    Liked,
+    /// List all of the artist's tracks.
+    All,
+    /// Don't list any tracks.
+    Hidden,
+}
+
+/// What the title of the main window shows, for example in the taskbar.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowTitle {
+    /// Always show the app name.
+    #[default]
+    AppName,
+    /// Show the current track and its artist.
+    Track,
+    /// Show the current track, its artist and the position in the track.
+    TrackAndPosition,
+}
+
+/// What happens when the titlebar is double-clicked.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TitlebarDoubleClick {
+    /// Maximize the window, or restore it if it is already maximized.
+    #[default]
+    Maximize,
+    /// Minimize the window.
+    Minimize,
+    /// Do nothing.
+    Nothing,
+}
+
+/// How many album art images are kept in memory.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageCacheSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl ImageCacheSize {
+    /// The number of images kept by the largest caches, like the ones used by library tables.
+    /// Smaller caches are sized relative to this.
+    pub fn budget(self) -> usize {
+        match self {
+            ImageCacheSize::Small => 100,
+            ImageCacheSize::Medium => 200,
+            ImageCacheSize::Large => 500,
+        }
+    }
+}
+
 fn default_grid_min_item_width() -> f32 {
     DEFAULT_GRID_MIN_ITEM_WIDTH
 }
 
+fn default_show_jump_bar() -> bool {
+    true
+}
+
+fn default_seek_while_dragging() -> bool {
+    true
+}
+
 pub fn clamp_grid_min_item_width(value: f32) -> f32 {
     if !value.is_finite() {
         return DEFAULT_GRID_MIN_ITEM_WIDTH;
@@ -41,7 +249,39 @@ pub struct InterfaceSettings {
     #[serde(default = "default_grid_min_item_width")]
     pub grid_min_item_width: f32,
     #[serde(default)]
+    pub grid_metadata: GridMetadata,
+    #[serde(default)]
     pub always_show_scrollbars: bool,
+    #[serde(default)]
+    pub list_density: ListDensity,
+    #[serde(default)]
+    pub track_click_action: TrackClickAction,
+    #[serde(default)]
+    pub single_track_context: SingleTrackContext,
+    #[serde(default = "default_show_jump_bar")]
+    pub show_jump_bar: bool,
+    #[serde(default = "default_seek_while_dragging")]
+    pub seek_while_dragging: bool,
+    #[serde(default)]
+    pub show_waveform: bool,
+    #[serde(default)]
+    pub show_format_badges: bool,
+    #[serde(default)]
+    pub close_behavior: CloseBehavior,
+    #[serde(default)]
+    pub titlebar_double_click: TitlebarDoubleClick,
+    #[serde(default)]
+    pub controls_layout: ControlsLayout,
+    #[serde(default)]
+    pub window_title: WindowTitle,
+    #[serde(default)]
+    pub missing_art: MissingArt,
+    #[serde(default)]
+    pub missing_art_path: Option<PathBuf>,
+    #[serde(default)]
+    pub artist_tracks: ArtistTracks,
+    #[serde(default)]
+    pub image_cache_size: ImageCacheSize,
 }
 
 impl InterfaceSettings {
@@ -63,7 +303,23 @@ impl Default for InterfaceSettings {
             two_column_library: false,
             startup_library_view: StartupLibraryView::default(),
             grid_min_item_width: DEFAULT_GRID_MIN_ITEM_WIDTH,
+            grid_metadata: GridMetadata::default(),
             always_show_scrollbars: false,
+            list_density: ListDensity::default(),
+            track_click_action: TrackClickAction::default(),
+            single_track_context: SingleTrackContext::default(),
+            show_jump_bar: true,
+            seek_while_dragging: true,
+            show_waveform: false,
+            show_format_badges: false,
+            close_behavior: CloseBehavior::default(),
+            titlebar_double_click: TitlebarDoubleClick::default(),
+            controls_layout: ControlsLayout::default(),
+            window_title: WindowTitle::default(),
+            missing_art: MissingArt::default(),
+            missing_art_path: None,
+            artist_tracks: ArtistTracks::default(),
+            image_cache_size: ImageCacheSize::default(),
         }
     }
 }
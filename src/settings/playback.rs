@@ -1,11 +1,120 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
+use crate::media::pipeline::DEFAULT_BUFFER_FRAMES;
+
 use super::replaygain::ReplayGainSettings;
 
+pub const MIN_BUFFER_FRAMES: usize = 4096;
+pub const MAX_BUFFER_FRAMES: usize = 65536;
+
+/// The minimum number of hardware threads required for [`ResamplerThreading::Auto`] to move
+/// resampling off of the playback thread.
+pub const AUTO_RESAMPLER_WORKER_MIN_THREADS: usize = 4;
+
+/// Controls which thread performs sample rate conversion.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ResamplerThreading {
+    /// Resample on the playback thread, between decoding and handing samples to the device.
+    #[default]
+    PlaybackThread,
+    /// Resample on a dedicated worker thread, decoupling decoding from device consumption.
+    Worker,
+    /// Use a dedicated worker thread if the system reports at least
+    /// [`AUTO_RESAMPLER_WORKER_MIN_THREADS`] hardware threads, otherwise resample on the playback
+    /// thread.
+    Auto,
+}
+
+impl ResamplerThreading {
+    /// Whether or not resampling should be performed on a dedicated worker thread.
+    pub fn use_worker(self) -> bool {
+        match self {
+            ResamplerThreading::PlaybackThread => false,
+            ResamplerThreading::Worker => true,
+            ResamplerThreading::Auto => std::thread::available_parallelism()
+                .map(|threads| threads.get() >= AUTO_RESAMPLER_WORKER_MIN_THREADS)
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// The quality of sample rate conversion, trading CPU usage for accuracy.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ResampleQuality {
+    /// Use shorter filters, for slower CPUs.
+    Fast,
+    /// A balance between CPU usage and accuracy.
+    #[default]
+    Balanced,
+    /// Use the longest filters, for critical listening.
+    High,
+}
+
+impl ResampleQuality {
+    /// The number of sub-chunks each resampler chunk is split into. Fewer sub-chunks result in
+    /// longer FFTs, and therefore longer and steeper anti-aliasing filters.
+    pub fn sub_chunks(self) -> usize {
+        match self {
+            ResampleQuality::Fast => 8,
+            ResampleQuality::Balanced => 2,
+            ResampleQuality::High => 1,
+        }
+    }
+}
+
+/// What happens when tracks are added to a queue that has reached
+/// [`PlaybackSettings::max_queue_length`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueOverflow {
+    /// Remove the oldest tracks that were already played to make room for the new ones.
+    #[default]
+    DropPlayed,
+    /// Don't add tracks that don't fit.
+    Reject,
+}
+
+/// Which part of the queue is shuffled when shuffle is turned on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ShuffleScope {
+    /// Only shuffle the tracks after the current one, keeping already played tracks in place.
+    #[default]
+    Upcoming,
+    /// Shuffle the entire queue, moving the current track to the start of it.
+    Entire,
+}
+
+/// What happens when the tracks in the queue are played again, such as by clicking Play on the
+/// album that's already playing.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayBehavior {
+    /// Keep playing from the current position, resuming playback if it's paused.
+    #[default]
+    Continue,
+    /// Start again from the first track in the queue.
+    Restart,
+    /// Replace the queue as if different tracks were played.
+    Replace,
+}
+
 fn default_keep_current_on_queue_clear() -> bool {
     true
 }
 
+fn default_buffer_frames() -> usize {
+    DEFAULT_BUFFER_FRAMES
+}
+
+pub fn clamp_buffer_frames(value: usize) -> usize {
+    value.clamp(MIN_BUFFER_FRAMES, MAX_BUFFER_FRAMES)
+}
+
 /// User-set playback settings, to be passed to the playback thread.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PlaybackSettings {
@@ -47,6 +156,127 @@ pub struct PlaybackSettings {
     /// ReplayGain settings.
     #[serde(default)]
     pub replaygain: ReplayGainSettings,
+
+    /// Determines which thread performs sample rate conversion.
+    ///
+    /// Resampling high sample rate or multichannel content can be expensive enough on slower CPUs
+    /// that the playback thread can't keep the device fed, causing underruns. Moving resampling to
+    /// a worker thread lets decoding and resampling happen in parallel. Changes apply when the
+    /// next track is opened.
+    ///
+    /// Defaults to resampling on the playback thread.
+    #[serde(default)]
+    pub resampler_threading: ResamplerThreading,
+
+    /// The quality of sample rate conversion, used when a track's sample rate doesn't match the
+    /// device's.
+    ///
+    /// Higher quality settings use longer filters, which attenuate aliasing more but cost more CPU
+    /// time. Changes apply immediately, the resampler is recreated the next time a packet is
+    /// decoded.
+    ///
+    /// Defaults to balanced.
+    #[serde(default)]
+    pub resample_quality: ResampleQuality,
+
+    /// The size, in frames per channel, of the buffers between the decoder, resampler and output
+    /// device.
+    ///
+    /// Larger buffers give the playback thread more headroom to keep up with the device, which can
+    /// prevent stuttering on slow or busy systems, but increase latency: pausing, seeking and
+    /// volume changes take longer to be heard. Smaller buffers make transport controls more
+    /// responsive at the risk of underruns. For reference, 8192 frames is roughly 190ms at 44.1kHz.
+    /// Values are clamped to between [`MIN_BUFFER_FRAMES`] and [`MAX_BUFFER_FRAMES`], and each
+    /// buffer is grown further if needed to hold a whole decoded packet. Changes apply when the
+    /// next track is opened.
+    ///
+    /// Defaults to [`DEFAULT_BUFFER_FRAMES`] (8192).
+    #[serde(default = "default_buffer_frames")]
+    pub buffer_frames: usize,
+
+    /// The maximum number of tracks the queue can hold, or `None` for no limit.
+    ///
+    /// Long radio sessions and repeatedly adding large selections can otherwise grow the queue
+    /// without bound. When the limit is reached, [`PlaybackSettings::queue_overflow`] decides what
+    /// happens to newly added tracks. Lowering the limit doesn't remove tracks that are already
+    /// queued.
+    ///
+    /// Defaults to no limit.
+    #[serde(default)]
+    pub max_queue_length: Option<usize>,
+
+    /// What happens when tracks are added to a full queue.
+    ///
+    /// Defaults to removing already played tracks.
+    #[serde(default)]
+    pub queue_overflow: QueueOverflow,
+
+    /// Which part of the queue is shuffled when shuffle is turned on. Turning shuffle off restores
+    /// the original order of the whole queue in either case.
+    ///
+    /// Defaults to only shuffling upcoming tracks.
+    #[serde(default)]
+    pub shuffle_scope: ShuffleScope,
+
+    /// What happens when the queue is replaced with the same tracks it already holds, in the same
+    /// order. Only applies while a track is playing or paused.
+    ///
+    /// Defaults to continuing from the current position.
+    #[serde(default)]
+    pub replay_behavior: ReplayBehavior,
+
+    /// The number of tracks the queue has to hold before replacing or clearing it asks for
+    /// confirmation, or `None` to never ask.
+    ///
+    /// The confirmation also offers to add the new tracks to the queue instead. Playing the tracks
+    /// the queue already holds doesn't ask, unless [`PlaybackSettings::replay_behavior`] rebuilds
+    /// the queue.
+    ///
+    /// Defaults to never asking.
+    #[serde(default)]
+    pub confirm_queue_discard_over: Option<usize>,
+
+    /// Whether the last played track should start playing when Hummingbird is opened.
+    ///
+    /// If the option is false, the last played track is opened paused. Tracks passed on the
+    /// command line always start playing.
+    ///
+    /// Defaults to false.
+    #[serde(default)]
+    pub resume_on_startup: bool,
+
+    /// The number of seconds playback has to be stopped or paused before the audio device is
+    /// released, or 0 to keep it open.
+    ///
+    /// Keeping the device open lets playback resume instantly, but some systems won't sleep, and
+    /// some devices can't be used in exclusive mode by other applications, while it's open. The
+    /// device is reopened when playback is resumed.
+    ///
+    /// Defaults to 0.
+    #[serde(default)]
+    pub release_device_after: u64,
+
+    /// Whether transitions started by the user fade out and back in instead of cutting.
+    ///
+    /// This applies to jumping to a track in the queue, skipping to the next or previous track,
+    /// and seeking. Tracks that end on their own are always followed by the next one without a
+    /// gap.
+    ///
+    /// Defaults to false.
+    #[serde(default)]
+    pub crossfade_on_manual_skip: bool,
+}
+
+impl PlaybackSettings {
+    pub fn normalized_buffer_frames(&self) -> usize {
+        clamp_buffer_frames(self.buffer_frames)
+    }
+
+    /// How long playback has to be idle before the audio device is released, if it should be
+    /// released at all.
+    pub fn release_device_timeout(&self) -> Option<Duration> {
+        (self.release_device_after > 0).then(|| Duration::from_secs(self.release_device_after))
+    }
 }
 
 #[allow(clippy::derivable_impls)]
@@ -57,6 +287,17 @@ impl Default for PlaybackSettings {
             prev_track_jump_first: false,
             keep_current_on_queue_clear: true,
             replaygain: ReplayGainSettings::default(),
+            resampler_threading: ResamplerThreading::default(),
+            resample_quality: ResampleQuality::default(),
+            buffer_frames: DEFAULT_BUFFER_FRAMES,
+            max_queue_length: None,
+            queue_overflow: QueueOverflow::default(),
+            shuffle_scope: ShuffleScope::default(),
+            replay_behavior: ReplayBehavior::default(),
+            confirm_queue_discard_over: None,
+            resume_on_startup: false,
+            release_device_after: 0,
+            crossfade_on_manual_skip: false,
         }
     }
 }
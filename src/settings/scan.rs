@@ -1,5 +1,6 @@
 #[cfg(not(target_os = "windows"))]
 use std::fs::exists;
+use std::time::Duration;
 
 use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
@@ -15,12 +16,60 @@ pub enum MissingFolderPolicy {
     DeleteFromLibrary,
 }
 
+/// Where album art found while scanning is kept.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtStorage {
+    /// Art is stored in the library database.
+    #[default]
+    Database,
+    /// Art is stored as image files in the data directory, and the library database only records
+    /// their names.
+    Files,
+}
+
+/// Which album art is used when a file has embedded art and its folder also contains a cover
+/// image.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtPriority {
+    /// Use the embedded art, and only fall back to the folder's cover image.
+    #[default]
+    EmbeddedFirst,
+    /// Use the folder's cover image, and only fall back to the embedded art.
+    FolderFirst,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ScanSettings {
     #[serde(default = "retrieve_default_paths")]
     pub paths: Vec<Utf8PathBuf>,
     #[serde(default)]
     pub missing_folder_policy: MissingFolderPolicy,
+    /// How long to wait for a single file's metadata to be read, in seconds, before giving up on
+    /// it. Files that time out are skipped until they change. `0` disables the timeout.
+    #[serde(default = "default_file_read_timeout")]
+    pub file_read_timeout: u64,
+    /// The minimum time between writes of the scan record, in minutes. Between writes, changes
+    /// are kept in memory, and they are always written when Hummingbird closes. `0` writes the
+    /// record after every scan.
+    #[serde(default)]
+    pub record_write_interval: u64,
+    /// Whether small thumbnails of album art are stored in the library during scanning. When
+    /// disabled, lists and grids scale the full-size art down while rendering instead.
+    #[serde(default = "default_generate_thumbnails")]
+    pub generate_thumbnails: bool,
+    /// Where album art is stored. Art that is already stored elsewhere is moved at the start of
+    /// the next scan.
+    #[serde(default)]
+    pub art_storage: ArtStorage,
+    /// Whether embedded art or cover images in the album's folder are preferred.
+    #[serde(default)]
+    pub art_priority: ArtPriority,
+    /// Whether the library is scanned when Hummingbird starts. When disabled, the library is only
+    /// scanned when requested.
+    #[serde(default = "default_scan_on_startup")]
+    pub scan_on_startup: bool,
 }
 
 impl Default for ScanSettings {
@@ -28,10 +77,41 @@ impl Default for ScanSettings {
         Self {
             paths: retrieve_default_paths(),
             missing_folder_policy: MissingFolderPolicy::default(),
+            file_read_timeout: default_file_read_timeout(),
+            record_write_interval: 0,
+            generate_thumbnails: true,
+            art_storage: ArtStorage::default(),
+            art_priority: ArtPriority::default(),
+            scan_on_startup: true,
         }
     }
 }
 
+impl ScanSettings {
+    /// Returns the per-file metadata read timeout, or `None` if it is disabled.
+    pub fn file_read_timeout(&self) -> Option<Duration> {
+        (self.file_read_timeout > 0).then_some(Duration::from_secs(self.file_read_timeout))
+    }
+
+    /// Returns the minimum time between scan record writes, or `None` to write after every scan.
+    pub fn record_write_interval(&self) -> Option<Duration> {
+        (self.record_write_interval > 0)
+            .then_some(Duration::from_secs(self.record_write_interval * 60))
+    }
+}
+
+fn default_file_read_timeout() -> u64 {
+    30
+}
+
+fn default_generate_thumbnails() -> bool {
+    true
+}
+
+fn default_scan_on_startup() -> bool {
+    true
+}
+
 fn retrieve_default_paths() -> Vec<Utf8PathBuf> {
     #[cfg(target_os = "windows")]
     {
@@ -10,6 +10,10 @@ use crate::{
 
 pub const DEFAULT_SIDEBAR_WIDTH: Pixels = px(225.0);
 pub const DEFAULT_QUEUE_WIDTH: Pixels = px(275.0);
+pub const MIN_SIDEBAR_WIDTH: Pixels = px(175.0);
+pub const MAX_SIDEBAR_WIDTH: Pixels = px(350.0);
+pub const MIN_QUEUE_WIDTH: Pixels = px(225.0);
+pub const MAX_QUEUE_WIDTH: Pixels = px(800.0);
 pub const DEFAULT_SPLIT_FRACTION: Pixels = px(0.50);
 pub const DEFAULT_LYRICS_FRACTION: Pixels = px(0.35);
 pub const DEFAULT_CONTROLS_LEFT_WIDTH: Pixels = px(275.0);
@@ -27,10 +31,24 @@ fn default_split_fraction() -> f32 {
     f32::from(DEFAULT_SPLIT_FRACTION)
 }
 
+/// Returns a stored panel width within the range the panel can be resized to. Missing or
+/// nonsensical widths, such as from a damaged file, are replaced with `default`.
+fn restore_width(width: f32, default: Pixels, min: Pixels, max: Pixels) -> Pixels {
+    if width.is_finite() && width > 0.0 {
+        px(width).clamp(min, max)
+    } else {
+        default
+    }
+}
+
 fn default_volume() -> f64 {
     1.0
 }
 
+fn default_show_queue() -> bool {
+    true
+}
+
 fn default_table_settings() -> HashMap<String, TableSettings> {
     HashMap::new()
 }
@@ -85,8 +103,10 @@ fn default_split_fractions() -> HashMap<String, f32> {
     HashMap::new()
 }
 
-/// The four view keys that have independent split fractions.
-pub const SPLIT_FRACTION_KEYS: [&str; 4] = ["albums", "tracks", "artists", "playlist"];
+/// The view keys that have independent split fractions.
+pub const SPLIT_FRACTION_KEYS: [&str; 6] = [
+    "albums", "timeline", "tracks", "artists", "playlist", "stats",
+];
 
 /// Data to store while quitting the app
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,7 +124,8 @@ pub struct StorageData {
     /// reading old config files.  New saves always populate `split_fractions`.
     #[serde(default = "default_split_fraction")]
     pub split_fraction: f32,
-    /// Per-view split fractions keyed by view name (albums, tracks, artists, playlist).
+    /// Per-view split fractions keyed by view name (albums, timeline, tracks, artists, playlist,
+    /// stats).
     #[serde(default = "default_split_fractions")]
     pub split_fractions: HashMap<String, f32>,
     #[serde(default = "default_table_settings")]
@@ -115,6 +136,9 @@ pub struct StorageData {
     pub playlist_sort_methods: HashMap<i64, PlaylistTrackSortMethod>,
     #[serde(default)]
     pub sidebar_collapsed: bool,
+    /// Whether the queue panel is open
+    #[serde(default = "default_show_queue")]
+    pub show_queue: bool,
     /// Fraction (0..1) of the lyrics panel height
     #[serde(default = "default_lyrics_fraction")]
     pub lyrics_fraction: f32,
@@ -154,6 +178,7 @@ impl StorageData {
             liked_tracks_sort_method: *models.liked_tracks_sort_method.read(cx),
             playlist_sort_methods: models.playlist_sort_methods.read(cx).clone(),
             sidebar_collapsed: *models.sidebar_collapsed.read(cx),
+            show_queue: *models.show_queue.read(cx),
             lyrics_fraction: (*models.lyrics_height.read(cx)).into(),
             controls_left_width: (*models.controls_left_width.read(cx)).into(),
             controls_right_width: (*models.controls_right_width.read(cx)).into(),
@@ -162,11 +187,21 @@ impl StorageData {
     }
 
     pub fn sidebar_width(&self) -> Pixels {
-        px(self.sidebar_width)
+        restore_width(
+            self.sidebar_width,
+            DEFAULT_SIDEBAR_WIDTH,
+            MIN_SIDEBAR_WIDTH,
+            MAX_SIDEBAR_WIDTH,
+        )
     }
 
     pub fn queue_width(&self) -> Pixels {
-        px(self.queue_width)
+        restore_width(
+            self.queue_width,
+            DEFAULT_QUEUE_WIDTH,
+            MIN_QUEUE_WIDTH,
+            MAX_QUEUE_WIDTH,
+        )
     }
 
     /// Return the split fraction for a specific view key (e.g. "albums").
@@ -211,6 +246,7 @@ impl Default for StorageData {
             liked_tracks_sort_method: default_liked_tracks_sort_method(),
             playlist_sort_methods: default_playlist_sort_methods(),
             sidebar_collapsed: false,
+            show_queue: default_show_queue(),
             lyrics_fraction: f32::from(DEFAULT_LYRICS_FRACTION),
             controls_left_width: f32::from(DEFAULT_CONTROLS_LEFT_WIDTH),
             controls_right_width: f32::from(DEFAULT_CONTROLS_RIGHT_WIDTH),
@@ -263,9 +299,12 @@ impl Storage {
 
 #[cfg(test)]
 mod tests {
-    use gpui::{Size, px};
+    use gpui::{Point, Size, px};
 
-    use super::{Storage, StorageData, TableSettings, TableViewModeSetting};
+    use super::{
+        DEFAULT_QUEUE_WIDTH, DEFAULT_SIDEBAR_WIDTH, MAX_QUEUE_WIDTH, MIN_SIDEBAR_WIDTH, Storage,
+        StorageData, TableSettings, TableViewModeSetting,
+    };
     use crate::{
         library::db::{LikedTrackSortMethod, PlaylistTrackSortMethod},
         test_support::TestDir,
@@ -309,6 +348,43 @@ mod tests {
         assert_eq!(data.lyrics_fraction, StorageData::default().lyrics_fraction);
     }
 
+    #[test]
+    fn load_or_default_shows_queue_for_files_without_queue_state() {
+        let dir = create_test_dir();
+        let path = dir.join("storage.json");
+        fs::write(&path, r#"{"current_track":null,"sidebar_collapsed":true}"#).unwrap();
+        let storage = Storage::new(path);
+
+        let data = storage.load_or_default();
+
+        assert!(data.sidebar_collapsed);
+        assert!(data.show_queue);
+    }
+
+    #[test]
+    fn panel_widths_are_clamped_when_restored() {
+        let data = StorageData {
+            sidebar_width: 20.0,
+            queue_width: 5000.0,
+            ..StorageData::default()
+        };
+
+        assert_eq!(data.sidebar_width(), MIN_SIDEBAR_WIDTH);
+        assert_eq!(data.queue_width(), MAX_QUEUE_WIDTH);
+    }
+
+    #[test]
+    fn invalid_panel_widths_are_restored_to_defaults() {
+        let data = StorageData {
+            sidebar_width: 0.0,
+            queue_width: f32::NAN,
+            ..StorageData::default()
+        };
+
+        assert_eq!(data.sidebar_width(), DEFAULT_SIDEBAR_WIDTH);
+        assert_eq!(data.queue_width(), DEFAULT_QUEUE_WIDTH);
+    }
+
     #[test]
     fn save_and_load_roundtrip_preserves_valid_data() {
         let dir = create_test_dir();
@@ -347,12 +423,14 @@ mod tests {
                 (42, PlaylistTrackSortMethod::ArtistDesc),
             ]),
             sidebar_collapsed: true,
+            show_queue: false,
             lyrics_fraction: 0.7,
             controls_left_width: 300.0,
             controls_right_width: 250.0,
             window_information: Some(WindowInformation {
                 maximized: false,
                 size: Size::new(px(800.0), px(800.0)),
+                origin: Some(Point::new(px(40.0), px(60.0))),
             }),
         };
 
@@ -375,6 +453,7 @@ mod tests {
         );
         assert_eq!(loaded.playlist_sort_methods, expected.playlist_sort_methods);
         assert_eq!(loaded.sidebar_collapsed, expected.sidebar_collapsed);
+        assert_eq!(loaded.show_queue, expected.show_queue);
         assert_eq!(loaded.lyrics_fraction, expected.lyrics_fraction);
         assert_eq!(loaded.controls_left_width, expected.controls_left_width);
         assert_eq!(loaded.controls_right_width, expected.controls_right_width);
@@ -416,12 +495,14 @@ mod tests {
             liked_tracks_sort_method: LikedTrackSortMethod::TitleDesc,
             playlist_sort_methods: HashMap::from([(7, PlaylistTrackSortMethod::RecentlyAdded)]),
             sidebar_collapsed: true,
+            show_queue: false,
             lyrics_fraction: 0.4,
             controls_left_width: 200.0,
             controls_right_width: 190.0,
             window_information: Some(WindowInformation {
                 maximized: false,
                 size: Size::new(px(800.0), px(800.0)),
+                origin: Some(Point::new(px(40.0), px(60.0))),
             }),
         };
 
@@ -440,6 +521,7 @@ mod tests {
         );
         assert_eq!(loaded.playlist_sort_methods, stored.playlist_sort_methods);
         assert_eq!(loaded.sidebar_collapsed, stored.sidebar_collapsed);
+        assert_eq!(loaded.show_queue, stored.show_queue);
         assert_eq!(loaded.lyrics_fraction, stored.lyrics_fraction);
         assert_eq!(loaded.controls_left_width, stored.controls_left_width);
         assert_eq!(loaded.controls_right_width, stored.controls_right_width);
@@ -0,0 +1,339 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use super::Settings;
+
+/// The key an exported theme is stored under in an exported settings file.
+///
+/// The rest of the file has the same layout as `settings.json`, so an exported file can also be
+/// used directly as a settings file.
+pub const EXPORTED_THEME_KEY: &str = "exported_theme";
+
+/// A theme file bundled with exported settings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExportedTheme {
+    /// The file name of the theme, without any directories.
+    pub file_name: String,
+    pub contents: Value,
+}
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Invalid settings file: {0}")]
+    Invalid(#[from] serde_json::Error),
+    #[error("Settings file does not contain a settings object")]
+    NotAnObject,
+}
+
+/// Creates an exportable copy of the given settings.
+///
+/// Scan paths are machine-specific, so they are left out unless `include_scan_paths` is set.
+pub fn export_settings(
+    settings: &Settings,
+    include_scan_paths: bool,
+    theme: Option<ExportedTheme>,
+) -> Value {
+    let mut value = serde_json::to_value(settings).expect("settings should always serialize");
+
+    if !include_scan_paths
+        && let Some(scanning) = value.get_mut("scanning").and_then(Value::as_object_mut)
+    {
+        scanning.remove("paths");
+    }
+
+    if let Some(theme) = theme
+        && let Some(object) = value.as_object_mut()
+    {
+        object.insert(
+            EXPORTED_THEME_KEY.to_string(),
+            serde_json::to_value(theme).expect("theme should always serialize"),
+        );
+    }
+
+    value
+}
+
+/// Merges an exported settings file on top of the current settings.
+///
+/// Only the fields present in the imported file are changed, so files exported from older or
+/// newer versions of Hummingbird don't reset settings they don't know about. Returns the merged
+/// settings and the theme bundled with the file, if any.
+pub fn import_settings(
+    current: &Settings,
+    contents: &str,
+) -> Result<(Settings, Option<ExportedTheme>), ImportError> {
+    let mut imported: Value = serde_json::from_str(contents)?;
+    let Some(object) = imported.as_object_mut() else {
+        return Err(ImportError::NotAnObject);
+    };
+
+    let theme = object
+        .remove(EXPORTED_THEME_KEY)
+        .and_then(|theme| serde_json::from_value::<ExportedTheme>(theme).ok());
+
+    let mut merged = serde_json::to_value(current).expect("settings should always serialize");
+    merge_values(&mut merged, imported);
+
+    Ok((serde_json::from_value(merged)?, theme))
+}
+
+/// Returns the default settings, keeping the configured scan paths.
+pub fn reset_settings(current: &Settings) -> Settings {
+    let mut settings = Settings::default();
+    settings.scanning.paths = current.scanning.paths.clone();
+    settings
+}
+
+/// Writes a bundled theme to the themes folder in `data_dir`, returning the path to select in
+/// `interface.theme`.
+///
+/// A different theme that already uses the same file name is never overwritten: the bundled theme
+/// is written under a numbered name instead, like `theme (2).json`. If the same theme is already
+/// there, it is reused.
+pub fn write_exported_theme(data_dir: &Path, theme: &ExportedTheme) -> io::Result<String> {
+    let file_name = Path::new(&theme.file_name);
+    let stem = file_name
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid theme file name"))?;
+    let extension = file_name
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| format!(".{extension}"))
+        .unwrap_or_default();
+
+    let themes_dir = data_dir.join("themes");
+    fs::create_dir_all(&themes_dir)?;
+
+    for n in 1.. {
+        let file_name = if n == 1 {
+            format!("{stem}{extension}")
+        } else {
+            format!("{stem} ({n}){extension}")
+        };
+        let path = themes_dir.join(&file_name);
+
+        if path.exists() {
+            let existing = fs::read(&path)
+                .ok()
+                .and_then(|existing| serde_json::from_slice::<Value>(&existing).ok());
+            if existing.as_ref() != Some(&theme.contents) {
+                continue;
+            }
+        } else {
+            fs::write(&path, serde_json::to_vec_pretty(&theme.contents)?)?;
+        }
+
+        return Ok(format!("themes/{file_name}"));
+    }
+
+    unreachable!("ran out of theme file names")
+}
+
+/// Makes sure the theme selected by imported settings exists in `data_dir`.
+///
+/// Settings exported without their theme may refer to a theme file that was never copied to this
+/// machine. In that case the currently selected theme is kept instead, and the missing theme is
+/// returned so that it can be reported.
+pub fn keep_available_theme(
+    data_dir: &Path,
+    current: &Settings,
+    imported: &mut Settings,
+) -> Option<String> {
+    let theme = imported.interface.theme.as_ref()?;
+    if data_dir.join(theme).is_file() {
+        return None;
+    }
+
+    let missing = theme.clone();
+    imported.interface.theme = current.interface.theme.clone();
+    Some(missing)
+}
+
+/// Recursively merges `overlay` into `base`. Objects are merged key by key, everything else is
+/// replaced.
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        EXPORTED_THEME_KEY, ExportedTheme, ImportError, export_settings, import_settings,
+        keep_available_theme, reset_settings, write_exported_theme,
+    };
+    use crate::{settings::Settings, test_support::TestDir};
+    use serde_json::json;
+
+    fn settings_with_paths() -> Settings {
+        let mut settings = Settings::default();
+        settings.scanning.paths = vec!["/music".into()];
+        settings
+    }
+
+    #[test]
+    fn export_settings_excludes_scan_paths_by_default() {
+        let settings = settings_with_paths();
+
+        let exported = export_settings(&settings, false, None);
+        assert!(exported["scanning"].get("paths").is_none());
+
+        let exported = export_settings(&settings, true, None);
+        assert_eq!(exported["scanning"]["paths"], json!(["/music"]));
+    }
+
+    #[test]
+    fn export_settings_bundles_theme() {
+        let theme = ExportedTheme {
+            file_name: "mytheme.json".to_string(),
+            contents: json!({ "background_primary": "#000000" }),
+        };
+
+        let exported = export_settings(&Settings::default(), false, Some(theme.clone()));
+
+        assert_eq!(
+            serde_json::from_value::<ExportedTheme>(exported[EXPORTED_THEME_KEY].clone()).unwrap(),
+            theme
+        );
+    }
+
+    #[test]
+    fn import_settings_merges_partial_files() {
+        let mut current = settings_with_paths();
+        current.playback.prev_track_jump_first = true;
+
+        let (settings, theme) = import_settings(
+            &current,
+            r#"{ "playback": { "always_repeat": true }, "unknown": { "field": 1 } }"#,
+        )
+        .unwrap();
+
+        assert!(settings.playback.always_repeat);
+        assert!(settings.playback.prev_track_jump_first);
+        assert_eq!(settings.scanning.paths, current.scanning.paths);
+        assert!(theme.is_none());
+    }
+
+    #[test]
+    fn import_settings_round_trips_export() {
+        let mut current = Settings::default();
+        current.interface.always_show_scrollbars = true;
+        let exported = export_settings(&current, false, None).to_string();
+
+        let (settings, _) = import_settings(&settings_with_paths(), &exported).unwrap();
+
+        assert!(settings.interface.always_show_scrollbars);
+        assert_eq!(
+            settings.scanning.paths,
+            settings_with_paths().scanning.paths
+        );
+    }
+
+    #[test]
+    fn import_settings_rejects_invalid_files() {
+        let current = Settings::default();
+
+        assert!(matches!(
+            import_settings(&current, "{not valid json"),
+            Err(ImportError::Invalid(_))
+        ));
+        assert!(matches!(
+            import_settings(&current, "[]"),
+            Err(ImportError::NotAnObject)
+        ));
+        assert!(matches!(
+            import_settings(&current, r#"{ "playback": { "always_repeat": "yes" } }"#),
+            Err(ImportError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn reset_settings_keeps_scan_paths() {
+        let mut current = settings_with_paths();
+        current.playback.always_repeat = true;
+
+        let settings = reset_settings(&current);
+
+        assert!(!settings.playback.always_repeat);
+        assert_eq!(settings.scanning.paths, current.scanning.paths);
+    }
+
+    #[test]
+    fn keep_available_theme_rejects_missing_theme_files() {
+        let dir = TestDir::new("hummingbird-settings-transfer-missing-theme-test");
+        let mut current = Settings::default();
+        current.interface.theme = Some("themes/current.json".to_string());
+
+        let mut imported = Settings::default();
+        imported.interface.theme = Some("themes/missing.json".to_string());
+
+        assert_eq!(
+            keep_available_theme(dir.path(), &current, &mut imported),
+            Some("themes/missing.json".to_string())
+        );
+        assert_eq!(imported.interface.theme, current.interface.theme);
+
+        std::fs::create_dir_all(dir.path().join("themes")).unwrap();
+        std::fs::write(dir.path().join("themes/present.json"), "{}").unwrap();
+        imported.interface.theme = Some("themes/present.json".to_string());
+
+        assert_eq!(
+            keep_available_theme(dir.path(), &current, &mut imported),
+            None
+        );
+        assert_eq!(
+            imported.interface.theme.as_deref(),
+            Some("themes/present.json")
+        );
+    }
+
+    #[test]
+    fn write_exported_theme_strips_directories() {
+        let dir = TestDir::new("hummingbird-settings-transfer-test");
+        let theme = ExportedTheme {
+            file_name: "../../evil.json".to_string(),
+            contents: json!({}),
+        };
+
+        let selected = write_exported_theme(dir.path(), &theme).unwrap();
+
+        assert_eq!(selected, "themes/evil.json");
+        assert!(dir.path().join("themes/evil.json").is_file());
+    }
+
+    #[test]
+    fn write_exported_theme_keeps_existing_themes() {
+        let dir = TestDir::new("hummingbird-settings-transfer-test");
+        let theme = |contents| ExportedTheme {
+            file_name: "custom.json".to_string(),
+            contents,
+        };
+
+        let first = write_exported_theme(dir.path(), &theme(json!({ "a": 1 }))).unwrap();
+        let second = write_exported_theme(dir.path(), &theme(json!({ "a": 2 }))).unwrap();
+        let again = write_exported_theme(dir.path(), &theme(json!({ "a": 1 }))).unwrap();
+
+        assert_eq!(first, "themes/custom.json");
+        assert_eq!(second, "themes/custom (2).json");
+        assert_eq!(again, "themes/custom.json");
+
+        let written: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(dir.path().join(&first)).unwrap()).unwrap();
+        assert_eq!(written, json!({ "a": 1 }));
+    }
+}
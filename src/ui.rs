@@ -9,16 +9,23 @@ pub mod components;
 mod constants;
 mod controls;
 pub mod data;
+mod database_recovery;
+mod drop_notice;
+pub mod format;
 mod global_actions;
 mod header;
+mod instance;
 pub mod library;
 mod lyrics;
 pub mod models;
 mod queue;
+mod queue_confirmation;
 mod right_sidebar;
 mod scroll_follow;
 mod search;
 mod settings;
 mod theme;
+mod tray;
 mod troubleshooting;
 pub mod util;
+mod window_title;
@@ -12,7 +12,7 @@ use tracing::debug;
 
 use crate::{
     library::{
-        db::create_pool,
+        db::{LibraryAccess, create_pool},
         scan::{ScanEvent, ScanInterface, start_scanner},
     },
     paths,
@@ -26,28 +26,42 @@ use crate::{
         storage::{Storage, StorageData},
     },
     ui::{
-        assets::HummingbirdAssetSource,
+        assets::{HummingbirdAssetSource, missing_art},
         caching::HummingbirdImageCache,
         command_palette::{CommandPalette, CommandPaletteHolder},
         components::dropdown,
-        library::{self, missing_folder_dialog::MissingFolderDialog},
+        library::{
+            self, missing_folder_dialog::MissingFolderDialog,
+            track_properties::TrackPropertiesDialog,
+        },
         models::WindowInformation,
     },
 };
 
 use super::{
     about::about_dialog,
-    arguments::parse_args_and_prepare,
+    arguments::{file_args, parse_args_and_prepare},
     components::{input, modal, popover, window_chrome::window_chrome},
     controls::Controls,
+    database_recovery::{self, DatabaseProblem},
+    drop_notice::DropNotice,
     global_actions::register_actions,
     header::Header,
+    instance,
     library::Library,
     models::{self, CurrentTrack, Models, PlaybackInfo, build_models},
+    queue_confirmation::QueueConfirmationDialog,
     right_sidebar::RightSidebar,
     search::SearchView,
     theme::setup_theme,
-    util::drop_image_from_app,
+    tray,
+    util::{drop_image_from_app, queue_dropped_paths},
+    window_title,
+};
+
+const WINDOW_MIN_SIZE: Size<Pixels> = Size {
+    width: px(800.0),
+    height: px(600.0),
 };
 
 struct WindowShadow {
@@ -61,6 +75,9 @@ struct WindowShadow {
     pub show_about: Entity<bool>,
     pub about_focus: FocusHandle,
     pub missing_folder_dialog: Entity<MissingFolderDialog>,
+    pub track_properties: Entity<TrackPropertiesDialog>,
+    pub queue_confirmation: Entity<QueueConfirmationDialog>,
+    pub drop_notice: Entity<DropNotice>,
     pub palette: Entity<CommandPalette>,
     pub image_cache: Entity<HummingbirdImageCache>,
 }
@@ -75,6 +92,12 @@ impl Render for WindowShadow {
             ScanEvent::WaitingForMissingFolderDecision { .. }
         );
         let show_sidebar = *self.show_queue.read(cx) || *self.show_lyrics.read(cx);
+        let show_track_properties = cx.global::<Models>().track_properties.read(cx).is_some();
+        let show_queue_confirmation = cx
+            .global::<Models>()
+            .pending_queue_change
+            .read(cx)
+            .is_some();
 
         div()
             .image_cache(self.image_cache.clone())
@@ -84,14 +107,9 @@ impl Render for WindowShadow {
                 div()
                     .cursor(CursorStyle::Arrow)
                     .on_drop(|ev: &ExternalPaths, _, cx| {
-                        let items = ev
-                            .paths()
-                            .iter()
-                            .map(|path| QueueItemData::new(cx, path.clone(), None, None))
-                            .collect();
-
-                        let playback_interface = cx.global::<PlaybackInterface>();
-                        playback_interface.queue_list(items);
+                        queue_dropped_paths(cx, ev.paths().to_vec(), |items, cx| {
+                            cx.global::<PlaybackInterface>().queue_list(items);
+                        });
                     })
                     .overflow_hidden()
                     .size_full()
@@ -110,8 +128,10 @@ impl Render for WindowShadow {
                             .max_w_full()
                             .max_h_full()
                             .overflow_hidden()
+                            .relative()
                             .child(self.library.clone())
-                            .when(show_sidebar, |this| this.child(right_sidebar)),
+                            .when(show_sidebar, |this| this.child(right_sidebar))
+                            .child(self.drop_notice.clone()),
                     )
                     .child(self.header.clone())
                     .child(self.search.clone())
@@ -124,6 +144,12 @@ impl Render for WindowShadow {
                     })
                     .when(show_missing_folder_dialog, |this| {
                         this.child(self.missing_folder_dialog.clone())
+                    })
+                    .when(show_track_properties, |this| {
+                        this.child(self.track_properties.clone())
+                    })
+                    .when(show_queue_confirmation, |this| {
+                        this.child(self.queue_confirmation.clone())
                     }),
             ))
     }
@@ -163,11 +189,23 @@ pub fn run() -> anyhow::Result<()> {
         )
     })?;
 
-    let pool = crate::RUNTIME
-        .block_on(create_pool(data_dir.join("library.db")))
-        .inspect_err(|error| {
+    if instance::forward_to_running(&data_dir, &file_args()) {
+        return Ok(());
+    }
+
+    let db_path = data_dir.join("library.db");
+
+    let pool = match crate::RUNTIME.block_on(create_pool(&db_path)) {
+        Ok(pool) => pool,
+        Err(error) => {
             tracing::error!(?error, "fatal: unable to create database pool");
-        })?;
+
+            return match DatabaseProblem::from_error(&error) {
+                Some(problem) => database_recovery::run(problem, db_path),
+                None => Err(error.into()),
+            };
+        }
+    };
 
     Application::with_platform(current_platform(false))
         .with_assets(HummingbirdAssetSource::new(pool.clone()))
@@ -206,6 +244,7 @@ pub fn run() -> anyhow::Result<()> {
             let settings = cx.global::<SettingsGlobal>().model.read(cx);
             let language = settings.interface.language.clone();
             let playback_settings = settings.playback.clone();
+            let resume_on_startup = playback_settings.resume_on_startup;
             let scanning_settings = settings.scanning.clone();
             #[cfg(feature = "update")]
             let update_settings = settings.update.clone();
@@ -238,12 +277,19 @@ pub fn run() -> anyhow::Result<()> {
             cx.observe(&settings_model, |_, cx| cx.refresh_windows())
                 .detach();
 
+            missing_art::init(cx);
+
             if !language.is_empty() {
                 I18N_MANAGER.write().unwrap().locale = Locale::new_from_locale_identifier(language);
             }
 
+            // a library that was just rebuilt is empty, so it's always scanned
+            let scan_on_startup = scanning_settings.scan_on_startup
+                || database_recovery::take_rebuild_marker(&data_dir);
             let mut scan_interface: ScanInterface = start_scanner(pool.clone(), scanning_settings);
-            scan_interface.scan();
+            if scan_on_startup {
+                scan_interface.scan();
+            }
             scan_interface.start_broadcast(cx);
 
             cx.set_global(scan_interface);
@@ -270,11 +316,20 @@ pub fn run() -> anyhow::Result<()> {
             );
             playback_interface.start_broadcast(cx);
 
+            match cx.get_shuffle_skipped_tracks() {
+                Ok(skips) => playback_interface.set_shuffle_skips(skips.into_iter().collect()),
+                Err(err) => {
+                    tracing::error!("could not load tracks skipped when shuffling: {err:?}")
+                }
+            }
+
             if !parse_args_and_prepare(cx, &playback_interface)
                 && let Some(pos) = initial_position
             {
                 playback_interface.jump(pos);
-                playback_interface.pause();
+                if !resume_on_startup {
+                    playback_interface.pause();
+                }
             }
             cx.set_global(playback_interface);
 
@@ -291,10 +346,21 @@ pub fn run() -> anyhow::Result<()> {
                     .clone()
                     .write(cx, Some(window_information.clone()));
 
+                let displays: Vec<_> = cx.displays().iter().map(|d| d.bounds()).collect();
+                let bounds = window_information
+                    .restored_bounds(&displays, WINDOW_MIN_SIZE)
+                    .unwrap_or_else(|| {
+                        Bounds::centered(
+                            None,
+                            window_information.restored_size(WINDOW_MIN_SIZE),
+                            cx,
+                        )
+                    });
+
                 if window_information.maximized {
-                    WindowBounds::Maximized(Bounds::centered(None, window_information.size, cx))
+                    WindowBounds::Maximized(bounds)
                 } else {
-                    WindowBounds::Windowed(Bounds::centered(None, window_information.size, cx))
+                    WindowBounds::Windowed(bounds)
                 }
             } else {
                 WindowBounds::Maximized(Bounds::centered(None, size(px(1024.0), px(700.0)), cx))
@@ -305,7 +371,7 @@ pub fn run() -> anyhow::Result<()> {
                     window_bounds: Some(bounds),
                     window_background: WindowBackgroundAppearance::Opaque,
                     window_decorations: Some(WindowDecorations::Client),
-                    window_min_size: Some(size(px(800.0), px(600.0))),
+                    window_min_size: Some(WINDOW_MIN_SIZE),
                     titlebar: Some(TitlebarOptions {
                         title: Some(tr!("APP_NAME").into()),
                         appears_transparent: true,
@@ -319,24 +385,31 @@ pub fn run() -> anyhow::Result<()> {
                     ..Default::default()
                 },
                 |window, cx| {
-                    window.set_window_title(tr!("APP_NAME").to_string().as_str());
-
                     register_pbc_event_handlers(cx);
                     init_pbc_task(cx, window);
 
+                    tray::init(cx, window.window_handle());
+                    window_title::init(window, cx);
+                    window.on_window_should_close(cx, |window, cx| {
+                        !tray::minimize_to_tray(window, cx)
+                    });
+
                     let palette = CommandPalette::new(cx, window);
 
                     cx.set_global(CommandPaletteHolder::new(palette.clone()));
 
-                    // Update `StorageData` and save it to file system while quitting the app
+                    // Update `StorageData` and save it to file system while quitting the app, along with any
+                    // unsaved changes to the scan record
                     cx.on_app_quit({
                         let storage = storage.clone();
                         move |cx| {
                             let data = StorageData::new(cx);
                             let storage = storage.clone();
+                            let flush_scan_record = cx.global::<ScanInterface>().flush();
 
                             cx.background_executor().spawn(async move {
                                 storage.save(&data);
+                                flush_scan_record.await;
                                 crate::logging::flush();
                             })
                         }
@@ -355,16 +428,21 @@ pub fn run() -> anyhow::Result<()> {
                                 cx.global::<Models>().window_information.clone();
 
                             let maximized = window.is_maximized();
-                            let size = if maximized {
-                                window_information.read(cx).clone()
-                            } else {
-                                None
-                            }
-                            .map(|v| v.size)
-                            .unwrap_or(window.bounds().size);
-
-                            window_information
-                                .write(cx, Some(WindowInformation { maximized, size }));
+                            let bounds = window.bounds();
+                            // keep the size and position from before the window was maximized
+                            let (size, origin) = match window_information.read(cx) {
+                                Some(previous) if maximized => (previous.size, previous.origin),
+                                _ => (bounds.size, Some(bounds.origin)),
+                            };
+
+                            window_information.write(
+                                cx,
+                                Some(WindowInformation {
+                                    maximized,
+                                    size,
+                                    origin,
+                                }),
+                            );
                         })
                         .detach();
 
@@ -373,7 +451,7 @@ pub fn run() -> anyhow::Result<()> {
                         })
                         .detach();
 
-                        let show_queue = cx.new(|_| true);
+                        let show_queue = cx.global::<Models>().show_queue.clone();
                         let show_lyrics = cx.new(|_| false);
                         let show_about = cx.global::<Models>().show_about.clone();
                         let about_focus = cx.focus_handle();
@@ -383,6 +461,19 @@ pub fn run() -> anyhow::Result<()> {
                         })
                         .detach();
 
+                        let track_properties = cx.global::<Models>().track_properties.clone();
+                        cx.observe(&track_properties, |_, _, cx| {
+                            cx.notify();
+                        })
+                        .detach();
+
+                        let pending_queue_change =
+                            cx.global::<Models>().pending_queue_change.clone();
+                        cx.observe(&pending_queue_change, |_, _, cx| {
+                            cx.notify();
+                        })
+                        .detach();
+
                         WindowShadow {
                             controls: Controls::new(cx, show_queue.clone(), show_lyrics.clone()),
                             right_sidebar: RightSidebar::new(
@@ -398,6 +489,9 @@ pub fn run() -> anyhow::Result<()> {
                             show_about,
                             about_focus,
                             missing_folder_dialog: MissingFolderDialog::new(cx),
+                            track_properties: TrackPropertiesDialog::new(cx),
+                            queue_confirmation: QueueConfirmationDialog::new(cx),
+                            drop_notice: DropNotice::new(cx),
                             palette,
                             // use a really small global image cache
                             // this is literally just to ensure that images are *always* removed
@@ -411,6 +505,8 @@ pub fn run() -> anyhow::Result<()> {
                 },
             )
             .unwrap();
+
+            instance::listen(cx, &data_dir);
         });
 
     Ok(())
@@ -13,6 +13,17 @@ struct Args {
     files: Option<Vec<PathBuf>>,
 }
 
+/// Returns the files passed as command line arguments, made absolute so they can be sent to a copy
+/// of Hummingbird running in another directory.
+pub fn file_args() -> Vec<PathBuf> {
+    Args::parse()
+        .files
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| std::path::absolute(&path).unwrap_or(path))
+        .collect()
+}
+
 /// Parses the arguments provided by the user and handles them. Returns true if files were provided
 /// for playback as command line arguments.
 pub fn parse_args_and_prepare(cx: &mut App, interface: &PlaybackInterface) -> bool {
@@ -1,5 +1,6 @@
 pub mod bundled;
 pub mod db;
+pub mod missing_art;
 
 use std::borrow::Cow;
 
@@ -10,12 +11,18 @@ use url::Url;
 use crate::ui::assets::bundled::BundledAssets;
 
 pub struct HummingbirdAssetSource {
-    pool: SqlitePool,
+    pool: Option<SqlitePool>,
 }
 
 impl HummingbirdAssetSource {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self { pool: Some(pool) }
+    }
+
+    /// An asset source for when the library couldn't be opened. Images from the library aren't
+    /// available.
+    pub fn bundled_only() -> Self {
+        Self { pool: None }
     }
 }
 
@@ -24,7 +31,10 @@ impl AssetSource for HummingbirdAssetSource {
         let url = Url::parse(&path[1..])?;
 
         match url.scheme() {
-            "db" => db::load(&self.pool, url),
+            "db" => match &self.pool {
+                Some(pool) => db::load(pool, url),
+                None => Ok(None),
+            },
             "bundled" => BundledAssets::load(url),
             _ => panic!("invalid url scheme for resource"),
         }
@@ -4,6 +4,8 @@ use anyhow::anyhow;
 use sqlx::SqlitePool;
 use url::Url;
 
+use crate::{library::art::read_art, ui::assets::missing_art::missing_art};
+
 pub fn load(pool: &SqlitePool, url: Url) -> gpui::Result<Option<Cow<'static, [u8]>>> {
     match url
         .host_str()
@@ -25,13 +27,12 @@ pub fn load(pool: &SqlitePool, url: Url) -> gpui::Result<Option<Cow<'static, [u8
                 _ => unimplemented!("invalid image type '{image_type}'"),
             };
 
-            let (image,): (Vec<u8>,) =
+            let (image, image_path): (Option<Vec<u8>>, Option<String>) =
                 crate::RUNTIME.block_on(sqlx::query_as(query).bind(id).fetch_one(pool))?;
 
-            if !image.is_empty() {
-                Ok(Some(Cow::Owned(image)))
-            } else {
-                Ok(None)
+            match read_art(image, image_path)? {
+                Some(data) => Ok(Some(Cow::Owned(data))),
+                None => Ok(missing_art()),
             }
         }
         _ => Ok(None),
@@ -0,0 +1,106 @@
+use std::{
+    borrow::Cow,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use gpui::App;
+use tracing::warn;
+
+use crate::{
+    settings::{SettingsGlobal, interface::MissingArt},
+    ui::assets::bundled::BundledAssets,
+};
+
+/// A music note drawn in translucent grey, so the album art background of the theme shows
+/// through it.
+const PLACEHOLDER_PATH: &str = "images/missing-art.png";
+
+enum MissingArtImage {
+    Blank,
+    Placeholder,
+    Custom(Arc<[u8]>),
+}
+
+/// The image shown for albums without art, along with the settings it was loaded from.
+struct MissingArtState {
+    setting: MissingArt,
+    path: Option<PathBuf>,
+    image: MissingArtImage,
+}
+
+// art is loaded by the asset source and by background tasks, neither of which can read globals
+static MISSING_ART: RwLock<MissingArtState> = RwLock::new(MissingArtState {
+    setting: MissingArt::Placeholder,
+    path: None,
+    image: MissingArtImage::Placeholder,
+});
+
+/// Returns the image to show for an album without art, or `None` if nothing should be shown.
+pub fn missing_art() -> Option<Cow<'static, [u8]>> {
+    match &MISSING_ART.read().unwrap().image {
+        MissingArtImage::Blank => None,
+        MissingArtImage::Placeholder => BundledAssets::get(PLACEHOLDER_PATH).map(|file| file.data),
+        MissingArtImage::Custom(data) => Some(Cow::Owned(data.to_vec())),
+    }
+}
+
+/// Loads the image for albums without art from the settings, reloading it when they change.
+pub fn init(cx: &mut App) {
+    sync_missing_art(cx);
+
+    let settings = cx.global::<SettingsGlobal>().model.clone();
+    cx.observe(&settings, |_, cx| sync_missing_art(cx)).detach();
+}
+
+fn sync_missing_art(cx: &App) {
+    let interface = &cx.global::<SettingsGlobal>().model.read(cx).interface;
+    let setting = interface.missing_art;
+    let path = interface.missing_art_path.clone();
+
+    {
+        let state = MISSING_ART.read().unwrap();
+        if state.setting == setting && state.path == path {
+            return;
+        }
+    }
+
+    let image = match (setting, &path) {
+        (MissingArt::Blank, _) => MissingArtImage::Blank,
+        (MissingArt::Placeholder, _) | (MissingArt::Custom, None) => MissingArtImage::Placeholder,
+        (MissingArt::Custom, Some(path)) => match std::fs::read(path) {
+            Ok(data) if image::guess_format(&data).is_ok() => MissingArtImage::Custom(data.into()),
+            Ok(_) => {
+                warn!("Image for missing album art at {path:?} is not a supported image");
+                MissingArtImage::Placeholder
+            }
+            Err(err) => {
+                warn!(
+                    ?err,
+                    "Failed to read image for missing album art at {path:?}: {err}"
+                );
+                MissingArtImage::Placeholder
+            }
+        },
+    };
+
+    *MISSING_ART.write().unwrap() = MissingArtState {
+        setting,
+        path,
+        image,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PLACEHOLDER_PATH;
+    use crate::ui::assets::bundled::BundledAssets;
+
+    #[test]
+    fn placeholder_is_a_transparent_image() {
+        let file = BundledAssets::get(PLACEHOLDER_PATH).unwrap();
+        let image = image::load_from_memory(&file.data).unwrap().to_rgba8();
+
+        assert_eq!(image.get_pixel(0, 0).0[3], 0);
+    }
+}
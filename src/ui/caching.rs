@@ -1,4 +1,8 @@
-use std::{collections::VecDeque, mem::take};
+use std::{
+    collections::VecDeque,
+    mem::take,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use futures::FutureExt;
 use gpui::{
@@ -8,6 +12,36 @@ use gpui::{
 use rustc_hash::{FxBuildHasher, FxHashMap};
 use tracing::{error, trace};
 
+use crate::settings::SettingsGlobal;
+
+/// The image cache budget that the sizes passed to [`hummingbird_cache`] and
+/// [`HummingbirdImageCache::new`] are chosen for. Caches are scaled by the configured budget
+/// relative to this.
+const REFERENCE_BUDGET: usize = 200;
+
+static LIVE_CACHES: AtomicUsize = AtomicUsize::new(0);
+static CACHED_IMAGES: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of image caches that currently exist, and how many images they hold in total.
+pub fn image_cache_occupancy() -> (usize, usize) {
+    (
+        LIVE_CACHES.load(Ordering::Relaxed),
+        CACHED_IMAGES.load(Ordering::Relaxed),
+    )
+}
+
+/// Returns the configured image cache budget.
+pub fn image_cache_budget(cx: &App) -> usize {
+    cx.try_global::<SettingsGlobal>()
+        .map(|settings| settings.model.read(cx).interface.image_cache_size.budget())
+        .unwrap_or(REFERENCE_BUDGET)
+}
+
+/// Scales a cache size chosen for the default budget to the configured one.
+fn scaled_max_items(max_items: usize, cx: &App) -> usize {
+    (max_items * image_cache_budget(cx) / REFERENCE_BUDGET).max(1)
+}
+
 pub fn hummingbird_cache(
     id: impl Into<ElementId>,
     max_items: usize,
@@ -39,6 +73,7 @@ impl ImageCacheProvider for HummingbirdImageCacheProvider {
 }
 
 pub struct HummingbirdImageCache {
+    /// The size of the cache for the default budget, see [`REFERENCE_BUDGET`].
     max_items: usize,
     usage_list: VecDeque<u64>,
     cache: FxHashMap<u64, (ImageCacheItem, Resource)>,
@@ -48,7 +83,12 @@ impl HummingbirdImageCache {
     pub fn new(max_items: usize, cx: &mut App) -> Entity<Self> {
         cx.new(|cx| {
             trace!("Creating HummingbirdImageCache");
+            LIVE_CACHES.fetch_add(1, Ordering::Relaxed);
+
             cx.on_release(|this: &mut Self, cx| {
+                LIVE_CACHES.fetch_sub(1, Ordering::Relaxed);
+                CACHED_IMAGES.fetch_sub(this.cache.len(), Ordering::Relaxed);
+
                 for (idx, (mut image, resource)) in take(&mut this.cache) {
                     if let Some(Ok(image)) = image.get() {
                         trace!("Dropping image {idx}");
@@ -94,7 +134,9 @@ impl ImageCache for HummingbirdImageCache {
         let load_future = AssetLogger::<ImageAssetLoader>::load(resource.clone(), cx);
         let task = cx.background_executor().spawn(load_future).shared();
 
-        if self.usage_list.len() >= self.max_items {
+        // the budget can change while the cache exists, so this may evict more than one item
+        let max_items = scaled_max_items(self.max_items, cx);
+        while self.usage_list.len() >= max_items {
             trace!("Image cache is full, evicting oldest item");
 
             let oldest = self.usage_list.pop_back().unwrap();
@@ -109,8 +151,10 @@ impl ImageCache for HummingbirdImageCache {
             }
 
             ImageSource::Resource(image.1).remove_asset(cx);
+            CACHED_IMAGES.fetch_sub(1, Ordering::Relaxed);
         }
 
+        CACHED_IMAGES.fetch_add(1, Ordering::Relaxed);
         self.cache.insert(
             hash,
             (
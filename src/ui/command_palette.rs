@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use cntp_i18n::tr;
+use cntp_i18n::{I18nString, tr, trn};
 use gpui::{
     Action, App, AppContext, Context, Entity, EventEmitter, FocusHandle, Global, IntoElement,
     ParentElement, Render, SharedString, Styled, Window, actions, div, px,
@@ -8,28 +8,72 @@ use gpui::{
 use nucleo::Utf32String;
 use rustc_hash::FxHashMap;
 use std::hash::Hash;
-use tracing::error;
+use tracing::{debug, error};
 
 #[cfg(feature = "update")]
 use crate::ui::global_actions::CheckForUpdates;
-use crate::ui::{
-    components::{
-        modal::modal,
-        palette::{FinderItemLeft, Palette, PaletteItem},
+use crate::{
+    library::{db::LibraryAccess, types::PlaylistType},
+    ui::{
+        components::{
+            icons::{PLAYLIST, STAR_FILLED, USERS},
+            modal::modal,
+            palette::{FinderItemLeft, Palette, PaletteItem},
+        },
+        global_actions::{
+            About, ClearRating, ForceScan, Next, PlayPause, PlayRandom, Previous, Quit, RateFive,
+            RateFour, RateOne, RateThree, RateTwo, Search, Settings, ShuffleAll, ToggleLike,
+            ToggleMute, ToggleQueue,
+        },
+        library::ViewSwitchMessage,
+        models::Models,
+        troubleshooting::{CopyTroubleshootingInfo, OpenLog},
     },
-    global_actions::{
-        About, ForceScan, Next, PlayPause, Previous, Quit, Search, Settings, ShuffleAll,
-    },
-    troubleshooting::{CopyTroubleshootingInfo, OpenLog},
 };
 
 actions!(hummingbird, [OpenPalette]);
 
+/// What happens when a command is selected.
+enum CommandTarget {
+    Action {
+        action: Box<dyn Action + Sync>,
+        focus_handle: Option<FocusHandle>,
+    },
+    Artist(i64),
+    Album(i64),
+    Playlist(i64, PlaylistType),
+}
+
+impl PartialEq for CommandTarget {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (CommandTarget::Action { action: a, .. }, CommandTarget::Action { action: b, .. }) => {
+                a.partial_eq(&(**b))
+            }
+            (CommandTarget::Artist(a), CommandTarget::Artist(b)) => a == b,
+            (CommandTarget::Album(a), CommandTarget::Album(b)) => a == b,
+            (CommandTarget::Playlist(a, _), CommandTarget::Playlist(b, _)) => a == b,
+            _ => false,
+        }
+    }
+}
+
 pub struct Command {
     category: Option<SharedString>,
     name: SharedString,
-    action: Box<dyn Action + Sync>,
-    focus_handle: Option<FocusHandle>,
+    /// Shown on the right and matched along with the name, e.g. the artist of an album.
+    detail: Option<SharedString>,
+    target: CommandTarget,
+}
+
+/// The name of the command that gives the current track `stars` stars.
+fn rate_command_name(stars: i32) -> I18nString {
+    trn!(
+        "ACTION_RATE",
+        "Rate Current Track {{count}} Star",
+        "Rate Current Track {{count}} Stars",
+        count = stars
+    )
 }
 
 impl Command {
@@ -42,22 +86,53 @@ impl Command {
         Arc::new(Command {
             category: category.map(Into::into),
             name: name.into(),
-            action: Box::new(action),
-            focus_handle,
+            detail: None,
+            target: CommandTarget::Action {
+                action: Box::new(action),
+                focus_handle,
+            },
+        })
+    }
+
+    /// Creates a command that navigates to a page in the library.
+    fn library(
+        name: impl Into<SharedString>,
+        detail: Option<SharedString>,
+        target: CommandTarget,
+    ) -> Arc<Self> {
+        Arc::new(Command {
+            category: None,
+            name: name.into(),
+            detail,
+            target,
         })
     }
+
+    fn view_switch_message(&self) -> Option<ViewSwitchMessage> {
+        match self.target {
+            CommandTarget::Action { .. } => None,
+            CommandTarget::Artist(id) => Some(ViewSwitchMessage::Artist(id)),
+            CommandTarget::Album(id) => Some(ViewSwitchMessage::Release(id, None)),
+            CommandTarget::Playlist(id, _) => Some(ViewSwitchMessage::Playlist(id)),
+        }
+    }
 }
 
 impl PartialEq for Command {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name && self.action.partial_eq(&(*other.action))
+        self.name == other.name && self.target == other.target
     }
 }
 
 impl Hash for Command {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.name.hash(state);
-        self.action.name().hash(state);
+        match &self.target {
+            CommandTarget::Action { action, .. } => action.name().hash(state),
+            CommandTarget::Artist(id)
+            | CommandTarget::Album(id)
+            | CommandTarget::Playlist(id, _) => id.hash(state),
+        }
     }
 }
 
@@ -66,7 +141,19 @@ impl PaletteItem for Command {
         &self,
         _: &mut gpui::App,
     ) -> Option<super::components::palette::FinderItemLeft> {
-        self.category.clone().map(FinderItemLeft::Text)
+        match self.target {
+            CommandTarget::Action { .. } => self.category.clone().map(FinderItemLeft::Text),
+            CommandTarget::Artist(_) => Some(FinderItemLeft::Icon(USERS.into())),
+            CommandTarget::Album(id) => Some(FinderItemLeft::Image(
+                format!("!db://album/{id}/thumb").into(),
+            )),
+            CommandTarget::Playlist(_, PlaylistType::System) => {
+                Some(FinderItemLeft::Icon(STAR_FILLED.into()))
+            }
+            CommandTarget::Playlist(_, PlaylistType::User) => {
+                Some(FinderItemLeft::Icon(PLAYLIST.into()))
+            }
+        }
     }
 
     fn middle_content(&self, _: &mut gpui::App) -> SharedString {
@@ -74,9 +161,13 @@ impl PaletteItem for Command {
     }
 
     fn right_content(&self, cx: &mut gpui::App) -> Option<SharedString> {
+        let CommandTarget::Action { action, .. } = &self.target else {
+            return self.detail.clone();
+        };
+
         cx.key_bindings()
             .borrow()
-            .bindings_for_action(&(*self.action))
+            .bindings_for_action(&(**action))
             .last()
             .map(|binding| {
                 binding
@@ -88,6 +179,55 @@ impl PaletteItem for Command {
                     .into()
             })
     }
+
+    fn category(&self) -> Option<I18nString> {
+        Some(match self.target {
+            CommandTarget::Action { .. } => tr!("ACTIONS", "Actions"),
+            CommandTarget::Artist(_) => tr!("ARTISTS"),
+            CommandTarget::Album(_) => tr!("ALBUMS"),
+            CommandTarget::Playlist(..) => tr!("PLAYLISTS", "Playlists"),
+        })
+    }
+}
+
+/// Loads the artists, albums and playlists that can be jumped to from the palette.
+fn load_library_commands(cx: &mut App) -> Vec<Arc<Command>> {
+    let mut commands = Vec::new();
+
+    match cx.list_artists_search() {
+        Ok(artists) => commands.extend(
+            artists
+                .into_iter()
+                .map(|(id, name)| Command::library(name, None, CommandTarget::Artist(id))),
+        ),
+        Err(e) => debug!("Failed to load artists for command palette: {:?}", e),
+    }
+
+    match cx.list_albums_search() {
+        Ok(albums) => commands.extend(albums.into_iter().map(|(id, title, artist)| {
+            Command::library(title, Some(artist.into()), CommandTarget::Album(id as i64))
+        })),
+        Err(e) => debug!("Failed to load albums for command palette: {:?}", e),
+    }
+
+    match cx.get_all_playlists() {
+        Ok(playlists) => commands.extend(playlists.iter().map(|playlist| {
+            let name: SharedString = if playlist.is_liked_songs() {
+                tr!("LIKED_SONGS").into()
+            } else {
+                playlist.name.0.clone()
+            };
+
+            Command::library(
+                name,
+                None,
+                CommandTarget::Playlist(playlist.id, playlist.playlist_type),
+            )
+        })),
+        Err(e) => debug!("Failed to load playlists for command palette: {:?}", e),
+    }
+
+    commands
 }
 
 type MatcherFunc = Box<dyn Fn(&Arc<Command>, &mut App) -> Utf32String + 'static>;
@@ -97,30 +237,43 @@ pub struct CommandPalette {
     show: Entity<bool>,
     palette: Entity<Palette<Command, MatcherFunc, OnAccept>>,
     items: FxHashMap<(&'static str, i64), Arc<Command>>,
+    library_items: Vec<Arc<Command>>,
 }
 
 impl CommandPalette {
     pub fn new(cx: &mut App, _: &mut Window) -> Entity<Self> {
         cx.new(|cx| {
             let show = cx.new(|_| false);
-            let matcher: MatcherFunc = Box::new(|item, _| item.name.to_string().into());
+            let matcher: MatcherFunc = Box::new(|item, _| match &item.detail {
+                Some(detail) => format!("{} {}", item.name, detail).into(),
+                None => item.name.to_string().into(),
+            });
 
             let show_clone = show.clone();
             let on_accept: OnAccept = Box::new(move |item, cx| {
                 let item = item.clone();
                 let show_clone = show_clone.clone();
                 cx.defer(move |cx| {
-                    if let Some(focus_handle) = &item.focus_handle
-                        && let Err(err) =
-                            cx.update_window(cx.active_window().unwrap(), |_, window, cx| {
-                                focus_handle.focus(window, cx);
-                            })
+                    if let Some(message) = item.view_switch_message() {
+                        let switcher_model = cx.global::<Models>().switcher_model.clone();
+                        switcher_model.update(cx, |_, cx| cx.emit(message));
+                    } else if let CommandTarget::Action {
+                        action,
+                        focus_handle,
+                    } = &item.target
                     {
-                        error!("Failed to focus window, action may not trigger: {}", err);
+                        if let Some(focus_handle) = focus_handle
+                            && let Err(err) =
+                                cx.update_window(cx.active_window().unwrap(), |_, window, cx| {
+                                    focus_handle.focus(window, cx);
+                                })
+                        {
+                            error!("Failed to focus window, action may not trigger: {}", err);
+                        }
+
+                        cx.dispatch_action(&(**action));
                     }
 
-                    cx.dispatch_action(&(*item.action));
-
                     show_clone.update(cx, |show, cx| {
                         *show = false;
                         cx.notify();
@@ -138,13 +291,7 @@ impl CommandPalette {
                     CommandEvent::RemoveCommand(id) => this.items.remove(id),
                 };
 
-                let vec: Vec<_> = this.items.values().cloned().collect();
-
-                this.palette.update(cx, |_, cx| {
-                    cx.emit(vec);
-                });
-
-                cx.notify();
+                this.update_palette_items(cx);
             })
             .detach();
 
@@ -244,6 +391,25 @@ impl CommandPalette {
                     None,
                 ),
             );
+            items.insert(
+                ("player::toggle_mute", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_PLAYBACK")),
+                    tr!("ACTION_TOGGLE_MUTE", "Mute/Unmute"),
+                    ToggleMute,
+                    None,
+                ),
+            );
+
+            items.insert(
+                ("view::toggle_queue", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_HUMMINGBIRD")),
+                    tr!("ACTION_TOGGLE_QUEUE"),
+                    ToggleQueue,
+                    None,
+                ),
+            );
 
             items.insert(
                 ("scan::forcescan", 0),
@@ -264,6 +430,79 @@ impl CommandPalette {
                     None,
                 ),
             );
+            items.insert(
+                ("player::play_random", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_PLAYBACK")),
+                    tr!("ACTION_PLAY_RANDOM", "Play a Random Track"),
+                    PlayRandom,
+                    None,
+                ),
+            );
+            items.insert(
+                ("player::toggle_like", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_PLAYBACK")),
+                    tr!("ACTION_TOGGLE_LIKE", "Like/Unlike Current Track"),
+                    ToggleLike,
+                    None,
+                ),
+            );
+            items.insert(
+                ("player::clear_rating", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_PLAYBACK")),
+                    tr!("ACTION_CLEAR_RATING", "Clear Rating of Current Track"),
+                    ClearRating,
+                    None,
+                ),
+            );
+
+            items.insert(
+                ("player::rate_one", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_PLAYBACK")),
+                    rate_command_name(1),
+                    RateOne,
+                    None,
+                ),
+            );
+            items.insert(
+                ("player::rate_two", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_PLAYBACK")),
+                    rate_command_name(2),
+                    RateTwo,
+                    None,
+                ),
+            );
+            items.insert(
+                ("player::rate_three", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_PLAYBACK")),
+                    rate_command_name(3),
+                    RateThree,
+                    None,
+                ),
+            );
+            items.insert(
+                ("player::rate_four", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_PLAYBACK")),
+                    rate_command_name(4),
+                    RateFour,
+                    None,
+                ),
+            );
+            items.insert(
+                ("player::rate_five", 0),
+                Command::new(
+                    Some(tr!("ACTION_GROUP_PLAYBACK")),
+                    rate_command_name(5),
+                    RateFive,
+                    None,
+                ),
+            );
 
             let palette = Palette::new(
                 cx,
@@ -282,6 +521,10 @@ impl CommandPalette {
                 });
                 weak_self
                     .update(cx, |this: &mut Self, cx| {
+                        // the library may have changed since the palette was last opened
+                        this.library_items = load_library_commands(cx);
+                        this.update_palette_items(cx);
+
                         this.palette.update(cx, |palette, cx| {
                             palette.reset(cx);
                         });
@@ -296,10 +539,26 @@ impl CommandPalette {
             Self {
                 show,
                 items,
+                library_items: Vec::new(),
                 palette,
             }
         })
     }
+
+    fn update_palette_items(&self, cx: &mut Context<Self>) {
+        let vec: Vec<_> = self
+            .items
+            .values()
+            .chain(self.library_items.iter())
+            .cloned()
+            .collect();
+
+        self.palette.update(cx, |_, cx| {
+            cx.emit(vec);
+        });
+
+        cx.notify();
+    }
 }
 
 impl Render for CommandPalette {
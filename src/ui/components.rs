@@ -14,6 +14,7 @@ pub mod menu_bar;
 pub mod menus_builder;
 pub mod modal;
 pub mod nav_button;
+pub mod now_playing;
 pub mod palette;
 pub mod playback_controls;
 pub mod popover;
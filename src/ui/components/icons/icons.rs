@@ -54,3 +54,9 @@ pub const PENCIL: &str = "!bundled:icons/pencil.svg";
 #[cfg(feature = "update")]
 pub const UPDATE: &str = "!bundled:icons/arrow-big-down-lines.svg";
 pub const FILE_EXPORT: &str = "!bundled:icons/file-export.svg";
+pub const HISTORY: &str = "!bundled:icons/history.svg";
+pub const CHART_BAR: &str = "!bundled:icons/chart-bar.svg";
+pub const REFRESH: &str = "!bundled:icons/refresh.svg";
+pub const INFO_CIRCLE: &str = "!bundled:icons/info-circle.svg";
+pub const DOTS: &str = "!bundled:icons/dots.svg";
+pub const CALENDAR: &str = "!bundled:icons/calendar.svg";
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     path::PathBuf,
     sync::{Arc, OnceLock},
 };
@@ -13,9 +14,11 @@ use sqlx::SqlitePool;
 use tracing::error;
 
 use crate::{
+    library::art::read_art,
     media::{lookup_table::try_open_media, traits::MediaProviderFeatures},
     ui::{
         app::Pool,
+        assets::missing_art::missing_art,
         util::{drop_image_from_app, find_art_file_for_path},
     },
     util::rgb_to_bgr,
@@ -28,8 +31,14 @@ fn decode_rgba_to_render_image(mut image: image::RgbaImage) -> anyhow::Result<Ar
     Ok(Arc::new(RenderImage::new(frames)))
 }
 
-fn decode_to_render_image(data: &[u8]) -> anyhow::Result<Arc<RenderImage>> {
-    let image = image::load_from_memory(data)?.to_rgba8();
+fn decode_to_render_image(data: &[u8], thumb: bool) -> anyhow::Result<Arc<RenderImage>> {
+    let mut image = image::load_from_memory(data)?.to_rgba8();
+
+    // albums scanned without thumbnails only have full-size art
+    if thumb && (image.width() > 72 || image.height() > 72) {
+        image = imageops::thumbnail(&image, 72, 72);
+    }
+
     decode_rgba_to_render_image(image)
 }
 
@@ -80,18 +89,23 @@ impl ManagedImageKey {
                 } else {
                     include_str!("../../../queries/assets/find_album_art.sql")
                 };
-                let Some((image_encoded,)): Option<(Vec<u8>,)> =
+                let Some((image, image_path)): Option<(Option<Vec<u8>>, Option<String>)> =
                     sqlx::query_as(query).bind(id).fetch_optional(&pool).await?
                 else {
                     return Ok(None);
                 };
 
-                if image_encoded.is_empty() {
-                    return Ok(None);
-                }
-
                 let image = crate::RUNTIME
-                    .spawn_blocking(move || decode_to_render_image(&image_encoded).map(Some))
+                    .spawn_blocking(move || -> anyhow::Result<Option<Arc<RenderImage>>> {
+                        let Some(image_encoded) = read_art(image, image_path)?
+                            .map(Cow::Owned)
+                            .or_else(missing_art)
+                        else {
+                            return Ok(None);
+                        };
+
+                        decode_to_render_image(&image_encoded, thumb).map(Some)
+                    })
                     .await??;
 
                 Ok(image)
@@ -1,11 +1,17 @@
+use std::rc::Rc;
+
 use gpui::{prelude::FluentBuilder, *};
 
-use crate::ui::{
-    components::icons::{CHECK, LOCK, icon},
-    theme::Theme,
+use crate::{
+    library::types::MAX_RATING,
+    ui::{
+        components::icons::{CHECK, LOCK, STAR, STAR_FILLED, icon},
+        theme::Theme,
+    },
 };
 
 type ClickEvHandler = Box<dyn Fn(&ClickEvent, &mut Window, &mut App)>;
+type RatingHandler = Rc<dyn Fn(i32, &mut Window, &mut App)>;
 
 #[derive(IntoElement)]
 pub struct MenuItem {
@@ -206,6 +212,74 @@ impl RenderOnce for CheckMenuItem {
     }
 }
 
+/// A row of stars for picking a rating. Clicking the current rating clears it.
+#[derive(IntoElement)]
+pub struct RatingMenuItem {
+    id: ElementId,
+    rating: i32,
+    on_change: RatingHandler,
+}
+
+impl RatingMenuItem {
+    pub fn new(
+        id: impl Into<ElementId>,
+        rating: i32,
+        func: impl Fn(i32, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            rating,
+            on_change: Rc::new(func),
+        }
+    }
+}
+
+impl RenderOnce for RatingMenuItem {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = cx.global::<Theme>();
+
+        div()
+            .id(self.id)
+            .flex()
+            .px(px(6.0))
+            .pt(px(5.0))
+            .pb(px(5.0))
+            .line_height(rems(1.25))
+            .min_w_full()
+            .bg(theme.menu_item)
+            .border_1()
+            // line the stars up with the text of the other items
+            .child(div().w(px(18.0)).h(px(18.0)).mr(px(7.0)).flex_shrink_0())
+            .child(
+                div()
+                    .flex()
+                    .gap(px(2.0))
+                    .my_auto()
+                    .children((1..=MAX_RATING).map(|star| {
+                        let on_change = self.on_change.clone();
+                        let rating = self.rating;
+
+                        div()
+                            .id(("rating-star", star as u64))
+                            .rounded(px(3.0))
+                            .p(px(1.0))
+                            .cursor_pointer()
+                            .hover(|this| this.bg(theme.menu_item_hover))
+                            .active(|this| this.bg(theme.menu_item_active))
+                            .child(
+                                icon(if star <= rating { STAR_FILLED } else { STAR })
+                                    .size(px(16.0))
+                                    .text_color(theme.text_secondary),
+                            )
+                            .on_click(move |_, window, cx| {
+                                let new_rating = if star == rating { 0 } else { star };
+                                on_change(new_rating, window, cx);
+                            })
+                    })),
+            )
+    }
+}
+
 /// A horizontal separator line for visually grouping menu items.
 #[derive(IntoElement)]
 pub struct MenuSeparator;
@@ -244,6 +318,15 @@ pub fn menu_check_item(
     CheckMenuItem::new(id, checked, text, func)
 }
 
+/// Creates a star rating menu item.
+pub fn menu_rating_item(
+    id: impl Into<ElementId>,
+    rating: i32,
+    func: impl Fn(i32, &mut Window, &mut App) + 'static,
+) -> RatingMenuItem {
+    RatingMenuItem::new(id, rating, func)
+}
+
 /// Creates a menu separator.
 pub fn menu_separator() -> MenuSeparator {
     MenuSeparator
@@ -0,0 +1,80 @@
+use std::{path::Path, time::Duration};
+
+use gpui::{
+    Animation, AnimationExt, App, IntoElement, ParentElement, RenderOnce, Styled, Window, bounce,
+    div, ease_in_out, px,
+};
+
+use crate::{
+    playback::thread::PlaybackState,
+    ui::{models::PlaybackInfo, theme::Theme},
+};
+
+const INDICATOR_SIZE: f32 = 14.0;
+
+/// The heights of the bars while paused, as a fraction of the indicator's height.
+const BAR_HEIGHTS: [f32; 3] = [0.6, 1.0, 0.4];
+
+/// How long each bar takes to rise and fall. These differ so the bars don't move in sync.
+const BAR_PERIODS_MS: [u64; 3] = [700, 500, 900];
+
+/// Equalizer bars marking the currently playing track. The bars move while playing and freeze
+/// while paused.
+#[derive(IntoElement)]
+pub struct NowPlayingIndicator {
+    playing: bool,
+}
+
+pub fn now_playing_indicator(playing: bool) -> NowPlayingIndicator {
+    NowPlayingIndicator { playing }
+}
+
+/// Returns `Some` if the track at `path` is the current track, containing whether or not it is
+/// playing.
+pub fn now_playing(cx: &App, path: &Path) -> Option<bool> {
+    let info = cx.global::<PlaybackInfo>();
+    let is_current = info
+        .current_track
+        .read(cx)
+        .as_ref()
+        .is_some_and(|current| current.get_path() == path);
+
+    is_current.then(|| *info.playback_state.read(cx) == PlaybackState::Playing)
+}
+
+impl RenderOnce for NowPlayingIndicator {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = cx.global::<Theme>();
+
+        div()
+            .flex()
+            .items_end()
+            .justify_center()
+            .gap(px(2.0))
+            .w(px(INDICATOR_SIZE))
+            .h(px(INDICATOR_SIZE))
+            .flex_shrink_0()
+            .children(BAR_HEIGHTS.into_iter().zip(BAR_PERIODS_MS).enumerate().map(
+                |(i, (height, period))| {
+                    let bar = div()
+                        .w(px(3.0))
+                        .h(px(INDICATOR_SIZE * height))
+                        .rounded(px(1.0))
+                        .bg(theme.slider_foreground);
+
+                    if self.playing {
+                        bar.with_animation(
+                            ("now-playing-bar", i),
+                            Animation::new(Duration::from_millis(period))
+                                .repeat()
+                                .with_easing(bounce(ease_in_out)),
+                            |bar, delta| bar.h(px(INDICATOR_SIZE * (0.25 + 0.75 * delta))),
+                        )
+                        .into_any_element()
+                    } else {
+                        bar.into_any_element()
+                    }
+                },
+            ))
+    }
+}
@@ -2,23 +2,22 @@ use std::rc::Rc;
 
 use cntp_i18n::tr;
 use gpui::{
-    App, ClickEvent, ElementId, FontWeight, IntoElement, ParentElement, RenderOnce, SharedString,
-    Styled, Window, div, prelude::FluentBuilder, px,
+    App, AppContext, ClickEvent, ElementId, FontWeight, InteractiveElement, IntoElement,
+    ParentElement, RenderOnce, SharedString, Styled, Window, div, prelude::FluentBuilder, px,
 };
 
 use crate::{
-    playback::{
-        interface::{PlaybackInterface, replace_queue},
-        queue::QueueItemData,
-    },
+    playback::{interface::PlaybackInterface, queue::QueueItemData},
     ui::{
         components::{
             button::{ButtonIntent, ButtonSize, button},
-            icons::{CIRCLE_PLUS, PAUSE, PLAY, SHUFFLE, icon},
+            icons::{CHEVRON_DOWN, CIRCLE_PLUS, PAUSE, PLAY, SHUFFLE, icon},
+            menu::{menu, menu_item},
+            popover::{PopoverPosition, popover},
             tooltip::build_tooltip,
         },
         global_actions::PlayPause,
-        models::PlaybackInfo,
+        queue_confirmation::{replace_queue, replace_queue_with_shuffle},
     },
 };
 
@@ -31,9 +30,98 @@ pub struct PlaybackControls {
     current_track_in_listing: bool,
     is_playing: bool,
     get_track_listing: TrackListingProvider,
+    show_play_menu: bool,
 }
 
 impl PlaybackControls {
+    /// Adds a menu next to the play button for playing the tracks in the order they are listed
+    /// in, even while shuffle is on.
+    pub fn play_menu(mut self) -> Self {
+        self.show_play_menu = true;
+        self
+    }
+
+    fn play_menu_button(
+        id: ElementId,
+        get_track_listing: TrackListingProvider,
+        disabled: bool,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> impl IntoElement {
+        let is_open = window.use_keyed_state((id.clone(), "open"), cx, |_, _| false);
+        let close = {
+            let is_open = is_open.clone();
+            move |cx: &mut App| is_open.write(cx, false)
+        };
+
+        let get_tracks_play = get_track_listing.clone();
+        let get_tracks_in_order = get_track_listing.clone();
+        let get_tracks_shuffle = get_track_listing;
+
+        let play_menu = menu()
+            .item(menu_item("play-menu-play", Some(PLAY), tr!("PLAY"), {
+                let close = close.clone();
+                move |_, _, cx| {
+                    replace_queue(get_tracks_play(cx), cx);
+                    close(cx);
+                }
+            }))
+            .item(menu_item(
+                "play-menu-in-order",
+                Some(PLAY),
+                tr!("PLAY_IN_ALBUM_ORDER", "Play in Album Order"),
+                {
+                    let close = close.clone();
+                    move |_, _, cx| {
+                        replace_queue_with_shuffle(get_tracks_in_order(cx), false, cx);
+                        close(cx);
+                    }
+                },
+            ))
+            .item(menu_item(
+                "play-menu-shuffle",
+                Some(SHUFFLE),
+                tr!("SHUFFLE"),
+                {
+                    let close = close.clone();
+                    move |_, _, cx| {
+                        replace_queue_with_shuffle(get_tracks_shuffle(cx), true, cx);
+                        close(cx);
+                    }
+                },
+            ));
+
+        div()
+            .relative()
+            .flex_none()
+            .child({
+                let is_open = is_open.clone();
+                Self::icon_button_with_tooltip(
+                    id,
+                    CHEVRON_DOWN,
+                    tr!("PLAY_OPTIONS", "More Ways to Play").into(),
+                    disabled,
+                    move |_, _, cx| {
+                        is_open.update(cx, |open, cx| {
+                            *open = !*open;
+                            cx.notify();
+                        });
+                    },
+                )
+            })
+            .when(*is_open.read(cx), |this| {
+                let dismiss = close.clone();
+                this.child(
+                    popover()
+                        .position(PopoverPosition::BottomLeft)
+                        .edge_offset(px(4.0))
+                        .on_dismiss(move |_, cx| dismiss(cx))
+                        .on_mouse_down_out(move |_, _, cx| close(cx))
+                        .child(play_menu),
+                )
+            })
+    }
+
     fn icon_button_with_tooltip(
         id: impl Into<ElementId>,
         icon_name: &'static str,
@@ -53,7 +141,7 @@ impl PlaybackControls {
 }
 
 impl RenderOnce for PlaybackControls {
-    fn render(self, _: &mut Window, _: &mut App) -> impl IntoElement {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         let get_tracks_play = self.get_track_listing.clone();
         let get_tracks_add = self.get_track_listing.clone();
         let get_tracks_shuffle = self.get_track_listing.clone();
@@ -98,6 +186,15 @@ impl RenderOnce for PlaybackControls {
                         tr!("PLAY", "Play")
                     })),
             )
+            .when(self.show_play_menu, |this| {
+                this.child(Self::play_menu_button(
+                    (self.id_prefix.clone(), 3).into(),
+                    self.get_track_listing.clone(),
+                    !has_tracks,
+                    window,
+                    cx,
+                ))
+            })
             .child(Self::icon_button_with_tooltip(
                 (self.id_prefix.clone(), 1),
                 CIRCLE_PLUS,
@@ -114,11 +211,7 @@ impl RenderOnce for PlaybackControls {
                 tr!("SHUFFLE").into(),
                 !has_tracks,
                 move |_, _, cx| {
-                    if !(*cx.global::<PlaybackInfo>().shuffling.read(cx)) {
-                        cx.global::<PlaybackInterface>().toggle_shuffle();
-                    }
-
-                    replace_queue(get_tracks_shuffle(cx), cx);
+                    replace_queue_with_shuffle(get_tracks_shuffle(cx), true, cx);
                 },
             ))
     }
@@ -137,5 +230,6 @@ pub fn playback_controls(
         current_track_in_listing,
         is_playing,
         get_track_listing: Rc::new(get_track_listing),
+        show_play_menu: false,
     }
 }
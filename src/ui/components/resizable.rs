@@ -21,6 +21,16 @@ pub enum SizeMode {
 
 const HANDLE_SIZE: Pixels = px(6.0);
 
+/// The largest share of the window a side panel can take up, so a panel restored at a width
+/// meant for a larger window doesn't cover everything else.
+pub const MAX_PANEL_FRACTION: f32 = 0.5;
+
+/// How far below its minimum size, as a share of the minimum, a collapsible panel has to be
+/// dragged before it is hidden.
+const COLLAPSE_FRACTION: f32 = 0.5;
+
+type CollapseHandler = Rc<dyn Fn(&mut Window, &mut App)>;
+
 pub struct Resizable {
     id: ElementId,
     style: StyleRefinement,
@@ -32,6 +42,7 @@ pub struct Resizable {
     default_size: Pixels,
     border_width: Pixels,
     size_mode: SizeMode,
+    on_collapse: Option<CollapseHandler>,
 }
 
 impl Resizable {
@@ -47,6 +58,7 @@ impl Resizable {
             default_size: px(225.0),
             border_width: px(1.0),
             size_mode: SizeMode::default(),
+            on_collapse: None,
         }
     }
 
@@ -70,6 +82,13 @@ impl Resizable {
         self
     }
 
+    /// Lets the panel be hidden by dragging it well past its minimum size. The size is left at
+    /// what it was before the drag started, so the panel comes back the same size.
+    pub fn on_collapse(mut self, handler: impl Fn(&mut Window, &mut App) + 'static) -> Self {
+        self.on_collapse = Some(Rc::new(handler));
+        self
+    }
+
     pub fn percent_mode(mut self) -> Self {
         self.size_mode = SizeMode::Percent;
         self
@@ -78,6 +97,12 @@ impl Resizable {
     fn is_horizontal(&self) -> bool {
         matches!(self.edge, ResizeEdge::Left | ResizeEdge::Right)
     }
+
+    /// The range the size is kept in. If the maximum is pushed below the minimum, for example by
+    /// a small window, the minimum wins.
+    fn size_range(&self) -> (Pixels, Pixels) {
+        (self.min_size, self.max_size.max(self.min_size))
+    }
 }
 
 impl Styled for Resizable {
@@ -140,7 +165,9 @@ impl Element for Resizable {
         let mut style = Style::default();
         style.refine(&self.style);
 
-        let size = *self.size.read(cx);
+        // stored sizes can be outside of the range, e.g. after the window was made smaller
+        let (min_size, max_size) = self.size_range();
+        let size = self.size.read(cx).clamp(min_size, max_size);
         match self.size_mode {
             SizeMode::Pixels => {
                 if self.is_horizontal() {
@@ -215,15 +242,15 @@ impl Element for Resizable {
         window.set_cursor_style(cursor_style, handle_hitbox);
 
         let size_entity = self.size.clone();
-        let min_size = self.min_size;
-        let max_size = self.max_size;
+        let (min_size, max_size) = self.size_range();
         let default_size = self.default_size;
         let edge = self.edge;
         let size_mode = self.size_mode;
+        let on_collapse = self.on_collapse.clone();
 
         // Precompute container_size for percent mode from actual rendered bounds and current fraction.
         let container_size_for_paint = if size_mode == SizeMode::Percent {
-            let frac = f32::from(*self.size.read(cx));
+            let frac = f32::from(self.size.read(cx).clamp(min_size, max_size));
             let elem_size = if self.is_horizontal() {
                 bounds.size.width
             } else {
@@ -291,7 +318,7 @@ impl Element for Resizable {
                     let mut drag_state = state_down.borrow_mut();
                     drag_state.is_dragging = true;
                     drag_state.start_position = axis_position(edge, ev.position);
-                    drag_state.start_size = *size_entity_down.read(cx);
+                    drag_state.start_size = size_entity_down.read(cx).clamp(min_size, max_size);
                 });
 
                 let state_move = state.clone();
@@ -325,9 +352,23 @@ impl Element for Resizable {
                         }
                     };
                     let clamped_size = new_size.clamp(min_size, max_size);
+                    let start_size = drag_state.start_size;
 
                     drop(drag_state);
 
+                    if let Some(on_collapse) = &on_collapse
+                        && new_size < min_size * (1.0 - COLLAPSE_FRACTION)
+                    {
+                        state_move.borrow_mut().is_dragging = false;
+                        size_entity_move.update(cx, |size, cx| {
+                            *size = start_size;
+                            cx.notify();
+                        });
+                        on_collapse(window, cx);
+                        window.refresh();
+                        return;
+                    }
+
                     size_entity_move.update(cx, |size, cx| {
                         *size = clamped_size;
                         cx.notify();
@@ -1,7 +1,7 @@
 use gpui::{
-    App, Div, ElementId, FontWeight, InteractiveElement, IntoElement, ParentElement, Pixels,
-    RenderOnce, SharedString, Stateful, StatefulInteractiveElement, StyleRefinement, Styled,
-    Window, deferred, div, prelude::FluentBuilder, px,
+    AnyElement, App, Div, ElementId, FontWeight, InteractiveElement, IntoElement, ParentElement,
+    Pixels, RenderOnce, SharedString, Stateful, StatefulInteractiveElement, StyleRefinement,
+    Styled, Window, deferred, div, prelude::FluentBuilder, px,
 };
 
 use crate::{
@@ -65,6 +65,7 @@ pub struct SidebarItem {
     parent_div: Stateful<Div>,
     children_div: Div,
     icon: Option<&'static str>,
+    leading: Option<AnyElement>,
     active: bool,
     collapsed: bool,
     label: Option<SharedString>,
@@ -77,6 +78,12 @@ impl SidebarItem {
         self
     }
 
+    /// Shows an element in place of the icon.
+    pub fn leading(mut self, element: impl IntoElement) -> Self {
+        self.leading = Some(element.into_any_element());
+        self
+    }
+
     pub fn active(mut self) -> Self {
         self.active = true;
         self
@@ -117,6 +124,8 @@ impl RenderOnce for SidebarItem {
         let state = window.use_keyed_state(self.state_id.clone(), cx, |_, _| false);
         let theme = cx.global::<Theme>();
 
+        let has_leading = self.leading.is_some();
+
         let item = self
             .parent_div
             .flex()
@@ -156,7 +165,19 @@ impl RenderOnce for SidebarItem {
                 this.bg(theme.nav_button_active)
                     .border_color(theme.nav_button_active_border)
             })
-            .when_none(&self.icon, |this| {
+            .when_some(self.leading, |this, leading| {
+                this.child(
+                    div()
+                        .size(px(18.0))
+                        .flex_shrink_0()
+                        .min_w(px(18.0))
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .child(leading),
+                )
+            })
+            .when(self.icon.is_none() && !has_leading, |this| {
                 this.child(div().size(px(18.0)).flex_shrink_0().min_w(px(18.0)))
             })
             .when_some(self.icon, |this, used_icon| {
@@ -229,6 +250,7 @@ pub fn sidebar_item(id: impl Into<ElementId>) -> SidebarItem {
         parent_div: div().id(element_id),
         children_div: div(),
         icon: None,
+        leading: None,
         active: false,
         collapsed: false,
         label: None,
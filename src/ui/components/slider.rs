@@ -7,12 +7,19 @@ use crate::ui::theme::Theme;
 type ClickHandler = dyn FnMut(f32, &mut Window, &mut App);
 type DoubleClickHandler = dyn FnMut(&mut Window, &mut App);
 
+struct DragState {
+    dragging: bool,
+    last_update: Instant,
+    value: f32,
+}
+
 pub struct Slider {
     pub(self) id: Option<ElementId>,
     pub(self) style: StyleRefinement,
     pub(self) value: f32,
     pub(self) on_change: Option<Rc<RefCell<ClickHandler>>>,
     pub(self) on_double_click: Option<Rc<RefCell<DoubleClickHandler>>>,
+    pub(self) on_release: Option<Rc<RefCell<ClickHandler>>>,
 }
 
 impl Slider {
@@ -35,6 +42,13 @@ impl Slider {
         self.on_double_click = Some(Rc::new(RefCell::new(func)));
         self
     }
+
+    /// Called with the final value when the mouse is released after clicking or dragging the
+    /// slider. Requires an `on_change` handler.
+    pub fn on_release(mut self, func: impl FnMut(f32, &mut Window, &mut App) + 'static) -> Self {
+        self.on_release = Some(Rc::new(RefCell::new(func)));
+        self
+    }
 }
 
 impl Styled for Slider {
@@ -143,13 +157,17 @@ impl Element for Slider {
 
         if let Some(func) = self.on_change.as_ref() {
             let on_double_click = self.on_double_click.clone();
+            let on_release = self.on_release.clone();
             window.with_optional_element_state(
                 id,
-                #[allow(clippy::type_complexity)]
-                move |v: Option<Option<Rc<RefCell<(bool, Instant)>>>>, cx| {
-                    let drag_state = v
-                        .flatten()
-                        .unwrap_or_else(|| Rc::new(RefCell::new((false, Instant::now()))));
+                move |v: Option<Option<Rc<RefCell<DragState>>>>, cx| {
+                    let drag_state = v.flatten().unwrap_or_else(|| {
+                        Rc::new(RefCell::new(DragState {
+                            dragging: false,
+                            last_update: Instant::now(),
+                            value: 0.0,
+                        }))
+                    });
                     let func = func.clone();
                     let func_copy = func.clone();
 
@@ -169,7 +187,7 @@ impl Element for Slider {
                                 (on_double_click.borrow_mut())(window, cx);
                             }
 
-                            drag_state_1.borrow_mut().0 = false;
+                            drag_state_1.borrow_mut().dragging = false;
                             return;
                         }
 
@@ -180,30 +198,41 @@ impl Element for Slider {
 
                         (func.borrow_mut())(value, window, cx);
                         let mut state = drag_state_1.borrow_mut();
-                        state.0 = true;
-                        state.1 = Instant::now();
+                        state.dragging = true;
+                        state.last_update = Instant::now();
+                        state.value = value;
                     });
 
                     let drag_state_2 = drag_state.clone();
 
                     cx.on_mouse_event(move |ev: &MouseMoveEvent, _, window, cx| {
                         let mut state = drag_state_2.borrow_mut();
-                        if state.0 && state.1.elapsed().as_millis() >= 1 {
+                        if state.dragging && state.last_update.elapsed().as_millis() >= 1 {
                             let relative = ev.position - bounds.origin;
                             let relative_x: f32 = relative.x.into();
                             let width: f32 = bounds.size.width.into();
                             let value = (relative_x / width).clamp(0.0, 1.0);
 
                             (func_copy.borrow_mut())(value, window, cx);
-                            state.1 = Instant::now();
+                            state.last_update = Instant::now();
+                            state.value = value;
                         }
                     });
 
                     let drag_state_3 = drag_state.clone();
 
-                    cx.on_mouse_event(move |_ev: &MouseUpEvent, _, _window, _cx| {
+                    cx.on_mouse_event(move |_ev: &MouseUpEvent, _, window, cx| {
                         let mut state = drag_state_3.borrow_mut();
-                        state.0 = false;
+                        if !state.dragging {
+                            return;
+                        }
+                        state.dragging = false;
+                        let value = state.value;
+                        drop(state);
+
+                        if let Some(on_release) = on_release.as_ref() {
+                            (on_release.borrow_mut())(value, window, cx);
+                        }
                     });
 
                     ((), if id.is_some() { Some(drag_state) } else { None })
@@ -220,5 +249,6 @@ pub fn slider() -> Slider {
         value: 0.0,
         on_change: None,
         on_double_click: None,
+        on_release: None,
     }
 }
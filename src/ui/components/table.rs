@@ -7,6 +7,7 @@ mod table_item;
 use std::{rc::Rc, sync::Arc};
 
 use crate::{
+    library::db::LibraryFilter,
     settings::{
         SettingsGlobal,
         interface::clamp_grid_min_item_width,
@@ -15,8 +16,10 @@ use crate::{
     ui::{
         caching::hummingbird_cache,
         components::{
+            button::button,
             context::context,
             drag_drop::DragPreview,
+            dropdown::dropdown,
             icons::{CHEVRON_DOWN, CHEVRON_UP, GRID, GRID_INACTIVE, LIST, LIST_INACTIVE, icon},
             menu::{menu, menu_check_item},
             nav_button::nav_button,
@@ -35,13 +38,40 @@ use gpui::{prelude::FluentBuilder, *};
 use indexmap::IndexMap;
 use rustc_hash::{FxBuildHasher, FxHashMap};
 use table_data::{
-    Column, ColumnReorderDrag, GridContext, TABLE_HEADER_GROUP, TABLE_IMAGE_COLUMN_WIDTH,
-    TableData, TableSort,
+    Column, ColumnReorderDrag, FilterOptions, GridContext, TABLE_HEADER_GROUP,
+    TABLE_IMAGE_COLUMN_WIDTH, TableData, TableSort,
 };
 use table_item::TableItem;
 
 type RowMap<T, C> = FxHashMap<usize, Entity<TableItem<T, C>>>;
 
+/// The letters shown in the jump bar, in order. `#` covers everything that doesn't start with a
+/// letter.
+const JUMP_BAR_LETTERS: &str = "#ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+fn jump_letter(text: &str) -> char {
+    match text
+        .trim_start()
+        .chars()
+        .next()
+        .map(|c| c.to_ascii_uppercase())
+    {
+        Some(c @ 'A'..='Z') => c,
+        _ => '#',
+    }
+}
+
+/// Returns the index of the first row starting with each letter.
+fn build_jump_targets(texts: &[String]) -> FxHashMap<char, usize> {
+    let mut targets = FxHashMap::default();
+
+    for (idx, text) in texts.iter().enumerate() {
+        targets.entry(jump_letter(text)).or_insert(idx);
+    }
+
+    targets
+}
+
 #[allow(type_alias_bounds)]
 pub type OnSelectHandler<T, C>
 where
@@ -75,8 +105,15 @@ where
 
     items: Option<Arc<Vec<T::Identifier>>>,
     sort_method: Entity<Option<TableSort<C>>>,
+    filter: Entity<LibraryFilter>,
+    // only present if the rows can be filtered
+    filter_options: Option<Arc<FilterOptions>>,
     on_select: Option<OnSelectHandler<T, C>>,
     scroll_handle: UniformListScrollHandle,
+
+    // only present while the rows are sorted by name
+    jump_targets: Option<Arc<FxHashMap<char, usize>>>,
+    pending_grid_scroll: Option<usize>,
 }
 
 pub enum TableEvent {
@@ -121,6 +158,8 @@ where
             let grid_scroll_handle = UniformListScrollHandle::new();
 
             let sort_method = cx.new(|_| None);
+            let filter = cx.new(|_| LibraryFilter::default());
+            let filter_options = T::get_filter_options(cx).map(Arc::new);
             let scroll_handle = UniformListScrollHandle::new();
 
             if let Some(offset) = initial_scroll_offset {
@@ -143,19 +182,21 @@ where
                     });
             }
 
-            let items = T::get_rows(cx, None).ok().map(Arc::new);
+            let items = T::get_rows(cx, None, &LibraryFilter::default())
+                .ok()
+                .map(Arc::new);
+            let jump_targets = items
+                .as_ref()
+                .and_then(|items| T::get_sort_text(cx, None, items))
+                .map(|texts| Arc::new(build_jump_targets(&texts)));
 
-            cx.observe(&sort_method, |this: &mut Table<T, C>, sort, cx| {
-                let sort_method = *sort.read(cx);
-                let items = T::get_rows(cx, sort_method).ok().map(Arc::new);
-
-                this.views = cx.new(|_| FxHashMap::default());
-                this.render_counter = cx.new(|_| 0);
-                this.grid_views = cx.new(|_| FxHashMap::default());
-                this.grid_render_counter = cx.new(|_| 0);
-                this.items = items;
+            cx.observe(&sort_method, |this: &mut Table<T, C>, _, cx| {
+                this.reload_rows(cx);
+            })
+            .detach();
 
-                cx.notify();
+            cx.observe(&filter, |this: &mut Table<T, C>, _, cx| {
+                this.reload_rows(cx);
             })
             .detach();
 
@@ -188,16 +229,9 @@ where
 
             cx.subscribe(&cx.entity(), |this, _, event, cx| match event {
                 TableEvent::NewRows => {
-                    let sort_method = *this.sort_method.read(cx);
-                    let items = T::get_rows(cx, sort_method).ok().map(Arc::new);
-
-                    this.views = cx.new(|_| FxHashMap::default());
-                    this.render_counter = cx.new(|_| 0);
-                    this.grid_views = cx.new(|_| FxHashMap::default());
-                    this.grid_render_counter = cx.new(|_| 0);
-                    this.items = items;
-
-                    cx.notify();
+                    // a scan may have added new genres or decades
+                    this.filter_options = T::get_filter_options(cx).map(Arc::new);
+                    this.reload_rows(cx);
                 }
             })
             .detach();
@@ -214,12 +248,154 @@ where
                 grid_scroll_handle,
                 items,
                 sort_method,
+                filter,
+                filter_options,
                 on_select,
                 scroll_handle,
+                jump_targets,
+                pending_grid_scroll: None,
             }
         })
     }
 
+    /// Fetches the rows again with the current sort and filter, dropping the cached row views.
+    fn reload_rows(&mut self, cx: &mut Context<Self>) {
+        let sort_method = *self.sort_method.read(cx);
+        let filter = self.filter.read(cx).clone();
+        let items = T::get_rows(cx, sort_method, &filter).ok().map(Arc::new);
+
+        self.views = cx.new(|_| FxHashMap::default());
+        self.render_counter = cx.new(|_| 0);
+        self.grid_views = cx.new(|_| FxHashMap::default());
+        self.grid_render_counter = cx.new(|_| 0);
+        self.items = items;
+        self.update_jump_targets(cx);
+
+        cx.notify();
+    }
+
+    fn update_jump_targets(&mut self, cx: &mut App) {
+        let sort_method = *self.sort_method.read(cx);
+        self.jump_targets = self
+            .items
+            .clone()
+            .and_then(|items| T::get_sort_text(cx, sort_method, &items))
+            .map(|texts| Arc::new(build_jump_targets(&texts)));
+    }
+
+    fn jump_to(&mut self, idx: usize, cx: &mut Context<Self>) {
+        match *self.view_mode.read(cx) {
+            TableViewMode::List => self.scroll_handle.scroll_to_item(idx, ScrollStrategy::Top),
+            TableViewMode::Grid => self.pending_grid_scroll = Some(idx),
+        }
+
+        cx.notify();
+    }
+
+    fn render_jump_bar(
+        &self,
+        targets: &FxHashMap<char, usize>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let theme = cx.global::<Theme>();
+
+        div()
+            .id("table-jump-bar")
+            .flex()
+            .flex_col()
+            .justify_center()
+            .flex_shrink_0()
+            .h_full()
+            .px(px(2.0))
+            .children(JUMP_BAR_LETTERS.chars().enumerate().map(|(i, letter)| {
+                let target = targets.get(&letter).copied();
+
+                div()
+                    .id(("table-jump-letter", i))
+                    .w(px(16.0))
+                    .flex()
+                    .justify_center()
+                    .rounded(px(3.0))
+                    .text_size(px(10.0))
+                    .line_height(px(14.0))
+                    .font_weight(FontWeight::BOLD)
+                    .child(SharedString::from(letter.to_string()))
+                    .when_none(&target, |this| this.text_color(theme.text_disabled))
+                    .when_some(target, |this, idx| {
+                        this.text_color(theme.text_secondary)
+                            .cursor_pointer()
+                            .hover(|this| this.bg(theme.nav_button_hover).text_color(theme.text))
+                            .on_click(cx.listener(move |this, _, _, cx| this.jump_to(idx, cx)))
+                    })
+            }))
+    }
+
+    fn render_filter_bar(&self, options: &FilterOptions, cx: &App) -> impl IntoElement {
+        let filter = self.filter.read(cx).clone();
+        let is_filtered = !filter.is_empty();
+
+        let mut genre_dropdown = dropdown::<Option<String>>("table-genre-filter")
+            .w(px(200.0))
+            .selected(filter.genre.clone())
+            .option(None, tr!("FILTER_ALL_GENRES", "All Genres"));
+        for genre in &options.genres {
+            genre_dropdown = genre_dropdown.option(Some(genre.clone()), genre.clone());
+        }
+
+        let mut decade_dropdown = dropdown::<Option<(i32, i32)>>("table-decade-filter")
+            .w(px(150.0))
+            .selected(filter.years)
+            .option(None, tr!("FILTER_ALL_YEARS", "All Years"));
+        for &decade in &options.decades {
+            decade_dropdown = decade_dropdown.option(
+                Some((decade, decade + 9)),
+                tr!(
+                    "FILTER_DECADE",
+                    "{{decade}}s",
+                    decade = decade,
+                    #description="A decade, such as the 1990s. The year is the first year \
+                        of the decade."
+                ),
+            );
+        }
+
+        let genre_filter = self.filter.clone();
+        let decade_filter = self.filter.clone();
+        let clear_filter = self.filter.clone();
+
+        div()
+            .flex()
+            .items_center()
+            .gap(px(8.0))
+            .px(px(18.0))
+            .pb(px(10.0))
+            .child(genre_dropdown.on_change(move |genre, _, cx| {
+                genre_filter.update(cx, |filter, cx| {
+                    filter.genre = genre.clone();
+                    cx.notify();
+                });
+            }))
+            .child(decade_dropdown.on_change(move |years, _, cx| {
+                decade_filter.update(cx, |filter, cx| {
+                    filter.years = *years;
+                    cx.notify();
+                });
+            }))
+            .when(is_filtered, |this| {
+                this.child(
+                    button()
+                        .id("table-clear-filters")
+                        .child(tr!("FILTER_CLEAR", "Clear Filters"))
+                        .on_click(move |_, _, cx| {
+                            clear_filter.update(cx, |filter, cx| {
+                                *filter = LibraryFilter::default();
+                                cx.notify();
+                            });
+                        }),
+                )
+            })
+    }
+
     pub fn get_scroll_offset(&self, cx: &App) -> f32 {
         let offset = match *self.view_mode.read(cx) {
             TableViewMode::List => self.scroll_handle.0.borrow().base_handle.offset(),
@@ -303,7 +479,7 @@ where
         let default_columns = T::default_columns();
 
         let Some(settings) = settings else {
-            return (default_columns, FxHashMap::default());
+            return Self::split_default_columns(default_columns);
         };
 
         let legacy_order: Vec<String>;
@@ -314,6 +490,7 @@ where
         } else if !settings.hidden_columns.is_empty() {
             legacy_order = default_columns
                 .keys()
+                .filter(|c| c.is_shown_by_default())
                 .filter(|c| {
                     !settings
                         .hidden_columns
@@ -323,7 +500,7 @@ where
                 .collect();
             &legacy_order
         } else {
-            return (default_columns, FxHashMap::default());
+            return Self::split_default_columns(default_columns);
         };
 
         let mut visible_columns = IndexMap::with_hasher(FxBuildHasher);
@@ -363,6 +540,24 @@ where
         (visible_columns, hidden_widths)
     }
 
+    /// Splits the default columns into the ones shown and hidden before any settings are saved.
+    fn split_default_columns(
+        default_columns: IndexMap<C, f32, FxBuildHasher>,
+    ) -> (IndexMap<C, f32, FxBuildHasher>, FxHashMap<C, f32>) {
+        let mut visible_columns = IndexMap::with_hasher(FxBuildHasher);
+        let mut hidden_widths = FxHashMap::default();
+
+        for (col, width) in default_columns {
+            if col.is_shown_by_default() || !col.is_hideable() {
+                visible_columns.insert(col, width);
+            } else {
+                hidden_widths.insert(col, width);
+            }
+        }
+
+        (visible_columns, hidden_widths)
+    }
+
     pub fn get_settings(&self, cx: &App) -> TableSettings {
         let columns = self.columns.read(cx);
         let hidden = self.hidden_column_widths.read(cx);
@@ -428,10 +623,26 @@ where
         let grid_render_counter = self.grid_render_counter.clone();
         let view_mode = *self.view_mode.read(cx);
         let grid_scroll_handle = self.grid_scroll_handle.clone();
-        let grid_min_item_width = {
+        let (grid_min_item_width, show_jump_bar, density) = {
             let settings = cx.global::<SettingsGlobal>().model.read(cx);
-            clamp_grid_min_item_width(settings.interface.grid_min_item_width)
+            (
+                clamp_grid_min_item_width(settings.interface.grid_min_item_width),
+                settings.interface.show_jump_bar,
+                settings.interface.list_density,
+            )
         };
+        let header_height = density.table_row_height();
+        let grid_scroll_to = self.pending_grid_scroll.take();
+        let jump_bar = self
+            .jump_targets
+            .clone()
+            .filter(|_| show_jump_bar)
+            .map(|targets| self.render_jump_bar(&targets, cx));
+
+        let filter_bar = self
+            .filter_options
+            .clone()
+            .map(|options| self.render_filter_bar(&options, cx));
 
         let columns = self.columns.clone();
         let context_menu_context = self.context_menu_context.clone();
@@ -452,7 +663,7 @@ where
             header = header.child(
                 div()
                     .w(px(TABLE_IMAGE_COLUMN_WIDTH))
-                    .h(px(36.0))
+                    .h(px(header_height))
                     .pl(px(18.0))
                     .pr(px(10.0))
                     .py(px(2.0))
@@ -482,9 +693,9 @@ where
                     .flex()
                     .when(!is_last, |this| this.w(px(base_width)))
                     .when(is_last, |this| this.flex_grow().min_w(px(base_width)))
-                    .h(px(36.0))
+                    .h(px(header_height))
                     .px(px(12.0))
-                    .py(px(6.0))
+                    .py(px(density.row_padding()))
                     .when(!T::has_images() && i == 0, |div| div.pl(px(18.0)))
                     .text_sm()
                     .flex_shrink_0()
@@ -547,7 +758,12 @@ where
             );
 
             if column_id.is_resizable() && !is_last {
-                header = header.child(column_resize_handle(i, self.columns.clone(), default_width));
+                header = header.child(column_resize_handle(
+                    i,
+                    self.columns.clone(),
+                    default_width,
+                    header_height,
+                ));
             }
         }
 
@@ -637,13 +853,14 @@ where
             .w_full()
             .h_full()
             .child(title_bar)
+            .when_some(filter_bar, |this, filter_bar| this.child(filter_bar))
             .when(view_mode == TableViewMode::List, |this| {
                 this.child(header_with_context)
             })
             .when_some(items, |this, items| {
                 let items_len = items.len();
 
-                this.child(match view_mode {
+                let content = match view_mode {
                     TableViewMode::List => div()
                         .image_cache(hummingbird_cache((T::get_table_name(), 0_usize), 200))
                         .relative()
@@ -749,7 +966,8 @@ where
                                 )
                                 .min_item_width(px(grid_min_item_width))
                                 .gap(px(gap))
-                                .py(px(grid_padding)),
+                                .py(px(grid_padding))
+                                .scroll_to_item(grid_scroll_to),
                             )
                             .child(floating_scrollbar(
                                 "grid-scrollbar",
@@ -757,7 +975,17 @@ where
                                 RightPad::Pad,
                             ))
                     }
-                })
+                };
+
+                this.child(
+                    div()
+                        .flex()
+                        .w_full()
+                        .h_full()
+                        .min_h_0()
+                        .child(content.flex_grow().min_w_0())
+                        .children(jump_bar),
+                )
             })
     }
 }
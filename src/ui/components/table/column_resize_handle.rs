@@ -22,6 +22,7 @@ where
     column_index: usize,
     columns: Entity<Arc<IndexMap<C, f32, FxBuildHasher>>>,
     default_width: f32,
+    height: f32,
 }
 
 impl<C> ColumnResizeHandle<C>
@@ -32,12 +33,14 @@ where
         column_index: usize,
         columns: Entity<Arc<IndexMap<C, f32, FxBuildHasher>>>,
         default_width: f32,
+        height: f32,
     ) -> Self {
         Self {
             id: ElementId::Name(format!("column-resize-handle-{}", column_index).into()),
             column_index,
             columns,
             default_width,
+            height,
         }
     }
 }
@@ -80,7 +83,7 @@ where
         let mut element = div()
             .id(self.id.clone())
             .w(px(COLUMN_RESIZE_HANDLE_WIDTH))
-            .h(px(36.0)) // Match header height
+            .h(px(self.height)) // Match header height
             .flex_shrink_0()
             .cursor_col_resize()
             .ml(px(-COLUMN_RESIZE_HANDLE_WIDTH / 2.0))
@@ -231,9 +234,10 @@ pub fn column_resize_handle<C>(
     column_index: usize,
     columns: Entity<Arc<IndexMap<C, f32, FxBuildHasher>>>,
     default_width: f32,
+    height: f32,
 ) -> ColumnResizeHandle<C>
 where
     C: Column + 'static,
 {
-    ColumnResizeHandle::new(column_index, columns, default_width)
+    ColumnResizeHandle::new(column_index, columns, default_width, height)
 }
@@ -6,13 +6,16 @@ use super::{
     OnSelectHandler,
     table_data::{Column, GridContext, TableData, TableDragData},
 };
-use crate::ui::{
-    components::{
-        context::context,
-        drag_drop::{AlbumDragData, DragPreview, TrackDragData},
-        managed_image::{ManagedImageKey, managed_image},
+use crate::{
+    settings::SettingsGlobal,
+    ui::{
+        components::{
+            context::context,
+            drag_drop::{AlbumDragData, DragPreview, TrackDragData},
+            managed_image::{ManagedImageKey, managed_image},
+        },
+        theme::Theme,
     },
-    theme::Theme,
 };
 
 #[derive(Clone)]
@@ -26,8 +29,8 @@ where
     row: Arc<T>,
     id: ElementId,
     image_key: Option<ManagedImageKey>,
-    primary_text: SharedString,
-    secondary_text: Option<SharedString>,
+    /// The text shown under the image, if any, as a primary and an optional secondary line.
+    text: Option<(SharedString, Option<SharedString>)>,
     on_select: Option<OnSelectHandler<T, C>>,
     is_available: bool,
 }
@@ -49,19 +52,30 @@ where
         let element_id = row.get_element_id().into();
         let image_key = row.get_full_image_key();
         let is_available = row.is_available(cx);
-        let grid_content = row.get_grid_content_for(cx, context);
-        let (primary_text, secondary_text) = grid_content.unwrap_or(("".into(), None));
+        let text = row.get_grid_content_for(cx, context);
 
-        Some(cx.new(|_| Self {
-            context_menu_context,
-            grid_context: context,
-            row,
-            id: element_id,
-            image_key,
-            primary_text,
-            secondary_text,
-            on_select,
-            is_available,
+        Some(cx.new(|cx| {
+            // which lines are shown depends on the interface settings
+            let settings = cx.global::<SettingsGlobal>().model.clone();
+            cx.observe(&settings, |this: &mut Self, _, cx| {
+                let text = this.row.get_grid_content_for(cx, this.grid_context);
+                if text != this.text {
+                    this.text = text;
+                    cx.notify();
+                }
+            })
+            .detach();
+
+            Self {
+                context_menu_context,
+                grid_context: context,
+                row,
+                id: element_id,
+                image_key,
+                text,
+                on_select,
+                is_available,
+            }
         }))
     }
 }
@@ -159,30 +173,33 @@ where
             );
         }
 
-        let content = container
-            .child(img_container)
-            .child(
-                div()
-                    .mt(px(8.0))
-                    .w_full()
-                    .text_sm()
-                    .font_weight(FontWeight::BOLD)
-                    .text_ellipsis()
-                    .overflow_hidden()
-                    .whitespace_nowrap()
-                    .child(self.primary_text.clone()),
-            )
-            .when_some(self.secondary_text.clone(), |this, secondary| {
+        let content = container.child(img_container).when_some(
+            self.text.clone(),
+            |this, (primary, secondary)| {
                 this.child(
-                    gpui::div()
+                    div()
+                        .mt(px(8.0))
                         .w_full()
-                        .text_xs()
-                        .text_color(theme.text_secondary)
+                        .text_sm()
+                        .font_weight(FontWeight::BOLD)
                         .text_ellipsis()
                         .overflow_hidden()
-                        .child(secondary),
+                        .whitespace_nowrap()
+                        .child(primary),
                 )
-            });
+                .when_some(secondary, |this, secondary| {
+                    this.child(
+                        gpui::div()
+                            .w_full()
+                            .text_xs()
+                            .text_color(theme.text_secondary)
+                            .text_ellipsis()
+                            .overflow_hidden()
+                            .child(secondary),
+                    )
+                })
+            },
+        );
 
         if let Some((menu, overlay)) = context_menu {
             let ctx = context(self.id.clone())
@@ -1,12 +1,15 @@
-use std::{fmt::Debug, hash::Hash, sync::Arc};
+use std::{fmt::Debug, hash::Hash, path::Path, sync::Arc};
 
 use gpui::{AnyElement, App, ElementId, SharedString, Window};
 use indexmap::IndexMap;
 use rustc_hash::FxBuildHasher;
 
-use crate::ui::components::{
-    drag_drop::{AlbumDragData, TrackDragData},
-    managed_image::ManagedImageKey,
+use crate::{
+    library::db::LibraryFilter,
+    ui::components::{
+        drag_drop::{AlbumDragData, TrackDragData},
+        managed_image::ManagedImageKey,
+    },
 };
 
 #[derive(Clone, Debug)]
@@ -47,6 +50,12 @@ pub trait Column: Clone + Copy + Debug + Hash + PartialEq + Eq {
         true
     }
 
+    /// Returns whether this column is shown before the user has picked which columns to show.
+    /// Defaults to true.
+    fn is_shown_by_default(&self) -> bool {
+        true
+    }
+
     /// Returns all possible column variants for this type.
     /// Required for building the column visibility menu.
     fn all_columns() -> &'static [Self];
@@ -61,6 +70,14 @@ where
     pub ascending: bool,
 }
 
+/// The facets a table's rows can be filtered by.
+#[derive(Clone, Debug, Default)]
+pub struct FilterOptions {
+    pub genres: Vec<String>,
+    /// The first year of each decade with releases in it.
+    pub decades: Vec<i32>,
+}
+
 /// Context in which a grid item is being displayed.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GridContext {
@@ -84,8 +101,31 @@ where
 
     /// Retrieves the rows of the table. The rows are returned as a vector of identifiers, which
     /// can be used to retrieve the full row data. The sort parameter can be used to specify the
-    /// sorting order of the rows.
-    fn get_rows(cx: &mut App, sort: Option<TableSort<C>>) -> anyhow::Result<Vec<Self::Identifier>>;
+    /// sorting order of the rows, and the filter narrows down which rows are returned. Tables
+    /// that don't return filter options from `get_filter_options` can ignore the filter.
+    fn get_rows(
+        cx: &mut App,
+        sort: Option<TableSort<C>>,
+        filter: &LibraryFilter,
+    ) -> anyhow::Result<Vec<Self::Identifier>>;
+
+    /// Retrieves the genres and decades the rows can be filtered by, or `None` if the table
+    /// doesn't support filtering.
+    fn get_filter_options(_cx: &mut App) -> Option<FilterOptions> {
+        None
+    }
+
+    /// Retrieves the text each of the given rows is sorted by, in the same order as `rows`.
+    ///
+    /// This is used to build the jump-to-letter bar, so it should only return `Some` when the
+    /// rows are sorted alphabetically by the returned text.
+    fn get_sort_text(
+        _cx: &mut App,
+        _sort: Option<TableSort<C>>,
+        _rows: &[Self::Identifier],
+    ) -> Option<Vec<String>> {
+        None
+    }
 
     /// Retrieves a specific row of the table. The row is returned as an Arc to the table data,
     /// which can be used to retrieve the row data as SharedStrings. The id parameter is used to
@@ -126,6 +166,24 @@ where
         true
     }
 
+    /// Returns the file this row plays, if any. Rows playing the current track are marked with a
+    /// now playing indicator.
+    fn get_location(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Returns the library id of the track this row shows, if any. Rows showing a track are
+    /// reloaded when the track is changed outside of a scan.
+    fn get_track_id(&self) -> Option<i64> {
+        None
+    }
+
+    /// Returns the library id of the album this row shows, if any. Rows showing an album are
+    /// reloaded when the album is changed outside of a scan.
+    fn get_album_id(&self) -> Option<i64> {
+        None
+    }
+
     /// Returns drag data for this row, if dragging is supported. If None is returned, dragging is
     /// not supported. Default implementation returns None.
     fn get_drag_data(&self) -> Option<TableDragData> {
@@ -8,10 +8,15 @@ use super::{
     OnSelectHandler,
     table_data::{Column, GridContext, TABLE_IMAGE_COLUMN_WIDTH, TableData, TableDragData},
 };
-use crate::ui::{
-    components::context::context,
-    components::drag_drop::{AlbumDragData, DragPreview, TrackDragData},
-    theme::Theme,
+use crate::{
+    settings::{SettingsGlobal, interface::ListDensity},
+    ui::{
+        components::context::context,
+        components::drag_drop::{AlbumDragData, DragPreview, TrackDragData},
+        components::now_playing::{now_playing, now_playing_indicator},
+        models::{AlbumEvent, Models, PlaybackInfo, TrackEvent},
+        theme::Theme,
+    },
 };
 
 #[derive(Clone)]
@@ -28,6 +33,7 @@ where
     id: Option<ElementId>,
     image_path: Option<SharedString>,
     is_available: bool,
+    density: ListDensity,
 }
 
 impl<T, C> TableItem<T, C>
@@ -56,7 +62,55 @@ where
 
         let image_path = row.as_ref().and_then(|row| row.get_image_path());
         let is_available = row.as_ref().is_some_and(|row| row.is_available(cx));
+        let settings = cx.global::<SettingsGlobal>().model.clone();
+        let density = settings.read(cx).interface.list_density;
         cx.new(|cx| {
+            cx.observe(&settings, |this: &mut TableItem<T, C>, m, cx| {
+                let density = m.read(cx).interface.list_density;
+                if this.density != density {
+                    this.density = density;
+                    cx.notify();
+                }
+            })
+            .detach();
+
+            // only rows that can play a track need to follow playback
+            if row.as_ref().is_some_and(|row| row.get_location().is_some()) {
+                let playback_info = cx.global::<PlaybackInfo>().clone();
+                cx.observe(&playback_info.current_track, |_, _, cx| cx.notify())
+                    .detach();
+                cx.observe(&playback_info.playback_state, |_, _, cx| cx.notify())
+                    .detach();
+
+                // ratings and shuffle flags can change without the table being reloaded
+                let playlist_tracker = cx.global::<Models>().playlist_tracker.clone();
+                cx.subscribe(
+                    &playlist_tracker,
+                    |this: &mut TableItem<T, C>, _, event: &TrackEvent, cx| {
+                        let id = event.track_id();
+                        if this.row.as_ref().and_then(|row| row.get_track_id()) == Some(id) {
+                            this.reload_row(cx);
+                        }
+                    },
+                )
+                .detach();
+            }
+
+            // album ratings can change without the table being reloaded
+            if row.as_ref().is_some_and(|row| row.get_album_id().is_some()) {
+                let playlist_tracker = cx.global::<Models>().playlist_tracker.clone();
+                cx.subscribe(
+                    &playlist_tracker,
+                    |this: &mut TableItem<T, C>, _, event: &AlbumEvent, cx| {
+                        let id = event.album_id();
+                        if this.row.as_ref().and_then(|row| row.get_album_id()) == Some(id) {
+                            this.reload_row(cx);
+                        }
+                    },
+                )
+                .detach();
+            }
+
             cx.observe(columns, |this: &mut TableItem<T, C>, m, cx| {
                 this.columns = m.read(cx).clone();
 
@@ -79,9 +133,26 @@ where
                 id,
                 row,
                 is_available,
+                density,
             }
         })
     }
+
+    fn reload_row(&mut self, cx: &mut Context<Self>) {
+        let Some(row) = self
+            .row
+            .as_ref()
+            .and_then(|row| T::get_row(cx, row.get_table_id()).ok().flatten())
+        else {
+            return;
+        };
+
+        let keys = self.columns.keys();
+        self.data = Some(keys.into_iter().map(|v| row.get_column(cx, *v)).collect());
+        self.row = Some(row);
+
+        cx.notify();
+    }
 }
 
 impl<T, C> Render for TableItem<T, C>
@@ -95,7 +166,14 @@ where
         let context_menu = self.row.as_ref().and_then(|row| {
             row.get_context_menu(window, cx, &self.context_menu_context, GridContext::Table)
         });
+        let playing = self
+            .row
+            .as_ref()
+            .and_then(|row| row.get_location())
+            .and_then(|location| now_playing(cx, location));
         let theme = cx.global::<Theme>();
+        let row_height = self.density.table_row_height();
+        let row_padding = self.density.row_padding();
         let drag_data = if is_available {
             self.row.as_ref().and_then(|row| row.get_drag_data())
         } else {
@@ -106,6 +184,9 @@ where
             .w_full()
             .flex()
             .id(self.id.clone().unwrap_or("bad".into()))
+            .when(playing.is_some(), |this| {
+                this.bg(theme.now_playing_highlight)
+            })
             .when_some(self.on_select.clone(), {
                 let row_data = row_data.clone();
                 move |div, on_select| {
@@ -158,7 +239,7 @@ where
             row = row.child(
                 div()
                     .w(px(TABLE_IMAGE_COLUMN_WIDTH))
-                    .h(px(36.0))
+                    .h(px(row_height))
                     .text_sm()
                     .pl(px(11.0))
                     .flex_shrink_0()
@@ -177,6 +258,19 @@ where
                             .bg(theme.album_art_background)
                             .when_some(self.image_path.clone(), |div, image| {
                                 div.child(img(image).w(px(22.0)).h(px(22.0)).rounded(px(3.0)))
+                            })
+                            .when_some(playing, |div, playing| {
+                                div.relative().child(
+                                    div()
+                                        .absolute()
+                                        .inset_0()
+                                        .rounded(px(3.0))
+                                        .bg(gpui::rgba(0x00000088))
+                                        .flex()
+                                        .items_center()
+                                        .justify_center()
+                                        .child(now_playing_indicator(playing)),
+                                )
                             }),
                     ),
             );
@@ -197,11 +291,19 @@ where
                     div()
                         .when(!is_last, |this| this.w(px(base_width)))
                         .when(is_last, |this| this.flex_grow().min_w(px(base_width)))
-                        .h(px(36.0))
+                        .h(px(row_height))
                         .px(px(12.0))
-                        .py(px(6.0))
+                        .py(px(row_padding))
                         .when(!T::has_images() && i == 0, |div| div.pl(px(17.0)))
                         .when(monospace, |div| div.font_family("Roboto Mono"))
+                        .when_some(
+                            playing.filter(|_| !T::has_images() && i == 0),
+                            |div, playing| {
+                                div.flex()
+                                    .gap(px(8.0))
+                                    .child(div().my_auto().child(now_playing_indicator(playing)))
+                            },
+                        )
                         .text_sm()
                         .flex_shrink_0()
                         .overflow_hidden()
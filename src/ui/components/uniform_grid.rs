@@ -23,6 +23,8 @@ pub struct UniformGrid {
     item_extra_height: Pixels,
     overscan_rows: usize,
     auto_height: bool,
+    row_height: Option<Pixels>,
+    scroll_to_item: Option<usize>,
     interactivity: Interactivity,
     render_item: RenderItemCallback,
 }
@@ -61,6 +63,8 @@ impl UniformGrid {
             item_extra_height: px(DEFAULT_ITEM_EXTRA_HEIGHT),
             overscan_rows: DEFAULT_OVERSCAN_ROWS,
             auto_height: false,
+            row_height: None,
+            scroll_to_item: None,
             interactivity,
             render_item: Rc::new(render_item),
         };
@@ -98,9 +102,34 @@ impl UniformGrid {
         self
     }
 
+    /// Lays the items out in a single column of rows with the given height, like a list. Combined
+    /// with [`UniformGrid::auto_height`], this lists rows inside a scrolling page while only
+    /// drawing the ones that are visible.
+    pub fn rows(mut self, height: Pixels) -> Self {
+        self.row_height = Some(height);
+        self
+    }
+
+    /// Scrolls the row containing the given item to the top of the grid during the next layout.
+    pub fn scroll_to_item(mut self, item: Option<usize>) -> Self {
+        self.scroll_to_item = item;
+        self
+    }
+
     fn compute_metrics(&self, viewport_width: Pixels) -> GridMetrics {
         let width = viewport_width.max(px(0.0));
         let gap = self.gap.max(px(0.0));
+
+        if let Some(row_height) = self.row_height {
+            return GridMetrics {
+                columns: 1,
+                item_width: width,
+                item_height: row_height,
+                row_stride: row_height + gap,
+                row_count: self.item_count,
+            };
+        }
+
         let min_item_width = self.min_item_width.max(px(1.0));
 
         let columns = (((width + gap) / (min_item_width + gap)).floor().max(1.0)) as usize;
@@ -248,6 +277,7 @@ impl Element for UniformGrid {
         let scroll_handle = self.scroll_handle.clone();
         let metrics_for_bounds = self.compute_metrics(bounds.size.width);
         let auto_height = self.auto_height;
+        let scroll_to_item = self.scroll_to_item.filter(|idx| *idx < item_count);
 
         self.interactivity.prepaint(
             id,
@@ -281,8 +311,17 @@ impl Element for UniformGrid {
                         bounds: mask_bounds,
                     };
                     window.with_content_mask(Some(content_mask), |window| {
+                        // the grid isn't scrolled itself, but rows scrolled out of view by an
+                        // enclosing container don't need to be drawn
+                        let visible = window.content_mask().bounds;
+
                         for row in 0..metrics.row_count {
                             let row_y = bounds.origin.y + top_padding + metrics.row_stride * row;
+                            if row_y + metrics.item_height < visible.top()
+                                || row_y > visible.bottom()
+                            {
+                                continue;
+                            }
 
                             for col in 0..metrics.columns {
                                 let idx = row * metrics.columns + col;
@@ -311,6 +350,10 @@ impl Element for UniformGrid {
                     return hitbox;
                 }
 
+                if let Some(idx) = scroll_to_item {
+                    scroll_offset.y = -(metrics.row_stride * (idx / metrics.columns));
+                }
+
                 let min_vertical_scroll_offset = bounds.size.height - content_size.height;
                 if scroll_offset.y < min_vertical_scroll_offset {
                     scroll_offset.y = min_vertical_scroll_offset;
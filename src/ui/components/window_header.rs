@@ -1,10 +1,14 @@
 use gpui::{prelude::FluentBuilder, *};
 use smallvec::SmallVec;
 
-use crate::ui::{
-    components::icons::{CROSS, MAXIMIZE, MINIMIZE, MINUS, icon},
-    constants::APP_ROUNDING,
-    theme::Theme,
+use crate::{
+    settings::{SettingsGlobal, interface::TitlebarDoubleClick},
+    ui::{
+        components::icons::{CROSS, MAXIMIZE, MINIMIZE, MINUS, icon},
+        constants::APP_ROUNDING,
+        theme::Theme,
+        tray::close_main_window,
+    },
 };
 
 #[derive(IntoElement)]
@@ -86,9 +90,16 @@ impl RenderOnce for WindowHeader {
                         window.start_window_move();
                     }
                 })
-                .on_click(|ev, window, _| {
-                    if ev.click_count() == 2 {
-                        window.zoom_window();
+                .on_click(|ev, window, cx| {
+                    if ev.click_count() != 2 {
+                        return;
+                    }
+
+                    let settings = cx.global::<SettingsGlobal>().model.read(cx);
+                    match settings.interface.titlebar_double_click {
+                        TitlebarDoubleClick::Maximize => window.zoom_window(),
+                        TitlebarDoubleClick::Minimize => window.minimize_window(),
+                        TitlebarDoubleClick::Nothing => {}
                     }
                 })
             })
@@ -190,7 +201,7 @@ impl RenderOnce for WindowButton {
             })
             .on_click(move |_, window, cx| match self {
                 WindowButton::Close(false) => window.remove_window(),
-                WindowButton::Close(true) => cx.quit(),
+                WindowButton::Close(true) => close_main_window(window, cx),
                 WindowButton::Minimize => {
                     if !cfg!(target_os = "windows") {
                         window.minimize_window()
@@ -2,19 +2,21 @@ mod replaygain;
 
 use crate::{
     library::{db::LibraryAccess, types::Track},
+    media::waveform::{WAVEFORM_POINTS, compute_waveform},
     playback::{events::RepeatState, interface::PlaybackInterface, thread::PlaybackState},
-    settings::SettingsGlobal,
+    settings::{SettingsGlobal, replaygain::ReplayGainMode},
     ui::{
         caching::hummingbird_cache,
         components::{
             context::context,
             icons::{
-                MENU, MICROPHONE, NEXT_TRACK, PAUSE, PLAY, PREV_TRACK, REPEAT, REPEAT_OFF,
+                DOTS, MENU, MICROPHONE, NEXT_TRACK, PAUSE, PLAY, PREV_TRACK, REPEAT, REPEAT_OFF,
                 REPEAT_ONCE, SHUFFLE, VOLUME, VOLUME_OFF, icon,
             },
             managed_image::{ManagedImageKey, managed_image},
-            menu::{menu, menu_item},
-            tooltip::build_tooltip,
+            menu::{menu, menu_check_item, menu_item, menu_separator},
+            popover::{PopoverPosition, popover},
+            tooltip::{build_tooltip, tooltip_container},
             volume_tooltip::build_volume_tooltip,
         },
         library::context_menus::{
@@ -25,9 +27,19 @@ use crate::{
     },
 };
 use cntp_i18n::tr;
+use futures::TryFutureExt as _;
 use gpui::{Corner, InteractiveElement, *};
 use prelude::FluentBuilder;
-use std::{path::PathBuf, rc::Rc};
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+use tracing::warn;
 
 use self::replaygain::ReplayGainButton;
 use super::{
@@ -36,6 +48,7 @@ use super::{
         slider::slider,
     },
     constants::APP_ROUNDING,
+    format::format_duration,
     global_actions::{Next, PlayPause, Previous},
     models::{Models, PlaybackInfo},
     theme::Theme,
@@ -51,24 +64,45 @@ pub struct Controls {
     right_width: Entity<Pixels>,
 }
 
+/// The widest the track info can be in the compact layout.
+const COMPACT_LEFT_MAX_WIDTH: Pixels = px(200.0);
+/// The width of the volume section in the compact layout.
+const COMPACT_RIGHT_WIDTH: Pixels = px(160.0);
+
 impl Controls {
     pub fn new(cx: &mut App, show_queue: Entity<bool>, show_lyrics: Entity<bool>) -> Entity<Self> {
         let models = cx.global::<Models>();
         let left_width = models.controls_left_width.clone();
         let right_width = models.controls_right_width.clone();
-        cx.new(|cx| Self {
-            info_section: InfoSection::new(cx),
-            scrubber: Scrubber::new(cx),
-            secondary_controls: SecondaryControls::new(cx, show_queue, show_lyrics),
-            left_width,
-            right_width,
+        cx.new(|cx| {
+            let settings = cx.global::<SettingsGlobal>().model.clone();
+            cx.observe(&settings, |_, _, cx| cx.notify()).detach();
+
+            Self {
+                info_section: InfoSection::new(cx),
+                scrubber: Scrubber::new(cx),
+                secondary_controls: SecondaryControls::new(cx, show_queue, show_lyrics),
+                left_width,
+                right_width,
+            }
         })
     }
 }
 
+/// Whether the compact playback controls should be shown in `window`.
+fn is_compact(window: &Window, cx: &App) -> bool {
+    cx.global::<SettingsGlobal>()
+        .model
+        .read(cx)
+        .interface
+        .controls_layout
+        .is_compact(window.viewport_size().width.into())
+}
+
 impl Render for Controls {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let decorations = window.window_decorations();
+        let compact = is_compact(window, cx);
         let theme = cx.global::<Theme>();
 
         div()
@@ -97,7 +131,11 @@ impl Render for Controls {
                     ResizeEdge::Right,
                 )
                 .min_size(px(150.0))
-                .max_size(px(500.0))
+                .max_size(if compact {
+                    COMPACT_LEFT_MAX_WIDTH
+                } else {
+                    px(500.0)
+                })
                 .default_size(DEFAULT_CONTROLS_LEFT_WIDTH)
                 .border_width(px(0.0))
                 .child(self.info_section.clone()),
@@ -109,8 +147,14 @@ impl Render for Controls {
                     self.right_width.clone(),
                     ResizeEdge::Left,
                 )
-                .min_size(px(180.0))
-                .max_size(px(500.0))
+                // the stored widths are kept, so the full layout comes back the same size
+                .when(compact, |this| {
+                    this.min_size(COMPACT_RIGHT_WIDTH)
+                        .max_size(COMPACT_RIGHT_WIDTH)
+                })
+                .when(!compact, |this| {
+                    this.min_size(px(180.0)).max_size(px(500.0))
+                })
                 .default_size(DEFAULT_CONTROLS_RIGHT_WIDTH)
                 .border_width(px(0.0))
                 .child(self.secondary_controls.clone()),
@@ -653,10 +697,22 @@ impl Render for PlaybackSection {
     }
 }
 
+/// The number of waveforms kept in memory, so returning to a recently played track doesn't decode
+/// it again.
+const WAVEFORM_CACHE_SIZE: usize = 16;
+
+const WAVEFORM_HEIGHT: f32 = 18.0;
+
 pub struct Scrubber {
     position: Entity<u64>,
     duration: Entity<u64>,
     playback_section: Entity<PlaybackSection>,
+    /// The position the scrubber is being dragged to, from 0.0 to 1.0.
+    preview: Option<f32>,
+    /// Waveforms of recently played tracks, oldest first.
+    waveforms: VecDeque<(PathBuf, Rc<[f32]>)>,
+    /// The track a waveform is currently being computed for, and the flag that stops it.
+    loading_waveform: Option<(PathBuf, Arc<AtomicBool>)>,
 }
 
 impl Scrubber {
@@ -675,15 +731,147 @@ impl Scrubber {
             })
             .detach();
 
-            Self {
+            let current_track = cx.global::<PlaybackInfo>().current_track.clone();
+            cx.observe(&current_track, |this: &mut Self, _, cx| {
+                this.load_waveform(cx);
+                cx.notify();
+            })
+            .detach();
+
+            let settings = cx.global::<SettingsGlobal>().model.clone();
+            cx.observe(&settings, |this: &mut Self, _, cx| {
+                this.load_waveform(cx);
+                cx.notify();
+            })
+            .detach();
+
+            let mut scrubber = Self {
                 position: position_model,
                 duration: duration_model,
                 playback_section: PlaybackSection::new(cx),
+                preview: None,
+                waveforms: VecDeque::new(),
+                loading_waveform: None,
+            };
+            scrubber.load_waveform(cx);
+
+            scrubber
+        })
+    }
+
+    fn waveform(&self, path: &Path) -> Option<Rc<[f32]>> {
+        self.waveforms
+            .iter()
+            .find(|(waveform_path, _)| waveform_path == path)
+            .map(|(_, peaks)| peaks.clone())
+    }
+
+    fn current_waveform(&self, cx: &App) -> Option<Rc<[f32]>> {
+        let current_track = cx.global::<PlaybackInfo>().current_track.read(cx);
+        self.waveform(current_track.as_ref()?.get_path())
+    }
+
+    /// Computes the waveform of the current track in the background, if waveforms are enabled and
+    /// it isn't already cached.
+    fn load_waveform(&mut self, cx: &mut Context<Self>) {
+        let show_waveform = cx
+            .global::<SettingsGlobal>()
+            .model
+            .read(cx)
+            .interface
+            .show_waveform;
+        let path = cx
+            .global::<PlaybackInfo>()
+            .current_track
+            .read(cx)
+            .as_ref()
+            .map(|track| track.get_path().clone())
+            .filter(|_| show_waveform);
+
+        if let Some((loading, cancelled)) = &self.loading_waveform {
+            if path.as_ref() == Some(loading) {
+                return;
             }
+
+            // the track changed, so the old waveform would never be shown
+            cancelled.store(true, Ordering::Relaxed);
+            self.loading_waveform = None;
+        }
+
+        let Some(path) = path else {
+            return;
+        };
+
+        if self.waveform(&path).is_some() {
+            return;
+        }
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.loading_waveform = Some((path.clone(), cancelled.clone()));
+
+        let task = crate::RUNTIME.spawn_blocking({
+            let path = path.clone();
+            move || compute_waveform(&path, WAVEFORM_POINTS, &cancelled)
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.err_into().await.flatten();
+
+            this.update(cx, |this: &mut Self, cx| {
+                if this
+                    .loading_waveform
+                    .as_ref()
+                    .is_some_and(|(loading, _)| *loading == path)
+                {
+                    this.loading_waveform = None;
+                }
+
+                match result {
+                    Ok(Some(peaks)) => {
+                        if this.waveforms.len() >= WAVEFORM_CACHE_SIZE {
+                            this.waveforms.pop_front();
+                        }
+                        this.waveforms.push_back((path, peaks.into()));
+                        cx.notify();
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        warn!(?err, "Failed to compute waveform for {path:?}: {err}");
+                    }
+                }
+            })
+            .ok();
         })
+        .detach();
     }
 }
 
+/// A waveform overview of the current track, with the part before `progress` highlighted.
+fn waveform(peaks: &[f32], progress: f32, theme: &Theme) -> Div {
+    let played = (progress.clamp(0.0, 1.0) * peaks.len() as f32) as usize;
+
+    div()
+        .w_full()
+        .h(px(WAVEFORM_HEIGHT))
+        .mb(px(4.0))
+        .flex()
+        .items_center()
+        .gap(px(1.0))
+        .children(peaks.iter().enumerate().map(|(i, peak)| {
+            div()
+                .flex_1()
+                .min_w(px(0.0))
+                // keep silent parts visible as a line
+                .h(relative(peak.max(0.08)))
+                .rounded(px(1.0))
+                .bg(if i < played {
+                    theme.slider_foreground
+                } else {
+                    theme.slider_background
+                })
+        }))
+}
+
 impl Render for Scrubber {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let theme = cx.global::<Theme>();
@@ -692,8 +880,29 @@ impl Render for Scrubber {
         let position_secs = position_ms / 1_000;
         let duration_ms = duration_secs.saturating_mul(1_000);
         let remaining_secs = duration_secs.saturating_sub(position_secs);
+        let seek_while_dragging = cx
+            .global::<SettingsGlobal>()
+            .model
+            .read(cx)
+            .interface
+            .seek_while_dragging;
+        let change_view = cx.entity().downgrade();
+        let release_view = change_view.clone();
 
         let window_width = window.viewport_size().width;
+        let progress = match self.preview {
+            Some(preview) => preview,
+            None if duration_ms > 0 => position_ms as f32 / duration_ms as f32,
+            None => 0.0,
+        };
+        let show_waveform = cx
+            .global::<SettingsGlobal>()
+            .model
+            .read(cx)
+            .interface
+            .show_waveform;
+        // until the waveform is ready only the seek bar is shown
+        let waveform_peaks = show_waveform.then(|| self.current_waveform(cx)).flatten();
 
         div()
             .pl(px(13.0))
@@ -714,11 +923,12 @@ impl Render for Scrubber {
                     .items_end()
                     .mt(px(6.0))
                     .mb(px(6.0))
-                    .child(div().mr(px(6.0)).line_height(rems(1.0)).child(format!(
-                        "{:02}:{:02}",
-                        position_secs / 60,
-                        position_secs % 60
-                    )))
+                    .child(
+                        div()
+                            .mr(px(6.0))
+                            .line_height(rems(1.0))
+                            .child(format_duration(position_secs)),
+                    )
                     .when(window_width > px(900.0), |this| {
                         this.child(
                             div()
@@ -727,46 +937,85 @@ impl Render for Scrubber {
                                 .border_l(px(2.0))
                                 .pl(px(6.0))
                                 .text_color(rgb(0xcbd5e1))
-                                .child(format!(
-                                    "{:02}:{:02}",
-                                    duration_secs / 60,
-                                    duration_secs % 60
-                                )),
+                                .child(format_duration(duration_secs)),
                         )
                     })
                     .child(self.playback_section.clone())
                     .child(div().h(px(30.0)))
-                    .child(div().ml(auto()).line_height(rems(1.0)).child(format!(
-                        "-{:02}:{:02}",
-                        remaining_secs / 60,
-                        remaining_secs % 60
-                    ))),
+                    .child(
+                        div()
+                            .ml(auto())
+                            .line_height(rems(1.0))
+                            .child(format!("-{}", format_duration(remaining_secs))),
+                    ),
             )
             .child(
-                slider()
+                div()
                     .w_full()
-                    .h(px(6.0))
-                    .rounded(px(3.0))
-                    .id("scrubber-back")
-                    .value(if duration_ms > 0 {
-                        position_ms as f32 / duration_ms as f32
-                    } else {
-                        0.0
+                    .relative()
+                    .when_some(waveform_peaks, |this, peaks| {
+                        this.child(waveform(&peaks, progress, theme))
                     })
-                    .on_change(move |v, _, cx| {
-                        let info = cx.global::<PlaybackInfo>().clone();
-
-                        if duration_secs > 0
-                            && *info.playback_state.read(cx) != PlaybackState::Stopped
-                        {
-                            cx.global::<PlaybackInterface>()
-                                .seek(v as f64 * duration_secs as f64);
-                        }
+                    .child(
+                        slider()
+                            .w_full()
+                            .h(px(6.0))
+                            .rounded(px(3.0))
+                            .id("scrubber-back")
+                            .value(progress)
+                            .on_change(move |v, _, cx| {
+                                if !can_seek(duration_secs, cx) {
+                                    return;
+                                }
+
+                                if seek_while_dragging {
+                                    cx.global::<PlaybackInterface>()
+                                        .scrub(v as f64 * duration_secs as f64);
+                                }
+
+                                let _ = change_view.update(cx, |this, cx| {
+                                    this.preview = Some(v);
+                                    cx.notify();
+                                });
+                            })
+                            .on_release(move |v, _, cx| {
+                                if !seek_while_dragging && can_seek(duration_secs, cx) {
+                                    cx.global::<PlaybackInterface>()
+                                        .seek(v as f64 * duration_secs as f64);
+                                }
+
+                                let _ = release_view.update(cx, |this, cx| {
+                                    this.preview = None;
+                                    cx.notify();
+                                });
+                            }),
+                    )
+                    .when_some(self.preview, |this, preview| {
+                        let preview_secs = (preview as f64 * duration_secs as f64) as u64;
+
+                        this.child(
+                            div()
+                                .absolute()
+                                .bottom(px(12.0))
+                                .left(relative(preview))
+                                .child(
+                                    tooltip_container(theme)
+                                        .ml(px(-24.0))
+                                        .font_weight(FontWeight::NORMAL)
+                                        .child(format_duration(preview_secs)),
+                                ),
+                        )
                     }),
             )
     }
 }
 
+/// Whether the scrubber can currently be used to seek.
+fn can_seek(duration_secs: u64, cx: &App) -> bool {
+    duration_secs > 0
+        && *cx.global::<PlaybackInfo>().playback_state.read(cx) != PlaybackState::Stopped
+}
+
 #[derive(IntoElement)]
 struct SidebarToggleButton {
     div: Stateful<Div>,
@@ -831,6 +1080,7 @@ pub struct SecondaryControls {
     show_queue: Entity<bool>,
     show_lyrics: Entity<bool>,
     replaygain_button: Entity<ReplayGainButton>,
+    show_overflow: bool,
 }
 
 impl SecondaryControls {
@@ -838,27 +1088,165 @@ impl SecondaryControls {
         cx.new(|cx| {
             let info = cx.global::<PlaybackInfo>().clone();
             let volume = info.volume.clone();
+            let muted = info.muted.clone();
 
             cx.observe(&volume, |_, _, cx| {
                 cx.notify();
             })
             .detach();
 
+            cx.observe(&muted, |_, _, cx| {
+                cx.notify();
+            })
+            .detach();
+
+            cx.observe(&show_queue, |_, _, cx| cx.notify()).detach();
+            cx.observe(&show_lyrics, |_, _, cx| cx.notify()).detach();
+
             Self {
                 info,
                 show_queue,
                 show_lyrics,
                 replaygain_button: ReplayGainButton::new(cx),
+                show_overflow: false,
             }
         })
     }
+
+    fn close_overflow(&mut self, cx: &mut Context<Self>) {
+        self.show_overflow = false;
+        cx.notify();
+    }
+
+    /// The button for the compact layout's menu, which holds the controls that don't fit.
+    fn overflow_menu(&self, cx: &mut Context<Self>) -> AnyElement {
+        let theme = cx.global::<Theme>();
+        let rg_mode = cx
+            .global::<SettingsGlobal>()
+            .model
+            .read(cx)
+            .playback
+            .replaygain
+            .mode;
+        let queue_active = *self.show_queue.read(cx);
+        let lyrics_active = *self.show_lyrics.read(cx);
+        let show_queue = self.show_queue.clone();
+        let show_lyrics = self.show_lyrics.clone();
+        let replaygain_button = self.replaygain_button.clone();
+        let entity = cx.entity().downgrade();
+
+        let close = {
+            let entity = entity.clone();
+            move |cx: &mut App| {
+                entity.update(cx, |this, cx| this.close_overflow(cx)).ok();
+            }
+        };
+
+        let menu = menu()
+            .item(menu_check_item(
+                "overflow-queue",
+                queue_active,
+                tr!("QUEUE_TITLE"),
+                {
+                    let close = close.clone();
+                    move |_, _, cx| {
+                        show_queue.update(cx, |m, cx| {
+                            *m = !*m;
+                            cx.notify();
+                        });
+                        close(cx);
+                    }
+                },
+            ))
+            .item(menu_check_item(
+                "overflow-lyrics",
+                lyrics_active,
+                tr!("LYRICS"),
+                {
+                    let close = close.clone();
+                    move |_, _, cx| {
+                        show_lyrics.update(cx, |m, cx| {
+                            *m = !*m;
+                            cx.notify();
+                        });
+                        close(cx);
+                    }
+                },
+            ))
+            .item(menu_separator())
+            .item(menu_check_item(
+                "overflow-replaygain",
+                rg_mode != ReplayGainMode::Off,
+                tr!("REPLAY_GAIN"),
+                {
+                    let close = close.clone();
+                    move |_, _, cx| {
+                        close(cx);
+                        replaygain_button.update(cx, |button, cx| button.open_popover(cx));
+                    }
+                },
+            ));
+
+        div()
+            .relative()
+            .child(
+                div()
+                    .rounded(px(3.0))
+                    .w(px(25.0))
+                    .h(px(25.0))
+                    .mt(px(2.0))
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .id("controls-overflow-button")
+                    .cursor_pointer()
+                    .tooltip(build_tooltip(tr!("CONTROLS_MORE", "More Controls")))
+                    .bg(theme.playback_button)
+                    .hover(|this| this.bg(theme.playback_button_hover))
+                    .active(|this| this.bg(theme.playback_button_active))
+                    .on_mouse_down(MouseButton::Left, |_, window, cx| {
+                        cx.stop_propagation();
+                        window.prevent_default();
+                    })
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.show_overflow = !this.show_overflow;
+                        cx.notify();
+                    }))
+                    .child(
+                        icon(DOTS)
+                            .size(px(14.0))
+                            .when(queue_active || lyrics_active, |this| {
+                                this.text_color(theme.playback_button_toggled)
+                            }),
+                    ),
+            )
+            .when(self.show_overflow, |this| {
+                let dismiss = close.clone();
+                this.child(
+                    popover()
+                        .position(PopoverPosition::TopRight)
+                        .edge_offset(px(8.0))
+                        .on_dismiss(move |_, cx| dismiss(cx))
+                        .on_mouse_down_out(move |_, _, cx| close(cx))
+                        .child(menu),
+                )
+            })
+            .into_any_element()
+    }
 }
 
 impl Render for SecondaryControls {
-    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let compact = is_compact(window, cx);
+        if !compact {
+            self.show_overflow = false;
+        }
+        let overflow_menu = compact.then(|| self.overflow_menu(cx));
+
         let theme = cx.global::<Theme>();
         let volume = *self.info.volume.read(cx);
         let prev_volume = *self.info.prev_volume.read(cx);
+        let muted = *self.info.muted.read(cx);
         let show_queue = self.show_queue.clone();
         let show_lyrics = self.show_lyrics.clone();
         let lyrics_active = *self.show_lyrics.read(cx);
@@ -885,17 +1273,21 @@ impl Render for SecondaryControls {
                         .bg(theme.playback_button)
                         .hover(|this| this.bg(theme.playback_button_hover))
                         .active(|this| this.bg(theme.playback_button_active))
-                        .when(volume <= 0.0, |div| {
+                        .when(muted || volume <= 0.0, |div| {
                             div.child(icon(VOLUME_OFF).size(px(14.0)))
                                 .on_click(move |_, _, cx| {
-                                    cx.global::<PlaybackInterface>().set_volume(prev_volume);
+                                    if muted {
+                                        cx.global::<PlaybackInterface>().toggle_mute();
+                                    } else {
+                                        cx.global::<PlaybackInterface>().set_volume(prev_volume);
+                                    }
                                 })
                                 .tooltip(build_tooltip(tr!("UNMUTE", "Unmute")))
                         })
-                        .when(volume > 0.0, |div| {
+                        .when(!muted && volume > 0.0, |div| {
                             div.child(icon(VOLUME).size(px(14.0)))
                                 .on_click(move |_, _, cx| {
-                                    cx.global::<PlaybackInterface>().set_volume(0 as f64);
+                                    cx.global::<PlaybackInterface>().toggle_mute();
                                 })
                                 .tooltip(build_tooltip(tr!("MUTE", "Mute")))
                         }),
@@ -935,35 +1327,39 @@ impl Render for SecondaryControls {
                             ));
                         }),
                 )
+                // in the compact layout, everything after the volume is moved into a menu
+                .children(overflow_menu)
                 .child(self.replaygain_button.clone())
-                .child(
-                    div()
-                        .h(px(24.0))
-                        .w(px(1.0))
-                        .mt(px(3.0))
-                        .mx(px(4.0))
-                        .bg(theme.border_color),
-                )
-                .child(
-                    sidebar_toggle_button("queue-button", MENU, queue_active)
-                        .on_click(move |_, _, cx| {
-                            show_queue.update(cx, |m, cx| {
-                                *m = !*m;
-                                cx.notify();
+                .when(!compact, |this| {
+                    this.child(
+                        div()
+                            .h(px(24.0))
+                            .w(px(1.0))
+                            .mt(px(3.0))
+                            .mx(px(4.0))
+                            .bg(theme.border_color),
+                    )
+                    .child(
+                        sidebar_toggle_button("queue-button", MENU, queue_active)
+                            .on_click(move |_, _, cx| {
+                                show_queue.update(cx, |m, cx| {
+                                    *m = !*m;
+                                    cx.notify();
+                                })
                             })
-                        })
-                        .tooltip(build_tooltip(tr!("QUEUE_TITLE"))),
-                )
-                .child(
-                    sidebar_toggle_button("lyrics-button", MICROPHONE, lyrics_active)
-                        .on_click(move |_, _, cx| {
-                            show_lyrics.update(cx, |m, cx| {
-                                *m = !*m;
-                                cx.notify();
+                            .tooltip(build_tooltip(tr!("QUEUE_TITLE"))),
+                    )
+                    .child(
+                        sidebar_toggle_button("lyrics-button", MICROPHONE, lyrics_active)
+                            .on_click(move |_, _, cx| {
+                                show_lyrics.update(cx, |m, cx| {
+                                    *m = !*m;
+                                    cx.notify();
+                                })
                             })
-                        })
-                        .tooltip(build_tooltip(tr!("LYRICS", "Lyrics"))),
-                ),
+                            .tooltip(build_tooltip(tr!("LYRICS", "Lyrics"))),
+                    )
+                }),
         )
     }
 }
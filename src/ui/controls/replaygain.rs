@@ -11,6 +11,7 @@ use crate::{
 use cntp_i18n::tr;
 use gpui::{prelude::FluentBuilder, *};
 
+use super::is_compact;
 use crate::ui::theme::Theme;
 
 pub struct ReplayGainButton {
@@ -39,10 +40,20 @@ impl ReplayGainButton {
         self.show_popover = false;
         cx.notify();
     }
+
+    /// Shows the ReplayGain popover, used by the compact layout's menu since the button itself is
+    /// hidden there.
+    pub fn open_popover(&mut self, cx: &mut Context<Self>) {
+        self.show_popover = true;
+        cx.notify();
+    }
 }
 
 impl Render for ReplayGainButton {
-    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // in the compact layout the button is moved into a menu, but the popover is still shown
+        // from here
+        let compact = is_compact(window, cx);
         let theme = cx.global::<Theme>();
         let rg_settings = self.settings.read(cx).playback.replaygain;
         let rg_mode = rg_settings.mode;
@@ -51,38 +62,40 @@ impl Render for ReplayGainButton {
 
         div()
             .relative()
-            .child(
-                div()
-                    .rounded(px(3.0))
-                    .w(px(25.0))
-                    .h(px(25.0))
-                    .mt(px(2.0))
-                    .flex()
-                    .items_center()
-                    .justify_center()
-                    .border_color(theme.playback_button_border)
-                    .id("rg-button")
-                    .cursor_pointer()
-                    .tooltip(build_tooltip(tr!("REPLAY_GAIN", "ReplayGain")))
-                    .bg(theme.playback_button)
-                    .hover(|this| this.bg(theme.playback_button_hover))
-                    .active(|this| this.bg(theme.playback_button_active))
-                    .on_mouse_down(MouseButton::Left, |_, window, cx| {
-                        cx.stop_propagation();
-                        window.prevent_default();
-                    })
-                    .on_click(cx.listener(|this, _, _, cx| {
-                        this.show_popover = !this.show_popover;
-                        cx.notify();
-                    }))
-                    .child(
-                        icon(ADJUSTMENTS)
-                            .size(px(14.0))
-                            .when(rg_mode != ReplayGainMode::Off, |this| {
-                                this.text_color(theme.playback_button_toggled)
-                            }),
-                    ),
-            )
+            .when(!compact, |this| {
+                this.child(
+                    div()
+                        .rounded(px(3.0))
+                        .w(px(25.0))
+                        .h(px(25.0))
+                        .mt(px(2.0))
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .border_color(theme.playback_button_border)
+                        .id("rg-button")
+                        .cursor_pointer()
+                        .tooltip(build_tooltip(tr!("REPLAY_GAIN", "ReplayGain")))
+                        .bg(theme.playback_button)
+                        .hover(|this| this.bg(theme.playback_button_hover))
+                        .active(|this| this.bg(theme.playback_button_active))
+                        .on_mouse_down(MouseButton::Left, |_, window, cx| {
+                            cx.stop_propagation();
+                            window.prevent_default();
+                        })
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.show_popover = !this.show_popover;
+                            cx.notify();
+                        }))
+                        .child(
+                            icon(ADJUSTMENTS)
+                                .size(px(14.0))
+                                .when(rg_mode != ReplayGainMode::Off, |this| {
+                                    this.text_color(theme.playback_button_toggled)
+                                }),
+                        ),
+                )
+            })
             .when(show_popover, |this| {
                 let entity = cx.entity().downgrade();
                 let entity2 = entity.clone();
@@ -0,0 +1,327 @@
+//! The window shown instead of the main window when the library database can't be opened.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use cntp_i18n::tr;
+use gpui::*;
+use gpui_platform::current_platform;
+use prelude::FluentBuilder;
+use sqlx::migrate::MigrateError;
+use tracing::{error, info};
+
+use crate::{
+    paths,
+    settings::setup_settings,
+    ui::{
+        app::find_fonts,
+        assets::HummingbirdAssetSource,
+        components::{
+            button::{ButtonIntent, ButtonSize, button},
+            icons::{ALERT_CIRCLE, LOCK, icon},
+        },
+        theme::{Theme, setup_theme},
+    },
+};
+
+/// Why the library database couldn't be opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseProblem {
+    /// The database is locked, most likely by another copy of Hummingbird.
+    Locked,
+    /// The database file is damaged, or isn't a database at all.
+    Corrupt,
+}
+
+impl DatabaseProblem {
+    /// Returns the problem behind an error from opening the database, if it's one the user can
+    /// do something about.
+    pub fn from_error(error: &sqlx::Error) -> Option<Self> {
+        match error {
+            sqlx::Error::Database(error) => problem_for_code(&error.code()?),
+            sqlx::Error::Migrate(error) => match error.as_ref() {
+                MigrateError::Execute(error) | MigrateError::ExecuteMigration(error, _) => {
+                    Self::from_error(error)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Maps an SQLite result code to a problem. Extended result codes are reduced to their primary
+/// code first.
+fn problem_for_code(code: &str) -> Option<DatabaseProblem> {
+    match code.parse::<i32>().ok()? & 0xff {
+        // SQLITE_BUSY and SQLITE_LOCKED
+        5 | 6 => Some(DatabaseProblem::Locked),
+        // SQLITE_CORRUPT and SQLITE_NOTADB
+        11 | 26 => Some(DatabaseProblem::Corrupt),
+        _ => None,
+    }
+}
+
+/// Shows a window explaining why the library at `db_path` couldn't be opened. Damaged libraries
+/// can be rebuilt from this window, which starts Hummingbird again once it's done.
+pub fn run(problem: DatabaseProblem, db_path: PathBuf) -> anyhow::Result<()> {
+    let data_dir = paths::data_dir();
+
+    Application::with_platform(current_platform(false))
+        .with_assets(HummingbirdAssetSource::bundled_only())
+        .run(move |cx: &mut App| {
+            find_fonts(cx).expect("unable to load fonts");
+
+            setup_settings(cx, data_dir.join("settings.json"));
+            setup_theme(cx, data_dir);
+
+            let bounds = Bounds::centered(None, size(px(480.0), px(280.0)), cx);
+
+            cx.open_window(
+                WindowOptions {
+                    window_bounds: Some(WindowBounds::Windowed(bounds)),
+                    titlebar: Some(TitlebarOptions {
+                        title: Some(tr!("APP_NAME").into()),
+                        ..Default::default()
+                    }),
+                    app_id: Some("org.mailliw.hummingbird".to_string()),
+                    kind: WindowKind::Normal,
+                    ..Default::default()
+                },
+                |window, cx| {
+                    window.on_window_should_close(cx, |_, cx| {
+                        cx.quit();
+                        true
+                    });
+
+                    cx.new(|_| DatabaseRecovery {
+                        problem,
+                        db_path,
+                        rebuild_failed: false,
+                    })
+                },
+            )
+            .expect("unable to open window");
+
+            cx.activate(true);
+        });
+
+    Ok(())
+}
+
+struct DatabaseRecovery {
+    problem: DatabaseProblem,
+    db_path: PathBuf,
+    /// Whether the last attempt at rebuilding the library failed.
+    rebuild_failed: bool,
+}
+
+impl DatabaseRecovery {
+    fn rebuild(&mut self, cx: &mut Context<Self>) {
+        if let Err(err) = set_aside_library(&self.db_path) {
+            error!(?err, "Failed to set aside the damaged library: {err}");
+            self.rebuild_failed = true;
+            cx.notify();
+            return;
+        }
+
+        info!("Damaged library set aside, restarting");
+
+        if let Err(err) = relaunch() {
+            error!(?err, "Failed to restart Hummingbird: {err}");
+        }
+
+        cx.quit();
+    }
+}
+
+/// Left next to the database once it has been set aside, so the next start scans every folder
+/// even if scanning on startup is turned off.
+const REBUILD_MARKER: &str = "rebuild_library";
+
+/// Returns whether the library in `data_dir` was just set aside to be rebuilt, removing the
+/// marker so it's only rebuilt once.
+pub fn take_rebuild_marker(data_dir: &Path) -> bool {
+    let marker = data_dir.join(REBUILD_MARKER);
+
+    match fs::remove_file(&marker) {
+        Ok(()) => true,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => false,
+        Err(err) => {
+            error!(?err, "Failed to remove the library rebuild marker: {err}");
+            true
+        }
+    }
+}
+
+/// Renames the damaged database so a new one is created in its place, and removes the scan record
+/// so every file is scanned into the new library on the next start.
+fn set_aside_library(db_path: &Path) -> io::Result<()> {
+    let mut damaged = db_path.as_os_str().to_owned();
+    damaged.push(".damaged");
+    fs::rename(db_path, damaged)?;
+
+    let mut stale = Vec::new();
+    for suffix in ["-wal", "-shm"] {
+        let mut path = db_path.as_os_str().to_owned();
+        path.push(suffix);
+        stale.push(PathBuf::from(path));
+    }
+
+    if let Some(directory) = db_path.parent() {
+        stale.push(directory.join("scan_record.hsr"));
+        stale.push(directory.join("scan_record_checkpoint.hsr"));
+    }
+
+    for path in stale {
+        match fs::remove_file(&path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => (),
+        }
+    }
+
+    if let Some(directory) = db_path.parent() {
+        fs::write(directory.join(REBUILD_MARKER), [])?;
+    }
+
+    Ok(())
+}
+
+/// Starts a new copy of Hummingbird with the same arguments.
+fn relaunch() -> io::Result<()> {
+    Command::new(std::env::current_exe()?)
+        .args(std::env::args_os().skip(1))
+        .spawn()?;
+
+    Ok(())
+}
+
+impl Render for DatabaseRecovery {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.global::<Theme>();
+        let is_corrupt = self.problem == DatabaseProblem::Corrupt;
+
+        let (icon_path, title, body) = match self.problem {
+            DatabaseProblem::Locked => (
+                LOCK,
+                tr!("DATABASE_LOCKED_TITLE", "Hummingbird is already running"),
+                tr!(
+                    "DATABASE_LOCKED_BODY",
+                    "Another copy of Hummingbird is using your library. Switch to that copy, or \
+                    close it and open Hummingbird again."
+                ),
+            ),
+            DatabaseProblem::Corrupt => (
+                ALERT_CIRCLE,
+                tr!("DATABASE_CORRUPT_TITLE", "Your library is damaged"),
+                tr!(
+                    "DATABASE_CORRUPT_BODY",
+                    "Hummingbird couldn't read its library. It can set the damaged copy aside \
+                    and rebuild your library by scanning your folders again. Playlists and play \
+                    counts will be lost."
+                ),
+            ),
+        };
+
+        div()
+            .size_full()
+            .font_family("Inter")
+            .bg(theme.background_primary)
+            .text_color(theme.text)
+            .p(px(24.0))
+            .flex()
+            .flex_col()
+            .gap(px(14.0))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(12.0))
+                    .child(
+                        icon(icon_path)
+                            .size(px(28.0))
+                            .flex_shrink_0()
+                            .text_color(theme.callout_text),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(18.0))
+                            .font_weight(FontWeight::BOLD)
+                            .line_height(px(24.0))
+                            .child(title),
+                    ),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .line_height(px(20.0))
+                    .text_color(theme.text_secondary)
+                    .child(body),
+            )
+            .when(self.rebuild_failed, |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .line_height(px(20.0))
+                        .text_color(theme.callout_text)
+                        .child(tr!(
+                            "DATABASE_REBUILD_FAILED",
+                            "Your library couldn't be rebuilt. Check the log for details."
+                        )),
+                )
+            })
+            .child(
+                div()
+                    .mt_auto()
+                    .flex()
+                    .justify_end()
+                    .gap(px(10.0))
+                    .child(
+                        button()
+                            .id("database-recovery-quit")
+                            .size(ButtonSize::Large)
+                            .child(tr!("QUIT"))
+                            .on_click(|_, _, cx| cx.quit()),
+                    )
+                    .when(is_corrupt, |this| {
+                        this.child(
+                            button()
+                                .id("database-recovery-rebuild")
+                                .size(ButtonSize::Large)
+                                .intent(ButtonIntent::Primary)
+                                .child(tr!("DATABASE_REBUILD", "Rebuild Library"))
+                                .on_click(cx.listener(|this, _, _, cx| this.rebuild(cx))),
+                        )
+                    }),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DatabaseProblem, problem_for_code};
+
+    #[test]
+    fn busy_and_locked_codes_are_locked() {
+        assert_eq!(problem_for_code("5"), Some(DatabaseProblem::Locked));
+        assert_eq!(problem_for_code("6"), Some(DatabaseProblem::Locked));
+        // SQLITE_BUSY_SNAPSHOT
+        assert_eq!(problem_for_code("517"), Some(DatabaseProblem::Locked));
+    }
+
+    #[test]
+    fn corrupt_codes_are_corrupt() {
+        assert_eq!(problem_for_code("11"), Some(DatabaseProblem::Corrupt));
+        assert_eq!(problem_for_code("26"), Some(DatabaseProblem::Corrupt));
+    }
+
+    #[test]
+    fn other_codes_are_not_recoverable() {
+        assert_eq!(problem_for_code("1"), None);
+        assert_eq!(problem_for_code("2067"), None);
+        assert_eq!(problem_for_code("not a code"), None);
+    }
+}
@@ -0,0 +1,78 @@
+//! Tells the user when files dropped onto the window were skipped because they can't be played.
+
+use std::time::Duration;
+
+use cntp_i18n::trn;
+use gpui::*;
+
+use crate::ui::{
+    components::{callout::callout, icons::ALERT_CIRCLE},
+    models::Models,
+    theme::Theme,
+};
+
+/// How long the notice stays visible.
+const NOTICE_DURATION: Duration = Duration::from_secs(5);
+
+/// Shows the notice for `count` skipped files.
+pub fn show_skipped_files(cx: &mut App, count: usize) {
+    let skipped = cx.global::<Models>().skipped_dropped_files.clone();
+    skipped.write(cx, Some(count));
+}
+
+pub struct DropNotice {
+    hide_task: Option<Task<()>>,
+}
+
+impl DropNotice {
+    pub fn new(cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let skipped = cx.global::<Models>().skipped_dropped_files.clone();
+            cx.observe(&skipped, |this: &mut Self, skipped, cx| {
+                // every new drop restarts the timer
+                this.hide_task = skipped.read(cx).is_some().then(|| {
+                    cx.spawn(async move |_, cx| {
+                        cx.background_executor().timer(NOTICE_DURATION).await;
+                        let _ = skipped.update(cx, |skipped, cx| {
+                            *skipped = None;
+                            cx.notify();
+                        });
+                    })
+                });
+
+                cx.notify();
+            })
+            .detach();
+
+            DropNotice { hide_task: None }
+        })
+    }
+}
+
+impl Render for DropNotice {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(count) = *cx.global::<Models>().skipped_dropped_files.read(cx) else {
+            return div().into_any_element();
+        };
+        let theme = cx.global::<Theme>();
+
+        div()
+            .absolute()
+            .right(px(16.0))
+            .bottom(px(16.0))
+            .w(px(360.0))
+            .rounded(px(6.0))
+            .bg(theme.elevated_background)
+            .shadow_lg()
+            .child(
+                callout(trn!(
+                    "DROP_SKIPPED_FILES",
+                    "{{count}} dropped file was skipped because it can't be played.",
+                    "{{count}} dropped files were skipped because they can't be played.",
+                    count = count
+                ))
+                .icon(ALERT_CIRCLE),
+            )
+            .into_any_element()
+    }
+}
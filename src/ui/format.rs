@@ -0,0 +1,254 @@
+//! Formatting for the numbers, dates and durations shown in the interface. These follow the
+//! language selected in Hummingbird rather than the system's locale, so every view formats them
+//! the same way.
+
+use chrono::{DateTime, Utc};
+use cntp_i18n::{Date, I18N_MANAGER, StringModifier, tr, trn};
+use gpui::SharedString;
+
+use crate::library::types::Track;
+
+/// Formats a date with the app's locale. `fields` is the set of fields to show, such as `"YMD"`,
+/// and `length` is one of `"short"`, `"medium"` or `"long"`.
+pub fn format_date(
+    date: &DateTime<Utc>,
+    fields: &'static str,
+    length: &'static str,
+) -> SharedString {
+    let fields_var = (None, fields);
+    let length_var = (Some("length"), length);
+    let variables = [&fields_var, &length_var];
+    let locale = &I18N_MANAGER.read().unwrap().locale;
+    Date.transform(locale, date, &variables).into()
+}
+
+/// Formats a point in time as a date with the app's locale, followed by the local time.
+pub fn format_date_time(date: &DateTime<Utc>) -> SharedString {
+    // the date is formatted as-is, so it has to be moved into local time first
+    let local = date.with_timezone(&chrono::Local).naive_local().and_utc();
+    let time = local.format("%H:%M");
+
+    format!("{} {time}", format_date(&local, "YMD", "short")).into()
+}
+
+/// How long ago a date was, in the largest unit that fits.
+#[derive(Debug, PartialEq)]
+enum Ago {
+    Today,
+    Yesterday,
+    Days(i64),
+    Weeks(i64),
+    Months(i64),
+    Years(i64),
+}
+
+fn ago(days: i64) -> Ago {
+    match days {
+        ..=0 => Ago::Today,
+        1 => Ago::Yesterday,
+        2..=13 => Ago::Days(days),
+        14..=59 => Ago::Weeks(days / 7),
+        60..=729 => Ago::Months(days / 30),
+        _ => Ago::Years(days / 365),
+    }
+}
+
+/// Formats a point in the past relative to today, such as "Yesterday" or "3 weeks ago".
+pub fn format_relative_date(date: &DateTime<Utc>) -> SharedString {
+    let today = chrono::Local::now().date_naive();
+    let day = date.with_timezone(&chrono::Local).date_naive();
+
+    match ago((today - day).num_days()) {
+        Ago::Today => tr!("DATE_TODAY", "Today").into(),
+        Ago::Yesterday => tr!("DATE_YESTERDAY", "Yesterday").into(),
+        Ago::Days(count) => trn!(
+            "DATE_DAYS_AGO",
+            "{{count}} day ago",
+            "{{count}} days ago",
+            count = count
+        )
+        .into(),
+        Ago::Weeks(count) => trn!(
+            "DATE_WEEKS_AGO",
+            "{{count}} week ago",
+            "{{count}} weeks ago",
+            count = count
+        )
+        .into(),
+        Ago::Months(count) => trn!(
+            "DATE_MONTHS_AGO",
+            "{{count}} month ago",
+            "{{count}} months ago",
+            count = count
+        )
+        .into(),
+        Ago::Years(count) => trn!(
+            "DATE_YEARS_AGO",
+            "{{count}} year ago",
+            "{{count}} years ago",
+            count = count
+        )
+        .into(),
+    }
+}
+
+/// Formats a count, grouping thousands with the separator used by the app's language.
+pub fn format_number(number: i64) -> SharedString {
+    let separator = tr!(
+        "NUMBER_GROUP_SEPARATOR",
+        ",",
+        #description="Separates groups of three digits in large numbers, such as the commas in \
+            1,234,567. Use the separator that is normal for your language."
+    );
+
+    let grouped = group_digits(number.unsigned_abs(), &separator.to_string());
+
+    if number < 0 {
+        format!("-{grouped}").into()
+    } else {
+        grouped.into()
+    }
+}
+
+fn group_digits(number: u64, separator: &str) -> String {
+    let digits = number.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * separator.len());
+
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+/// Formats a duration in seconds as minutes and seconds.
+pub fn format_duration(secs: u64) -> String {
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+/// Formats a size in bytes, such as the size of a file, with binary units.
+pub fn format_bytes(bytes: f64) -> String {
+    const SUFFIX: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    const UNIT: f64 = 1024.0;
+    if bytes <= 0.0 {
+        return "0 B".to_string();
+    }
+
+    let power = ((bytes.ln() / UNIT.ln()).floor() as usize).min(SUFFIX.len() - 1);
+    let value = bytes / UNIT.powi(power as i32);
+    if value >= 10.0 || value.fract() == 0.0 {
+        format!("{value:.0} {}", SUFFIX[power])
+    } else {
+        format!("{value:.1} {}", SUFFIX[power])
+    }
+}
+
+/// Returns a short label for a track's file format, such as `FLAC` or `MP3 320`. Tracks scanned
+/// before codecs were recorded fall back to the file extension.
+pub fn format_badge(track: &Track) -> Option<SharedString> {
+    let extension = track.location.extension().and_then(|ext| ext.to_str());
+    format_label(track.codec.as_deref(), extension, track.bitrate).map(SharedString::from)
+}
+
+fn format_label(
+    codec: Option<&str>,
+    extension: Option<&str>,
+    bitrate: Option<i64>,
+) -> Option<String> {
+    let Some(codec) = codec else {
+        return extension.map(str::to_uppercase);
+    };
+
+    // uncompressed audio is better known by its container, like WAV or AIFF
+    if codec.starts_with("pcm") || codec.starts_with("adpcm") {
+        return Some(extension.unwrap_or("pcm").to_uppercase());
+    }
+
+    let name = codec.to_uppercase();
+
+    // the bitrate of lossless audio only depends on how well it compresses, so it isn't shown
+    match bitrate {
+        Some(bitrate) if bitrate > 0 && !matches!(codec, "flac" | "alac" | "wavpack") => {
+            Some(format!("{name} {bitrate}"))
+        }
+        _ => Some(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ago, ago, format_bytes, format_duration, format_label, group_digits};
+
+    #[test]
+    fn groups_digits_in_threes() {
+        assert_eq!(group_digits(0, ","), "0");
+        assert_eq!(group_digits(999, ","), "999");
+        assert_eq!(group_digits(1_000, ","), "1,000");
+        assert_eq!(group_digits(1_234_567, "\u{a0}"), "1\u{a0}234\u{a0}567");
+    }
+
+    #[test]
+    fn formats_durations_as_minutes_and_seconds() {
+        assert_eq!(format_duration(5), "00:05");
+        assert_eq!(format_duration(754), "12:34");
+        assert_eq!(format_duration(4_000), "66:40");
+    }
+
+    #[test]
+    fn relative_dates_use_largest_unit() {
+        assert_eq!(ago(0), Ago::Today);
+        assert_eq!(ago(1), Ago::Yesterday);
+        assert_eq!(ago(6), Ago::Days(6));
+        assert_eq!(ago(20), Ago::Weeks(2));
+        assert_eq!(ago(95), Ago::Months(3));
+        assert_eq!(ago(800), Ago::Years(2));
+    }
+
+    #[test]
+    fn formats_byte_sizes_with_binary_units() {
+        assert_eq!(format_bytes(0.0), "0 B");
+        assert_eq!(format_bytes(512.0), "512 B");
+        assert_eq!(format_bytes(1_536.0), "1.5 KiB");
+        assert_eq!(format_bytes(34_603_008.0), "33 MiB");
+    }
+
+    #[test]
+    fn format_labels_show_bitrate_of_lossy_codecs() {
+        assert_eq!(
+            format_label(Some("mp3"), Some("mp3"), Some(320)).as_deref(),
+            Some("MP3 320")
+        );
+        assert_eq!(
+            format_label(Some("flac"), Some("flac"), Some(912)).as_deref(),
+            Some("FLAC")
+        );
+        assert_eq!(
+            format_label(Some("opus"), Some("ogg"), None).as_deref(),
+            Some("OPUS")
+        );
+    }
+
+    #[test]
+    fn format_labels_name_pcm_after_container() {
+        assert_eq!(
+            format_label(Some("pcm_s16le"), Some("wav"), Some(1411)).as_deref(),
+            Some("WAV")
+        );
+        assert_eq!(
+            format_label(Some("pcm_s24be"), None, None).as_deref(),
+            Some("PCM")
+        );
+    }
+
+    #[test]
+    fn format_labels_fall_back_to_extension() {
+        assert_eq!(
+            format_label(None, Some("m4a"), None).as_deref(),
+            Some("M4A")
+        );
+        assert_eq!(format_label(None, None, None), None);
+    }
+}
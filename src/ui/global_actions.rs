@@ -1,27 +1,48 @@
+use std::sync::Arc;
+
 use cntp_i18n::tr;
 use gpui::{App, AppContext, KeyBinding, MenuItem, actions};
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::{
-    library::{db::LibraryAccess, scan::ScanInterface},
+    library::{db::LibraryAccess, scan::ScanInterface, types::Track},
     playback::{interface::PlaybackInterface, queue::QueueItemData, thread::PlaybackState},
     ui::{
         command_palette::OpenPalette,
         components::menus_builder::{MenuBuilder, MenusBuilder, menu_item, menu_separator},
-        library::playlist_view,
+        library::{context_menus::set_track_rating, playlist_view},
+        queue_confirmation::{replace_queue, replace_queue_with_shuffle},
         settings::open_settings_window,
         troubleshooting::{CopyTroubleshootingInfo, OpenLog, copy_troubleshooting_info, open_log},
     },
 };
 
-use super::models::{Models, PlaybackInfo};
+use super::models::{Models, PlaybackInfo, PlaylistEvent};
 
 actions!(hummingbird, [Quit, About, CloseWindow, Search, Settings]);
 #[cfg(feature = "update")]
 actions!(hummingbird, [CheckForUpdates]);
-actions!(player, [PlayPause, Next, Previous, ShuffleAll]);
+actions!(
+    player,
+    [
+        PlayPause, Next, Previous, ShuffleAll, PlayRandom, ToggleMute
+    ]
+);
+actions!(
+    player,
+    [
+        ToggleLike,
+        ClearRating,
+        RateOne,
+        RateTwo,
+        RateThree,
+        RateFour,
+        RateFive
+    ]
+);
 actions!(scan, [ForceScan, Scan]);
 actions!(hummingbird, [HideSelf, HideOthers, ShowAll]);
+actions!(view, [ToggleQueue]);
 actions!(help, [Discord, Patreon, Issues]);
 
 pub fn register_actions(cx: &mut App) {
@@ -31,6 +52,15 @@ pub fn register_actions(cx: &mut App) {
     cx.on_action(play_pause);
     cx.on_action(next);
     cx.on_action(previous);
+    cx.on_action(toggle_mute);
+    cx.on_action(toggle_like);
+    cx.on_action(|_: &ClearRating, cx| rate_current_track(cx, 0));
+    cx.on_action(|_: &RateOne, cx| rate_current_track(cx, 1));
+    cx.on_action(|_: &RateTwo, cx| rate_current_track(cx, 2));
+    cx.on_action(|_: &RateThree, cx| rate_current_track(cx, 3));
+    cx.on_action(|_: &RateFour, cx| rate_current_track(cx, 4));
+    cx.on_action(|_: &RateFive, cx| rate_current_track(cx, 5));
+    cx.on_action(toggle_queue);
     cx.on_action(hide_self);
     cx.on_action(hide_others);
     cx.on_action(show_all);
@@ -43,6 +73,7 @@ pub fn register_actions(cx: &mut App) {
     cx.on_action(patreon);
     cx.on_action(issues);
     cx.on_action(shuffle_all);
+    cx.on_action(play_random);
     cx.on_action(scan);
     cx.on_action(open_log);
     cx.on_action(copy_troubleshooting_info);
@@ -63,8 +94,10 @@ pub fn register_actions(cx: &mut App) {
     cx.bind_keys([KeyBinding::new("secondary-left", Previous, None)]);
     cx.bind_keys([KeyBinding::new("secondary-p", Search, None)]);
     cx.bind_keys([KeyBinding::new("secondary-f", Search, None)]);
+    cx.bind_keys([KeyBinding::new("/", Search, Some("!TextInput"))]);
     cx.bind_keys([KeyBinding::new("secondary-shift-p", OpenPalette, None)]);
     cx.bind_keys([KeyBinding::new("secondary-,", Settings, None)]);
+    cx.bind_keys([KeyBinding::new("secondary-alt-q", ToggleQueue, None)]);
     cx.bind_keys([KeyBinding::new(
         "escape",
         CloseWindow,
@@ -74,6 +107,15 @@ pub fn register_actions(cx: &mut App) {
     cx.bind_keys([KeyBinding::new("alt-shift-s", ForceScan, None)]);
     cx.bind_keys([KeyBinding::new("alt-s", Scan, None)]);
     cx.bind_keys([KeyBinding::new("space", PlayPause, None)]);
+    cx.bind_keys([KeyBinding::new("alt-r", PlayRandom, None)]);
+    cx.bind_keys([KeyBinding::new("m", ToggleMute, Some("!TextInput"))]);
+    cx.bind_keys([KeyBinding::new("alt-l", ToggleLike, Some("!TextInput"))]);
+    cx.bind_keys([KeyBinding::new("alt-0", ClearRating, Some("!TextInput"))]);
+    cx.bind_keys([KeyBinding::new("alt-1", RateOne, Some("!TextInput"))]);
+    cx.bind_keys([KeyBinding::new("alt-2", RateTwo, Some("!TextInput"))]);
+    cx.bind_keys([KeyBinding::new("alt-3", RateThree, Some("!TextInput"))]);
+    cx.bind_keys([KeyBinding::new("alt-4", RateFour, Some("!TextInput"))]);
+    cx.bind_keys([KeyBinding::new("alt-5", RateFive, Some("!TextInput"))]);
 
     let mut app_menu = MenuBuilder::new(tr!("APP_NAME"))
         .add_item(menu_item(tr!("ABOUT", "About Hummingbird"), About, false))
@@ -123,7 +165,13 @@ pub fn register_actions(cx: &mut App) {
                 OpenPalette,
                 false,
             ))
-            .add_item(menu_item(tr!("SEARCH", "Search"), Search, false)),
+            .add_item(menu_item(tr!("SEARCH", "Search"), Search, false))
+            .add_item(menu_separator(false))
+            .add_item(menu_item(
+                tr!("ACTION_TOGGLE_QUEUE", "Show/Hide Queue"),
+                ToggleQueue,
+                false,
+            )),
         )
         .add_menu(
             MenuBuilder::new(tr!("LIBRARY"))
@@ -132,6 +180,11 @@ pub fn register_actions(cx: &mut App) {
                     ShuffleAll,
                     false,
                 ))
+                .add_item(menu_item(
+                    tr!("LIBRARY_PLAY_RANDOM", "Play a Random Track"),
+                    PlayRandom,
+                    false,
+                ))
                 .add_item(menu_separator(false))
                 .add_item(menu_item(tr!("LIBRARY_SCAN", "Scan"), Scan, false))
                 .add_item(menu_item(
@@ -225,6 +278,64 @@ fn previous(_: &Previous, cx: &mut App) {
     interface.previous();
 }
 
+fn toggle_mute(_: &ToggleMute, cx: &mut App) {
+    let interface = cx.global::<PlaybackInterface>();
+    interface.toggle_mute();
+}
+
+/// Returns the track that's playing, if it's in the library.
+fn current_library_track(cx: &App) -> Option<Arc<Track>> {
+    let path = cx
+        .global::<PlaybackInfo>()
+        .current_track
+        .read(cx)
+        .as_ref()?
+        .get_path()
+        .clone();
+
+    cx.get_track_by_path(&path)
+        .inspect_err(|err| error!(?err, "Failed to look up the current track: {err}"))
+        .ok()
+        .flatten()
+}
+
+fn toggle_like(_: &ToggleLike, cx: &mut App) {
+    let Some(track) = current_library_track(cx) else {
+        return;
+    };
+
+    // the liked songs playlist always has the id 1
+    let result = match cx.playlist_has_track(1, track.id) {
+        Ok(Some(item_id)) => cx.remove_playlist_item(item_id),
+        Ok(None) => cx.add_playlist_item(1, track.id).map(|_| ()),
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = result {
+        error!(?err, "Failed to like or unlike the current track: {err}");
+        return;
+    }
+
+    let playlist_tracker = cx.global::<Models>().playlist_tracker.clone();
+    playlist_tracker.update(cx, |_, cx| {
+        cx.emit(PlaylistEvent::PlaylistUpdated(1));
+    });
+}
+
+fn rate_current_track(cx: &mut App, rating: i32) {
+    if let Some(track) = current_library_track(cx) {
+        set_track_rating(cx, track.id, rating);
+    }
+}
+
+fn toggle_queue(_: &ToggleQueue, cx: &mut App) {
+    let show_queue = cx.global::<Models>().show_queue.clone();
+    show_queue.update(cx, |show_queue, cx| {
+        *show_queue = !*show_queue;
+        cx.notify();
+    });
+}
+
 fn hide_self(_: &HideSelf, cx: &mut App) {
     cx.hide();
 }
@@ -280,11 +391,17 @@ fn shuffle_all(_: &ShuffleAll, cx: &mut App) {
             .map(|v| QueueItemData::new(cx, v.0.into(), Some(v.1), Some(v.2)))
             .collect();
 
-        let interface = cx.global::<PlaybackInterface>();
+        replace_queue_with_shuffle(tracks, true, cx);
+    }
+}
 
-        if !(*cx.global::<PlaybackInfo>().shuffling.read(cx)) {
-            interface.toggle_shuffle();
+fn play_random(_: &PlayRandom, cx: &mut App) {
+    match cx.random_track() {
+        Ok(Some((location, id, album_id))) => {
+            let item = QueueItemData::new(cx, location.into(), Some(id), album_id);
+            replace_queue(vec![item], cx);
         }
-        interface.replace_queue(tracks);
+        Ok(None) => info!("Library is empty, no random track to play"),
+        Err(err) => warn!(?err, "Failed to pick a random track: {err}"),
     }
 }
@@ -108,6 +108,7 @@ impl Render for ScanStatus {
                             FOLDER_CHECK
                         }
                         ScanEvent::Cleaning
+                        | ScanEvent::CleaningProgress { .. }
                         | ScanEvent::PlaylistsUpdated(_)
                         | ScanEvent::ScanProgress { .. }
                         | ScanEvent::WaitingForMissingFolderDecision { .. } => FOLDER_SEARCH,
@@ -138,6 +139,29 @@ impl Render for ScanStatus {
                     }
                 }
                 ScanEvent::Cleaning => SharedString::from(""),
+                ScanEvent::CleaningProgress {
+                    checked,
+                    total,
+                    removed,
+                    to_remove,
+                } => {
+                    if checked < total {
+                        tr!(
+                            "SCAN_CLEANING_CHECKING",
+                            "Checking for removed files {{percentage}}%",
+                            percentage = (*checked as f64 / *total as f64 * 100.0).round()
+                        )
+                        .into()
+                    } else {
+                        tr!(
+                            "SCAN_CLEANING_REMOVING",
+                            "Removing {{removed}} of {{to_remove}} missing tracks...",
+                            removed = removed,
+                            to_remove = to_remove
+                        )
+                        .into()
+                    }
+                }
                 ScanEvent::PlaylistsUpdated(_) => SharedString::from(""),
                 ScanEvent::WaitingForMissingFolderDecision { .. } => {
                     tr!("SCANNING_MISSING_DIALOG_TITLE").into()
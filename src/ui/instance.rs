@@ -0,0 +1,254 @@
+//! Keeps Hummingbird to a single running copy. The first copy listens on a local port, which it
+//! records in the data directory, and copies started after it send their files there and exit.
+
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use gpui::{App, AsyncApp};
+use rand::{RngExt, distr::Alphanumeric, rng};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    playback::interface::PlaybackInterface,
+    ui::{tray, util::queue_dropped_paths},
+};
+
+const INSTANCE_FILE: &str = "instance.json";
+const TIMEOUT: Duration = Duration::from_secs(2);
+/// Sent back once a message has been accepted, so a copy of Hummingbird can tell it isn't talking
+/// to some other program that happens to be using the port now.
+const ACK: &str = "ok";
+
+/// Where the running copy is listening, and the token other copies have to send it.
+#[derive(Serialize, Deserialize)]
+struct InstanceInfo {
+    port: u16,
+    token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ForwardedArgs {
+    token: String,
+    files: Vec<PathBuf>,
+}
+
+/// Sends `files` to the copy of Hummingbird that's already running. Returns false if there isn't
+/// one, in which case this copy should start normally.
+pub fn forward_to_running(data_dir: &Path, files: &[PathBuf]) -> bool {
+    let Some(info) = fs::read(data_dir.join(INSTANCE_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_slice::<InstanceInfo>(&contents).ok())
+    else {
+        return false;
+    };
+
+    match send(info.port, info.token, files) {
+        Ok(()) => {
+            info!(
+                "Hummingbird is already running, sent {} files to it",
+                files.len()
+            );
+            true
+        }
+        Err(err) => {
+            // the last copy most likely didn't exit cleanly
+            debug!(?err, "No running copy of Hummingbird found: {err}");
+            false
+        }
+    }
+}
+
+fn send(port: u16, token: String, files: &[PathBuf]) -> io::Result<()> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let message = ForwardedArgs {
+        token,
+        files: files.to_vec(),
+    };
+    serde_json::to_writer(&mut stream, &message)?;
+    stream.write_all(b"\n")?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+
+    if reply.trim_end() == ACK {
+        Ok(())
+    } else {
+        Err(io::Error::other("unexpected reply"))
+    }
+}
+
+/// Reads the files sent by another copy of Hummingbird, if it sent the right token.
+fn receive(stream: TcpStream, token: &str) -> io::Result<Option<Vec<PathBuf>>> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let message: ForwardedArgs = serde_json::from_str(&line)?;
+    if message.token != token {
+        return Ok(None);
+    }
+
+    let mut stream = reader.into_inner();
+    writeln!(stream, "{ACK}")?;
+
+    Ok(Some(message.files))
+}
+
+/// Starts listening for other copies of Hummingbird. Files they send are added to the queue, and
+/// the main window is brought to the front.
+pub fn listen(cx: &mut App, data_dir: &Path) {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!(
+                ?err,
+                "Failed to listen for other copies of Hummingbird: {err}"
+            );
+            return;
+        }
+    };
+
+    let port = match listener.local_addr() {
+        Ok(address) => address.port(),
+        Err(err) => {
+            error!(
+                ?err,
+                "Failed to listen for other copies of Hummingbird: {err}"
+            );
+            return;
+        }
+    };
+
+    let info = InstanceInfo {
+        port,
+        token: rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(|b| b as char)
+            .collect(),
+    };
+
+    let path = data_dir.join(INSTANCE_FILE);
+    if let Err(err) = serde_json::to_vec(&info)
+        .map_err(io::Error::from)
+        .and_then(|contents| fs::write(&path, contents))
+    {
+        error!(?err, "Failed to write '{}': {err}", path.display());
+        return;
+    }
+
+    let (files_tx, mut files_rx) = unbounded_channel();
+
+    std::thread::Builder::new()
+        .name("instance".to_string())
+        .spawn(move || accept_loop(listener, info.token, files_tx))
+        .expect("could not start instance thread");
+
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        while let Some(files) = files_rx.recv().await {
+            let _ = cx.update(|cx| open_forwarded(files, cx));
+        }
+    })
+    .detach();
+
+    cx.on_app_quit(move |_| {
+        let _ = fs::remove_file(&path);
+        async {}
+    })
+    .detach();
+}
+
+fn accept_loop(listener: TcpListener, token: String, files_tx: UnboundedSender<Vec<PathBuf>>) {
+    for stream in listener.incoming() {
+        let files = stream.and_then(|stream| receive(stream, &token));
+
+        match files {
+            Ok(Some(files)) => {
+                if files_tx.send(files).is_err() {
+                    return;
+                }
+            }
+            Ok(None) => warn!("Ignored a message with the wrong token"),
+            Err(err) => warn!(?err, "Failed to read a message from another copy: {err}"),
+        }
+    }
+}
+
+fn open_forwarded(files: Vec<PathBuf>, cx: &mut App) {
+    tray::show_main_window(cx);
+
+    if files.is_empty() {
+        return;
+    }
+
+    info!(
+        "Queueing files sent by another copy of Hummingbird: {:?}",
+        files
+    );
+    queue_dropped_paths(cx, files, |items, cx| {
+        cx.global::<PlaybackInterface>().queue_list(items);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{Ipv4Addr, TcpListener},
+        path::PathBuf,
+        thread,
+    };
+
+    use super::{receive, send};
+
+    fn forward(sent_token: &str) -> (bool, Option<Vec<PathBuf>>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let receiver = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            receive(stream, "secret").unwrap()
+        });
+
+        let files = [
+            PathBuf::from("/music/a.flac"),
+            PathBuf::from("/music/b.mp3"),
+        ];
+        let sent = send(port, sent_token.to_string(), &files).is_ok();
+
+        (sent, receiver.join().unwrap())
+    }
+
+    #[test]
+    fn files_are_forwarded() {
+        let (sent, received) = forward("secret");
+
+        assert!(sent);
+        assert_eq!(
+            received,
+            Some(vec![
+                PathBuf::from("/music/a.flac"),
+                PathBuf::from("/music/b.mp3")
+            ])
+        );
+    }
+
+    #[test]
+    fn wrong_tokens_are_rejected() {
+        let (sent, received) = forward("guess");
+
+        assert!(!sent);
+        assert_eq!(received, None);
+    }
+}
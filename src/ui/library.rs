@@ -3,14 +3,18 @@ use artist_detail_view::ArtistDetailView;
 use artist_view::ArtistView;
 use cntp_i18n::tr;
 use gpui::{prelude::FluentBuilder, *};
+use history_view::HistoryView;
 use navigation::NavigationView;
 use release_view::ReleaseView;
+use stats_view::StatsView;
+use timeline_view::TimelineView;
 use tracing::debug;
 use track_view::TrackView;
 
 #[derive(Clone, Default)]
 struct ScrollStateStorage {
     album_view_scroll: Option<f32>,
+    timeline_view_scroll: Option<f32>,
     track_view_scroll: Option<f32>,
     artist_view_scroll: Option<f32>,
 }
@@ -39,12 +43,16 @@ mod album_view;
 mod artist_detail_view;
 mod artist_view;
 pub mod context_menus;
+mod history_view;
 pub mod missing_folder_dialog;
 mod navigation;
 pub mod playlist_view;
 mod release_view;
 mod sidebar;
+mod stats_view;
+mod timeline_view;
 mod track_listing;
+pub mod track_properties;
 mod track_view;
 mod update_playlist;
 
@@ -204,9 +212,12 @@ impl EventEmitter<ViewSwitchMessage> for NavigationHistory {}
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum LibrarySection {
     Albums,
+    Timeline,
     Artists,
     Tracks,
     Playlists,
+    History,
+    Stats,
 }
 
 impl LibrarySection {
@@ -215,10 +226,13 @@ impl LibrarySection {
     fn from_message(msg: &ViewSwitchMessage) -> Option<Self> {
         match msg {
             ViewSwitchMessage::Albums => Some(Self::Albums),
+            ViewSwitchMessage::Timeline => Some(Self::Timeline),
             ViewSwitchMessage::Tracks => Some(Self::Tracks),
             ViewSwitchMessage::Artists | ViewSwitchMessage::Artist(_) => Some(Self::Artists),
             ViewSwitchMessage::Playlist(_) => Some(Self::Playlists),
-            // Release can appear under Albums or Artists – keep current section.
+            ViewSwitchMessage::History => Some(Self::History),
+            ViewSwitchMessage::Stats => Some(Self::Stats),
+            // Release can appear under Albums, Timeline or Artists – keep current section.
             ViewSwitchMessage::Release(_, _) => None,
             ViewSwitchMessage::Back | ViewSwitchMessage::Forward | ViewSwitchMessage::Refresh => {
                 None
@@ -230,22 +244,28 @@ impl LibrarySection {
 #[derive(Clone)]
 enum LibraryView {
     Album(Entity<AlbumView>),
+    Timeline(Entity<TimelineView>),
     Tracks(Entity<TrackView>),
     Release(Entity<ReleaseView>),
     Playlist(Entity<PlaylistView>),
     Artists(Entity<ArtistView>),
     ArtistDetail(Entity<ArtistDetailView>),
+    History(Entity<HistoryView>),
+    Stats(Entity<StatsView>),
 }
 
 impl LibraryView {
     fn split_key(&self) -> &'static str {
         match self {
             LibraryView::Album(_) => "albums",
+            LibraryView::Timeline(_) => "timeline",
             LibraryView::Tracks(_) => "tracks",
             LibraryView::Artists(_) => "artists",
             LibraryView::Playlist(_) => "playlist",
             LibraryView::Release(_) => "albums",
             LibraryView::ArtistDetail(_) => "artists",
+            LibraryView::History(_) => "tracks",
+            LibraryView::Stats(_) => "stats",
         }
     }
 }
@@ -268,12 +288,15 @@ pub struct Library {
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ViewSwitchMessage {
     Albums,
+    Timeline,
     Tracks,
     Artists,
     /// album id, track id
     Release(i64, Option<i64>),
     Artist(i64),
     Playlist(i64),
+    History,
+    Stats,
     Back,
     Forward,
     Refresh,
@@ -318,6 +341,11 @@ fn make_view(
             model.clone(),
             scroll_state.album_view_scroll,
         )),
+        ViewSwitchMessage::Timeline => LibraryView::Timeline(TimelineView::new(
+            cx,
+            model.clone(),
+            scroll_state.timeline_view_scroll,
+        )),
         ViewSwitchMessage::Tracks => LibraryView::Tracks(TrackView::new(
             cx,
             model.clone(),
@@ -335,6 +363,8 @@ fn make_view(
             LibraryView::ArtistDetail(ArtistDetailView::new(cx, *id, model.clone()))
         }
         ViewSwitchMessage::Playlist(id) => LibraryView::Playlist(PlaylistView::new(cx, *id)),
+        ViewSwitchMessage::History => LibraryView::History(HistoryView::new(cx)),
+        ViewSwitchMessage::Stats => LibraryView::Stats(StatsView::new(cx)),
         ViewSwitchMessage::Back => panic!("improper use of make_view (cannot make Back)"),
         ViewSwitchMessage::Forward => panic!("improper use of make_view (cannot make Forward)"),
         ViewSwitchMessage::Refresh => panic!("improper use of make_view (cannot make Refresh)"),
@@ -357,6 +387,9 @@ impl Library {
                     if let LibraryView::Album(album_view) = &this.view {
                         let scroll_pos = album_view.read(cx).get_scroll_offset(cx);
                         this.scroll_state.album_view_scroll = Some(scroll_pos);
+                    } else if let LibraryView::Timeline(timeline_view) = &this.view {
+                        let scroll_pos = timeline_view.read(cx).get_scroll_offset();
+                        this.scroll_state.timeline_view_scroll = Some(scroll_pos);
                     } else if let LibraryView::Tracks(track_view) = &this.view {
                         let scroll_pos = track_view.read(cx).get_scroll_offset(cx);
                         this.scroll_state.track_view_scroll = Some(scroll_pos);
@@ -542,11 +575,14 @@ impl Render for Library {
         fn render_library_view(view: &LibraryView) -> AnyElement {
             match view {
                 LibraryView::Album(v) => v.clone().into_any_element(),
+                LibraryView::Timeline(v) => v.clone().into_any_element(),
                 LibraryView::Tracks(v) => v.clone().into_any_element(),
                 LibraryView::Release(v) => v.clone().into_any_element(),
                 LibraryView::Playlist(v) => v.clone().into_any_element(),
                 LibraryView::Artists(v) => v.clone().into_any_element(),
                 LibraryView::ArtistDetail(v) => v.clone().into_any_element(),
+                LibraryView::History(v) => v.clone().into_any_element(),
+                LibraryView::Stats(v) => v.clone().into_any_element(),
             }
         }
 
@@ -646,6 +682,8 @@ impl Render for Library {
                             cx.artist_id_for_album(album_id)
                                 .ok()
                                 .map(ViewSwitchMessage::Artist)
+                        } else if this.section == LibrarySection::Timeline {
+                            Some(ViewSwitchMessage::Timeline)
                         } else {
                             Some(ViewSwitchMessage::Albums)
                         }
@@ -11,6 +11,7 @@ use crate::{
         types::{Album, DBString, Track, table::AlbumColumn},
     },
     playback::{queue::QueueItemData, thread::PlaybackState},
+    settings::{SettingsGlobal, interface::ArtistTracks},
     ui::{
         availability::{has_available_tracks, is_track_available},
         caching::hummingbird_cache,
@@ -48,12 +49,16 @@ pub struct ArtistDetailView {
     artist_id: i64,
     artist_name: Option<DBString>,
     album_ids: Vec<(u32, String)>,
-    liked_track_items: Vec<Entity<TrackItem>>,
     all_tracks: Arc<Vec<Track>>,
-    liked_tracks: Arc<Vec<Track>>,
+    /// The tracks listed below the albums, which are all tracks or only the liked ones depending
+    /// on `track_mode`.
+    tracks: Arc<Vec<Track>>,
+    track_mode: ArtistTracks,
     scroll_handle: ScrollHandle,
     grid_views: Entity<FxHashMap<usize, Entity<GridItem<Album, AlbumColumn>>>>,
     grid_render_counter: Entity<usize>,
+    track_views: Entity<FxHashMap<usize, Entity<TrackItem>>>,
+    track_render_counter: Entity<usize>,
     nav_model: Entity<super::NavigationHistory>,
     liked_sort: LikedTrackSortMethod,
 }
@@ -75,56 +80,56 @@ impl ArtistDetailView {
                 .unwrap_or_else(|_| Arc::new(Vec::new()));
 
             let liked_sort = *cx.global::<Models>().liked_tracks_sort_method.read(cx);
+            let track_mode = cx
+                .global::<SettingsGlobal>()
+                .model
+                .read(cx)
+                .interface
+                .artist_tracks;
 
-            let liked_tracks = cx
-                .get_liked_tracks_by_artist(artist_id, liked_sort)
-                .unwrap_or_else(|_| Arc::new(Vec::new()));
-
-            let liked_track_items: Vec<Entity<TrackItem>> = liked_tracks
-                .iter()
-                .map(|track| {
-                    TrackItem::new(
-                        cx,
-                        track.clone(),
-                        false,
-                        ArtistNameVisibility::OnlyIfDifferent(artist_name.clone()),
-                        TrackItemLeftField::Art,
-                        None,
-                        false,
-                        None,
-                        Some(liked_tracks.clone()),
-                        false,
-                        false,
-                    )
-                })
-                .collect();
+            let tracks = Self::load_tracks(cx, artist_id, liked_sort, track_mode);
 
             let playlist_tracker = cx.global::<Models>().playlist_tracker.clone();
 
             cx.subscribe(&playlist_tracker, move |this: &mut Self, _, ev, cx| {
-                if let PlaylistEvent::PlaylistUpdated(1) = ev {
-                    let liked_tracks = cx
-                        .get_liked_tracks_by_artist(artist_id, this.liked_sort)
-                        .unwrap_or_else(|_| Arc::new(Vec::new()));
+                if let PlaylistEvent::PlaylistUpdated(1) = ev
+                    && this.track_mode == ArtistTracks::Liked
+                {
+                    this.reload_tracks(cx);
+                }
+            })
+            .detach();
 
-                    this.set_liked_tracks(liked_tracks, cx);
+            // the track rows are sized here, so re-render when the list density changes
+            let settings = cx.global::<SettingsGlobal>().model.clone();
+            cx.observe(&settings, |this: &mut Self, settings, cx| {
+                let track_mode = settings.read(cx).interface.artist_tracks;
+                if this.track_mode != track_mode {
+                    this.track_mode = track_mode;
+                    this.reload_tracks(cx);
                 }
+
+                cx.notify();
             })
             .detach();
 
             let grid_views = cx.new(|_| FxHashMap::default());
             let grid_render_counter = cx.new(|_| 0usize);
+            let track_views = cx.new(|_| FxHashMap::default());
+            let track_render_counter = cx.new(|_| 0usize);
 
             ArtistDetailView {
                 artist_id,
                 artist_name,
                 album_ids,
-                liked_track_items,
                 all_tracks,
-                liked_tracks: liked_tracks.clone(),
+                tracks,
+                track_mode,
                 scroll_handle: ScrollHandle::new(),
                 grid_views,
                 grid_render_counter,
+                track_views,
+                track_render_counter,
                 nav_model,
                 liked_sort,
             }
@@ -143,36 +148,28 @@ impl ArtistDetailView {
 
         self.liked_sort = next_sort;
         self.sync_sort_with_model(cx);
+        self.reload_tracks(cx);
+    }
 
-        let liked_tracks = cx
-            .get_liked_tracks_by_artist(self.artist_id, self.liked_sort)
-            .unwrap_or_else(|_| Arc::new(Vec::new()));
+    fn load_tracks(
+        cx: &mut App,
+        artist_id: i64,
+        sort_method: LikedTrackSortMethod,
+        track_mode: ArtistTracks,
+    ) -> Arc<Vec<Track>> {
+        if track_mode == ArtistTracks::Hidden {
+            return Arc::new(Vec::new());
+        }
 
-        self.set_liked_tracks(liked_tracks, cx);
+        cx.get_artist_tracks(artist_id, sort_method, track_mode == ArtistTracks::Liked)
+            .unwrap_or_else(|_| Arc::new(Vec::new()))
     }
 
-    fn set_liked_tracks(&mut self, liked_tracks: Arc<Vec<Track>>, cx: &mut Context<Self>) {
-        self.liked_tracks = liked_tracks;
-
-        self.liked_track_items = self
-            .liked_tracks
-            .iter()
-            .map(|track: &Track| {
-                TrackItem::new(
-                    cx,
-                    track.clone(),
-                    false,
-                    ArtistNameVisibility::OnlyIfDifferent(self.artist_name.clone()),
-                    TrackItemLeftField::Art,
-                    None,
-                    false,
-                    None,
-                    Some(self.liked_tracks.clone()),
-                    false,
-                    false,
-                )
-            })
-            .collect();
+    fn reload_tracks(&mut self, cx: &mut Context<Self>) {
+        self.tracks = Self::load_tracks(cx, self.artist_id, self.liked_sort, self.track_mode);
+
+        // rows are created as they're scrolled into view, so drop the ones for the old list
+        self.track_views.update(cx, |views, _| views.clear());
 
         cx.notify();
     }
@@ -180,10 +177,7 @@ impl ArtistDetailView {
     fn toggle_liked_sort_order(&mut self, cx: &mut Context<Self>) {
         self.liked_sort = Self::toggled_sort(self.liked_sort);
         self.sync_sort_with_model(cx);
-        let liked_tracks = cx
-            .get_liked_tracks_by_artist(self.artist_id, self.liked_sort)
-            .unwrap_or_else(|_| Arc::new(Vec::new()));
-        self.set_liked_tracks(liked_tracks, cx);
+        self.reload_tracks(cx);
     }
 
     fn base_sort(sort_method: LikedTrackSortMethod) -> LikedTrackSortMethod {
@@ -269,6 +263,7 @@ impl Render for ArtistDetailView {
         let grid_min_item_width = crate::settings::interface::clamp_grid_min_item_width(
             settings.interface.grid_min_item_width,
         );
+        let track_row_height = settings.interface.list_density.track_row_height() + 1.0;
 
         let album_count = self.album_ids.len();
         let album_ids = self.album_ids.clone();
@@ -291,20 +286,20 @@ impl Render for ArtistDetailView {
             });
         let has_available_artist_tracks = has_available_tracks(self.all_tracks.as_ref());
 
-        let current_track_in_liked = cx
+        let current_track_in_tracks = cx
             .global::<PlaybackInfo>()
             .current_track
             .read(cx)
             .clone()
             .is_some_and(|current_track| {
-                self.liked_tracks
+                self.tracks
                     .iter()
                     .any(|track| current_track == track.location && is_track_available(track))
             });
-        let has_available_liked_tracks = has_available_tracks(self.liked_tracks.as_ref());
+        let has_available_listed_tracks = has_available_tracks(self.tracks.as_ref());
 
-        let liked_track_header =
-            if !self.liked_track_items.is_empty() {
+        let track_header =
+            if !self.tracks.is_empty() {
                 Some(
                     div()
                         .border_t_1()
@@ -320,7 +315,11 @@ impl Render for ArtistDetailView {
                                 .font_weight(FontWeight::BOLD)
                                 .text_size(px(18.0))
                                 .my_auto()
-                                .child(tr!("ARTIST_LIKED_TRACKS", "Liked Tracks")),
+                                .child(if self.track_mode == ArtistTracks::All {
+                                    tr!("ARTIST_TRACKS", "Tracks")
+                                } else {
+                                    tr!("ARTIST_LIKED_TRACKS", "Liked Tracks")
+                                }),
                         )
                         .child(
                             div()
@@ -330,13 +329,13 @@ impl Render for ArtistDetailView {
                                 .pb(px(13.0))
                                 .child(playback_controls(
                                     "artist-liked",
-                                    has_available_liked_tracks,
-                                    current_track_in_liked,
+                                    has_available_listed_tracks,
+                                    current_track_in_tracks,
                                     is_playing,
                                     {
-                                        let liked_tracks = self.liked_tracks.clone();
+                                        let tracks = self.tracks.clone();
                                         move |cx| {
-                                            liked_tracks
+                                            tracks
                                                 .iter()
                                                 .filter(|track| is_track_available(track))
                                                 .map(|track| {
@@ -450,34 +449,42 @@ impl Render for ArtistDetailView {
                                     .pb(px(10.0))
                                     .w_full()
                                     .text_ellipsis()
-                                    .when_some(self.artist_name.clone(), |this, name| {
-                                        this.child(name)
-                                    }),
+                                    .child(
+                                        self.artist_name
+                                            .clone()
+                                            .map(|name| name.0)
+                                            .unwrap_or_else(|| tr!("UNKNOWN_ARTIST").into()),
+                                    ),
                             )
                             .when(!self.all_tracks.is_empty(), |this| {
-                                this.child(div().pb(px(18.0)).child(playback_controls(
-                                    "artist",
-                                    has_available_artist_tracks,
-                                    current_track_in_artist,
-                                    is_playing,
-                                    {
-                                        let all_tracks = self.all_tracks.clone();
-                                        move |cx| {
-                                            all_tracks
-                                                .iter()
-                                                .filter(|track| is_track_available(track))
-                                                .map(|track| {
-                                                    QueueItemData::new(
-                                                        cx,
-                                                        track.location.clone(),
-                                                        Some(track.id),
-                                                        track.album_id,
-                                                    )
-                                                })
-                                                .collect()
-                                        }
-                                    },
-                                )))
+                                this.child(
+                                    div().pb(px(18.0)).child(
+                                        playback_controls(
+                                            "artist",
+                                            has_available_artist_tracks,
+                                            current_track_in_artist,
+                                            is_playing,
+                                            {
+                                                let all_tracks = self.all_tracks.clone();
+                                                move |cx| {
+                                                    all_tracks
+                                                        .iter()
+                                                        .filter(|track| is_track_available(track))
+                                                        .map(|track| {
+                                                            QueueItemData::new(
+                                                                cx,
+                                                                track.location.clone(),
+                                                                Some(track.id),
+                                                                track.album_id,
+                                                            )
+                                                        })
+                                                        .collect()
+                                                }
+                                            },
+                                        )
+                                        .play_menu(),
+                                    ),
+                                )
                             }),
                     )
                     .when(album_count > 0, |this| {
@@ -547,17 +554,60 @@ impl Render for ArtistDetailView {
                             ),
                         )
                     })
-                    .when_some(liked_track_header, |this, header| {
+                    .when_some(track_header, |this, header| {
+                        let tracks = self.tracks.clone();
+                        let artist_name = self.artist_name.clone();
+                        let track_views = self.track_views.clone();
+                        let track_render_counter = self.track_render_counter.clone();
+
                         this.child(header).child(
                             div()
                                 .w_full()
                                 .border_t_1()
                                 .border_color(theme.border_color)
-                                .image_cache(retain_all("artist_liked_tracks_cache"))
-                                .children(
-                                    self.liked_track_items
-                                        .iter()
-                                        .map(|item| div().h(px(40.0)).child(item.clone())),
+                                .image_cache(hummingbird_cache("artist-tracks-cache", 50))
+                                .child(
+                                    uniform_grid(
+                                        "artist-tracks",
+                                        tracks.len(),
+                                        None,
+                                        move |idx, _, cx| {
+                                            prune_views(
+                                                &track_views,
+                                                &track_render_counter,
+                                                idx,
+                                                cx,
+                                            );
+
+                                            let view = create_or_retrieve_view(
+                                                &track_views,
+                                                idx,
+                                                |cx| {
+                                                    TrackItem::new(
+                                                        cx,
+                                                        tracks[idx].clone(),
+                                                        false,
+                                                        ArtistNameVisibility::OnlyIfDifferent(
+                                                            artist_name.clone(),
+                                                        ),
+                                                        TrackItemLeftField::Art,
+                                                        None,
+                                                        false,
+                                                        None,
+                                                        Some(tracks.clone()),
+                                                        false,
+                                                        false,
+                                                    )
+                                                },
+                                                cx,
+                                            );
+
+                                            div().size_full().child(view).into_any_element()
+                                        },
+                                    )
+                                    .rows(px(track_row_height))
+                                    .gap(px(0.0))
+                                    .auto_height(),
                                 ),
                         )
                     }),
@@ -4,18 +4,18 @@ pub mod track;
 
 use std::{path::Path, process::Command, rc::Rc, sync::Arc};
 
+use camino::Utf8PathBuf;
 use cntp_i18n::tr;
 use gpui::{AnyElement, App, AppContext, Entity, IntoElement, SharedString, Window};
 
 use crate::{
     library::{
         db::{self, LibraryAccess},
+        scan::ScanInterface,
         types::{Album, Track},
     },
-    playback::{
-        interface::{PlaybackInterface, replace_queue},
-        queue::QueueItemData,
-    },
+    playback::{interface::PlaybackInterface, queue::QueueItemData},
+    settings::{SettingsGlobal, interface::TrackClickAction},
     ui::{
         availability::is_track_available,
         library::{
@@ -23,7 +23,8 @@ use crate::{
             add_to_playlist::AddToPlaylist,
             context_menus::{album::AlbumContextMenu, track::TrackContextMenu},
         },
-        models::{Models, PlaybackInfo, PlaylistEvent},
+        models::{AlbumEvent, Models, PlaylistEvent, TrackEvent},
+        queue_confirmation::{replace_queue, replace_queue_with_index, replace_queue_with_shuffle},
     },
 };
 
@@ -116,14 +117,13 @@ pub fn play_from_track(
         return;
     }
 
-    let playback_interface = cx.global::<PlaybackInterface>();
     if let Some(index) = queue_items
         .iter()
         .position(|item| item.get_path() == &track.location)
     {
-        playback_interface.replace_queue_with_index(queue_items, index);
+        replace_queue_with_index(queue_items, index, cx);
     } else {
-        playback_interface.replace_queue(queue_items);
+        replace_queue(queue_items, cx);
     }
 }
 
@@ -173,6 +173,21 @@ pub fn play_from_track_listing(
     play_from_track(cx, track, queue_items);
 }
 
+/// Plays a track that was clicked in a track list, following the configured
+/// [`TrackClickAction`]. `play_from_here` is called to play the surrounding tracks.
+pub fn play_clicked_track(cx: &mut App, track: &Track, play_from_here: impl FnOnce(&mut App)) {
+    match cx
+        .global::<SettingsGlobal>()
+        .model
+        .read(cx)
+        .interface
+        .track_click_action
+    {
+        TrackClickAction::PlayFromHere => play_from_here(cx),
+        TrackClickAction::PlayTrack => play_track_now(cx, track),
+    }
+}
+
 pub fn track_show_in_file_manager_label() -> SharedString {
     if cfg!(target_os = "macos") {
         tr!("SHOW_IN_FINDER", "Show in Finder").into()
@@ -222,6 +237,11 @@ pub fn remove_from_playlist(
 
 fn play_track_now(cx: &mut App, track: &Track) {
     let data = QueueItemData::new(cx, track.location.clone(), Some(track.id), track.album_id);
+    play_item_now(cx, data);
+}
+
+/// Adds an item to the end of the queue and jumps to it.
+pub fn play_item_now(cx: &mut App, data: QueueItemData) {
     let playback_interface = cx.global::<PlaybackInterface>();
     let queue_length = cx
         .global::<Models>()
@@ -247,6 +267,54 @@ fn queue_track(cx: &mut App, track: &Track) {
     cx.global::<PlaybackInterface>().queue(data);
 }
 
+/// Sends the set of tracks that are skipped when shuffling to the playback thread.
+fn sync_shuffle_skips(cx: &mut App) {
+    match cx.get_shuffle_skipped_tracks() {
+        Ok(skips) => cx
+            .global::<PlaybackInterface>()
+            .set_shuffle_skips(skips.into_iter().collect()),
+        Err(err) => tracing::error!("could not load tracks skipped when shuffling: {err:?}"),
+    }
+}
+
+fn set_track_skip_on_shuffle(cx: &mut App, track: &Track, skip: bool) {
+    if let Err(err) = cx.set_track_skip(track.id, skip) {
+        tracing::error!("could not update skip on shuffle for track: {err:?}");
+        return;
+    }
+
+    sync_shuffle_skips(cx);
+
+    let playlist_tracker = cx.global::<Models>().playlist_tracker.clone();
+    playlist_tracker.update(cx, |_, cx| {
+        cx.emit(TrackEvent::SkipOnShuffleChanged(track.id));
+    });
+}
+
+pub fn set_track_rating(cx: &mut App, track_id: i64, rating: i32) {
+    if let Err(err) = cx.set_track_rating(track_id, rating) {
+        tracing::error!("could not update rating for track: {err:?}");
+        return;
+    }
+
+    let playlist_tracker = cx.global::<Models>().playlist_tracker.clone();
+    playlist_tracker.update(cx, |_, cx| {
+        cx.emit(TrackEvent::RatingChanged(track_id));
+    });
+}
+
+fn set_album_rating(cx: &mut App, album: &Album, rating: i32) {
+    if let Err(err) = cx.set_album_rating(album.id, rating) {
+        tracing::error!("could not update rating for album: {err:?}");
+        return;
+    }
+
+    let playlist_tracker = cx.global::<Models>().playlist_tracker.clone();
+    playlist_tracker.update(cx, |_, cx| {
+        cx.emit(AlbumEvent::RatingChanged(album.id));
+    });
+}
+
 pub(crate) fn navigate_to_track_artist(cx: &mut App, track: &Track) {
     let Some(album_id) = track.album_id else {
         return;
@@ -341,11 +409,25 @@ fn shuffle_album(cx: &mut App, album: &Album) {
         return;
     }
 
-    let interface = cx.global::<PlaybackInterface>();
-    if !(*cx.global::<PlaybackInfo>().shuffling.read(cx)) {
-        interface.toggle_shuffle();
-    }
-    replace_queue(queue_items, cx);
+    replace_queue_with_shuffle(queue_items, true, cx);
+}
+
+/// Re-reads an album's files, updating its tracks and album information even if the files haven't
+/// changed.
+pub(crate) fn refresh_album(cx: &mut App, album_id: i64) {
+    let tracks = match cx.list_tracks_in_album(album_id) {
+        Ok(tracks) => tracks,
+        Err(err) => {
+            tracing::error!("could not load tracks to refresh album: {err:?}");
+            return;
+        }
+    };
+
+    let paths = tracks
+        .iter()
+        .filter_map(|track| Utf8PathBuf::from_path_buf(track.location.clone()).ok())
+        .collect();
+    cx.global::<ScanInterface>().refresh_paths(paths);
 }
 
 fn queue_album(cx: &mut App, album: &Album) {
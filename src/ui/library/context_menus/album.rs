@@ -8,15 +8,15 @@ use crate::{
     ui::{
         availability::album_has_available_tracks,
         components::{
-            icons::{PLAY, PLUS, SHUFFLE, USERS},
-            menu::{menu, menu_item, menu_separator},
+            icons::{PLAY, PLUS, REFRESH, SHUFFLE, USERS},
+            menu::{menu, menu_item, menu_rating_item, menu_separator},
         },
     },
 };
 
 use super::{
     AlbumContextMenuContext, navigate_to_artist, play_album_next, play_album_now, queue_album,
-    shuffle_album,
+    refresh_album, set_album_rating, shuffle_album,
 };
 
 #[derive(IntoElement)]
@@ -38,8 +38,11 @@ impl RenderOnce for AlbumContextMenu {
         let album_for_shuffle = self.album.clone();
         let album_for_queue = self.album.clone();
         let album_for_artist = self.album.clone();
+        let album_for_rating = self.album.clone();
+        let album_id = self.album.id;
         let show_go_to_artist = self.context.show_go_to_artist;
         let is_available = album_has_available_tracks(cx, album.id);
+        let rating = album.rating;
         let menu = menu()
             .item(
                 menu_item("album_play", Some(PLAY), tr!("PLAY"), move |_, _, cx| {
@@ -79,9 +82,16 @@ impl RenderOnce for AlbumContextMenu {
                     },
                 )
                 .disabled(!is_available),
-            );
+            )
+            .item(menu_rating_item(
+                "album_rating",
+                rating,
+                move |rating, _, cx| {
+                    set_album_rating(cx, &album_for_rating, rating);
+                },
+            ));
 
-        if show_go_to_artist {
+        let menu = if show_go_to_artist {
             menu.item(menu_separator()).item(menu_item(
                 "album_go_to_artist",
                 Some(USERS),
@@ -92,6 +102,15 @@ impl RenderOnce for AlbumContextMenu {
             ))
         } else {
             menu
-        }
+        };
+
+        menu.item(menu_separator()).item(menu_item(
+            "album_refresh",
+            Some(REFRESH),
+            tr!("REFRESH_ALBUM", "Refresh album"),
+            move |_, _, cx| {
+                refresh_album(cx, album_id);
+            },
+        ))
     }
 }
@@ -9,9 +9,12 @@ use crate::{
     ui::{
         availability::is_track_path_available,
         components::{
-            icons::{DISC, FOLDER_SEARCH, PLAY, PLAYLIST_ADD, PLAYLIST_REMOVE, PLUS, USERS},
-            menu::{menu, menu_item, menu_separator},
+            icons::{
+                DISC, FOLDER_SEARCH, INFO_CIRCLE, PLAY, PLAYLIST_ADD, PLAYLIST_REMOVE, PLUS, USERS,
+            },
+            menu::{menu, menu_check_item, menu_item, menu_rating_item, menu_separator},
         },
+        library::track_properties::show_track_properties,
         models::Models,
     },
 };
@@ -19,7 +22,8 @@ use crate::{
 use super::{
     PlaylistMenuInfo, TrackContextMenuContext, navigate_to_track_album, navigate_to_track_artist,
     play_track_next, play_track_now, queue_track, remove_from_playlist,
-    reveal_track_in_file_manager, track_show_in_file_manager_label,
+    reveal_track_in_file_manager, set_track_rating, set_track_skip_on_shuffle,
+    track_show_in_file_manager_label,
 };
 use crate::ui::app::Pool;
 
@@ -59,6 +63,7 @@ impl RenderOnce for TrackContextMenu {
         let track_for_artist = self.track.clone();
         let track_for_album = self.track.clone();
         let track_for_reveal = self.track.clone();
+        let track_for_skip = self.track.clone();
         let can_go_to_artist = track_for_artist.album_id.is_some();
         let can_go_to_album = track_for_album.album_id.is_some();
         let can_reveal_track = is_track_path_available(track_for_reveal.location.as_path());
@@ -66,6 +71,9 @@ impl RenderOnce for TrackContextMenu {
         let play_from_here = self.context.play_from_here.clone();
         let playlist_info = self.playlist_info;
         let is_available = self.is_available;
+        let track_id = self.track.id;
+        let skip_on_shuffle = track.skip_on_shuffle;
+        let rating = track.rating;
 
         menu()
             .item(
@@ -108,6 +116,21 @@ impl RenderOnce for TrackContextMenu {
                 )
                 .disabled(!is_available),
             )
+            .item(menu_check_item(
+                "track_skip_on_shuffle",
+                skip_on_shuffle,
+                tr!("SKIP_WHEN_SHUFFLING", "Skip when shuffling"),
+                move |_, _, cx| {
+                    set_track_skip_on_shuffle(cx, &track_for_skip, !skip_on_shuffle);
+                },
+            ))
+            .item(menu_rating_item(
+                "track_rating",
+                rating,
+                move |rating, _, cx| {
+                    set_track_rating(cx, track_id, rating);
+                },
+            ))
             .item(menu_separator())
             .when(self.context.show_go_to_artist, |menu| {
                 menu.item(
@@ -183,5 +206,14 @@ impl RenderOnce for TrackContextMenu {
                     .disabled(!is_available),
                 )
             })
+            .item(menu_separator())
+            .item(menu_item(
+                "track_properties",
+                Some(INFO_CIRCLE),
+                tr!("PROPERTIES"),
+                move |_, _, cx| {
+                    show_track_properties(cx, track_id);
+                },
+            ))
     }
 }
@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use cntp_i18n::tr;
+use gpui::*;
+use prelude::FluentBuilder;
+
+use crate::{
+    library::{
+        db::LibraryAccess,
+        types::{PlayHistoryEntry, Track},
+    },
+    settings::SettingsGlobal,
+    ui::{
+        components::{
+            button::{ButtonIntent, button},
+            scrollbar::{RightPad, floating_scrollbar},
+            table::table_data::TABLE_MAX_WIDTH,
+        },
+        format::format_date_time,
+        library::track_listing::{
+            ArtistNameVisibility,
+            track_item::{TrackItem, TrackItemLeftField},
+        },
+        models::Models,
+        theme::Theme,
+    },
+};
+
+/// The number of history entries loaded at a time.
+const PAGE_SIZE: i64 = 200;
+
+struct HistoryItem {
+    history_id: i64,
+    played_at: SharedString,
+    track_item: Entity<TrackItem>,
+}
+
+pub struct HistoryView {
+    items: Vec<HistoryItem>,
+    has_more: bool,
+    /// The track of every play in the history, most recent first. Playing from a history entry
+    /// continues through the rest of the history, including entries that haven't been loaded.
+    queue_context: Arc<Vec<Track>>,
+    scroll_handle: ScrollHandle,
+}
+
+impl HistoryView {
+    pub(super) fn new(cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let play_history = cx.global::<Models>().play_history.clone();
+
+            cx.observe(&play_history, |this: &mut Self, _, cx| this.load_new(cx))
+                .detach();
+
+            let settings = cx.global::<SettingsGlobal>().model.clone();
+            cx.observe(&settings, |_, _, cx| cx.notify()).detach();
+
+            let mut view = HistoryView {
+                items: Vec::new(),
+                has_more: false,
+                queue_context: load_queue_context(cx),
+                scroll_handle: ScrollHandle::new(),
+            };
+            view.load(PAGE_SIZE, cx);
+
+            view
+        })
+    }
+
+    /// Loads up to `limit` more history entries after the ones already shown.
+    fn load(&mut self, limit: i64, cx: &mut Context<Self>) {
+        let entries = cx
+            .get_play_history(limit, self.items.len() as i64)
+            .unwrap_or_else(|_| Arc::new(Vec::new()));
+        self.has_more = entries.len() as i64 == limit;
+
+        let items: Vec<HistoryItem> = entries
+            .iter()
+            .map(|entry| self.history_item(entry, cx))
+            .collect();
+        self.items.extend(items);
+
+        cx.notify();
+    }
+
+    /// Adds the plays recorded since the newest entry shown to the top, leaving the rows that are
+    /// already shown as they are.
+    fn load_new(&mut self, cx: &mut Context<Self>) {
+        let Some(newest) = self.items.first().map(|item| item.history_id) else {
+            self.queue_context = load_queue_context(cx);
+            self.load(PAGE_SIZE, cx);
+            return;
+        };
+
+        let entries = cx
+            .get_play_history_after(newest)
+            .unwrap_or_else(|_| Arc::new(Vec::new()));
+        if entries.is_empty() {
+            return;
+        }
+
+        self.queue_context = load_queue_context(cx);
+        for item in &self.items {
+            let queue_context = self.queue_context.clone();
+            item.track_item.update(cx, |track_item, _| {
+                track_item.set_queue_context(Some(queue_context))
+            });
+        }
+
+        let items: Vec<HistoryItem> = entries
+            .iter()
+            .map(|entry| self.history_item(entry, cx))
+            .collect();
+        self.items.splice(0..0, items);
+
+        cx.notify();
+    }
+
+    fn history_item(&self, entry: &PlayHistoryEntry, cx: &mut App) -> HistoryItem {
+        HistoryItem {
+            history_id: entry.history_id,
+            played_at: format_played_at(entry),
+            track_item: TrackItem::new(
+                cx,
+                entry.track.clone(),
+                false,
+                ArtistNameVisibility::Always,
+                TrackItemLeftField::Art,
+                None,
+                false,
+                None,
+                Some(self.queue_context.clone()),
+                true,
+                true,
+            ),
+        }
+    }
+}
+
+fn load_queue_context(cx: &App) -> Arc<Vec<Track>> {
+    cx.get_play_history_tracks()
+        .unwrap_or_else(|_| Arc::new(Vec::new()))
+}
+
+fn format_played_at(entry: &PlayHistoryEntry) -> SharedString {
+    format_date_time(&entry.played_at)
+}
+
+impl Render for HistoryView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.global::<Theme>();
+        let settings = cx.global::<SettingsGlobal>().model.read(cx);
+        let full_width = settings.interface.effective_full_width();
+        let row_height = settings.interface.list_density.track_row_height();
+        let scroll_handle = self.scroll_handle.clone();
+
+        div()
+            .flex()
+            .flex_col()
+            .w_full()
+            .h_full()
+            .when(!full_width, |this: Div| this.max_w(px(TABLE_MAX_WIDTH)))
+            .pt(px(10.0))
+            .child(
+                div()
+                    .w_full()
+                    .pb(px(10.0))
+                    .px(px(18.0))
+                    .line_height(px(26.0))
+                    .font_weight(FontWeight::BOLD)
+                    .text_size(px(26.0))
+                    .child(tr!("RECENTLY_PLAYED", "Recently Played")),
+            )
+            .child(
+                div()
+                    .relative()
+                    .w_full()
+                    .h_full()
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .id("history-list")
+                            .image_cache(retain_all("history_cache"))
+                            .w_full()
+                            .h_full()
+                            .flex()
+                            .flex_col()
+                            .overflow_y_scroll()
+                            .track_scroll(&scroll_handle)
+                            .border_t_1()
+                            .border_color(theme.border_color)
+                            .when(self.items.is_empty(), |this| {
+                                this.child(
+                                    div()
+                                        .px(px(18.0))
+                                        .py(px(12.0))
+                                        .text_sm()
+                                        .text_color(theme.text_secondary)
+                                        .child(tr!(
+                                            "RECENTLY_PLAYED_EMPTY",
+                                            "Tracks you play will appear here."
+                                        )),
+                                )
+                            })
+                            .children(self.items.iter().map(|item| {
+                                div()
+                                    .id(("history-entry", item.history_id as u64))
+                                    .flex()
+                                    .w_full()
+                                    .child(
+                                        div().flex_grow().min_w_0().child(item.track_item.clone()),
+                                    )
+                                    .child(
+                                        div()
+                                            .flex_shrink_0()
+                                            .h(px(row_height))
+                                            .pr(px(18.0))
+                                            .flex()
+                                            .items_center()
+                                            .text_sm()
+                                            .text_color(theme.text_secondary)
+                                            .border_b_1()
+                                            .border_color(theme.border_color)
+                                            .child(item.played_at.clone()),
+                                    )
+                            }))
+                            .when(self.has_more, |this| {
+                                this.child(
+                                    div().p(px(12.0)).flex().justify_center().child(
+                                        button()
+                                            .intent(ButtonIntent::Secondary)
+                                            .id("history-load-more")
+                                            .child(tr!("RECENTLY_PLAYED_LOAD_MORE", "Load more"))
+                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                this.load(PAGE_SIZE, cx);
+                                            })),
+                                    ),
+                                )
+                            }),
+                    )
+                    .child(floating_scrollbar(
+                        "history_scrollbar",
+                        scroll_handle,
+                        RightPad::Pad,
+                    )),
+            )
+    }
+}
@@ -3,9 +3,9 @@ use std::sync::Arc;
 use cntp_i18n::tr;
 use gpui::{
     App, AppContext, Context, DragMoveEvent, Entity, FocusHandle, FontWeight, InteractiveElement,
-    IntoElement, KeyBinding, ParentElement, Render, SharedString, StatefulInteractiveElement,
-    Styled, UniformListScrollHandle, Window, actions, div, prelude::FluentBuilder, px, rems, rgba,
-    uniform_list,
+    IntoElement, KeyBinding, ParentElement, Pixels, Render, SharedString,
+    StatefulInteractiveElement, Styled, UniformListScrollHandle, Window, actions, div,
+    prelude::FluentBuilder, px, rems, rgba, uniform_list,
 };
 use rustc_hash::FxHashMap;
 use tracing::error;
@@ -48,8 +48,17 @@ use super::track_listing::track_item::TrackPlaylistInfo;
 
 actions!(playlist, [Export, Import]);
 
-// height + border
-const PLAYLIST_ITEM_HEIGHT: f32 = 40.0;
+/// The height of a playlist row at the current list density, including the gap between rows.
+fn playlist_item_height(cx: &App) -> Pixels {
+    let density = cx
+        .global::<crate::settings::SettingsGlobal>()
+        .model
+        .read(cx)
+        .interface
+        .list_density;
+
+    px(density.track_row_height() + 1.0)
+}
 
 pub fn bind_actions(cx: &mut App) {
     cx.bind_keys([KeyBinding::new("secondary-s", Export, None)]);
@@ -148,7 +157,7 @@ impl Render for PlaylistTrackItem {
         let mut element = div()
             .id(("playlist-track-item", self.playlist_item_id as u64))
             .w_full()
-            .h(px(PLAYLIST_ITEM_HEIGHT))
+            .h(playlist_item_height(cx))
             .relative()
             .when(item_state.is_being_dragged, |d| d.opacity(0.5));
 
@@ -196,7 +205,7 @@ impl PlaylistView {
             let playlist_tracker = cx.global::<Models>().playlist_tracker.clone();
 
             let list_id: gpui::ElementId = format!("playlist-{}", playlist_id).into();
-            let config = DragDropListConfig::new(list_id.clone(), px(PLAYLIST_ITEM_HEIGHT));
+            let config = DragDropListConfig::new(list_id.clone(), playlist_item_height(cx));
             let drag_drop_manager = DragDropListManager::new(cx, config);
 
             let playlist = cx.get_playlist(playlist_id).unwrap();
@@ -233,6 +242,20 @@ impl PlaylistView {
             })
             .detach();
 
+            let settings = cx.global::<crate::settings::SettingsGlobal>().model.clone();
+            cx.observe(&settings, |this: &mut Self, _, cx| {
+                let item_height = playlist_item_height(cx);
+                if this.drag_drop_manager.read(cx).config.item_height != item_height {
+                    this.drag_drop_manager.update(cx, |manager, _| {
+                        manager.config.item_height = item_height;
+                    });
+                    this.views = cx.new(|_| FxHashMap::default());
+                    this.render_counter = cx.new(|_| 0);
+                    cx.notify();
+                }
+            })
+            .detach();
+
             let focus_handle = cx.focus_handle();
 
             cx.register_command(
@@ -429,6 +452,7 @@ impl Render for PlaylistView {
         let drag_drop_manager = self.drag_drop_manager.clone();
         let list_id = self.list_id.clone();
         let item_count = items_clone.len();
+        let item_height = playlist_item_height(cx);
         let playlist_id = self.playlist.id;
         let is_custom_sort = self.is_custom_sort();
         let current_sort = self.sort_method;
@@ -726,51 +750,49 @@ impl Render for PlaylistView {
                                     let playlist_item_id = item.0;
                                     let track_id = item.1;
 
-                                    div().h(px(PLAYLIST_ITEM_HEIGHT)).child(
-                                        create_or_retrieve_view(
-                                            &views_model,
-                                            idx,
-                                            move |cx| {
-                                                let track = cx.get_track_by_id(track_id).unwrap();
-                                                let track_title: SharedString =
-                                                    track.title.clone().into();
-                                                let track_path = track.location.clone();
-                                                let album_id = track.album_id;
-
-                                                let track_item = TrackItem::new(
-                                                    cx,
-                                                    Arc::try_unwrap(track).unwrap(),
-                                                    false,
-                                                    ArtistNameVisibility::Always,
-                                                    TrackItemLeftField::Art,
-                                                    Some(TrackPlaylistInfo {
-                                                        id: pl_id,
-                                                        item_id: playlist_item_id,
-                                                    }),
-                                                    false, // vinyl_numbering - not applicable for playlists
-                                                    None, // max_track_num - not needed for Art left field
-                                                    None, // queue_context - playlist uses pl_id instead
-                                                    true, // show_go_to_album
-                                                    true, // show_go_to_artist
-                                                );
-
-                                                PlaylistTrackItem::new(
-                                                    cx,
-                                                    track_item,
-                                                    idx,
-                                                    playlist_item_id,
-                                                    track_title,
-                                                    drag_drop_manager,
-                                                    list_id,
-                                                    track_id,
-                                                    album_id,
-                                                    track_path,
-                                                    is_custom_sort,
-                                                )
-                                            },
-                                            cx,
-                                        ),
-                                    )
+                                    div().h(item_height).child(create_or_retrieve_view(
+                                        &views_model,
+                                        idx,
+                                        move |cx| {
+                                            let track = cx.get_track_by_id(track_id).unwrap();
+                                            let track_title: SharedString =
+                                                track.title.clone().into();
+                                            let track_path = track.location.clone();
+                                            let album_id = track.album_id;
+
+                                            let track_item = TrackItem::new(
+                                                cx,
+                                                Arc::try_unwrap(track).unwrap(),
+                                                false,
+                                                ArtistNameVisibility::Always,
+                                                TrackItemLeftField::Art,
+                                                Some(TrackPlaylistInfo {
+                                                    id: pl_id,
+                                                    item_id: playlist_item_id,
+                                                }),
+                                                false, // vinyl_numbering - not applicable for playlists
+                                                None, // max_track_num - not needed for Art left field
+                                                None, // queue_context - playlist uses pl_id instead
+                                                true, // show_go_to_album
+                                                true, // show_go_to_artist
+                                            );
+
+                                            PlaylistTrackItem::new(
+                                                cx,
+                                                track_item,
+                                                idx,
+                                                playlist_item_id,
+                                                track_title,
+                                                drag_drop_manager,
+                                                list_id,
+                                                track_id,
+                                                album_id,
+                                                track_path,
+                                                is_custom_sort,
+                                            )
+                                        },
+                                        cx,
+                                    ))
                                 })
                                 .collect()
                         })
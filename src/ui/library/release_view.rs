@@ -17,12 +17,16 @@ use crate::{
         availability::{has_available_tracks, is_track_available},
         caching::hummingbird_cache,
         components::{
+            button::{ButtonSize, button},
+            icons::{REFRESH, icon},
             playback_controls::playback_controls,
             scrollbar::{RightPad, ScrollableHandle, floating_scrollbar},
             table::table_data::TABLE_MAX_WIDTH,
+            tooltip::build_tooltip,
         },
         library::{
             ViewSwitchMessage,
+            context_menus::refresh_album,
             track_listing::{ArtistNameVisibility, TrackListing},
         },
         models::{Models, PlaybackInfo},
@@ -181,7 +185,12 @@ impl ReleaseView {
                                     })
                                 }
                             })
-                            .when_some(self.artist_name.clone(), |this, artist| this.child(artist)),
+                            .child(
+                                self.artist_name
+                                    .clone()
+                                    .map(|artist| artist.0)
+                                    .unwrap_or_else(|| tr!("UNKNOWN_ARTIST").into()),
+                            ),
                     )
                     .child(
                         div()
@@ -194,29 +203,45 @@ impl ReleaseView {
                             .text_ellipsis()
                             .child(self.album.title.clone()),
                     )
-                    .child(playback_controls(
-                        "release",
-                        has_available_tracks,
-                        current_track_in_album,
-                        is_playing,
-                        {
-                            let tracks = self.track_listing.tracks().clone();
-                            move |cx| {
-                                tracks
-                                    .iter()
-                                    .filter(|track| is_track_available(track))
-                                    .map(|track| {
-                                        QueueItemData::new(
-                                            cx,
-                                            track.location.clone(),
-                                            Some(track.id),
-                                            track.album_id,
-                                        )
-                                    })
-                                    .collect()
-                            }
-                        },
-                    )),
+                    .child(
+                        div()
+                            .flex()
+                            .gap(px(10.0))
+                            .child(playback_controls(
+                                "release",
+                                has_available_tracks,
+                                current_track_in_album,
+                                is_playing,
+                                {
+                                    let tracks = self.track_listing.tracks().clone();
+                                    move |cx| {
+                                        tracks
+                                            .iter()
+                                            .filter(|track| is_track_available(track))
+                                            .map(|track| {
+                                                QueueItemData::new(
+                                                    cx,
+                                                    track.location.clone(),
+                                                    Some(track.id),
+                                                    track.album_id,
+                                                )
+                                            })
+                                            .collect()
+                                    }
+                                },
+                            ))
+                            .child({
+                                let album_id = self.album.id;
+
+                                button()
+                                    .id("release-refresh")
+                                    .size(ButtonSize::Large)
+                                    .flex_none()
+                                    .tooltip(build_tooltip(tr!("REFRESH_ALBUM")))
+                                    .child(icon(REFRESH).size(px(16.0)).my_auto())
+                                    .on_click(move |_, _, cx| refresh_album(cx, album_id))
+                            }),
+                    ),
             )
     }
 
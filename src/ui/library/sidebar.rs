@@ -2,13 +2,13 @@ use std::sync::Arc;
 
 use cntp_i18n::{tr, trn};
 use gpui::{
-    App, AppContext, Context, Entity, IntoElement, ParentElement, Pixels, Render,
+    App, AppContext, Context, Entity, IntoElement, ParentElement, Pixels, Render, SharedString,
     StatefulInteractiveElement, Styled, Window, div, prelude::FluentBuilder, px,
 };
 
 use crate::settings::SettingsGlobal;
 
-use crate::settings::storage::DEFAULT_SIDEBAR_WIDTH;
+use crate::settings::storage::{DEFAULT_SIDEBAR_WIDTH, MAX_SIDEBAR_WIDTH, MIN_SIDEBAR_WIDTH};
 
 const COLLAPSED_SIDEBAR_WIDTH: Pixels = px(52.0);
 
@@ -16,16 +16,22 @@ use crate::ui::components::icons::{MENU, SIDEBAR, SIDEBAR_INACTIVE};
 use crate::ui::components::tooltip::build_tooltip;
 use crate::{
     library::{db::LibraryAccess, types::TrackStats},
+    playback::thread::PlaybackState,
     ui::{
         components::{
-            icons::{DISC, SEARCH, USERS},
+            icons::{CALENDAR, CHART_BAR, DISC, HISTORY, SEARCH, USERS},
             nav_button::nav_button,
-            resizable::{ResizeEdge, resizable},
+            now_playing::now_playing_indicator,
+            resizable::{MAX_PANEL_FRACTION, ResizeEdge, resizable},
             sidebar::{sidebar, sidebar_item, sidebar_separator},
         },
         global_actions::Search,
-        library::{NavigationHistory, ViewSwitchMessage, sidebar::playlists::PlaylistList},
-        models::Models,
+        library::{
+            NavigationHistory, ViewSwitchMessage,
+            context_menus::{navigate_to_track_album_and_reveal, resolve_library_track_by_path},
+            sidebar::playlists::PlaylistList,
+        },
+        models::{Models, PlaybackInfo},
         theme::Theme,
     },
 };
@@ -50,6 +56,15 @@ impl Sidebar {
             cx.observe(&sidebar_collapsed, |_, _, cx| cx.notify())
                 .detach();
 
+            let metadata = cx.global::<Models>().metadata.clone();
+            cx.observe(&metadata, |_, _, cx| cx.notify()).detach();
+
+            let playback_info = cx.global::<PlaybackInfo>().clone();
+            cx.observe(&playback_info.current_track, |_, _, cx| cx.notify())
+                .detach();
+            cx.observe(&playback_info.playback_state, |_, _, cx| cx.notify())
+                .detach();
+
             let scan_state = cx.global::<Models>().scan_state.clone();
 
             cx.observe(&scan_state, |this: &mut Self, _, cx| {
@@ -68,7 +83,7 @@ impl Sidebar {
 }
 
 impl Render for Sidebar {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let theme = cx.global::<Theme>();
         let stats_minutes = self.track_stats.total_duration / 60;
         let current_view = self.nav_model.read(cx).current();
@@ -95,6 +110,37 @@ impl Render for Sidebar {
 
         let toggle_icon = if collapsed { SIDEBAR_INACTIVE } else { SIDEBAR };
 
+        let playback_info = cx.global::<PlaybackInfo>();
+        let now_playing = playback_info.current_track.read(cx).is_some().then(|| {
+            let playing = *playback_info.playback_state.read(cx) == PlaybackState::Playing;
+            let metadata = cx.global::<Models>().metadata.read(cx);
+            let title: SharedString = metadata
+                .name
+                .clone()
+                .map(SharedString::from)
+                .unwrap_or_else(|| tr!("UNKNOWN_TRACK").into());
+
+            sidebar_item("now-playing")
+                .leading(now_playing_indicator(playing))
+                .when(!collapsed, |this| this.child(title.clone()))
+                .when(collapsed, |this| this.collapsed().collapsed_label(title))
+                .on_click(|_, _, cx| {
+                    let Some(path) = cx
+                        .global::<PlaybackInfo>()
+                        .current_track
+                        .read(cx)
+                        .as_ref()
+                        .map(|track| track.get_path().clone())
+                    else {
+                        return;
+                    };
+
+                    if let Some(track) = resolve_library_track_by_path(cx, &path) {
+                        navigate_to_track_album_and_reveal(cx, &track);
+                    }
+                })
+        });
+
         let search_and_toggle = div()
             .flex()
             .when(collapsed, |this| {
@@ -170,6 +216,23 @@ impl Render for Sidebar {
                         |this| this.active(),
                     ),
             )
+            .child(
+                sidebar_item("timeline")
+                    .icon(CALENDAR)
+                    .when(!collapsed, |this| this.child(tr!("TIMELINE")))
+                    .when(collapsed, |this| {
+                        this.collapsed().collapsed_label(tr!("TIMELINE"))
+                    })
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.nav_model.update(cx, |_, cx| {
+                            cx.emit(ViewSwitchMessage::Timeline);
+                        });
+                    }))
+                    .when(
+                        matches!(sidebar_view, ViewSwitchMessage::Timeline),
+                        |this| this.active(),
+                    ),
+            )
             .child(
                 sidebar_item("artists")
                     .icon(USERS)
@@ -206,8 +269,47 @@ impl Render for Sidebar {
                         this.active()
                     }),
             )
+            .child(
+                sidebar_item("history")
+                    .icon(HISTORY)
+                    .when(!collapsed, |this| {
+                        this.child(tr!("RECENTLY_PLAYED", "Recently Played"))
+                    })
+                    .when(collapsed, |this| {
+                        this.collapsed().collapsed_label(tr!("RECENTLY_PLAYED"))
+                    })
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.nav_model.update(cx, |_, cx| {
+                            cx.emit(ViewSwitchMessage::History);
+                        });
+                    }))
+                    .when(matches!(sidebar_view, ViewSwitchMessage::History), |this| {
+                        this.active()
+                    }),
+            )
+            .child(
+                sidebar_item("stats")
+                    .icon(CHART_BAR)
+                    .when(!collapsed, |this| {
+                        this.child(tr!("LISTENING_STATS", "Listening Stats"))
+                    })
+                    .when(collapsed, |this| {
+                        this.collapsed().collapsed_label(tr!("LISTENING_STATS"))
+                    })
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.nav_model.update(cx, |_, cx| {
+                            cx.emit(ViewSwitchMessage::Stats);
+                        });
+                    }))
+                    .when(matches!(sidebar_view, ViewSwitchMessage::Stats), |this| {
+                        this.active()
+                    }),
+            )
             .child(sidebar_separator())
             .child(self.playlists.clone())
+            .when_some(now_playing, |this, item| {
+                this.child(sidebar_separator()).child(item)
+            })
             .when(!collapsed, |this| {
                 this.child(
                     div()
@@ -247,8 +349,8 @@ impl Render for Sidebar {
                 sidebar_width.clone(),
                 ResizeEdge::Right,
             )
-            .min_size(px(175.0))
-            .max_size(px(350.0))
+            .min_size(MIN_SIDEBAR_WIDTH)
+            .max_size(MAX_SIDEBAR_WIDTH.min(window.viewport_size().width * MAX_PANEL_FRACTION))
             .default_size(DEFAULT_SIDEBAR_WIDTH)
             .h_full()
             .child(sidebar_content)
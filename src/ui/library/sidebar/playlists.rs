@@ -28,7 +28,8 @@ use crate::{
             textbox::Textbox,
         },
         library::{NavigationHistory, ViewSwitchMessage, playlist_view::find_playlist_tracks},
-        models::{Models, PlaybackInfo, PlaylistEvent},
+        models::{Models, PlaylistEvent},
+        queue_confirmation::{replace_queue, replace_queue_with_shuffle},
         theme::Theme,
     },
 };
@@ -260,8 +261,7 @@ impl Render for PlaylistList {
                                         tr!("PLAY"),
                                         move |_, _, cx| {
                                             let tracks = find_playlist_tracks(cx, pl_id);
-                                            let interface = cx.global::<PlaybackInterface>();
-                                            interface.replace_queue(tracks);
+                                            replace_queue(tracks, cx);
                                         },
                                     ))
                                     .item(menu_item(
@@ -282,11 +282,7 @@ impl Render for PlaylistList {
                                         tr!("SHUFFLE"),
                                         move |_, _, cx| {
                                             let tracks = find_playlist_tracks(cx, pl_id);
-                                            let interface = cx.global::<PlaybackInterface>();
-                                            if !(*cx.global::<PlaybackInfo>().shuffling.read(cx)) {
-                                                interface.toggle_shuffle();
-                                            }
-                                            interface.replace_queue(tracks);
+                                            replace_queue_with_shuffle(tracks, true, cx);
                                         },
                                     ))
                                     .item(menu_item(
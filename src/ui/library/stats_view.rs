@@ -0,0 +1,462 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, Utc};
+use cntp_i18n::{tr, trn};
+use gpui::*;
+use prelude::FluentBuilder;
+use tracing::error;
+
+use crate::{
+    library::{
+        db::LibraryAccess,
+        types::{ListeningStats, TopPlayed},
+    },
+    settings::SettingsGlobal,
+    ui::{
+        components::{
+            scrollbar::{RightPad, floating_scrollbar},
+            segmented_control::segmented_control,
+            table::table_data::TABLE_MAX_WIDTH,
+        },
+        format::{format_date, format_number},
+        library::{NavigationHistory, ViewSwitchMessage},
+        models::Models,
+        theme::Theme,
+    },
+};
+
+/// The number of entries shown in each of the top lists.
+const TOP_LIMIT: i64 = 10;
+
+/// The number of days shown in the activity chart.
+const ACTIVITY_DAYS: i64 = 30;
+
+const ACTIVITY_CHART_HEIGHT: f32 = 80.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StatsPeriod {
+    Week,
+    Month,
+    Year,
+    AllTime,
+}
+
+impl StatsPeriod {
+    fn since(self) -> Option<DateTime<Utc>> {
+        let days = match self {
+            StatsPeriod::Week => 7,
+            StatsPeriod::Month => 30,
+            StatsPeriod::Year => 365,
+            StatsPeriod::AllTime => return None,
+        };
+
+        Some(Utc::now() - Duration::days(days))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum TopKind {
+    Tracks,
+    Albums,
+    Artists,
+}
+
+pub struct StatsView {
+    period: StatsPeriod,
+    stats: Option<Arc<ListeningStats>>,
+    scroll_handle: ScrollHandle,
+}
+
+impl StatsView {
+    pub(super) fn new(cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let play_history = cx.global::<Models>().play_history.clone();
+            cx.observe(&play_history, |this: &mut Self, _, cx| this.load(cx))
+                .detach();
+
+            let settings = cx.global::<SettingsGlobal>().model.clone();
+            cx.observe(&settings, |_, _, cx| cx.notify()).detach();
+
+            let mut view = StatsView {
+                period: StatsPeriod::Month,
+                stats: None,
+                scroll_handle: ScrollHandle::new(),
+            };
+            view.load(cx);
+
+            view
+        })
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.stats = cx
+            .get_listening_stats(self.period.since(), activity_start(), TOP_LIMIT)
+            .inspect_err(|err| error!(?err, "Failed to load listening stats: {err}"))
+            .ok();
+
+        cx.notify();
+    }
+
+    fn set_period(&mut self, period: StatsPeriod, cx: &mut Context<Self>) {
+        if self.period != period {
+            self.period = period;
+            self.load(cx);
+        }
+    }
+}
+
+/// The first day shown in the activity chart, as local midnight.
+fn first_activity_day() -> NaiveDate {
+    Local::now().date_naive() - Duration::days(ACTIVITY_DAYS - 1)
+}
+
+fn activity_start() -> DateTime<Utc> {
+    first_activity_day()
+        .and_hms_opt(0, 0, 0)
+        .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+        .map(|midnight| midnight.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc::now() - Duration::days(ACTIVITY_DAYS))
+}
+
+fn format_played_length(seconds: i64) -> SharedString {
+    let minutes = seconds / 60;
+
+    if minutes < 60 {
+        trn!(
+            "STATS_PLAYED_LENGTH_MINUTES",
+            "{{count}} minute",
+            "{{count}} minutes",
+            count = minutes
+        )
+        .into()
+    } else {
+        tr!(
+            "STATS_PLAYED_LENGTH_HOURS",
+            "{{hours}} h {{minutes}} min",
+            hours = minutes / 60,
+            minutes = minutes % 60
+        )
+        .into()
+    }
+}
+
+fn summary_card(label: SharedString, value: SharedString, theme: &Theme) -> Div {
+    div()
+        .flex()
+        .flex_col()
+        .flex_1()
+        .gap(px(4.0))
+        .px(px(14.0))
+        .py(px(12.0))
+        .rounded(px(6.0))
+        .border_1()
+        .border_color(theme.border_color)
+        .bg(theme.background_secondary)
+        .child(
+            div()
+                .text_sm()
+                .text_color(theme.text_secondary)
+                .child(label),
+        )
+        .child(
+            div()
+                .text_size(px(22.0))
+                .font_weight(FontWeight::BOLD)
+                .child(value),
+        )
+}
+
+fn section_title(title: SharedString) -> Div {
+    div()
+        .pb(px(8.0))
+        .font_weight(FontWeight::BOLD)
+        .text_size(px(16.0))
+        .child(title)
+}
+
+fn format_day(day: NaiveDate) -> SharedString {
+    format_date(&day.and_time(NaiveTime::MIN).and_utc(), "YMD", "short")
+}
+
+fn activity_chart(stats: &ListeningStats, theme: &Theme) -> Div {
+    let first_day = first_activity_day();
+    let counts: Vec<i64> = (0..ACTIVITY_DAYS)
+        .map(|offset| {
+            let day = first_day + Duration::days(offset);
+            stats
+                .daily
+                .iter()
+                .find(|entry| entry.day == day)
+                .map_or(0, |entry| entry.play_count)
+        })
+        .collect();
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    let last_day = first_day + Duration::days(ACTIVITY_DAYS - 1);
+
+    div()
+        .flex()
+        .flex_col()
+        .gap(px(4.0))
+        .child(
+            div()
+                .h(px(ACTIVITY_CHART_HEIGHT))
+                .flex()
+                .items_end()
+                .gap(px(2.0))
+                .border_b_1()
+                .border_color(theme.border_color)
+                .children(counts.into_iter().map(|count| {
+                    let height = ACTIVITY_CHART_HEIGHT * count as f32 / max as f32;
+
+                    div()
+                        .flex_1()
+                        .h(px(height))
+                        .rounded_t(px(2.0))
+                        .bg(theme.button_primary)
+                })),
+        )
+        .child(
+            div()
+                .flex()
+                .justify_between()
+                .text_xs()
+                .text_color(theme.text_secondary)
+                .child(format_day(first_day))
+                .child(format_day(last_day)),
+        )
+}
+
+fn top_list(
+    kind: TopKind,
+    entries: &[TopPlayed],
+    theme: &Theme,
+    switcher_model: &Entity<NavigationHistory>,
+) -> Div {
+    let title = match kind {
+        TopKind::Tracks => tr!("STATS_TOP_TRACKS", "Top Tracks"),
+        TopKind::Albums => tr!("STATS_TOP_ALBUMS", "Top Albums"),
+        TopKind::Artists => tr!("STATS_TOP_ARTISTS", "Top Artists"),
+    };
+
+    div()
+        .flex()
+        .flex_col()
+        .flex_1()
+        .min_w(px(200.0))
+        .child(section_title(title.into()))
+        .when(entries.is_empty(), |this| {
+            this.child(
+                div()
+                    .text_sm()
+                    .text_color(theme.text_secondary)
+                    .child(tr!("STATS_NOTHING_PLAYED", "Nothing played yet.")),
+            )
+        })
+        .children(entries.iter().enumerate().map(|(i, entry)| {
+            let message = match kind {
+                TopKind::Tracks => None,
+                TopKind::Albums => Some(ViewSwitchMessage::Release(entry.id, None)),
+                TopKind::Artists => Some(ViewSwitchMessage::Artist(entry.id)),
+            };
+            let switcher_model = switcher_model.clone();
+            let element_id = match kind {
+                TopKind::Tracks => ("stats-top-track", entry.id as u64),
+                TopKind::Albums => ("stats-top-album", entry.id as u64),
+                TopKind::Artists => ("stats-top-artist", entry.id as u64),
+            };
+
+            div()
+                .id(element_id)
+                .flex()
+                .items_center()
+                .gap(px(10.0))
+                .py(px(5.0))
+                .px(px(6.0))
+                .rounded(px(4.0))
+                .child(
+                    div()
+                        .w(px(18.0))
+                        .flex_shrink_0()
+                        .text_sm()
+                        .text_color(theme.text_secondary)
+                        .child(format!("{}", i + 1)),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .flex_1()
+                        .min_w_0()
+                        .child(
+                            div()
+                                .text_sm()
+                                .font_weight(FontWeight::SEMIBOLD)
+                                .overflow_x_hidden()
+                                .text_ellipsis()
+                                .child(entry.name.clone()),
+                        )
+                        .when_some(entry.subtitle.clone(), |this, subtitle| {
+                            this.child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.text_secondary)
+                                    .overflow_x_hidden()
+                                    .text_ellipsis()
+                                    .child(subtitle),
+                            )
+                        }),
+                )
+                .child(
+                    div()
+                        .flex_shrink_0()
+                        .text_xs()
+                        .text_color(theme.text_secondary)
+                        .child(trn!(
+                            "STATS_PLAY_COUNT",
+                            "{{count}} play",
+                            "{{count}} plays",
+                            count = entry.play_count
+                        )),
+                )
+                .when_some(message, |this, message| {
+                    this.cursor_pointer()
+                        .hover(|this| this.bg(theme.nav_button_hover))
+                        .on_click(move |_, _, cx| {
+                            switcher_model.update(cx, |_, cx| cx.emit(message));
+                        })
+                })
+        }))
+}
+
+impl Render for StatsView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.global::<Theme>();
+        let full_width = cx
+            .global::<SettingsGlobal>()
+            .model
+            .read(cx)
+            .interface
+            .effective_full_width();
+        let switcher_model = cx.global::<Models>().switcher_model.clone();
+        let scroll_handle = self.scroll_handle.clone();
+        let view = cx.entity().downgrade();
+
+        let period_control = segmented_control("stats-period")
+            .fit_content()
+            .selected(self.period)
+            .option(StatsPeriod::Week, tr!("STATS_PERIOD_WEEK", "7 days"))
+            .option(StatsPeriod::Month, tr!("STATS_PERIOD_MONTH", "30 days"))
+            .option(StatsPeriod::Year, tr!("STATS_PERIOD_YEAR", "Year"))
+            .option(
+                StatsPeriod::AllTime,
+                tr!("STATS_PERIOD_ALL_TIME", "All time"),
+            )
+            .on_change(move |period, _, cx| {
+                view.update(cx, |this, cx| this.set_period(*period, cx))
+                    .ok();
+            });
+
+        div()
+            .flex()
+            .flex_col()
+            .w_full()
+            .h_full()
+            .when(!full_width, |this: Div| this.max_w(px(TABLE_MAX_WIDTH)))
+            .pt(px(10.0))
+            .child(
+                div()
+                    .w_full()
+                    .pb(px(10.0))
+                    .px(px(18.0))
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .line_height(px(26.0))
+                            .font_weight(FontWeight::BOLD)
+                            .text_size(px(26.0))
+                            .child(tr!("LISTENING_STATS", "Listening Stats")),
+                    )
+                    .child(period_control),
+            )
+            .child(
+                div()
+                    .relative()
+                    .w_full()
+                    .h_full()
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .id("stats-content")
+                            .w_full()
+                            .h_full()
+                            .flex()
+                            .flex_col()
+                            .gap(px(20.0))
+                            .px(px(18.0))
+                            .py(px(12.0))
+                            .overflow_y_scroll()
+                            .track_scroll(&scroll_handle)
+                            .border_t_1()
+                            .border_color(theme.border_color)
+                            .when_some(self.stats.clone(), |this, stats| {
+                                this.child(
+                                    div()
+                                        .flex()
+                                        .gap(px(12.0))
+                                        .child(summary_card(
+                                            tr!("STATS_PLAYED_LENGTH", "Length of tracks played")
+                                                .into(),
+                                            format_played_length(stats.totals.total_duration),
+                                            theme,
+                                        ))
+                                        .child(summary_card(
+                                            tr!("STATS_PLAYS", "Plays").into(),
+                                            format_number(stats.totals.play_count),
+                                            theme,
+                                        )),
+                                )
+                                .child(
+                                    div()
+                                        .flex()
+                                        .flex_col()
+                                        .child(section_title(
+                                            tr!("STATS_ACTIVITY", "Activity (last 30 days)").into(),
+                                        ))
+                                        .child(activity_chart(&stats, theme)),
+                                )
+                                .child(
+                                    div()
+                                        .flex()
+                                        .flex_wrap()
+                                        .gap(px(20.0))
+                                        .child(top_list(
+                                            TopKind::Tracks,
+                                            &stats.top_tracks,
+                                            theme,
+                                            &switcher_model,
+                                        ))
+                                        .child(top_list(
+                                            TopKind::Albums,
+                                            &stats.top_albums,
+                                            theme,
+                                            &switcher_model,
+                                        ))
+                                        .child(top_list(
+                                            TopKind::Artists,
+                                            &stats.top_artists,
+                                            theme,
+                                            &switcher_model,
+                                        )),
+                                )
+                            }),
+                    )
+                    .child(floating_scrollbar(
+                        "stats_scrollbar",
+                        scroll_handle,
+                        RightPad::Pad,
+                    )),
+            )
+    }
+}
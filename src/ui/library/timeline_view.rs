@@ -0,0 +1,393 @@
+use std::rc::Rc;
+
+use cntp_i18n::{tr, trn};
+use gpui::*;
+use prelude::FluentBuilder;
+use rustc_hash::FxHashMap;
+use tracing::error;
+
+use crate::{
+    library::{
+        db::LibraryAccess,
+        scan::ScanEvent,
+        types::{Album, table::AlbumColumn},
+    },
+    settings::{SettingsGlobal, interface::clamp_grid_min_item_width},
+    ui::{
+        caching::hummingbird_cache,
+        components::{
+            scrollbar::{RightPad, floating_scrollbar},
+            segmented_control::segmented_control,
+            table::{
+                grid_item::GridItem,
+                table_data::{GridContext, TABLE_MAX_WIDTH},
+            },
+            uniform_grid::uniform_grid,
+        },
+        library::{NavigationHistory, ViewSwitchMessage, context_menus::AlbumContextMenuContext},
+        models::Models,
+        theme::Theme,
+        util::{create_or_retrieve_view, prune_views},
+    },
+};
+
+type GridHandler = dyn Fn(&mut App, &(u32, String)) + 'static;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TimelineGrouping {
+    Decade,
+    Year,
+}
+
+impl TimelineGrouping {
+    /// Returns the first year of the period `year` falls in.
+    fn period_start(self, year: i32) -> i32 {
+        match self {
+            TimelineGrouping::Decade => year.div_euclid(10) * 10,
+            TimelineGrouping::Year => year,
+        }
+    }
+}
+
+/// Albums released in the same decade or year.
+#[derive(Debug, PartialEq)]
+struct TimelineSection {
+    /// The first year of the period, or `None` for albums without a release date.
+    period: Option<i32>,
+    /// The position of the section's first album in the whole timeline.
+    first: usize,
+    albums: Vec<(u32, String)>,
+}
+
+/// Splits albums in release order into sections. Albums without a release year have to come last.
+fn group_albums(
+    albums: &[(u32, String, Option<i32>)],
+    grouping: TimelineGrouping,
+) -> Vec<TimelineSection> {
+    let mut sections: Vec<TimelineSection> = Vec::new();
+
+    for (idx, (id, title, year)) in albums.iter().enumerate() {
+        let period = year.map(|year| grouping.period_start(year));
+
+        match sections.last_mut() {
+            Some(section) if section.period == period => {
+                section.albums.push((*id, title.clone()));
+            }
+            _ => sections.push(TimelineSection {
+                period,
+                first: idx,
+                albums: vec![(*id, title.clone())],
+            }),
+        }
+    }
+
+    sections
+}
+
+pub struct TimelineView {
+    grouping: TimelineGrouping,
+    albums: Vec<(u32, String, Option<i32>)>,
+    sections: Rc<Vec<TimelineSection>>,
+    scroll_handle: ScrollHandle,
+    grid_views: Entity<FxHashMap<usize, Entity<GridItem<Album, AlbumColumn>>>>,
+    grid_render_counter: Entity<usize>,
+    nav_model: Entity<NavigationHistory>,
+}
+
+impl TimelineView {
+    pub(super) fn new(
+        cx: &mut App,
+        nav_model: Entity<NavigationHistory>,
+        initial_scroll_offset: Option<f32>,
+    ) -> Entity<Self> {
+        cx.new(|cx| {
+            let state = cx.global::<Models>().scan_state.clone();
+            cx.observe(&state, |this: &mut Self, e, cx| {
+                if matches!(e.read(cx), ScanEvent::ScanCompleteIdle) {
+                    this.load(cx);
+                }
+            })
+            .detach();
+
+            // the grid is sized with the interface settings
+            let settings = cx.global::<SettingsGlobal>().model.clone();
+            cx.observe(&settings, |_, _, cx| cx.notify()).detach();
+
+            let scroll_handle = ScrollHandle::new();
+            if let Some(offset) = initial_scroll_offset {
+                scroll_handle.set_offset(point(px(0.0), px(-offset)));
+            }
+
+            let mut view = TimelineView {
+                grouping: TimelineGrouping::Decade,
+                albums: Vec::new(),
+                sections: Rc::new(Vec::new()),
+                scroll_handle,
+                grid_views: cx.new(|_| FxHashMap::default()),
+                grid_render_counter: cx.new(|_| 0),
+                nav_model,
+            };
+            view.load(cx);
+
+            view
+        })
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.albums = cx
+            .list_albums_by_year()
+            .inspect_err(|err| error!(?err, "Failed to load albums by year: {err}"))
+            .unwrap_or_default();
+        self.sections = Rc::new(group_albums(&self.albums, self.grouping));
+
+        // the albums may have moved, so the grid items can't be reused
+        self.grid_views = cx.new(|_| FxHashMap::default());
+        self.grid_render_counter = cx.new(|_| 0);
+
+        cx.notify();
+    }
+
+    pub fn get_scroll_offset(&self) -> f32 {
+        (-self.scroll_handle.offset().y).into()
+    }
+
+    fn set_grouping(&mut self, grouping: TimelineGrouping, cx: &mut Context<Self>) {
+        if self.grouping != grouping {
+            self.grouping = grouping;
+            self.sections = Rc::new(group_albums(&self.albums, grouping));
+            cx.notify();
+        }
+    }
+
+    fn section_title(&self, period: Option<i32>) -> SharedString {
+        match (period, self.grouping) {
+            (None, _) => tr!("TIMELINE_UNKNOWN", "Unknown").into(),
+            (Some(decade), TimelineGrouping::Decade) => {
+                tr!("FILTER_DECADE", decade = decade).into()
+            }
+            (Some(year), TimelineGrouping::Year) => year.to_string().into(),
+        }
+    }
+}
+
+impl Render for TimelineView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.global::<Theme>();
+        let settings = cx.global::<SettingsGlobal>().model.read(cx);
+        let full_width = settings.interface.effective_full_width();
+        let grid_min_item_width = clamp_grid_min_item_width(settings.interface.grid_min_item_width);
+        let scroll_handle = self.scroll_handle.clone();
+        let view = cx.entity().downgrade();
+
+        let grouping_control = segmented_control("timeline-grouping")
+            .fit_content()
+            .selected(self.grouping)
+            .option(
+                TimelineGrouping::Decade,
+                tr!("TIMELINE_BY_DECADE", "By decade"),
+            )
+            .option(TimelineGrouping::Year, tr!("TIMELINE_BY_YEAR", "By year"))
+            .on_change(move |grouping, _, cx| {
+                view.update(cx, |this, cx| this.set_grouping(*grouping, cx))
+                    .ok();
+            });
+
+        let nav_model = self.nav_model.clone();
+        let handler: Rc<GridHandler> = Rc::new(move |cx, id| {
+            nav_model.update(cx, |_, cx| {
+                cx.emit(ViewSwitchMessage::Release(id.0 as i64, None));
+            });
+        });
+
+        let sections = self
+            .sections
+            .iter()
+            .enumerate()
+            .map(|(section_idx, section)| {
+                let sections = self.sections.clone();
+                let grid_views_model = self.grid_views.clone();
+                let grid_render_counter = self.grid_render_counter.clone();
+                let handler = handler.clone();
+
+                div()
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .flex()
+                            .items_baseline()
+                            .gap(px(8.0))
+                            .px(px(18.0))
+                            .pt(px(10.0))
+                            .child(
+                                div()
+                                    .font_weight(FontWeight::BOLD)
+                                    .text_size(px(18.0))
+                                    .child(self.section_title(section.period)),
+                            )
+                            .child(div().text_sm().text_color(theme.text_secondary).child(trn!(
+                                "TIMELINE_ALBUM_COUNT",
+                                "{{count}} album",
+                                "{{count}} albums",
+                                count = section.albums.len()
+                            ))),
+                    )
+                    .child(
+                        div().px(px(10.0)).pt(px(2.0)).pb(px(10.0)).w_full().child(
+                            uniform_grid(
+                                ("timeline-grid", section_idx),
+                                section.albums.len(),
+                                None,
+                                move |idx, _, cx| {
+                                    let section = &sections[section_idx];
+                                    let item_idx = section.first + idx;
+
+                                    prune_views(
+                                        &grid_views_model,
+                                        &grid_render_counter,
+                                        item_idx,
+                                        cx,
+                                    );
+
+                                    let item_id = section.albums[idx].clone();
+                                    let handler = handler.clone();
+
+                                    let view = create_or_retrieve_view(
+                                        &grid_views_model,
+                                        item_idx,
+                                        |cx| {
+                                            GridItem::<Album, AlbumColumn>::new(
+                                                cx,
+                                                item_id,
+                                                Some(handler),
+                                                AlbumContextMenuContext::default(),
+                                                GridContext::Standalone,
+                                            )
+                                            .unwrap()
+                                        },
+                                        cx,
+                                    );
+
+                                    div()
+                                        .image_cache(hummingbird_cache(
+                                            ("timeline-grid", item_idx + 1),
+                                            1,
+                                        ))
+                                        .size_full()
+                                        .child(view)
+                                        .into_any_element()
+                                },
+                            )
+                            .min_item_width(px(grid_min_item_width))
+                            .gap(px(0.0))
+                            .auto_height(),
+                        ),
+                    )
+            });
+
+        div()
+            .flex()
+            .flex_col()
+            .w_full()
+            .h_full()
+            .when(!full_width, |this: Div| this.max_w(px(TABLE_MAX_WIDTH)))
+            .pt(px(10.0))
+            .child(
+                div()
+                    .w_full()
+                    .pb(px(10.0))
+                    .px(px(18.0))
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .line_height(px(26.0))
+                            .font_weight(FontWeight::BOLD)
+                            .text_size(px(26.0))
+                            .child(tr!("TIMELINE", "Timeline")),
+                    )
+                    .child(grouping_control),
+            )
+            .child(
+                div()
+                    .relative()
+                    .w_full()
+                    .h_full()
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .id("timeline-content")
+                            .w_full()
+                            .h_full()
+                            .flex()
+                            .flex_col()
+                            .overflow_y_scroll()
+                            .track_scroll(&scroll_handle)
+                            .border_t_1()
+                            .border_color(theme.border_color)
+                            .children(sections),
+                    )
+                    .child(floating_scrollbar(
+                        "timeline_scrollbar",
+                        scroll_handle,
+                        RightPad::Pad,
+                    )),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TimelineGrouping, TimelineSection, group_albums};
+
+    fn album(id: u32, year: Option<i32>) -> (u32, String, Option<i32>) {
+        (id, format!("Album {id}"), year)
+    }
+
+    #[test]
+    fn albums_are_grouped_by_decade() {
+        let albums = [
+            album(1, Some(1969)),
+            album(2, Some(1971)),
+            album(3, Some(1979)),
+            album(4, Some(1985)),
+        ];
+
+        let sections = group_albums(&albums, TimelineGrouping::Decade);
+        let periods: Vec<_> = sections.iter().map(|section| section.period).collect();
+        let firsts: Vec<_> = sections.iter().map(|section| section.first).collect();
+
+        assert_eq!(periods, vec![Some(1960), Some(1970), Some(1980)]);
+        assert_eq!(firsts, vec![0, 1, 3]);
+        assert_eq!(sections[1].albums.len(), 2);
+    }
+
+    #[test]
+    fn albums_can_be_grouped_by_year() {
+        let albums = [
+            album(1, Some(1971)),
+            album(2, Some(1971)),
+            album(3, Some(1972)),
+        ];
+
+        let sections = group_albums(&albums, TimelineGrouping::Year);
+        let periods: Vec<_> = sections.iter().map(|section| section.period).collect();
+
+        assert_eq!(periods, vec![Some(1971), Some(1972)]);
+    }
+
+    #[test]
+    fn albums_without_a_year_are_unknown() {
+        let albums = [album(1, Some(2001)), album(2, None)];
+
+        assert_eq!(
+            group_albums(&albums, TimelineGrouping::Decade).last(),
+            Some(&TimelineSection {
+                period: None,
+                first: 1,
+                albums: vec![(2, "Album 2".to_string())],
+            })
+        );
+    }
+}
@@ -8,6 +8,7 @@ use std::{rc::Rc, sync::Arc};
 
 use crate::ui::components::drag_drop::{DragPreview, TrackDragData};
 use crate::ui::components::icons::{STAR, STAR_FILLED, icon};
+use crate::ui::components::now_playing::{now_playing, now_playing_indicator};
 use crate::ui::library::context_menus::play_track_next;
 use crate::ui::library::context_menus::track::TrackContextMenu;
 use crate::ui::models::PlaylistEvent;
@@ -16,14 +17,16 @@ use crate::{
         db::{self, LibraryAccess},
         types::Track,
     },
+    settings::{SettingsGlobal, interface::ListDensity},
     ui::{
         app::Pool,
         availability::is_track_available,
         components::context::context,
+        format::format_badge,
         library::context_menus::{
-            PlaylistMenuInfo, TrackContextMenuContext, play_from_track_listing,
+            PlaylistMenuInfo, TrackContextMenuContext, play_clicked_track, play_from_track_listing,
         },
-        models::{Models, PlaybackInfo},
+        models::{Models, PlaybackInfo, TrackEvent},
         theme::Theme,
     },
 };
@@ -47,6 +50,8 @@ pub struct TrackItem {
     queue_context: Option<Arc<Vec<Track>>>,
     show_go_to_album: bool,
     show_go_to_artist: bool,
+    density: ListDensity,
+    show_format_badge: bool,
 }
 
 #[derive(Eq, PartialEq)]
@@ -95,15 +100,54 @@ impl TrackItem {
 
             let playlist_tracker = cx.global::<Models>().playlist_tracker.clone();
 
-            cx.subscribe(&playlist_tracker, move |this: &mut Self, _, ev, cx| {
-                if PlaylistEvent::PlaylistUpdated(1) == *ev {
-                    this.is_liked = cx.playlist_has_track(1, track_id).unwrap_or_default();
+            cx.subscribe(
+                &playlist_tracker,
+                move |this: &mut Self, _, ev: &PlaylistEvent, cx| {
+                    if PlaylistEvent::PlaylistUpdated(1) == *ev {
+                        this.is_liked = cx.playlist_has_track(1, track_id).unwrap_or_default();
+                        cx.notify();
+                    }
+                },
+            )
+            .detach();
+
+            // keep the context menu in sync with ratings and flags changed elsewhere
+            cx.subscribe(
+                &playlist_tracker,
+                move |this: &mut Self, _, ev: &TrackEvent, cx| {
+                    if ev.track_id() == track_id
+                        && let Ok(track) = cx.get_track_by_id(track_id)
+                    {
+                        this.track.rating = track.rating;
+                        this.track.skip_on_shuffle = track.skip_on_shuffle;
+                        cx.notify();
+                    }
+                },
+            )
+            .detach();
+
+            let playback_info = cx.global::<PlaybackInfo>().clone();
+            cx.observe(&playback_info.current_track, |_, _, cx| cx.notify())
+                .detach();
+            cx.observe(&playback_info.playback_state, |_, _, cx| cx.notify())
+                .detach();
+
+            let settings = cx.global::<SettingsGlobal>().model.clone();
+            cx.observe(&settings, |this: &mut Self, m, cx| {
+                let interface = &m.read(cx).interface;
+                let density = interface.list_density;
+                let show_format_badge = interface.show_format_badges;
+                if this.density != density || this.show_format_badge != show_format_badge {
+                    this.density = density;
+                    this.show_format_badge = show_format_badge;
                     cx.notify();
                 }
             })
             .detach();
 
             Self {
+                density: settings.read(cx).interface.list_density,
+                show_format_badge: settings.read(cx).interface.show_format_badges,
                 hover_group: format!("track-{}", track.id).into(),
                 is_liked: cx.playlist_has_track(1, track.id).unwrap_or_default(),
                 album_art: track
@@ -123,6 +167,12 @@ impl TrackItem {
             }
         })
     }
+
+    /// Replaces the tracks that are queued when this track is played. Takes effect the next time
+    /// the item is rendered.
+    pub fn set_queue_context(&mut self, queue_context: Option<Arc<Vec<Track>>>) {
+        self.queue_context = queue_context;
+    }
 }
 
 impl Render for TrackItem {
@@ -143,6 +193,7 @@ impl Render for TrackItem {
             .map(|max_num_str| measure_track_number_width(window, max_num_str))
             .unwrap_or(px(22.0));
         let current_track = cx.global::<PlaybackInfo>().current_track.read(cx).clone();
+        let playing = now_playing(cx, &self.track.location);
         let is_available = self.is_available;
 
         let track_location_for_drag = self.track.location.clone();
@@ -153,6 +204,11 @@ impl Render for TrackItem {
             && self.artist_name_visibility
                 != ArtistNameVisibility::OnlyIfDifferent(self.track.artist_names.clone());
 
+        let format_badge = self
+            .show_format_badge
+            .then(|| format_badge(&self.track))
+            .flatten();
+
         let track_menu_context = TrackContextMenuContext {
             show_go_to_album: self.show_go_to_album,
             show_go_to_artist: self.show_go_to_artist,
@@ -179,12 +235,14 @@ impl Render for TrackItem {
                                     let plid = self.pl_info.as_ref().map(|pl| pl.id);
                                     let queue_context = self.queue_context.clone();
                                     move |_, _, cx| {
-                                        play_from_track_listing(
-                                            cx,
-                                            &track,
-                                            plid,
-                                            queue_context.clone(),
-                                        )
+                                        play_clicked_track(cx, &track, |cx| {
+                                            play_from_track_listing(
+                                                cx,
+                                                &track,
+                                                plid,
+                                                queue_context.clone(),
+                                            )
+                                        })
                                     }
                                 })
                                 .on_aux_click({
@@ -245,14 +303,14 @@ impl Render for TrackItem {
                                     .flex()
                                     .flex_row()
                                     .border_b_1()
-                                    .h(px(39.0))
+                                    .h(px(self.density.track_row_height()))
                                     .id(("track", self.track.id as u64))
                                     .w_full()
                                     .border_color(theme.border_color)
                                     .when(is_available, |this| this.cursor_pointer())
                                     .when(!is_available, |this| this.cursor_default())
                                     .px(px(18.0))
-                                    .py(px(6.0))
+                                    .py(px(self.density.row_padding()))
                                     .group(self.hover_group.clone())
                                     .when(is_available, |this| {
                                         this.hover(|this| this.bg(theme.nav_button_hover))
@@ -275,7 +333,7 @@ impl Render for TrackItem {
                                     })
                                     .when_some(current_track, |this, track| {
                                         this.bg(if track == self.track.location {
-                                            theme.queue_item_current
+                                            theme.now_playing_highlight
                                         } else {
                                             theme.background_primary
                                         })
@@ -289,11 +347,19 @@ impl Render for TrackItem {
                                                 .text_align(TextAlign::Right)
                                                 .mr(px(13.0))
                                                 .text_color(theme.text_secondary)
+                                                .when_some(playing, |this, playing| {
+                                                    this.flex()
+                                                        .justify_end()
+                                                        .items_center()
+                                                        .child(now_playing_indicator(playing))
+                                                })
                                                 // TODO: handle these numerals better
-                                                .child(format!(
-                                                    "{}",
-                                                    self.track.track_number.unwrap_or_default()
-                                                )),
+                                                .when(playing.is_none(), |this| {
+                                                    this.child(format!(
+                                                        "{}",
+                                                        self.track.track_number.unwrap_or_default()
+                                                    ))
+                                                }),
                                         )
                                     })
                                     .when(self.left_field == TrackItemLeftField::Art, |this| {
@@ -312,6 +378,19 @@ impl Render for TrackItem {
                                                             .h(px(22.0))
                                                             .rounded(px(3.0)),
                                                     )
+                                                })
+                                                .when_some(playing, |this, playing| {
+                                                    this.relative().child(
+                                                        div()
+                                                            .absolute()
+                                                            .inset_0()
+                                                            .rounded(px(3.0))
+                                                            .bg(gpui::rgba(0x00000088))
+                                                            .flex()
+                                                            .items_center()
+                                                            .justify_center()
+                                                            .child(now_playing_indicator(playing)),
+                                                    )
                                                 }),
                                         )
                                     })
@@ -340,6 +419,22 @@ impl Render for TrackItem {
                                                 )
                                             }),
                                     )
+                                    .when_some(format_badge, |this, badge| {
+                                        this.child(
+                                            div()
+                                                .ml(px(10.0))
+                                                .my_auto()
+                                                .flex_shrink_0()
+                                                .px(px(4.0))
+                                                .rounded(px(3.0))
+                                                .border_1()
+                                                .border_color(theme.border_color)
+                                                .text_xs()
+                                                .font_weight(FontWeight::SEMIBOLD)
+                                                .text_color(theme.text_secondary)
+                                                .child(badge),
+                                        )
+                                    })
                                     .child(
                                         div()
                                             .id("like")
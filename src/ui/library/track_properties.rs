@@ -0,0 +1,646 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Context as _;
+use chrono::{TimeZone, Utc};
+use cntp_i18n::tr;
+use gpui::{
+    App, AppContext, ClipboardItem, Context, Entity, FocusHandle, FontWeight, InteractiveElement,
+    IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window,
+    div, prelude::FluentBuilder, px,
+};
+use tracing::warn;
+
+use crate::{
+    library::{
+        db::{AlbumMethod, LibraryAccess},
+        types::{Track, table::format_rating},
+    },
+    media::{
+        lookup_table::try_open_media,
+        metadata::{AudioProperties, Metadata},
+        traits::MediaProviderFeatures,
+    },
+    ui::{
+        components::{
+            button::{ButtonIntent, ButtonStyle, button},
+            modal::modal,
+        },
+        format::{format_bytes, format_date, format_date_time, format_duration},
+        library::ViewSwitchMessage,
+        models::Models,
+        theme::Theme,
+    },
+};
+
+/// Opens the properties dialog for a track.
+pub fn show_track_properties(cx: &mut App, track_id: i64) {
+    let track_properties = cx.global::<Models>().track_properties.clone();
+    track_properties.write(cx, Some(track_id));
+}
+
+fn close_track_properties(cx: &mut App) {
+    let track_properties = cx.global::<Models>().track_properties.clone();
+    track_properties.write(cx, None);
+}
+
+/// What the library knows about a track.
+struct TrackInfo {
+    track: Arc<Track>,
+    properties: AudioProperties,
+    album: Option<(i64, SharedString)>,
+    artist: Option<(i64, SharedString)>,
+}
+
+/// What is read from the track's file when the dialog is opened, rather than from the library.
+struct FileDetails {
+    size: Option<u64>,
+    /// The file's tags and whether it has embedded art, or `None` if they couldn't be read.
+    tags: Option<(Metadata, bool)>,
+}
+
+#[derive(Clone, Copy)]
+enum Link {
+    Album(i64),
+    Artist(i64),
+}
+
+struct Row {
+    label: SharedString,
+    value: SharedString,
+    link: Option<Link>,
+}
+
+impl Row {
+    fn new(label: impl Into<SharedString>, value: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            value: value.into(),
+            link: None,
+        }
+    }
+}
+
+struct Section {
+    title: SharedString,
+    rows: Vec<Row>,
+}
+
+/// A dialog showing everything known about the track in [`Models::track_properties`].
+pub struct TrackPropertiesDialog {
+    info: Option<TrackInfo>,
+    /// `None` while the file is being read.
+    file: Option<FileDetails>,
+    focus_handle: FocusHandle,
+}
+
+impl TrackPropertiesDialog {
+    pub fn new(cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let track_properties = cx.global::<Models>().track_properties.clone();
+
+            cx.observe(&track_properties, |this: &mut Self, model, cx| {
+                let track_id = *model.read(cx);
+                this.load(track_id, cx);
+            })
+            .detach();
+
+            Self {
+                info: None,
+                file: None,
+                focus_handle: cx.focus_handle(),
+            }
+        })
+    }
+
+    fn load(&mut self, track_id: Option<i64>, cx: &mut Context<Self>) {
+        self.info = track_id.and_then(|track_id| load_info(cx, track_id));
+        self.file = None;
+        cx.notify();
+
+        let Some(info) = &self.info else {
+            return;
+        };
+
+        let track_id = info.track.id;
+        let path = info.track.location.clone();
+        let task = cx
+            .background_executor()
+            .spawn(async move { read_file_details(&path) });
+
+        cx.spawn(async move |this, cx| {
+            let details = task.await;
+
+            this.update(cx, |this: &mut Self, cx| {
+                // the dialog may have been closed or opened for another track in the meantime
+                if this
+                    .info
+                    .as_ref()
+                    .is_some_and(|info| info.track.id == track_id)
+                {
+                    this.file = Some(details);
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn sections(&self) -> Vec<Section> {
+        let Some(info) = &self.info else {
+            return Vec::new();
+        };
+
+        let mut sections = vec![
+            file_section(info, self.file.as_ref()),
+            library_section(info),
+        ];
+
+        if let Some(section) = replaygain_section(&info.track) {
+            sections.push(section);
+        }
+
+        sections.push(tags_section(self.file.as_ref()));
+        sections
+    }
+}
+
+fn load_info(cx: &App, track_id: i64) -> Option<TrackInfo> {
+    let track = cx
+        .get_track_by_id(track_id)
+        .inspect_err(|err| warn!(?err, "Failed to load track {track_id}: {err}"))
+        .ok()?;
+    let properties = cx.get_track_audio_properties(track_id).unwrap_or_default();
+
+    let album = track
+        .album_id
+        .and_then(|album_id| cx.get_album_by_id(album_id, AlbumMethod::Metadata).ok())
+        .map(|album| (album.id, album.title.0.clone()));
+    let artist = track
+        .album_id
+        .and_then(|album_id| cx.artist_id_for_album(album_id).ok())
+        .and_then(|artist_id| {
+            let name = cx.get_artist_name_by_id(artist_id).ok()?;
+            Some((artist_id, SharedString::from((*name).clone())))
+        });
+
+    Some(TrackInfo {
+        track,
+        properties,
+        album,
+        artist,
+    })
+}
+
+fn read_file_details(path: &Path) -> FileDetails {
+    let size = std::fs::metadata(path).ok().map(|metadata| metadata.len());
+    let tags = read_tags(path)
+        .inspect_err(|err| warn!(?err, "Failed to read tags from {path:?}: {err}"))
+        .ok();
+
+    FileDetails { size, tags }
+}
+
+fn read_tags(path: &Path) -> anyhow::Result<(Metadata, bool)> {
+    let mut stream = try_open_media(path, MediaProviderFeatures::PROVIDES_METADATA)?
+        .context("no media provider found")?;
+
+    stream.start_playback()?;
+    let metadata = stream.read_metadata()?.clone();
+    let has_art = stream.read_image()?.is_some();
+    stream.close().ok();
+
+    Ok((metadata, has_art))
+}
+
+fn yes_no(value: bool) -> SharedString {
+    if value {
+        tr!("PROPERTIES_YES", "Yes").into()
+    } else {
+        tr!("PROPERTIES_NO", "No").into()
+    }
+}
+
+fn format_sample_rate(hz: u32) -> String {
+    // 44100 becomes 44.1, 48000 becomes 48
+    let khz = (f64::from(hz) / 1000.0).to_string();
+    tr!(
+        "PROPERTIES_SAMPLE_RATE_VALUE",
+        "{{rate}} kHz",
+        rate = khz.as_str()
+    )
+    .to_string()
+}
+
+fn format_channels(channels: u32) -> String {
+    match channels {
+        1 => tr!("PROPERTIES_CHANNELS_MONO", "Mono").to_string(),
+        2 => tr!("PROPERTIES_CHANNELS_STEREO", "Stereo").to_string(),
+        count => tr!(
+            "PROPERTIES_CHANNELS_COUNT",
+            "{{count}} channels",
+            count = count
+        )
+        .to_string(),
+    }
+}
+
+fn format_position(current: Option<u64>, max: Option<u64>) -> Option<String> {
+    match (current, max) {
+        (Some(current), Some(max)) => Some(format!("{current}/{max}")),
+        (Some(current), None) => Some(current.to_string()),
+        _ => None,
+    }
+}
+
+fn file_section(info: &TrackInfo, file: Option<&FileDetails>) -> Section {
+    let track = &info.track;
+    let properties = &info.properties;
+
+    let mut rows = vec![Row::new(
+        tr!("PROPERTIES_LOCATION", "Location"),
+        track.location.to_string_lossy().to_string(),
+    )];
+
+    if let Some(size) = file.and_then(|file| file.size) {
+        rows.push(Row::new(
+            tr!("PROPERTIES_SIZE", "Size"),
+            format_bytes(size as f64),
+        ));
+    }
+    rows.push(Row::new(
+        tr!("PROPERTIES_DURATION", "Duration"),
+        format_duration(track.duration.max(0) as u64),
+    ));
+    if let Some(codec) = &properties.codec {
+        rows.push(Row::new(
+            tr!("PROPERTIES_CODEC", "Codec"),
+            codec.to_uppercase(),
+        ));
+    }
+    if let Some(bitrate) = properties.bitrate {
+        rows.push(Row::new(
+            tr!("PROPERTIES_BITRATE", "Bitrate"),
+            tr!(
+                "PROPERTIES_BITRATE_VALUE",
+                "{{bitrate}} kbps",
+                bitrate = bitrate
+            ),
+        ));
+    }
+    if let Some(sample_rate) = properties.sample_rate {
+        rows.push(Row::new(
+            tr!("PROPERTIES_SAMPLE_RATE", "Sample rate"),
+            format_sample_rate(sample_rate),
+        ));
+    }
+    if let Some(bit_depth) = properties.bit_depth {
+        rows.push(Row::new(
+            tr!("PROPERTIES_BIT_DEPTH", "Bit depth"),
+            tr!(
+                "PROPERTIES_BIT_DEPTH_VALUE",
+                "{{bits}}-bit",
+                bits = bit_depth
+            ),
+        ));
+    }
+    if let Some(channels) = properties.channels {
+        rows.push(Row::new(
+            tr!("PROPERTIES_CHANNELS", "Channels"),
+            format_channels(channels),
+        ));
+    }
+
+    Section {
+        title: tr!("PROPERTIES_FILE", "File").into(),
+        rows,
+    }
+}
+
+fn library_section(info: &TrackInfo) -> Section {
+    let track = &info.track;
+    let mut rows = vec![Row::new(
+        tr!("PROPERTIES_TITLE", "Title"),
+        track.title.0.clone(),
+    )];
+
+    if let Some((album_id, title)) = &info.album {
+        rows.push(Row {
+            link: Some(Link::Album(*album_id)),
+            ..Row::new(tr!("COLUMN_ALBUM"), title.clone())
+        });
+    }
+    if let Some((artist_id, name)) = &info.artist {
+        rows.push(Row {
+            link: Some(Link::Artist(*artist_id)),
+            ..Row::new(tr!("COLUMN_ARTIST"), name.clone())
+        });
+    }
+    if let Some(rating) = format_rating(track.rating) {
+        rows.push(Row::new(tr!("COLUMN_RATING"), rating));
+    }
+    rows.push(Row::new(
+        tr!("PROPERTIES_ADDED", "Added"),
+        format_date_time(&track.created_at),
+    ));
+
+    Section {
+        title: tr!("PROPERTIES_LIBRARY", "Library").into(),
+        rows,
+    }
+}
+
+fn replaygain_section(track: &Track) -> Option<Section> {
+    let gains = [
+        (
+            tr!("PROPERTIES_RG_TRACK_GAIN", "Track gain"),
+            track.rg_track_gain.map(|gain| format!("{gain:+.2} dB")),
+        ),
+        (
+            tr!("PROPERTIES_RG_TRACK_PEAK", "Track peak"),
+            track.rg_track_peak.map(|peak| format!("{peak:.6}")),
+        ),
+        (
+            tr!("PROPERTIES_RG_ALBUM_GAIN", "Album gain"),
+            track.rg_album_gain.map(|gain| format!("{gain:+.2} dB")),
+        ),
+        (
+            tr!("PROPERTIES_RG_ALBUM_PEAK", "Album peak"),
+            track.rg_album_peak.map(|peak| format!("{peak:.6}")),
+        ),
+    ];
+
+    let rows: Vec<Row> = gains
+        .into_iter()
+        .filter_map(|(label, value)| Some(Row::new(label, value?)))
+        .collect();
+
+    (!rows.is_empty()).then(|| Section {
+        title: tr!("PROPERTIES_REPLAYGAIN", "ReplayGain").into(),
+        rows,
+    })
+}
+
+fn tags_section(file: Option<&FileDetails>) -> Section {
+    let title = tr!("PROPERTIES_TAGS", "Tags").into();
+
+    let Some(file) = file else {
+        return Section {
+            title,
+            rows: vec![Row::new(
+                tr!("PROPERTIES_STATUS", "Status"),
+                tr!("PROPERTIES_READING_TAGS", "Reading the file…"),
+            )],
+        };
+    };
+
+    let Some((metadata, has_art)) = &file.tags else {
+        return Section {
+            title,
+            rows: vec![Row::new(
+                tr!("PROPERTIES_STATUS"),
+                tr!(
+                    "PROPERTIES_TAGS_UNREADABLE",
+                    "The file's tags couldn't be read."
+                ),
+            )],
+        };
+    };
+
+    let mut rows = tag_rows(metadata);
+    rows.push(Row::new(
+        tr!("PROPERTIES_EMBEDDED_ART", "Embedded art"),
+        yes_no(*has_art),
+    ));
+
+    Section { title, rows }
+}
+
+/// Lists every tag that is set in the file, in roughly the order tag editors show them.
+fn tag_rows(metadata: &Metadata) -> Vec<Row> {
+    let date = if let Some(date) = &metadata.date {
+        Some(format_date(date, "YMD", "medium").to_string())
+    } else if let Some((year, month)) = metadata.year_month {
+        Utc.with_ymd_and_hms(i32::from(year), u32::from(month), 1, 0, 0, 0)
+            .single()
+            .map(|date| format_date(&date, "YM", "medium").to_string())
+    } else {
+        metadata.year.map(|year| year.to_string())
+    };
+
+    let tags: [(SharedString, Option<String>); 23] = [
+        (
+            tr!("PROPERTIES_TAG_TITLE", "Title").into(),
+            metadata.name.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_ARTIST", "Artist").into(),
+            metadata.artist.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_ALBUM_ARTIST", "Album artist").into(),
+            metadata.album_artist.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_ARTIST_SORT", "Artist sort order").into(),
+            metadata.artist_sort.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_ORIGINAL_ARTIST", "Original artist").into(),
+            metadata.original_artist.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_COMPOSER", "Composer").into(),
+            metadata.composer.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_ALBUM", "Album").into(),
+            metadata.album.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_ALBUM_SORT", "Album sort order").into(),
+            metadata.sort_album.clone(),
+        ),
+        (tr!("PROPERTIES_TAG_DATE", "Date").into(), date),
+        (
+            tr!("PROPERTIES_TAG_TRACK", "Track").into(),
+            format_position(metadata.track_current, metadata.track_max),
+        ),
+        (
+            tr!("PROPERTIES_TAG_DISC", "Disc").into(),
+            format_position(metadata.disc_current, metadata.disc_max),
+        ),
+        (
+            tr!("PROPERTIES_TAG_DISC_SUBTITLE", "Disc subtitle").into(),
+            metadata.disc_subtitle.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_GENRE", "Genre").into(),
+            metadata.genre.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_GROUPING", "Grouping").into(),
+            metadata.grouping.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_BPM", "BPM").into(),
+            metadata.bpm.map(|bpm| bpm.to_string()),
+        ),
+        (
+            tr!("PROPERTIES_TAG_COMPILATION", "Compilation").into(),
+            metadata.compilation.then(|| yes_no(true).to_string()),
+        ),
+        (
+            tr!("PROPERTIES_TAG_VINYL_NUMBERING", "Vinyl numbering").into(),
+            metadata.vinyl_numbering.then(|| yes_no(true).to_string()),
+        ),
+        (
+            tr!("PROPERTIES_TAG_LABEL", "Label").into(),
+            metadata.label.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_CATALOG", "Catalog number").into(),
+            metadata.catalog.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_ISRC", "ISRC").into(),
+            metadata.isrc.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_MBID_ALBUM", "MusicBrainz release ID").into(),
+            metadata.mbid_album.clone(),
+        ),
+        (
+            tr!("PROPERTIES_TAG_RATING", "Rating").into(),
+            metadata
+                .rating
+                .and_then(|rating| format_rating(i32::from(rating)))
+                .map(|rating| rating.to_string()),
+        ),
+        (
+            tr!("PROPERTIES_TAG_LYRICS", "Lyrics").into(),
+            metadata.lyrics.as_ref().map(|_| yes_no(true).to_string()),
+        ),
+    ];
+
+    tags.into_iter()
+        .filter_map(|(label, value)| Some(Row::new(label, value?)))
+        .collect()
+}
+
+/// Formats every section as plain text, for pasting into bug reports.
+fn sections_to_text(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|section| {
+            let rows = section
+                .rows
+                .iter()
+                .map(|row| format!("{}: {}", row.label, row.value))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            format!("{}\n{rows}", section.title)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn follow_link(link: Link, cx: &mut App) {
+    let switcher = cx.global::<Models>().switcher_model.clone();
+    switcher.update(cx, |_, cx| {
+        cx.emit(match link {
+            Link::Album(album_id) => ViewSwitchMessage::Release(album_id, None),
+            Link::Artist(artist_id) => ViewSwitchMessage::Artist(artist_id),
+        });
+    });
+
+    close_track_properties(cx);
+}
+
+impl Render for TrackPropertiesDialog {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.focus_handle.focus(window, cx);
+        let theme = cx.global::<Theme>();
+        let sections = self.sections();
+        let text = sections_to_text(&sections);
+
+        modal().on_exit(|_, cx| close_track_properties(cx)).child(
+            div()
+                .track_focus(&self.focus_handle)
+                .w(px(600.0))
+                .max_w_full()
+                .p(px(24.0))
+                .flex()
+                .flex_col()
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .mb(px(8.0))
+                        .child(
+                            div()
+                                .font_weight(FontWeight::BOLD)
+                                .text_size(px(22.0))
+                                .mr_auto()
+                                .child(tr!("PROPERTIES", "Properties")),
+                        )
+                        .child(
+                            button()
+                                .style(ButtonStyle::Regular)
+                                .intent(ButtonIntent::Secondary)
+                                .child(tr!("PROPERTIES_COPY_ALL", "Copy all"))
+                                .id("track-properties-copy")
+                                .on_click(move |_, _, cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
+                                }),
+                        ),
+                )
+                .child(
+                    div()
+                        .id("track-properties-sections")
+                        .max_h(px(480.0))
+                        .overflow_y_scroll()
+                        .flex()
+                        .flex_col()
+                        .children(sections.into_iter().enumerate().map(|(i, section)| {
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap(px(6.0))
+                                .mt(px(12.0))
+                                .child(div().font_weight(FontWeight::SEMIBOLD).child(section.title))
+                                .children(section.rows.into_iter().enumerate().map(
+                                    move |(j, row)| {
+                                        div()
+                                            .flex()
+                                            .gap(px(16.0))
+                                            .text_sm()
+                                            .child(
+                                                div()
+                                                    .w(px(150.0))
+                                                    .flex_shrink_0()
+                                                    .text_color(theme.text_secondary)
+                                                    .child(row.label),
+                                            )
+                                            .child(
+                                                div()
+                                                    .id(("track-properties-value", i * 100 + j))
+                                                    .min_w(px(0.0))
+                                                    .flex_1()
+                                                    .child(row.value)
+                                                    .when_some(row.link, |this, link| {
+                                                        this.cursor_pointer()
+                                                            .text_color(theme.text_link)
+                                                            .on_click(move |_, _, cx| {
+                                                                follow_link(link, cx);
+                                                            })
+                                                    }),
+                                            )
+                                    },
+                                ))
+                        })),
+                ),
+        )
+    }
+}
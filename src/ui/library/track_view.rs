@@ -8,15 +8,18 @@ use gpui::{prelude::FluentBuilder, *};
 
 use crate::{
     library::{
+        db::LibraryAccess,
         scan::ScanEvent,
         types::{Track, table::TrackColumn},
     },
-    playback::{interface::PlaybackInterface, queue::QueueItemData},
+    playback::queue::QueueItemData,
+    settings::{SettingsGlobal, interface::SingleTrackContext},
     ui::{
-        availability::is_track_path_available,
+        availability::{is_track_available, is_track_path_available},
         components::table::{Table, TableEvent, table_data::TABLE_MAX_WIDTH},
-        library::context_menus::{TrackContextMenuContext, play_from_track},
+        library::context_menus::{TrackContextMenuContext, play_from_track, play_item_now},
         models::Models,
+        queue_confirmation::replace_queue_with_index,
     },
 };
 
@@ -47,10 +50,24 @@ impl TrackView {
 
             let handler = Rc::new(
                 move |cx: &mut App, id: &(i64, String, Option<i64>, String)| {
-                    if let Some(table) = table_ref_clone.borrow().as_ref() {
-                        let items = table.read(cx).get_items();
-                        if let Some(items) = items {
-                            let queue_items: Vec<QueueItemData> = items
+                    let single_track_context = cx
+                        .global::<SettingsGlobal>()
+                        .model
+                        .read(cx)
+                        .interface
+                        .single_track_context;
+
+                    let queue_items: Vec<QueueItemData> = match (single_track_context, id.2) {
+                        (SingleTrackContext::AllVisible, _) => {
+                            let Some(items) = table_ref_clone
+                                .borrow()
+                                .as_ref()
+                                .and_then(|table| table.read(cx).get_items())
+                            else {
+                                return;
+                            };
+
+                            items
                                 .iter()
                                 .filter(|(_, _, _, path)| is_track_path_available(Path::new(path)))
                                 .map(|(id, _, album_id, path)| {
@@ -61,22 +78,40 @@ impl TrackView {
                                         *album_id,
                                     )
                                 })
-                                .collect();
+                                .collect()
+                        }
+                        (SingleTrackContext::Album, Some(album_id)) => cx
+                            .list_tracks_in_album(album_id)
+                            .unwrap_or_default()
+                            .iter()
+                            .filter(|track| is_track_available(track))
+                            .map(|track| {
+                                QueueItemData::new(
+                                    cx,
+                                    track.location.clone(),
+                                    Some(track.id),
+                                    track.album_id,
+                                )
+                            })
+                            .collect(),
+                        // tracks without an album are played on their own
+                        (SingleTrackContext::JustThis, _) | (SingleTrackContext::Album, None) => {
+                            let data =
+                                QueueItemData::new(cx, PathBuf::from(&id.3), Some(id.0), id.2);
+                            return play_item_now(cx, data);
+                        }
+                    };
 
-                            if queue_items.is_empty() {
-                                return;
-                            }
+                    if queue_items.is_empty() {
+                        return;
+                    }
 
-                            let index = queue_items
-                                .iter()
-                                .position(|item| item.get_db_id() == Some(id.0))
-                                .unwrap_or(0);
+                    let index = queue_items
+                        .iter()
+                        .position(|item| item.get_db_id() == Some(id.0))
+                        .unwrap_or(0);
 
-                            let playback = cx.global::<PlaybackInterface>();
-                            playback.replace_queue_with_index(queue_items, index);
-                            playback.play();
-                        }
-                    }
+                    replace_queue_with_index(queue_items, index, cx);
                 },
             );
 
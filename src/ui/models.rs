@@ -5,7 +5,9 @@ use std::{
 };
 
 use crate::{paths, services::mmb::discord::Discord, ui::library::NavigationHistory};
-use gpui::{App, AppContext, Entity, EventEmitter, Global, Pixels, RenderImage, Size};
+use gpui::{
+    App, AppContext, Bounds, Entity, EventEmitter, Global, Pixels, Point, RenderImage, Size, px,
+};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
@@ -13,7 +15,7 @@ use tracing::{debug, error, warn};
 
 use crate::{
     library::{
-        db::{LibraryAccess, LikedTrackSortMethod, PlaylistTrackSortMethod},
+        db::{self, LibraryAccess, LikedTrackSortMethod, PlaylistTrackSortMethod},
         scan::ScanEvent,
     },
     media::metadata::Metadata,
@@ -29,12 +31,9 @@ use crate::{
     settings::{
         SettingsGlobal,
         interface::StartupLibraryView,
-        storage::{
-            DEFAULT_LYRICS_FRACTION, DEFAULT_QUEUE_WIDTH, DEFAULT_SIDEBAR_WIDTH, StorageData,
-            TableSettings,
-        },
+        storage::{DEFAULT_LYRICS_FRACTION, StorageData, TableSettings},
     },
-    ui::library::ViewSwitchMessage,
+    ui::{app::Pool, library::ViewSwitchMessage, queue_confirmation::QueueChange},
 };
 
 // yes this looks a little silly
@@ -57,7 +56,40 @@ impl EventEmitter<Session> for LastFMState {}
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct WindowInformation {
     pub maximized: bool,
+    /// The size of the window when it isn't maximized.
     pub size: Size<Pixels>,
+    /// The position of the window when it isn't maximized. Older files don't store it.
+    #[serde(default)]
+    pub origin: Option<Point<Pixels>>,
+}
+
+impl WindowInformation {
+    /// Returns where the window should be opened, or `None` if it should be centered instead.
+    /// Windows that would open off-screen, for example because the display they were on has
+    /// been disconnected, are centered as well.
+    pub fn restored_bounds(
+        &self,
+        displays: &[Bounds<Pixels>],
+        min_size: Size<Pixels>,
+    ) -> Option<Bounds<Pixels>> {
+        let origin = self.origin?;
+        let size = self.restored_size(min_size);
+
+        // at least the titlebar has to be visible so the window can be moved
+        let titlebar = Bounds::new(origin, Size::new(size.width, px(37.0)));
+        displays
+            .iter()
+            .any(|display| display.intersects(&titlebar))
+            .then(|| Bounds::new(origin, size))
+    }
+
+    /// Returns the size the window should be opened at, no smaller than `min_size`.
+    pub fn restored_size(&self, min_size: Size<Pixels>) -> Size<Pixels> {
+        Size::new(
+            self.size.width.max(min_size.width),
+            self.size.height.max(min_size.height),
+        )
+    }
 }
 
 pub struct Models {
@@ -70,7 +102,15 @@ pub struct Models {
     pub lastfm: Entity<LastFMState>,
     pub switcher_model: Entity<NavigationHistory>,
     pub show_about: Entity<bool>,
+    /// The track whose properties dialog is open, if any.
+    pub track_properties: Entity<Option<i64>>,
+    /// A change to the queue that's waiting for the user to confirm it, if any.
+    pub pending_queue_change: Entity<Option<QueueChange>>,
+    /// How many files from the last drop were skipped because they can't be played, while the
+    /// notice about them is shown.
+    pub skipped_dropped_files: Entity<Option<usize>>,
     pub playlist_tracker: Entity<PlaylistInfoTransfer>,
+    pub play_history: Entity<PlayHistoryTracker>,
     pub sidebar_width: Entity<Pixels>,
     pub queue_width: Entity<Pixels>,
     pub split_widths: std::collections::HashMap<String, Entity<Pixels>>,
@@ -78,6 +118,7 @@ pub struct Models {
     pub liked_tracks_sort_method: Entity<LikedTrackSortMethod>,
     pub playlist_sort_methods: Entity<std::collections::HashMap<i64, PlaylistTrackSortMethod>>,
     pub sidebar_collapsed: Entity<bool>,
+    pub show_queue: Entity<bool>,
     pub lyrics_height: Entity<Pixels>,
     pub controls_left_width: Entity<Pixels>,
     pub controls_right_width: Entity<Pixels>,
@@ -117,6 +158,7 @@ pub struct PlaybackInfo {
     pub repeating: Entity<RepeatState>,
     pub volume: Entity<f64>,
     pub prev_volume: Entity<f64>,
+    pub muted: Entity<bool>,
 }
 
 impl Global for PlaybackInfo {}
@@ -158,6 +200,102 @@ pub enum PlaylistEvent {
 
 impl EventEmitter<PlaylistEvent> for PlaylistInfoTransfer {}
 
+/// Changes to a track that are made outside of a scan, so views showing it can refresh.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TrackEvent {
+    RatingChanged(i64),
+    SkipOnShuffleChanged(i64),
+}
+
+impl TrackEvent {
+    /// The id of the track that changed.
+    pub fn track_id(&self) -> i64 {
+        match self {
+            TrackEvent::RatingChanged(id) | TrackEvent::SkipOnShuffleChanged(id) => *id,
+        }
+    }
+}
+
+impl EventEmitter<TrackEvent> for PlaylistInfoTransfer {}
+
+/// Changes to an album that are made outside of a scan, so views showing it can refresh.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AlbumEvent {
+    RatingChanged(i64),
+}
+
+impl AlbumEvent {
+    /// The id of the album that changed.
+    pub fn album_id(&self) -> i64 {
+        match self {
+            AlbumEvent::RatingChanged(id) => *id,
+        }
+    }
+}
+
+impl EventEmitter<AlbumEvent> for PlaylistInfoTransfer {}
+
+/// Notified whenever a play is added to the play history.
+pub struct PlayHistoryTracker;
+
+/// How long a track has to be listened to before it counts as a play. Tracks shorter than twice
+/// this only have to be listened to for half of their length.
+const MIN_LISTEN_MS: u64 = 30_000;
+
+/// Position changes larger than this are seeks, and don't count as listening.
+const MAX_LISTEN_STEP_MS: u64 = 2_000;
+
+/// A track that has started playing, but hasn't been listened to for long enough to count as a
+/// play yet.
+struct PendingPlay {
+    path: PathBuf,
+    listened_ms: u64,
+    last_position: u64,
+}
+
+impl PendingPlay {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            listened_ms: 0,
+            last_position: 0,
+        }
+    }
+
+    /// Moves the play to `position`, counting the time in between as listened if `playing`.
+    /// Returns whether the track has now been listened to for long enough.
+    fn advance(&mut self, position: u64, playing: bool, duration_ms: u64) -> bool {
+        let step = position.saturating_sub(self.last_position);
+        if playing && position > self.last_position && step <= MAX_LISTEN_STEP_MS {
+            self.listened_ms += step;
+        }
+        self.last_position = position;
+
+        let required = if duration_ms > 0 {
+            MIN_LISTEN_MS.min(duration_ms / 2)
+        } else {
+            MIN_LISTEN_MS
+        };
+        self.listened_ms >= required
+    }
+}
+
+/// Adds a play of the track at `path` to the play history. The write happens on the database
+/// runtime, and `tracker` is notified once it has finished.
+fn record_play(cx: &mut App, path: PathBuf, tracker: Entity<PlayHistoryTracker>) {
+    let pool = cx.global::<Pool>().0.clone();
+    let task = crate::RUNTIME.spawn(async move { db::record_play(&pool, &path).await });
+
+    cx.spawn(async move |cx| match task.await {
+        Ok(Ok(true)) => tracker.update(cx, |_, cx| cx.notify()),
+        // the track isn't in the library, so there's nothing to record
+        Ok(Ok(false)) => {}
+        Ok(Err(err)) => error!("could not record play: {err:?}"),
+        Err(err) => error!("play history task panicked: {err:?}"),
+    })
+    .detach();
+}
+
 fn discord_rpc_enabled(cx: &App) -> bool {
     cx.global::<SettingsGlobal>()
         .model
@@ -223,6 +361,9 @@ pub fn build_models(
     let scan_state: Entity<ScanEvent> = cx.new(|_| ScanEvent::ScanCompleteIdle);
     let mmbs: Entity<MMBSList> = cx.new(|_| MMBSList(FxHashMap::default()));
     let show_about: Entity<bool> = cx.new(|_| false);
+    let track_properties: Entity<Option<i64>> = cx.new(|_| None);
+    let pending_queue_change: Entity<Option<QueueChange>> = cx.new(|_| None);
+    let skipped_dropped_files: Entity<Option<usize>> = cx.new(|_| None);
     let lastfm: Entity<LastFMState> = cx.new(|cx| {
         let directory = paths::data_dir();
         let path = directory.join("lastfm.json");
@@ -244,6 +385,7 @@ pub fn build_models(
     });
 
     let playlist_tracker: Entity<PlaylistInfoTransfer> = cx.new(|_| PlaylistInfoTransfer);
+    let play_history: Entity<PlayHistoryTracker> = cx.new(|_| PlayHistoryTracker);
 
     let discord_mmbs = mmbs.clone();
     create_discord_mmbs(cx, &discord_mmbs, discord_rpc_enabled(cx));
@@ -318,20 +460,8 @@ pub fn build_models(
 
     let switcher_model = cx.new(|_| NavigationHistory::new(startup_view));
 
-    let sidebar_width: Entity<Pixels> = cx.new(|_| {
-        if storage_data.sidebar_width > 0.0 {
-            storage_data.sidebar_width()
-        } else {
-            DEFAULT_SIDEBAR_WIDTH
-        }
-    });
-    let queue_width: Entity<Pixels> = cx.new(|_| {
-        if storage_data.queue_width > 0.0 {
-            storage_data.queue_width()
-        } else {
-            DEFAULT_QUEUE_WIDTH
-        }
-    });
+    let sidebar_width: Entity<Pixels> = cx.new(|_| storage_data.sidebar_width());
+    let queue_width: Entity<Pixels> = cx.new(|_| storage_data.queue_width());
     let split_widths: std::collections::HashMap<String, Entity<Pixels>> = {
         use crate::settings::storage::SPLIT_FRACTION_KEYS;
         SPLIT_FRACTION_KEYS
@@ -347,6 +477,7 @@ pub fn build_models(
     let liked_tracks_sort_method = cx.new(|_| storage_data.liked_tracks_sort_method);
     let playlist_sort_methods = cx.new(|_| storage_data.playlist_sort_methods.clone());
     let sidebar_collapsed: Entity<bool> = cx.new(|_| storage_data.sidebar_collapsed);
+    let show_queue: Entity<bool> = cx.new(|_| storage_data.show_queue);
     let lyrics_height: Entity<Pixels> = cx.new(|_| {
         if storage_data.lyrics_fraction > 0.0 {
             storage_data.lyrics_fraction()
@@ -384,7 +515,11 @@ pub fn build_models(
         lastfm,
         switcher_model,
         show_about,
+        track_properties,
+        pending_queue_change,
+        skipped_dropped_files,
         playlist_tracker,
+        play_history: play_history.clone(),
         sidebar_width,
         queue_width,
         split_widths,
@@ -392,6 +527,7 @@ pub fn build_models(
         liked_tracks_sort_method,
         playlist_sort_methods,
         sidebar_collapsed,
+        show_queue,
         lyrics_height,
         controls_left_width,
         controls_right_width,
@@ -408,6 +544,48 @@ pub fn build_models(
     let repeating: Entity<RepeatState> = cx.new(|_| initial_repeat);
     let volume: Entity<f64> = cx.new(|_| storage_data.volume);
     let prev_volume: Entity<f64> = cx.new(|_| storage_data.volume);
+    let muted: Entity<bool> = cx.new(|_| false);
+
+    // every new track (including repeats) is added to the play history once it has been
+    // listened to for long enough, so restoring the last session or skipping past a track doesn't
+    // count as a play
+    let pending_play: Entity<Option<PendingPlay>> = cx.new(|_| None);
+
+    cx.observe(&current_track, {
+        let pending_play = pending_play.clone();
+        move |current_track, cx| {
+            let play = current_track
+                .read(cx)
+                .as_ref()
+                .map(|track| PendingPlay::new(track.get_path().clone()));
+            pending_play.write(cx, play);
+        }
+    })
+    .detach();
+
+    cx.observe(&position, {
+        let duration = duration.clone();
+        let playback_state = playback_state.clone();
+        move |position, cx| {
+            let position = *position.read(cx);
+            let playing = *playback_state.read(cx) == PlaybackState::Playing;
+            let duration_ms = *duration.read(cx) * 1_000;
+
+            let listened = pending_play.update(cx, |pending, _| {
+                let done = pending.as_mut()?.advance(position, playing, duration_ms);
+                if done {
+                    pending.take().map(|play| play.path)
+                } else {
+                    None
+                }
+            });
+
+            if let Some(path) = listened {
+                record_play(cx, path, play_history.clone());
+            }
+        }
+    })
+    .detach();
 
     cx.set_global(PlaybackInfo {
         position,
@@ -418,6 +596,7 @@ pub fn build_models(
         repeating,
         volume,
         prev_volume,
+        muted,
     });
 }
 
@@ -436,3 +615,37 @@ pub fn create_discord_mmbs(cx: &mut App, mmbs_list: &Entity<MMBSList>, enabled:
         m.0.insert("discord".to_string(), Arc::new(Mutex::new(mmbs)));
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PendingPlay;
+
+    #[test]
+    fn plays_count_after_listening_long_enough() {
+        let mut play = PendingPlay::new("track.flac".into());
+
+        for position in (1_000..30_000).step_by(1_000) {
+            assert!(!play.advance(position, true, 180_000));
+        }
+        assert!(play.advance(30_000, true, 180_000));
+    }
+
+    #[test]
+    fn seeking_and_pausing_dont_count_as_listening() {
+        let mut play = PendingPlay::new("track.flac".into());
+
+        assert!(!play.advance(120_000, true, 180_000));
+        assert!(!play.advance(121_000, false, 180_000));
+        assert_eq!(play.listened_ms, 0);
+    }
+
+    #[test]
+    fn short_tracks_count_after_half_their_length() {
+        let mut play = PendingPlay::new("track.flac".into());
+
+        for position in (1_000..10_000).step_by(1_000) {
+            assert!(!play.advance(position, true, 20_000));
+        }
+        assert!(play.advance(10_000, true, 20_000));
+    }
+}
@@ -8,11 +8,14 @@ use crate::{
             context::context,
             drag_drop::{
                 AlbumDragData, DragData, DragDropItemState, DragDropListConfig,
-                DragDropListManager, DragPreview, DropIndicator, TrackDragData,
-                calculate_drop_target, check_drag_cancelled, continue_edge_scroll,
-                get_edge_scroll_direction, handle_drag_move, handle_drop, perform_edge_scroll,
+                DragDropListManager, DragPreview, DropIndicator, DropPosition, TrackDragData,
+                calculate_drop_target, calculate_move_target, check_drag_cancelled,
+                continue_edge_scroll, get_edge_scroll_direction, handle_drag_move, handle_drop,
+                perform_edge_scroll,
+            },
+            icons::{
+                CROSS, DISC, PLAY, PLAYLIST_ADD, PLAYLIST_REMOVE, SHUFFLE, TRASH, USERS, icon,
             },
-            icons::{CROSS, DISC, PLAYLIST_ADD, SHUFFLE, TRASH, USERS, icon},
             managed_image::{ManagedImageKey, managed_image},
             menu::{menu, menu_item, menu_separator},
             nav_button::nav_button,
@@ -30,10 +33,12 @@ use std::time::Duration;
 
 use super::{
     components::button::{ButtonSize, ButtonStyle, button},
+    format::format_duration,
     models::{Models, PlaybackInfo},
+    queue_confirmation::clear_queue,
     scroll_follow::SmoothScrollFollow,
     theme::Theme,
-    util::{create_or_retrieve_view_keyed, retain_views},
+    util::{create_or_retrieve_view_keyed, queue_dropped_paths, retain_views},
 };
 
 /// The list identifier for queue drag-drop operations
@@ -133,6 +138,7 @@ impl Render for QueueItem {
                     && scroll_handle.should_draw_scrollbar()
             };
             let is_current = self.current == self.idx;
+            let current = self.current;
             let image_key = album_id.map(ManagedImageKey::Album).or_else(|| {
                 self.item
                     .as_ref()
@@ -170,7 +176,7 @@ impl Render for QueueItem {
                         .border_color(theme.border_color)
                         .when(item_state.is_being_dragged, |div| div.opacity(0.5))
                         .when(is_current && !item_state.is_being_dragged, |div| {
-                            div.bg(theme.queue_item_current)
+                            div.bg(theme.now_playing_highlight)
                         })
                         .when(is_available, |div| {
                             div.on_click(move |_, _, cx| {
@@ -258,11 +264,7 @@ impl Render for QueueItem {
                                                     .ml(px(6.0))
                                                     .font_weight(FontWeight::SEMIBOLD)
                                                     .text_color(theme.text_secondary)
-                                                    .child(format!(
-                                                        "{:02}:{:02}",
-                                                        duration / 60,
-                                                        duration % 60
-                                                    )),
+                                                    .child(format_duration(duration.max(0) as u64)),
                                             )
                                         }),
                                 ),
@@ -270,6 +272,31 @@ impl Render for QueueItem {
                 )
                 .child(
                     menu()
+                        .item(
+                            menu_item(
+                                "queue_play_now",
+                                Some(PLAY),
+                                tr!("PLAY"),
+                                move |_, _, cx| {
+                                    cx.global::<PlaybackInterface>().jump(idx);
+                                },
+                            )
+                            .disabled(!is_available || is_current),
+                        )
+                        .item(
+                            menu_item(
+                                "queue_play_next",
+                                None::<SharedString>,
+                                tr!("PLAY_NEXT"),
+                                move |_, _, cx| {
+                                    let target =
+                                        calculate_move_target(idx, current, DropPosition::After);
+                                    cx.global::<PlaybackInterface>().move_item(idx, target);
+                                },
+                            )
+                            .disabled(is_current || idx == current + 1),
+                        )
+                        .item(menu_separator())
                         .when(self.add_to.is_some(), |menu| {
                             menu.item(
                                 menu_item(
@@ -462,7 +489,10 @@ impl Render for Queue {
                             .mr(px(9.0))
                             .ml_auto()
                             .on_click(cx.listener(|this: &mut Self, _, _, cx| {
-                                this.show_queue.update(cx, |v, _| *v = !(*v))
+                                this.show_queue.update(cx, |v, cx| {
+                                    *v = !(*v);
+                                    cx.notify();
+                                })
                             }))
                             .tooltip(build_tooltip(tr!("CLOSE", "Close"))),
                     ),
@@ -498,8 +528,22 @@ impl Render for Queue {
                             .child(tr!("CLEAR_QUEUE", "Clear"))
                             .w_full()
                             .id("clear-queue")
+                            .on_click(|_, _, cx| clear_queue(cx)),
+                    )
+                    .child(
+                        button()
+                            .style(ButtonStyle::MinimalNoRounding)
+                            .size(ButtonSize::Large)
+                            .child(icon(PLAYLIST_REMOVE).size(px(14.0)).my_auto())
+                            .child(tr!("CLEAR_PLAYED", "Clear Played"))
+                            .w_full()
+                            .id("clear-played")
+                            .tooltip(build_tooltip(tr!(
+                                "CLEAR_PLAYED_TOOLTIP",
+                                "Remove the tracks before the current one"
+                            )))
                             .on_click(|_, _, cx| {
-                                cx.global::<PlaybackInterface>().clear_queue();
+                                cx.global::<PlaybackInterface>().clear_played();
                             }),
                     )
                     .child(
@@ -574,71 +618,7 @@ impl Render for Queue {
                               event: &DragMoveEvent<TrackDragData>,
                               window,
                               cx| {
-                            let scroll_handle: ScrollableHandle = this.scroll_handle.clone().into();
-                            let config = this.drag_drop_manager.read(cx).config.clone();
-                            let mouse_pos = event.event.position;
-                            let container_bounds = event.bounds;
-
-                            this.drag_drop_manager.update(cx, |m, _| {
-                                m.state.is_dragging = true;
-                                m.state.set_mouse_y(mouse_pos.y);
-                                m.container_bounds = Some(container_bounds);
-                            });
-
-                            let direction = get_edge_scroll_direction(
-                                mouse_pos.y,
-                                container_bounds,
-                                &config.scroll_config,
-                            );
-                            let scrolled = perform_edge_scroll(
-                                &scroll_handle,
-                                direction,
-                                &config.scroll_config,
-                            );
-
-                            if scrolled {
-                                let entity = cx.entity().downgrade();
-                                let manager = this.drag_drop_manager.clone();
-                                let scroll_handle: ScrollableHandle =
-                                    this.scroll_handle.clone().into();
-
-                                window.on_next_frame(move |window, cx| {
-                                    if let Some(entity) = entity.upgrade() {
-                                        entity.update(cx, |_, cx| {
-                                            Self::schedule_edge_scroll(
-                                                manager,
-                                                scroll_handle,
-                                                window,
-                                                cx,
-                                            );
-                                        });
-                                    }
-                                });
-                            }
-
-                            if container_bounds.contains(&mouse_pos) {
-                                let scroll_offset_y = scroll_handle.offset().y;
-                                let drop_target = calculate_drop_target(
-                                    mouse_pos,
-                                    container_bounds,
-                                    scroll_offset_y,
-                                    config.item_height,
-                                    queue_len,
-                                );
-
-                                this.drag_drop_manager.update(cx, |m, _| {
-                                    if let Some((item_index, drop_position)) = drop_target {
-                                        m.state.update_drop_target(item_index, drop_position);
-                                    } else {
-                                        m.state.clear_drop_target();
-                                    }
-                                });
-                            } else {
-                                this.drag_drop_manager
-                                    .update(cx, |m, _| m.state.clear_drop_target());
-                            }
-
-                            cx.notify();
+                            this.handle_insert_drag_move(event, queue_len, window, cx);
                         },
                     ))
                     .on_drag_move::<AlbumDragData>(cx.listener(
@@ -646,71 +626,15 @@ impl Render for Queue {
                               event: &DragMoveEvent<AlbumDragData>,
                               window,
                               cx| {
-                            let scroll_handle: ScrollableHandle = this.scroll_handle.clone().into();
-                            let config = this.drag_drop_manager.read(cx).config.clone();
-                            let mouse_pos = event.event.position;
-                            let container_bounds = event.bounds;
-
-                            this.drag_drop_manager.update(cx, |m, _| {
-                                m.state.is_dragging = true;
-                                m.state.set_mouse_y(mouse_pos.y);
-                                m.container_bounds = Some(container_bounds);
-                            });
-
-                            let direction = get_edge_scroll_direction(
-                                mouse_pos.y,
-                                container_bounds,
-                                &config.scroll_config,
-                            );
-                            let scrolled = perform_edge_scroll(
-                                &scroll_handle,
-                                direction,
-                                &config.scroll_config,
-                            );
-
-                            if scrolled {
-                                let entity = cx.entity().downgrade();
-                                let manager = this.drag_drop_manager.clone();
-                                let scroll_handle: ScrollableHandle =
-                                    this.scroll_handle.clone().into();
-
-                                window.on_next_frame(move |window, cx| {
-                                    if let Some(entity) = entity.upgrade() {
-                                        entity.update(cx, |_, cx| {
-                                            Self::schedule_edge_scroll(
-                                                manager,
-                                                scroll_handle,
-                                                window,
-                                                cx,
-                                            );
-                                        });
-                                    }
-                                });
-                            }
-
-                            if container_bounds.contains(&mouse_pos) {
-                                let scroll_offset_y = scroll_handle.offset().y;
-                                let drop_target = calculate_drop_target(
-                                    mouse_pos,
-                                    container_bounds,
-                                    scroll_offset_y,
-                                    config.item_height,
-                                    queue_len,
-                                );
-
-                                this.drag_drop_manager.update(cx, |m, _| {
-                                    if let Some((item_index, drop_position)) = drop_target {
-                                        m.state.update_drop_target(item_index, drop_position);
-                                    } else {
-                                        m.state.clear_drop_target();
-                                    }
-                                });
-                            } else {
-                                this.drag_drop_manager
-                                    .update(cx, |m, _| m.state.clear_drop_target());
-                            }
-
-                            cx.notify();
+                            this.handle_insert_drag_move(event, queue_len, window, cx);
+                        },
+                    ))
+                    .on_drag_move::<ExternalPaths>(cx.listener(
+                        move |this: &mut Queue,
+                              event: &DragMoveEvent<ExternalPaths>,
+                              window,
+                              cx| {
+                            this.handle_insert_drag_move(event, queue_len, window, cx);
                         },
                     ))
                     .on_drop(
@@ -729,8 +653,6 @@ impl Render for Queue {
                     // track drops
                     .on_drop(cx.listener(
                         move |this: &mut Queue, drag_data: &TrackDragData, _, cx| {
-                            use crate::ui::components::drag_drop::DropPosition;
-
                             let queue_item = QueueItemData::new(
                                 cx,
                                 drag_data.path.clone(),
@@ -738,13 +660,7 @@ impl Render for Queue {
                                 drag_data.album_id,
                             );
 
-                            let drop_target = this.drag_drop_manager.read(cx).state.drop_target;
-
-                            if let Some((target_index, position)) = drop_target {
-                                let insert_pos = match position {
-                                    DropPosition::Before => target_index,
-                                    DropPosition::After => target_index + 1,
-                                };
+                            if let Some(insert_pos) = this.drop_insert_position(cx) {
                                 cx.global::<PlaybackInterface>()
                                     .insert_at(queue_item, insert_pos);
                             } else {
@@ -759,7 +675,6 @@ impl Render for Queue {
                     .on_drop(cx.listener(
                         move |this: &mut Queue, drag_data: &AlbumDragData, _, cx| {
                             use crate::library::db::LibraryAccess;
-                            use crate::ui::components::drag_drop::DropPosition;
 
                             if let Ok(tracks) = cx.list_tracks_in_album(drag_data.album_id) {
                                 let queue_items: Vec<QueueItemData> = tracks
@@ -774,13 +689,7 @@ impl Render for Queue {
                                     })
                                     .collect();
 
-                                let drop_target = this.drag_drop_manager.read(cx).state.drop_target;
-
-                                if let Some((target_index, position)) = drop_target {
-                                    let insert_pos = match position {
-                                        DropPosition::Before => target_index,
-                                        DropPosition::After => target_index + 1,
-                                    };
+                                if let Some(insert_pos) = this.drop_insert_position(cx) {
                                     cx.global::<PlaybackInterface>()
                                         .insert_list_at(queue_items, insert_pos);
                                 } else {
@@ -791,6 +700,24 @@ impl Render for Queue {
                             cx.notify();
                         },
                     ))
+                    // file drops from outside of the app
+                    .on_drop(
+                        cx.listener(move |this: &mut Queue, paths: &ExternalPaths, _, cx| {
+                            let insert_pos = this.drop_insert_position(cx);
+
+                            queue_dropped_paths(cx, paths.paths().to_vec(), move |items, cx| {
+                                if let Some(insert_pos) = insert_pos {
+                                    cx.global::<PlaybackInterface>()
+                                        .insert_list_at(items, insert_pos);
+                                } else {
+                                    cx.global::<PlaybackInterface>().queue_list(items);
+                                }
+                            });
+
+                            this.drag_drop_manager.update(cx, |m, _| m.state.end_drag());
+                            cx.notify();
+                        }),
+                    )
                     .child(
                         uniform_list("queue", queue_len, move |range, _, cx| {
                             let start = range.start;
@@ -950,6 +877,80 @@ impl Queue {
         }
     }
 
+    /// Updates the drop target while tracks from outside of the queue (library tracks, albums or
+    /// files) are dragged over it.
+    fn handle_insert_drag_move<T: 'static>(
+        &mut self,
+        event: &DragMoveEvent<T>,
+        queue_len: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let scroll_handle: ScrollableHandle = self.scroll_handle.clone().into();
+        let config = self.drag_drop_manager.read(cx).config.clone();
+        let mouse_pos = event.event.position;
+        let container_bounds = event.bounds;
+
+        self.drag_drop_manager.update(cx, |m, _| {
+            m.state.is_dragging = true;
+            m.state.set_mouse_y(mouse_pos.y);
+            m.container_bounds = Some(container_bounds);
+        });
+
+        let direction =
+            get_edge_scroll_direction(mouse_pos.y, container_bounds, &config.scroll_config);
+        let scrolled = perform_edge_scroll(&scroll_handle, direction, &config.scroll_config);
+
+        if scrolled {
+            let entity = cx.entity().downgrade();
+            let manager = self.drag_drop_manager.clone();
+            let scroll_handle: ScrollableHandle = self.scroll_handle.clone().into();
+
+            window.on_next_frame(move |window, cx| {
+                if let Some(entity) = entity.upgrade() {
+                    entity.update(cx, |_, cx| {
+                        Self::schedule_edge_scroll(manager, scroll_handle, window, cx);
+                    });
+                }
+            });
+        }
+
+        if container_bounds.contains(&mouse_pos) {
+            let scroll_offset_y = scroll_handle.offset().y;
+            let drop_target = calculate_drop_target(
+                mouse_pos,
+                container_bounds,
+                scroll_offset_y,
+                config.item_height,
+                queue_len,
+            );
+
+            self.drag_drop_manager.update(cx, |m, _| {
+                if let Some((item_index, drop_position)) = drop_target {
+                    m.state.update_drop_target(item_index, drop_position);
+                } else {
+                    m.state.clear_drop_target();
+                }
+            });
+        } else {
+            self.drag_drop_manager
+                .update(cx, |m, _| m.state.clear_drop_target());
+        }
+
+        cx.notify();
+    }
+
+    /// The queue position that tracks dropped onto the queue should be inserted at, or `None` if
+    /// they should be added to the end.
+    fn drop_insert_position(&self, cx: &App) -> Option<usize> {
+        let (target_index, position) = self.drag_drop_manager.read(cx).state.drop_target?;
+
+        Some(match position {
+            DropPosition::Before => target_index,
+            DropPosition::After => target_index + 1,
+        })
+    }
+
     fn schedule_edge_scroll(
         manager: Entity<DragDropListManager>,
         scroll_handle: ScrollableHandle,
@@ -0,0 +1,403 @@
+//! Asks before the queue is replaced or cleared, if it holds more tracks than the user is willing
+//! to lose by accident.
+
+use cntp_i18n::{tr, trn};
+use gpui::*;
+use prelude::FluentBuilder;
+
+use crate::{
+    playback::{
+        interface::PlaybackInterface,
+        queue::{QueueItemData, fit_to_limit},
+    },
+    settings::{
+        SettingsGlobal,
+        playback::{PlaybackSettings, ReplayBehavior},
+    },
+    ui::{
+        components::{
+            button::{ButtonIntent, ButtonSize, button},
+            icons::{ALERT_CIRCLE, icon},
+            modal::modal,
+        },
+        models::{Models, PlaybackInfo},
+        theme::Theme,
+    },
+};
+
+/// A change that discards the tracks in the queue.
+pub enum QueueChange {
+    /// Replace the queue with `items`, starting from `index` if there is one. If `shuffle` is set,
+    /// shuffle is turned on or off first.
+    Replace {
+        items: Vec<QueueItemData>,
+        index: Option<usize>,
+        shuffle: Option<bool>,
+    },
+    Clear,
+}
+
+impl QueueChange {
+    fn apply(self, cx: &mut App) {
+        let shuffling = *cx.global::<PlaybackInfo>().shuffling.read(cx);
+        let interface = cx.global::<PlaybackInterface>();
+
+        match self {
+            QueueChange::Replace {
+                items,
+                index,
+                shuffle,
+            } => {
+                if shuffle.is_some_and(|shuffle| shuffle != shuffling) {
+                    interface.toggle_shuffle();
+                }
+
+                if let Some(index) = index {
+                    interface.replace_queue_with_index(items, index);
+                    interface.play();
+                } else {
+                    interface.replace_queue(items);
+                }
+            }
+            QueueChange::Clear => interface.clear_queue(),
+        }
+    }
+}
+
+/// Replace the current queue with the given items.
+pub fn replace_queue(items: Vec<QueueItemData>, cx: &mut App) {
+    request(
+        QueueChange::Replace {
+            items,
+            index: None,
+            shuffle: None,
+        },
+        cx,
+    );
+}
+
+/// Replace the current queue with the given items, turning shuffle on or off once the queue is
+/// actually replaced.
+pub fn replace_queue_with_shuffle(items: Vec<QueueItemData>, shuffle: bool, cx: &mut App) {
+    request(
+        QueueChange::Replace {
+            items,
+            index: None,
+            shuffle: Some(shuffle),
+        },
+        cx,
+    );
+}
+
+/// Replace the current queue with the given items, and play the one at `index`.
+pub fn replace_queue_with_index(items: Vec<QueueItemData>, index: usize, cx: &mut App) {
+    request(
+        QueueChange::Replace {
+            items,
+            index: Some(index),
+            shuffle: None,
+        },
+        cx,
+    );
+}
+
+/// Clear the queue.
+pub fn clear_queue(cx: &mut App) {
+    request(QueueChange::Clear, cx);
+}
+
+fn request(change: QueueChange, cx: &mut App) {
+    let playback = &cx.global::<SettingsGlobal>().model.read(cx).playback;
+    let threshold = playback.confirm_queue_discard_over;
+    let shuffling = *cx.global::<PlaybackInfo>().shuffling.read(cx);
+
+    let queue = cx.global::<Models>().queue.read(cx).data.clone();
+    let queue = queue.read().expect("poisoned queue lock");
+
+    let discards = match &change {
+        QueueChange::Replace { items, index, .. } => {
+            replacing_discards(&queue, items, index.unwrap_or(0), shuffling, playback)
+        }
+        QueueChange::Clear => true,
+    };
+    let confirm = discards && needs_confirmation(threshold, queue.len());
+    drop(queue);
+
+    if confirm {
+        let pending = cx.global::<Models>().pending_queue_change.clone();
+        pending.write(cx, Some(change));
+    } else {
+        change.apply(cx);
+    }
+}
+
+/// Whether discarding a queue of `queue_len` tracks has to be confirmed first.
+fn needs_confirmation(threshold: Option<usize>, queue_len: usize) -> bool {
+    threshold.is_some_and(|threshold| queue_len > threshold)
+}
+
+/// Whether replacing `queue` with `items` loses anything. Replacing the queue with what it already
+/// holds doesn't, unless the user asked for the queue to be rebuilt anyway.
+fn replacing_discards(
+    queue: &[QueueItemData],
+    items: &[QueueItemData],
+    start_index: usize,
+    shuffling: bool,
+    settings: &PlaybackSettings,
+) -> bool {
+    settings.replay_behavior == ReplayBehavior::Replace
+        || shuffling
+        || !holds_same_tracks(queue, items, start_index, settings)
+}
+
+/// Whether the unshuffled `queue` holds exactly `items` in the same order, once they're cut down to
+/// the queue size limit like the playback thread does. A shuffled queue is never compared, since
+/// the order its tracks were queued in isn't known here.
+fn holds_same_tracks(
+    queue: &[QueueItemData],
+    items: &[QueueItemData],
+    start_index: usize,
+    settings: &PlaybackSettings,
+) -> bool {
+    if queue.len() > items.len() {
+        return false;
+    }
+
+    let (items, _) = fit_to_limit(settings, items.to_vec(), start_index);
+    queue == items.as_slice()
+}
+
+pub struct QueueConfirmationDialog;
+
+impl QueueConfirmationDialog {
+    pub fn new(cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let pending = cx.global::<Models>().pending_queue_change.clone();
+            cx.observe(&pending, |_, _, cx| cx.notify()).detach();
+
+            QueueConfirmationDialog
+        })
+    }
+}
+
+/// Closes the dialog, passing the pending change to `then`.
+fn resolve(cx: &mut App, then: impl FnOnce(QueueChange, &mut App)) {
+    let pending = cx.global::<Models>().pending_queue_change.clone();
+    let change = pending.update(cx, |pending, cx| {
+        cx.notify();
+        pending.take()
+    });
+
+    if let Some(change) = change {
+        then(change, cx);
+    }
+}
+
+impl Render for QueueConfirmationDialog {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.global::<Theme>();
+        let is_clear = matches!(
+            cx.global::<Models>().pending_queue_change.read(cx),
+            Some(QueueChange::Clear)
+        );
+        let count = cx
+            .global::<Models>()
+            .queue
+            .read(cx)
+            .data
+            .read()
+            .expect("poisoned queue lock")
+            .len();
+
+        let (title, confirm) = if is_clear {
+            (
+                tr!("QUEUE_CONFIRM_CLEAR_TITLE", "Clear the queue?"),
+                tr!("CLEAR_QUEUE"),
+            )
+        } else {
+            (
+                tr!("QUEUE_CONFIRM_REPLACE_TITLE", "Replace the queue?"),
+                tr!("QUEUE_CONFIRM_REPLACE", "Replace"),
+            )
+        };
+
+        modal().on_exit(|_, cx| resolve(cx, |_, _| ())).child(
+            div()
+                .w(px(440.0))
+                .max_w_full()
+                .p(px(24.0))
+                .flex()
+                .flex_col()
+                .gap(px(14.0))
+                .child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(12.0))
+                        .child(
+                            icon(ALERT_CIRCLE)
+                                .size(px(28.0))
+                                .flex_shrink_0()
+                                .text_color(theme.callout_text),
+                        )
+                        .child(
+                            div()
+                                .text_size(px(18.0))
+                                .font_weight(FontWeight::BOLD)
+                                .line_height(px(24.0))
+                                .child(title),
+                        ),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .line_height(px(20.0))
+                        .text_color(theme.text_secondary)
+                        .child(trn!(
+                            "QUEUE_CONFIRM_BODY",
+                            "The queue has {{count}} track in it, which will be removed.",
+                            "The queue has {{count}} tracks in it, which will be removed.",
+                            count = count
+                        )),
+                )
+                .child(
+                    div()
+                        .pt(px(6.0))
+                        .flex()
+                        .justify_end()
+                        .gap(px(10.0))
+                        .child(
+                            button()
+                                .id("queue-confirm-cancel")
+                                .size(ButtonSize::Large)
+                                .child(tr!("CANCEL"))
+                                .on_click(|_, _, cx| resolve(cx, |_, _| ())),
+                        )
+                        .when(!is_clear, |this| {
+                            this.child(
+                                button()
+                                    .id("queue-confirm-add")
+                                    .size(ButtonSize::Large)
+                                    .child(tr!("QUEUE_CONFIRM_ADD", "Add to Queue Instead"))
+                                    .on_click(|_, _, cx| {
+                                        resolve(cx, |change, cx| {
+                                            if let QueueChange::Replace { items, .. } = change {
+                                                cx.global::<PlaybackInterface>().queue_list(items);
+                                            }
+                                        })
+                                    }),
+                            )
+                        })
+                        .child(
+                            button()
+                                .id("queue-confirm-discard")
+                                .size(ButtonSize::Large)
+                                .intent(ButtonIntent::Danger)
+                                .child(confirm)
+                                .on_click(|_, _, cx| resolve(cx, |change, cx| change.apply(cx))),
+                        ),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{holds_same_tracks, needs_confirmation, replacing_discards};
+    use crate::{
+        playback::queue::QueueItemData,
+        settings::playback::{PlaybackSettings, QueueOverflow},
+    };
+
+    fn items(ids: &[i64]) -> Vec<QueueItemData> {
+        ids.iter()
+            .map(|id| {
+                serde_json::from_value(serde_json::json!({
+                    "db_id": id,
+                    "db_album_id": null,
+                    "path": format!("/music/{id}.flac"),
+                }))
+                .unwrap()
+            })
+            .collect()
+    }
+
+    fn limited(limit: usize, queue_overflow: QueueOverflow) -> PlaybackSettings {
+        PlaybackSettings {
+            max_queue_length: Some(limit),
+            queue_overflow,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn small_queues_are_discarded_without_asking() {
+        assert!(!needs_confirmation(Some(100), 100));
+        assert!(needs_confirmation(Some(100), 101));
+    }
+
+    #[test]
+    fn nothing_is_confirmed_without_a_threshold() {
+        assert!(!needs_confirmation(None, 10_000));
+    }
+
+    #[test]
+    fn the_same_tracks_in_the_same_order_are_kept() {
+        let settings = PlaybackSettings::default();
+
+        assert!(holds_same_tracks(
+            &items(&[1, 2, 3]),
+            &items(&[1, 2, 3]),
+            1,
+            &settings
+        ));
+    }
+
+    #[test]
+    fn a_reordered_queue_is_not_the_same() {
+        let settings = PlaybackSettings::default();
+
+        assert!(!holds_same_tracks(
+            &items(&[1, 2, 3]),
+            &items(&[3, 2, 1]),
+            0,
+            &settings
+        ));
+        assert!(!holds_same_tracks(
+            &items(&[1, 2, 3]),
+            &items(&[1, 2]),
+            0,
+            &settings
+        ));
+        assert!(!holds_same_tracks(
+            &items(&[1, 2]),
+            &items(&[1, 2, 3]),
+            0,
+            &settings
+        ));
+    }
+
+    #[test]
+    fn tracks_over_the_limit_are_compared_after_being_cut_down() {
+        let all = items(&[1, 2, 3, 4, 5]);
+
+        // starting from the fourth track drops the two played ones in front of it
+        let settings = limited(3, QueueOverflow::DropPlayed);
+        assert!(holds_same_tracks(&items(&[3, 4, 5]), &all, 3, &settings));
+        assert!(!holds_same_tracks(&items(&[1, 2, 3]), &all, 3, &settings));
+
+        // rejecting keeps the first tracks, as long as the one being played still fits
+        let settings = limited(3, QueueOverflow::Reject);
+        assert!(holds_same_tracks(&items(&[1, 2, 3]), &all, 0, &settings));
+        assert!(holds_same_tracks(&items(&[2, 3, 4]), &all, 3, &settings));
+    }
+
+    #[test]
+    fn a_shuffled_queue_is_always_discarded() {
+        let settings = PlaybackSettings::default();
+        let queue = items(&[1, 2, 3]);
+
+        assert!(!replacing_discards(&queue, &queue, 0, false, &settings));
+        assert!(replacing_discards(&queue, &queue, 0, true, &settings));
+    }
+}
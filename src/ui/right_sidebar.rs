@@ -2,9 +2,11 @@ use gpui::*;
 use prelude::FluentBuilder;
 
 use crate::{
-    settings::storage::{DEFAULT_LYRICS_FRACTION, DEFAULT_QUEUE_WIDTH},
+    settings::storage::{
+        DEFAULT_LYRICS_FRACTION, DEFAULT_QUEUE_WIDTH, MAX_QUEUE_WIDTH, MIN_QUEUE_WIDTH,
+    },
     ui::{
-        components::resizable::{ResizeEdge, resizable},
+        components::resizable::{MAX_PANEL_FRACTION, ResizeEdge, resizable},
         lyrics::Lyrics,
         models::Models,
         queue::Queue,
@@ -43,7 +45,7 @@ impl RightSidebar {
 }
 
 impl Render for RightSidebar {
-    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let show_queue = *self.show_queue.read(cx);
         let show_lyrics = *self.show_lyrics.read(cx);
         let queue_width = cx.global::<Models>().queue_width.clone();
@@ -51,11 +53,18 @@ impl Render for RightSidebar {
 
         let queue = self.queue.clone();
         let lyrics = self.lyrics.clone();
+        let show_queue_entity = self.show_queue.clone();
+        let show_lyrics_entity = self.show_lyrics.clone();
 
         resizable("queue-resizable", queue_width, ResizeEdge::Left)
-            .min_size(px(225.0))
-            .max_size(px(800.0))
+            .min_size(MIN_QUEUE_WIDTH)
+            .max_size(MAX_QUEUE_WIDTH.min(window.viewport_size().width * MAX_PANEL_FRACTION))
             .default_size(DEFAULT_QUEUE_WIDTH)
+            // dragging the panel most of the way closed hides it, like the close button does
+            .on_collapse(move |_, cx| {
+                show_queue_entity.write(cx, false);
+                show_lyrics_entity.write(cx, false);
+            })
             .h_full()
             .child(
                 div()
@@ -15,6 +15,9 @@ pub struct SearchView {
     show: Entity<bool>,
     search: Entity<SearchModel>,
     view_switcher: Entity<NavigationHistory>,
+    // whatever had focus before search was opened, restored when search is dismissed
+    return_focus: Option<FocusHandle>,
+    opened: bool,
 }
 
 impl SearchView {
@@ -51,6 +54,8 @@ impl SearchView {
                 view_switcher: cx.global::<Models>().switcher_model.clone(),
                 show,
                 search,
+                return_focus: None,
+                opened: false,
             }
         })
     }
@@ -63,7 +68,16 @@ impl SearchView {
         self.show.update(cx, |m, cx| {
             *m = false;
             cx.notify();
-        })
+        });
+        self.opened = false;
+    }
+
+    fn dismiss(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.reset(cx);
+
+        if let Some(handle) = self.return_focus.take() {
+            handle.focus(window, cx);
+        }
     }
 }
 
@@ -74,15 +88,20 @@ impl Render for SearchView {
         let weak = cx.weak_entity();
 
         if *show_read {
+            if !self.opened {
+                self.opened = true;
+                self.return_focus = window.focused(cx);
+            }
+
             // Focus the search palette instead of our own handle
             cx.update_entity(&self.search, |search, cx| {
                 search.focus(window, cx);
             });
 
             modal()
-                .on_exit(move |_, cx| {
+                .on_exit(move |window, cx| {
                     weak.update(cx, |this, cx| {
-                        this.reset(cx);
+                        this.dismiss(window, cx);
                     })
                     .expect("failed to update search view")
                 })
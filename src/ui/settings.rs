@@ -1,3 +1,4 @@
+mod backup;
 mod interface;
 mod library;
 mod playback;
@@ -17,15 +18,15 @@ use crate::{
     settings::{SettingsGlobal, storage::DEFAULT_SIDEBAR_WIDTH},
     ui::{
         components::{
-            icons::{ADJUSTMENTS, BOOKS, PLAY, WORLD},
+            icons::{ADJUSTMENTS, BOOKS, FILE_EXPORT, PLAY, WORLD},
             scrollbar::{RightPad, ScrollableHandle, floating_scrollbar},
             sidebar::{sidebar, sidebar_item},
             window_chrome::window_chrome,
             window_header::header,
         },
         settings::{
-            interface::InterfaceSettings, library::LibrarySettings, playback::PlaybackSettings,
-            services::ServicesSettings,
+            backup::BackupSettings, interface::InterfaceSettings, library::LibrarySettings,
+            playback::PlaybackSettings, services::ServicesSettings,
         },
         theme::Theme,
     },
@@ -74,6 +75,7 @@ enum SettingsSectionKind {
     Services,
     #[cfg(feature = "update")]
     Update,
+    Backup,
 }
 
 impl SettingsSectionKind {
@@ -85,6 +87,7 @@ impl SettingsSectionKind {
             Self::Services => "services",
             #[cfg(feature = "update")]
             Self::Update => "update",
+            Self::Backup => "backup",
         }
     }
 
@@ -96,6 +99,7 @@ impl SettingsSectionKind {
             Self::Services => ADJUSTMENTS,
             #[cfg(feature = "update")]
             Self::Update => super::components::icons::UPDATE,
+            Self::Backup => FILE_EXPORT,
         }
     }
 
@@ -107,6 +111,7 @@ impl SettingsSectionKind {
             Self::Services => tr!("SERVICES", "Services").into(),
             #[cfg(feature = "update")]
             Self::Update => tr!("UPDATE", "Update").into(),
+            Self::Backup => tr!("SETTINGS_BACKUP", "Backup & Reset").into(),
         }
     }
 }
@@ -119,6 +124,7 @@ enum SettingsSection {
     Services(Entity<ServicesSettings>),
     #[cfg(feature = "update")]
     Update(Entity<UpdateSettings>),
+    Backup(Entity<BackupSettings>),
 }
 
 impl SettingsSection {
@@ -130,6 +136,7 @@ impl SettingsSection {
             SettingsSectionKind::Services => Self::Services(ServicesSettings::new(cx)),
             #[cfg(feature = "update")]
             SettingsSectionKind::Update => Self::Update(UpdateSettings::new(cx)),
+            SettingsSectionKind::Backup => Self::Backup(BackupSettings::new(cx)),
         }
     }
 
@@ -141,6 +148,7 @@ impl SettingsSection {
             Self::Services(_) => SettingsSectionKind::Services,
             #[cfg(feature = "update")]
             Self::Update(_) => SettingsSectionKind::Update,
+            Self::Backup(_) => SettingsSectionKind::Backup,
         }
     }
 
@@ -152,6 +160,7 @@ impl SettingsSection {
             Self::Services(services) => services.clone().into_any_element(),
             #[cfg(feature = "update")]
             Self::Update(update) => update.clone().into_any_element(),
+            Self::Backup(backup) => backup.clone().into_any_element(),
         }
     }
 }
@@ -250,6 +259,8 @@ impl Render for SettingsWindow {
         #[cfg(feature = "update")]
         let sidebar = sidebar.child(self.render_section_item(SettingsSectionKind::Update, cx));
 
+        let sidebar = sidebar.child(self.render_section_item(SettingsSectionKind::Backup, cx));
+
         window_chrome(
             div()
                 .track_focus(&self.focus_handle)
@@ -0,0 +1,341 @@
+use std::{fs, path::PathBuf};
+
+use cntp_i18n::tr;
+use gpui::{
+    App, AppContext, Context, Entity, InteractiveElement, IntoElement, ParentElement,
+    PathPromptOptions, Render, SharedString, Styled, WeakEntity, Window, div,
+    prelude::FluentBuilder, px,
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    settings::{
+        Settings, SettingsGlobal, save_settings,
+        transfer::{
+            ExportedTheme, export_settings, import_settings, keep_available_theme, reset_settings,
+            write_exported_theme,
+        },
+    },
+    ui::components::{
+        button::{ButtonIntent, ButtonStyle, button},
+        callout::callout,
+        checkbox::checkbox,
+        icons::{ALERT_CIRCLE, FILE_EXPORT, icon},
+        label::label,
+        section_header::section_header,
+    },
+};
+
+pub struct BackupSettings {
+    settings: Entity<Settings>,
+    data_dir: PathBuf,
+    include_scan_paths: bool,
+    include_theme: bool,
+    confirming_reset: bool,
+    error: Option<SharedString>,
+}
+
+impl BackupSettings {
+    pub fn new(cx: &mut App) -> Entity<Self> {
+        let settings_global = cx.global::<SettingsGlobal>();
+        let settings = settings_global.model.clone();
+        let data_dir = settings_global
+            .path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        cx.new(|cx| {
+            cx.observe(&settings, |_, _, cx| cx.notify()).detach();
+
+            Self {
+                settings,
+                data_dir,
+                include_scan_paths: false,
+                include_theme: true,
+                confirming_reset: false,
+                error: None,
+            }
+        })
+    }
+
+    fn set_error(view: &WeakEntity<Self>, message: SharedString, cx: &mut gpui::AsyncApp) {
+        let _ = view.update(cx, |this, cx| {
+            this.error = Some(message);
+            cx.notify();
+        });
+    }
+
+    fn export(&self, view: WeakEntity<Self>, cx: &mut App) {
+        let settings = self.settings.read(cx);
+        let theme = self
+            .include_theme
+            .then(|| settings.interface.theme.clone())
+            .flatten()
+            .and_then(|theme| {
+                let path = self.data_dir.join(&theme);
+                let contents = fs::read_to_string(&path)
+                    .inspect_err(|err| warn!(?err, "Failed to read theme for export: {err}"))
+                    .ok()?;
+
+                Some(ExportedTheme {
+                    file_name: path.file_name()?.to_string_lossy().into_owned(),
+                    contents: serde_json::from_str(&contents).ok()?,
+                })
+            });
+        let exported = export_settings(settings, self.include_scan_paths, theme);
+
+        let Some(documents) = directories::UserDirs::new()
+            .and_then(|dirs| dirs.document_dir().map(|dir| dir.to_path_buf()))
+        else {
+            error!("Failed to get documents directory");
+            return;
+        };
+        let path_future = cx.prompt_for_new_path(&documents, Some("hummingbird-settings.json"));
+
+        cx.spawn(async move |cx| {
+            let Ok(Ok(Some(path))) = path_future.await else {
+                return info!("Settings export cancelled by user");
+            };
+
+            let result = fs::File::create(&path).and_then(|file| {
+                serde_json::to_writer_pretty(file, &exported).map_err(|e| e.into())
+            });
+
+            if let Err(err) = result {
+                error!(
+                    ?err,
+                    "Failed to export settings to {}: {err}",
+                    path.display()
+                );
+                Self::set_error(
+                    &view,
+                    tr!("SETTINGS_EXPORT_FAILED", "Failed to export settings.").into(),
+                    cx,
+                );
+            }
+        })
+        .detach();
+    }
+
+    fn import(&self, view: WeakEntity<Self>, cx: &mut App) {
+        let path_future = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(tr!("SETTINGS_IMPORT_SELECT", "Select a settings file...").into()),
+        });
+
+        let settings = self.settings.clone();
+        let data_dir = self.data_dir.clone();
+
+        cx.spawn(async move |cx| {
+            let Ok(Ok(Some(paths))) = path_future.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(err) => {
+                    error!(
+                        ?err,
+                        "Failed to read settings from {}: {err}",
+                        path.display()
+                    );
+                    return Self::set_error(
+                        &view,
+                        tr!("SETTINGS_IMPORT_FAILED", "Failed to import settings.").into(),
+                        cx,
+                    );
+                }
+            };
+
+            let current = settings.read_with(cx, |settings, _| settings.clone());
+            let (mut imported, theme) = match import_settings(&current, &contents) {
+                Ok(imported) => imported,
+                Err(err) => {
+                    error!(
+                        ?err,
+                        "Failed to import settings from {}: {err}",
+                        path.display()
+                    );
+                    return Self::set_error(
+                        &view,
+                        tr!(
+                            "SETTINGS_IMPORT_INVALID",
+                            "The selected file is not a valid settings file."
+                        )
+                        .into(),
+                        cx,
+                    );
+                }
+            };
+
+            if let Some(theme) = theme {
+                match write_exported_theme(&data_dir, &theme) {
+                    Ok(selected) => imported.interface.theme = Some(selected),
+                    Err(err) => warn!(?err, "Failed to write imported theme: {err}"),
+                }
+            }
+
+            let missing_theme = keep_available_theme(&data_dir, &current, &mut imported);
+            if let Some(theme) = &missing_theme {
+                warn!("Imported settings use theme {theme}, which isn't installed");
+            }
+
+            settings.update(cx, |settings, cx| {
+                *settings = imported;
+                save_settings(cx, settings);
+                cx.notify();
+            });
+
+            let _ = view.update(cx, |this, cx| {
+                this.error = missing_theme.map(|theme| {
+                    tr!(
+                        "SETTINGS_IMPORT_MISSING_THEME",
+                        "The imported settings use the theme {{theme}}, which isn't installed. \
+                        The current theme was kept.",
+                        theme = theme
+                    )
+                    .into()
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn reset(&mut self, cx: &mut Context<Self>) {
+        self.confirming_reset = false;
+        self.error = None;
+
+        self.settings.update(cx, |settings, cx| {
+            *settings = reset_settings(settings);
+            save_settings(cx, settings);
+            cx.notify();
+        });
+    }
+}
+
+impl Render for BackupSettings {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let export_view = cx.entity().downgrade();
+        let import_view = export_view.clone();
+        let confirming_reset = self.confirming_reset;
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(px(12.0))
+            .child(
+                section_header(tr!("SETTINGS_TRANSFER", "Import & Export"))
+                    .subtitle(tr!(
+                        "SETTINGS_TRANSFER_SUBTITLE",
+                        "Share your settings between computers. Importing only changes the \
+                        settings contained in the file."
+                    ))
+                    .child(
+                        div()
+                            .flex()
+                            .gap(px(8.0))
+                            .child(
+                                button()
+                                    .style(ButtonStyle::Regular)
+                                    .intent(ButtonIntent::Secondary)
+                                    .child(tr!("SETTINGS_IMPORT", "Import"))
+                                    .id("settings-import-button")
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.import(import_view.clone(), cx);
+                                    })),
+                            )
+                            .child(
+                                button()
+                                    .style(ButtonStyle::Regular)
+                                    .intent(ButtonIntent::Primary)
+                                    .child(
+                                        div()
+                                            .flex()
+                                            .gap(px(6.0))
+                                            .child(icon(FILE_EXPORT).my_auto().size(px(14.0)))
+                                            .child(tr!("SETTINGS_EXPORT", "Export")),
+                                    )
+                                    .id("settings-export-button")
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.export(export_view.clone(), cx);
+                                    })),
+                            ),
+                    ),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(callout(error).icon(ALERT_CIRCLE))
+            })
+            .child(
+                label(
+                    "settings-export-scan-paths",
+                    tr!("SETTINGS_EXPORT_SCAN_PATHS", "Include library folders"),
+                )
+                .subtext(tr!(
+                    "SETTINGS_EXPORT_SCAN_PATHS_SUBTEXT",
+                    "Folder locations usually differ between computers, so they aren't exported \
+                    by default."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.include_scan_paths = !this.include_scan_paths;
+                    cx.notify();
+                }))
+                .child(checkbox(
+                    "settings-export-scan-paths-check",
+                    self.include_scan_paths,
+                )),
+            )
+            .child(
+                label(
+                    "settings-export-theme",
+                    tr!("SETTINGS_EXPORT_THEME", "Include theme"),
+                )
+                .subtext(tr!(
+                    "SETTINGS_EXPORT_THEME_SUBTEXT",
+                    "Bundles the selected custom theme with the exported settings."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(cx.listener(|this, _, _, cx| {
+                    this.include_theme = !this.include_theme;
+                    cx.notify();
+                }))
+                .child(checkbox("settings-export-theme-check", self.include_theme)),
+            )
+            .child(
+                section_header(tr!("SETTINGS_RESET", "Reset"))
+                    .subtitle(tr!(
+                        "SETTINGS_RESET_SUBTITLE",
+                        "Restores every setting to its default value. Your library folders are \
+                        kept."
+                    ))
+                    .child(
+                        button()
+                            .style(ButtonStyle::Regular)
+                            .intent(ButtonIntent::Danger)
+                            .child(if confirming_reset {
+                                tr!("SETTINGS_RESET_CONFIRM", "Click again to reset")
+                            } else {
+                                tr!("SETTINGS_RESET_BUTTON", "Reset to defaults")
+                            })
+                            .id("settings-reset-button")
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                if this.confirming_reset {
+                                    this.reset(cx);
+                                } else {
+                                    this.confirming_reset = true;
+                                    cx.notify();
+                                }
+                            })),
+                    ),
+            )
+    }
+}
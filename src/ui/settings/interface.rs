@@ -2,16 +2,18 @@ use std::path::PathBuf;
 
 use cntp_i18n::tr;
 use gpui::{
-    App, AppContext, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
-    Window, div, px,
+    App, AppContext, Context, Entity, IntoElement, ParentElement, PathPromptOptions, Render,
+    SharedString, Styled, Window, div, prelude::FluentBuilder, px,
 };
 
 use crate::{
     settings::{
         SettingsGlobal,
         interface::{
-            DEFAULT_GRID_MIN_ITEM_WIDTH, MAX_GRID_MIN_ITEM_WIDTH, MIN_GRID_MIN_ITEM_WIDTH,
-            StartupLibraryView, clamp_grid_min_item_width,
+            ArtistTracks, CloseBehavior, ControlsLayout, DEFAULT_GRID_MIN_ITEM_WIDTH, GridMetadata,
+            ImageCacheSize, ListDensity, MAX_GRID_MIN_ITEM_WIDTH, MIN_GRID_MIN_ITEM_WIDTH,
+            MissingArt, SingleTrackContext, StartupLibraryView, TitlebarDoubleClick,
+            TrackClickAction, WindowTitle, clamp_grid_min_item_width,
         },
         save_settings,
     },
@@ -118,6 +120,33 @@ impl InterfaceSettings {
     }
 }
 
+/// Asks for an image to show for albums without art, and selects it if one is picked.
+fn choose_missing_art(settings: Entity<crate::settings::Settings>, cx: &mut App) {
+    let path_future = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+        prompt: Some(tr!("INTERFACE_MISSING_ART_SELECT", "Select an image...").into()),
+    });
+
+    cx.spawn(async move |cx| {
+        let Ok(Ok(Some(paths))) = path_future.await else {
+            return;
+        };
+        let Some(path) = paths.into_iter().next() else {
+            return;
+        };
+
+        settings.update(cx, move |settings, cx| {
+            settings.interface.missing_art = MissingArt::Custom;
+            settings.interface.missing_art_path = Some(path);
+            save_settings(cx, settings);
+            cx.notify();
+        });
+    })
+    .detach();
+}
+
 impl Render for InterfaceSettings {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let _theme = cx.global::<Theme>();
@@ -184,6 +213,322 @@ impl Render for InterfaceSettings {
                 })
         };
 
+        let list_density_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<ListDensity>("list-density-dropdown")
+                .w(px(250.0))
+                .selected(interface.list_density)
+                .option(
+                    ListDensity::Comfortable,
+                    tr!("INTERFACE_LIST_DENSITY_COMFORTABLE", "Comfortable"),
+                )
+                .option(
+                    ListDensity::Compact,
+                    tr!("INTERFACE_LIST_DENSITY_COMPACT", "Compact"),
+                )
+                .on_change(move |density, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.list_density = *density;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let track_click_action_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<TrackClickAction>("track-click-action-dropdown")
+                .w(px(250.0))
+                .selected(interface.track_click_action)
+                .option(TrackClickAction::PlayFromHere, tr!("PLAY_FROM_HERE"))
+                .option(
+                    TrackClickAction::PlayTrack,
+                    tr!(
+                        "INTERFACE_TRACK_CLICK_ACTION_PLAY_TRACK",
+                        "Play only this track"
+                    ),
+                )
+                .on_change(move |action, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.track_click_action = *action;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let single_track_context_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<SingleTrackContext>("single-track-context-dropdown")
+                .w(px(250.0))
+                .selected(interface.single_track_context)
+                .option(
+                    SingleTrackContext::JustThis,
+                    tr!(
+                        "INTERFACE_SINGLE_TRACK_CONTEXT_JUST_THIS",
+                        "Just this track"
+                    ),
+                )
+                .option(
+                    SingleTrackContext::AllVisible,
+                    tr!(
+                        "INTERFACE_SINGLE_TRACK_CONTEXT_ALL_VISIBLE",
+                        "All visible tracks"
+                    ),
+                )
+                .option(
+                    SingleTrackContext::Album,
+                    tr!("INTERFACE_SINGLE_TRACK_CONTEXT_ALBUM", "The track's album"),
+                )
+                .on_change(move |context, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.single_track_context = *context;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let grid_metadata_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<GridMetadata>("grid-metadata-dropdown")
+                .w(px(250.0))
+                .selected(interface.grid_metadata)
+                .option(
+                    GridMetadata::None,
+                    tr!("INTERFACE_GRID_METADATA_NONE", "Nothing"),
+                )
+                .option(
+                    GridMetadata::Title,
+                    tr!("INTERFACE_GRID_METADATA_TITLE", "Title"),
+                )
+                .option(
+                    GridMetadata::TitleArtist,
+                    tr!("INTERFACE_GRID_METADATA_TITLE_ARTIST", "Title and artist"),
+                )
+                .option(
+                    GridMetadata::TitleArtistYear,
+                    tr!(
+                        "INTERFACE_GRID_METADATA_TITLE_ARTIST_YEAR",
+                        "Title, artist and year"
+                    ),
+                )
+                .on_change(move |metadata, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.grid_metadata = *metadata;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let image_cache_size_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<ImageCacheSize>("image-cache-size-dropdown")
+                .w(px(250.0))
+                .selected(interface.image_cache_size)
+                .option(
+                    ImageCacheSize::Small,
+                    tr!("INTERFACE_IMAGE_CACHE_SIZE_SMALL", "Small"),
+                )
+                .option(
+                    ImageCacheSize::Medium,
+                    tr!("INTERFACE_IMAGE_CACHE_SIZE_MEDIUM", "Medium"),
+                )
+                .option(
+                    ImageCacheSize::Large,
+                    tr!("INTERFACE_IMAGE_CACHE_SIZE_LARGE", "Large"),
+                )
+                .on_change(move |size, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.image_cache_size = *size;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let close_behavior_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<CloseBehavior>("close-behavior-dropdown")
+                .w(px(250.0))
+                .selected(interface.close_behavior)
+                .option(
+                    CloseBehavior::Quit,
+                    tr!("INTERFACE_CLOSE_BEHAVIOR_QUIT", "Quit"),
+                )
+                .option(
+                    CloseBehavior::MinimizeToTray,
+                    tr!("INTERFACE_CLOSE_BEHAVIOR_TRAY", "Minimize to tray"),
+                )
+                .on_change(move |behavior, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.close_behavior = *behavior;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let controls_layout_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<ControlsLayout>("controls-layout-dropdown")
+                .w(px(250.0))
+                .selected(interface.controls_layout)
+                .option(
+                    ControlsLayout::Automatic,
+                    tr!("INTERFACE_CONTROLS_LAYOUT_AUTOMATIC", "Automatic"),
+                )
+                .option(
+                    ControlsLayout::Full,
+                    tr!("INTERFACE_CONTROLS_LAYOUT_FULL", "Full"),
+                )
+                .option(
+                    ControlsLayout::Compact,
+                    tr!("INTERFACE_CONTROLS_LAYOUT_COMPACT", "Compact"),
+                )
+                .on_change(move |layout, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.controls_layout = *layout;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let missing_art_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<MissingArt>("missing-art-dropdown")
+                .w(px(250.0))
+                .selected(interface.missing_art)
+                .option(
+                    MissingArt::Placeholder,
+                    tr!("INTERFACE_MISSING_ART_PLACEHOLDER", "Music note"),
+                )
+                .option(
+                    MissingArt::Custom,
+                    tr!("INTERFACE_MISSING_ART_CUSTOM", "Custom image..."),
+                )
+                .option(
+                    MissingArt::Blank,
+                    tr!("INTERFACE_MISSING_ART_BLANK", "Nothing"),
+                )
+                .on_change(move |missing_art, _, cx| {
+                    // a new image is picked every time, the setting changes once one is chosen
+                    if *missing_art == MissingArt::Custom {
+                        choose_missing_art(settings_c.clone(), cx);
+                        return;
+                    }
+
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.missing_art = *missing_art;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let artist_tracks_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<ArtistTracks>("artist-tracks-dropdown")
+                .w(px(250.0))
+                .selected(interface.artist_tracks)
+                .option(
+                    ArtistTracks::Liked,
+                    tr!("INTERFACE_ARTIST_TRACKS_LIKED", "Liked tracks"),
+                )
+                .option(
+                    ArtistTracks::All,
+                    tr!("INTERFACE_ARTIST_TRACKS_ALL", "All tracks"),
+                )
+                .option(
+                    ArtistTracks::Hidden,
+                    tr!("INTERFACE_ARTIST_TRACKS_HIDDEN", "Nothing"),
+                )
+                .on_change(move |artist_tracks, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.artist_tracks = *artist_tracks;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let missing_art_subtext: SharedString = match &interface.missing_art_path {
+            Some(path) if interface.missing_art == MissingArt::Custom => tr!(
+                "INTERFACE_MISSING_ART_CUSTOM_SUBTEXT",
+                "Albums without art show {{file}}. Covers already on screen change when they are \
+                next loaded.",
+                file = path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .as_ref()
+            )
+            .into(),
+            _ => tr!(
+                "INTERFACE_MISSING_ART_SUBTEXT",
+                "Choose what is shown in place of album art for albums that don't have any. \
+                Covers already on screen change when they are next loaded."
+            )
+            .into(),
+        };
+
+        let window_title_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<WindowTitle>("window-title-dropdown")
+                .w(px(250.0))
+                .selected(interface.window_title)
+                .option(
+                    WindowTitle::AppName,
+                    tr!("INTERFACE_WINDOW_TITLE_APP_NAME", "App name"),
+                )
+                .option(
+                    WindowTitle::Track,
+                    tr!("INTERFACE_WINDOW_TITLE_TRACK", "Current track"),
+                )
+                .option(
+                    WindowTitle::TrackAndPosition,
+                    tr!(
+                        "INTERFACE_WINDOW_TITLE_TRACK_AND_POSITION",
+                        "Current track and position"
+                    ),
+                )
+                .on_change(move |title, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.window_title = *title;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let titlebar_double_click_dropdown = {
+            let settings_c = settings.clone();
+            dropdown::<TitlebarDoubleClick>("titlebar-double-click-dropdown")
+                .w(px(250.0))
+                .selected(interface.titlebar_double_click)
+                .option(
+                    TitlebarDoubleClick::Maximize,
+                    tr!("INTERFACE_TITLEBAR_DOUBLE_CLICK_MAXIMIZE", "Maximize"),
+                )
+                .option(
+                    TitlebarDoubleClick::Minimize,
+                    tr!("INTERFACE_TITLEBAR_DOUBLE_CLICK_MINIMIZE", "Minimize"),
+                )
+                .option(
+                    TitlebarDoubleClick::Nothing,
+                    tr!("INTERFACE_TITLEBAR_DOUBLE_CLICK_NOTHING", "Do nothing"),
+                )
+                .on_change(move |action, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.interface.titlebar_double_click = *action;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+        };
+
         div()
             .flex()
             .flex_col()
@@ -221,6 +566,75 @@ impl Render for InterfaceSettings {
                 .w_full()
                 .child(startup_view_dropdown),
             )
+            .child(
+                label(
+                    "close-behavior-selector",
+                    tr!("INTERFACE_CLOSE_BEHAVIOR", "When closing the window"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_CLOSE_BEHAVIOR_SUBTEXT",
+                    "Minimizing to tray keeps music playing and shows Hummingbird in the system \
+                    tray. Use Quit in the tray menu to exit."
+                ))
+                .w_full()
+                .child(close_behavior_dropdown),
+            )
+            .child(
+                label(
+                    "window-title-selector",
+                    tr!("INTERFACE_WINDOW_TITLE", "Window title"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_WINDOW_TITLE_SUBTEXT",
+                    "Choose what the title of the window shows, such as in the taskbar. This can \
+                    be read by streaming overlays and other programs."
+                ))
+                .w_full()
+                .child(window_title_dropdown),
+            )
+            .when(cfg!(not(target_os = "windows")), |this| {
+                this.child(
+                    label(
+                        "titlebar-double-click-selector",
+                        tr!(
+                            "INTERFACE_TITLEBAR_DOUBLE_CLICK",
+                            "When double-clicking the titlebar"
+                        ),
+                    )
+                    .subtext(tr!(
+                        "INTERFACE_TITLEBAR_DOUBLE_CLICK_SUBTEXT",
+                        "Choose what double-clicking the top of the window does."
+                    ))
+                    .w_full()
+                    .child(titlebar_double_click_dropdown),
+                )
+            })
+            .child(
+                label(
+                    "track-click-action-selector",
+                    tr!("INTERFACE_TRACK_CLICK_ACTION", "When clicking a track"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_TRACK_CLICK_ACTION_SUBTEXT",
+                    "Play from here replaces the queue with the album or playlist, starting at \
+                    the clicked track. Play only this track adds it to the end of the queue."
+                ))
+                .w_full()
+                .child(track_click_action_dropdown),
+            )
+            .child(
+                label(
+                    "single-track-context-selector",
+                    tr!("INTERFACE_SINGLE_TRACK_CONTEXT", "When clicking in the Tracks list"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_SINGLE_TRACK_CONTEXT_SUBTEXT",
+                    "Choose what is queued when you click a track in the Tracks list. Playback \
+                    starts at the clicked track."
+                ))
+                .w_full()
+                .child(single_track_context_dropdown),
+            )
             .child({
                 let full_width_label = label(
                     "interface-full-width-library",
@@ -297,6 +711,78 @@ impl Render for InterfaceSettings {
                         }),
                 ),
             )
+            .child(
+                label(
+                    "grid-metadata-selector",
+                    tr!("INTERFACE_GRID_METADATA", "Show under album covers"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_GRID_METADATA_SUBTEXT",
+                    "Choose which details are shown under covers in album grids. The artist is \
+                    left out on artist pages."
+                ))
+                .w_full()
+                .child(grid_metadata_dropdown),
+            )
+            .child(
+                label(
+                    "list-density-selector",
+                    tr!("INTERFACE_LIST_DENSITY", "List density"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_LIST_DENSITY_SUBTEXT",
+                    "Compact rows fit more tracks on screen in tables and track lists."
+                ))
+                .w_full()
+                .child(list_density_dropdown),
+            )
+            .child(
+                label(
+                    "artist-tracks-selector",
+                    tr!("INTERFACE_ARTIST_TRACKS", "Tracks on artist pages"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_ARTIST_TRACKS_SUBTEXT",
+                    "Choose which of an artist's tracks are listed below their albums."
+                ))
+                .w_full()
+                .child(artist_tracks_dropdown),
+            )
+            .child(
+                label(
+                    "missing-art-selector",
+                    tr!("INTERFACE_MISSING_ART", "Albums without art"),
+                )
+                .subtext(missing_art_subtext)
+                .w_full()
+                .child(missing_art_dropdown),
+            )
+            .child(
+                label(
+                    "controls-layout-selector",
+                    tr!("INTERFACE_CONTROLS_LAYOUT", "Playback controls layout"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_CONTROLS_LAYOUT_SUBTEXT",
+                    "The compact layout narrows the controls and moves the queue, lyrics and \
+                    ReplayGain buttons into a menu. Automatic uses it in narrow windows."
+                ))
+                .w_full()
+                .child(controls_layout_dropdown),
+            )
+            .child(
+                label(
+                    "image-cache-size-selector",
+                    tr!("INTERFACE_IMAGE_CACHE_SIZE", "Album art memory cache"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_IMAGE_CACHE_SIZE_SUBTEXT",
+                    "A larger cache makes scrolling through big libraries smoother, but uses more \
+                    memory. A smaller cache is better for computers with little memory."
+                ))
+                .w_full()
+                .child(image_cache_size_dropdown),
+            )
             .child(
                 label(
                     "interface-always-show-scrollbars",
@@ -318,5 +804,92 @@ impl Render for InterfaceSettings {
                     interface.always_show_scrollbars,
                 )),
             )
+            .child(
+                label(
+                    "interface-show-jump-bar",
+                    tr!("INTERFACE_SHOW_JUMP_BAR", "Show jump-to-letter bar"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_SHOW_JUMP_BAR_SUBTEXT",
+                    "Shows an A–Z bar next to the Albums, Tracks and Artists lists while they are \
+                    sorted by name."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.update_interface(cx, |interface| {
+                        interface.show_jump_bar = !interface.show_jump_bar;
+                    });
+                }))
+                .child(checkbox(
+                    "interface-show-jump-bar-check",
+                    interface.show_jump_bar,
+                )),
+            )
+            .child(
+                label(
+                    "interface-show-format-badges",
+                    tr!("INTERFACE_SHOW_FORMAT_BADGES", "Show file formats"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_SHOW_FORMAT_BADGES_SUBTEXT",
+                    "Shows a badge like FLAC or MP3 320 next to tracks on album and playlist \
+                    pages. The Tracks list has a Format column for this instead."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.update_interface(cx, |interface| {
+                        interface.show_format_badges = !interface.show_format_badges;
+                    });
+                }))
+                .child(checkbox(
+                    "interface-show-format-badges-check",
+                    interface.show_format_badges,
+                )),
+            )
+            .child(
+                label(
+                    "interface-seek-while-dragging",
+                    tr!("INTERFACE_SEEK_WHILE_DRAGGING", "Seek while dragging"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_SEEK_WHILE_DRAGGING_SUBTEXT",
+                    "Follows the seek bar while it's being dragged. When off, playback only \
+                    jumps once the seek bar is released."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.update_interface(cx, |interface| {
+                        interface.seek_while_dragging = !interface.seek_while_dragging;
+                    });
+                }))
+                .child(checkbox(
+                    "interface-seek-while-dragging-check",
+                    interface.seek_while_dragging,
+                )),
+            )
+            .child(
+                label(
+                    "interface-show-waveform",
+                    tr!("INTERFACE_SHOW_WAVEFORM", "Show waveform"),
+                )
+                .subtext(tr!(
+                    "INTERFACE_SHOW_WAVEFORM_SUBTEXT",
+                    "Shows an overview of the current track's loudness above the seek bar."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.update_interface(cx, |interface| {
+                        interface.show_waveform = !interface.show_waveform;
+                    });
+                }))
+                .child(checkbox(
+                    "interface-show-waveform-check",
+                    interface.show_waveform,
+                )),
+            )
     }
 }
@@ -1,5 +1,5 @@
 use camino::{Utf8Path, Utf8PathBuf};
-use cntp_i18n::tr;
+use cntp_i18n::{tr, trn};
 use gpui::{
     App, AppContext, Context, Entity, InteractiveElement, IntoElement, ParentElement,
     PathPromptOptions, Render, SharedString, Styled, WeakEntity, Window, div,
@@ -25,36 +25,48 @@ fn merge_scan_paths(
 }
 
 use crate::{
-    library::scan::ScanInterface,
-    settings::{Settings, SettingsGlobal, save_settings, scan::MissingFolderPolicy},
+    library::scan::{ScanEvent, ScanInterface},
+    settings::{
+        Settings, SettingsGlobal, save_settings,
+        scan::{ArtPriority, ArtStorage, MissingFolderPolicy},
+    },
     ui::{
         components::{
             button::{ButtonIntent, ButtonStyle, button},
             callout::callout,
+            checkbox::checkbox,
             dropdown::dropdown,
             icons::{ALERT_CIRCLE, CIRCLE_PLUS, FOLDER_SEARCH, TRASH, icon},
             label::label,
             section_header::section_header,
         },
+        models::Models,
         theme::Theme,
     },
 };
 
 pub struct LibrarySettings {
     settings: Entity<Settings>,
+    scan_state: Entity<ScanEvent>,
     scanning_modified: bool,
+    confirming_force_scan: bool,
 }
 
 impl LibrarySettings {
     pub fn new(cx: &mut App) -> Entity<Self> {
         let settings = cx.global::<SettingsGlobal>().model.clone();
 
+        let scan_state = cx.global::<Models>().scan_state.clone();
+
         cx.new(|cx| {
             cx.observe(&settings, |_, _, cx| cx.notify()).detach();
+            cx.observe(&scan_state, |_, _, cx| cx.notify()).detach();
 
             Self {
                 settings,
+                scan_state,
                 scanning_modified: false,
+                confirming_force_scan: false,
             }
         })
     }
@@ -135,6 +147,11 @@ impl Render for LibrarySettings {
         let view = cx.entity().downgrade();
         let scanning = self.settings.read(cx).scanning.clone();
         let paths = scanning.paths;
+        let is_scanning = !matches!(
+            self.scan_state.read(cx),
+            ScanEvent::ScanCompleteIdle | ScanEvent::ScanCompleteWatching
+        );
+        let confirming_force_scan = self.confirming_force_scan;
 
         let list = if paths.is_empty() {
             div()
@@ -219,22 +236,71 @@ impl Render for LibrarySettings {
                     "Changes apply on your next scan. Duplicate folders are ignored."
                 ))
                 .child(
-                    button()
-                        .style(ButtonStyle::Regular)
-                        .intent(ButtonIntent::Primary)
+                    div()
+                        .flex()
+                        .gap(px(8.0))
+                        .child(if is_scanning {
+                            button()
+                                .style(ButtonStyle::Regular)
+                                .intent(ButtonIntent::Secondary)
+                                .child(tr!("SCANNING_STOP", "Stop Scan"))
+                                .id("library-settings-stop-scan")
+                                .on_click(|_, _, cx| {
+                                    cx.global::<ScanInterface>().stop();
+                                })
+                        } else {
+                            button()
+                                .style(ButtonStyle::Regular)
+                                .intent(ButtonIntent::Secondary)
+                                .child(tr!("SCANNING_SCAN_NOW", "Scan Now"))
+                                .id("library-settings-scan-now")
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.scanning_modified = false;
+                                    cx.global::<ScanInterface>().scan();
+                                    cx.notify();
+                                }))
+                        })
                         .child(
-                            div()
-                                .flex()
-                                .gap(px(6.0))
-                                .child(icon(CIRCLE_PLUS).my_auto().size(px(14.0)))
-                                .child(tr!("SCANNING_ADD_FOLDERS", "Add Folders")),
-                        )
-                        .id("library-settings-add-folder")
-                        .on_click(cx.listener(move |this, _, _, cx| {
-                            this.add_folder(view.clone(), cx);
-                        })),
+                            button()
+                                .style(ButtonStyle::Regular)
+                                .intent(ButtonIntent::Primary)
+                                .child(
+                                    div()
+                                        .flex()
+                                        .gap(px(6.0))
+                                        .child(icon(CIRCLE_PLUS).my_auto().size(px(14.0)))
+                                        .child(tr!("SCANNING_ADD_FOLDERS", "Add Folders")),
+                                )
+                                .id("library-settings-add-folder")
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.add_folder(view.clone(), cx);
+                                })),
+                        ),
                 ),
             )
+            .child({
+                let settings_c = self.settings.clone();
+
+                label(
+                    "scan-on-startup",
+                    tr!("SCANNING_SCAN_ON_STARTUP", "Scan on startup"),
+                )
+                .subtext(tr!(
+                    "SCANNING_SCAN_ON_STARTUP_SUBTEXT",
+                    "Turn this off for large libraries that rarely change. You can still scan \
+                    from here or from the Library menu."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(move |_, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.scanning.scan_on_startup = !s.scanning.scan_on_startup;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+                .child(checkbox("scan-on-startup-check", scanning.scan_on_startup))
+            })
             .child(
                 label(
                     "missing-folder-policy",
@@ -275,6 +341,224 @@ impl Render for LibrarySettings {
                         })
                 }),
             )
+            .child(
+                label(
+                    "file-read-timeout",
+                    tr!("SCANNING_FILE_READ_TIMEOUT", "File read timeout"),
+                )
+                .subtext(tr!(
+                    "SCANNING_FILE_READ_TIMEOUT_SUBTEXT",
+                    "Skips files that take too long to read, so that a damaged file can't stall \
+                    the scan. Skipped files are retried when they change."
+                ))
+                .w_full()
+                .child({
+                    let settings_c = self.settings.clone();
+                    let mut dd = dropdown::<u64>("file-read-timeout-dropdown")
+                        .w(px(250.0))
+                        .selected(scanning.file_read_timeout)
+                        .option(0, tr!("SCANNING_FILE_READ_TIMEOUT_NEVER", "Never"))
+                        .on_change(move |timeout, _, cx| {
+                            settings_c.update(cx, |s, cx| {
+                                s.scanning.file_read_timeout = *timeout;
+                                save_settings(cx, s);
+                                cx.notify();
+                            });
+                        });
+
+                    for seconds in [10, 30, 60, 120] {
+                        dd = dd.option(
+                            seconds,
+                            trn!(
+                                "SCANNING_FILE_READ_TIMEOUT_SECONDS",
+                                "{{count}} second",
+                                "{{count}} seconds",
+                                count = seconds
+                            ),
+                        );
+                    }
+
+                    dd
+                }),
+            )
+            .child(
+                label(
+                    "record-write-interval",
+                    tr!("SCANNING_RECORD_WRITE_INTERVAL", "Save scan progress"),
+                )
+                .subtext(tr!(
+                    "SCANNING_RECORD_WRITE_INTERVAL_SUBTEXT",
+                    "Saving less often reduces disk writes if you scan frequently. Progress is \
+                    always saved when Hummingbird closes."
+                ))
+                .w_full()
+                .child({
+                    let settings_c = self.settings.clone();
+                    let mut dd = dropdown::<u64>("record-write-interval-dropdown")
+                        .w(px(250.0))
+                        .selected(scanning.record_write_interval)
+                        .option(
+                            0,
+                            tr!(
+                                "SCANNING_RECORD_WRITE_INTERVAL_EVERY_SCAN",
+                                "After every scan"
+                            ),
+                        )
+                        .on_change(move |interval, _, cx| {
+                            settings_c.update(cx, |s, cx| {
+                                s.scanning.record_write_interval = *interval;
+                                save_settings(cx, s);
+                                cx.notify();
+                            });
+                        });
+
+                    for minutes in [5, 15, 60] {
+                        dd = dd.option(
+                            minutes,
+                            trn!(
+                                "SCANNING_RECORD_WRITE_INTERVAL_MINUTES",
+                                "At most every minute",
+                                "At most every {{count}} minutes",
+                                count = minutes
+                            ),
+                        );
+                    }
+
+                    dd
+                }),
+            )
+            .child({
+                let settings_c = self.settings.clone();
+
+                label(
+                    "generate-thumbnails",
+                    tr!(
+                        "SCANNING_GENERATE_THUMBNAILS",
+                        "Generate album art thumbnails"
+                    ),
+                )
+                .subtext(tr!(
+                    "SCANNING_GENERATE_THUMBNAILS_SUBTEXT",
+                    "Turning this off makes scanning faster and the library smaller, but lists \
+                    use more memory while scrolling. Applies to albums added by the next scan."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(move |_, _, cx| {
+                    settings_c.update(cx, |s, cx| {
+                        s.scanning.generate_thumbnails = !s.scanning.generate_thumbnails;
+                        save_settings(cx, s);
+                        cx.notify();
+                    });
+                })
+                .child(checkbox(
+                    "generate-thumbnails-check",
+                    scanning.generate_thumbnails,
+                ))
+            })
+            .child(
+                label(
+                    "art-priority",
+                    tr!("SCANNING_ART_PRIORITY", "Preferred album art"),
+                )
+                .subtext(tr!(
+                    "SCANNING_ART_PRIORITY_SUBTEXT",
+                    "Which art to use when a file has embedded art and its folder also has a cover \
+                    image, like cover.jpg. The next scan reads every file again to apply this."
+                ))
+                .w_full()
+                .child({
+                    let settings_c = self.settings.clone();
+                    dropdown::<ArtPriority>("art-priority-dropdown")
+                        .w(px(250.0))
+                        .selected(scanning.art_priority)
+                        .option(
+                            ArtPriority::EmbeddedFirst,
+                            tr!("SCANNING_ART_PRIORITY_EMBEDDED", "Embedded art"),
+                        )
+                        .option(
+                            ArtPriority::FolderFirst,
+                            tr!("SCANNING_ART_PRIORITY_FOLDER", "Folder cover image"),
+                        )
+                        .on_change(move |priority, _, cx| {
+                            settings_c.update(cx, |s, cx| {
+                                s.scanning.art_priority = *priority;
+                                save_settings(cx, s);
+                                cx.notify();
+                            });
+                        })
+                }),
+            )
+            .child(
+                label(
+                    "art-storage",
+                    tr!("SCANNING_ART_STORAGE", "Store album art"),
+                )
+                .subtext(tr!(
+                    "SCANNING_ART_STORAGE_SUBTEXT",
+                    "Storing art as separate files keeps the library database small and quick to \
+                    back up. Existing art is moved during the next scan."
+                ))
+                .w_full()
+                .child({
+                    let settings_c = self.settings.clone();
+                    dropdown::<ArtStorage>("art-storage-dropdown")
+                        .w(px(250.0))
+                        .selected(scanning.art_storage)
+                        .option(
+                            ArtStorage::Database,
+                            tr!("SCANNING_ART_STORAGE_DATABASE", "In the library database"),
+                        )
+                        .option(
+                            ArtStorage::Files,
+                            tr!("SCANNING_ART_STORAGE_FILES", "As separate files"),
+                        )
+                        .on_change(move |storage, _, cx| {
+                            settings_c.update(cx, |s, cx| {
+                                s.scanning.art_storage = *storage;
+                                save_settings(cx, s);
+                                cx.notify();
+                            });
+                        })
+                }),
+            )
+            .child(
+                label(
+                    "force-rescan",
+                    tr!("SCANNING_FORCE_RESCAN", "Rescan entire library"),
+                )
+                .subtext(tr!(
+                    "SCANNING_FORCE_RESCAN_SUBTEXT",
+                    "Reads every file again and rebuilds all album information, which can fix \
+                    wrong metadata or art. This can take a long time for large libraries."
+                ))
+                .w_full()
+                .child(
+                    button()
+                        .style(ButtonStyle::Regular)
+                        .intent(ButtonIntent::Warning)
+                        .child(if confirming_force_scan {
+                            tr!("SCANNING_FORCE_RESCAN_CONFIRM", "Click again to rescan")
+                        } else {
+                            tr!("SCANNING_FORCE_RESCAN_BUTTON", "Rescan")
+                        })
+                        .id("library-settings-force-rescan")
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            if this.confirming_force_scan {
+                                this.confirming_force_scan = false;
+                                this.scanning_modified = false;
+
+                                let interface = cx.global::<ScanInterface>();
+                                interface.stop();
+                                interface.force_scan();
+                            } else {
+                                this.confirming_force_scan = true;
+                            }
+
+                            cx.notify();
+                        })),
+                ),
+            )
             .when(self.scanning_modified, |this| {
                 this.child(
                     callout(tr!(
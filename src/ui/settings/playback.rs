@@ -1,13 +1,21 @@
-use cntp_i18n::tr;
+use cntp_i18n::{tr, trn};
 use gpui::{
     App, AppContext, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
     Window, div, px,
 };
 
 use crate::{
-    settings::{Settings, SettingsGlobal, save_settings},
+    media::pipeline::DEFAULT_BUFFER_FRAMES,
+    settings::{
+        Settings, SettingsGlobal,
+        playback::{
+            MAX_BUFFER_FRAMES, MIN_BUFFER_FRAMES, QueueOverflow, ReplayBehavior, ResampleQuality,
+            ResamplerThreading, ShuffleScope,
+        },
+        save_settings,
+    },
     ui::components::{
-        checkbox::checkbox, label::label, labeled_slider::labeled_slider,
+        checkbox::checkbox, dropdown::dropdown, label::label, labeled_slider::labeled_slider,
         section_header::section_header,
     },
 };
@@ -44,6 +52,277 @@ impl Render for PlaybackSettings {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let playback = self.settings.read(cx).playback.clone();
 
+        let resampler_threading_dropdown = {
+            let settings = self.settings.clone();
+            dropdown::<ResamplerThreading>("playback-resampler-threading-dropdown")
+                .w(px(250.0))
+                .selected(playback.resampler_threading)
+                .option(
+                    ResamplerThreading::PlaybackThread,
+                    tr!(
+                        "PLAYBACK_RESAMPLER_THREADING_PLAYBACK_THREAD",
+                        "Playback thread"
+                    ),
+                )
+                .option(
+                    ResamplerThreading::Worker,
+                    tr!("PLAYBACK_RESAMPLER_THREADING_WORKER", "Dedicated thread"),
+                )
+                .option(
+                    ResamplerThreading::Auto,
+                    tr!("PLAYBACK_RESAMPLER_THREADING_AUTO", "Automatic"),
+                )
+                .on_change(move |threading, _, cx| {
+                    settings.update(cx, |settings, cx| {
+                        settings.playback.resampler_threading = *threading;
+                        save_settings(cx, settings);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let resample_quality_dropdown = {
+            let settings = self.settings.clone();
+            dropdown::<ResampleQuality>("playback-resample-quality-dropdown")
+                .w(px(250.0))
+                .selected(playback.resample_quality)
+                .option(
+                    ResampleQuality::Fast,
+                    tr!("PLAYBACK_RESAMPLE_QUALITY_FAST", "Fast"),
+                )
+                .option(
+                    ResampleQuality::Balanced,
+                    tr!("PLAYBACK_RESAMPLE_QUALITY_BALANCED", "Balanced"),
+                )
+                .option(
+                    ResampleQuality::High,
+                    tr!("PLAYBACK_RESAMPLE_QUALITY_HIGH", "High quality"),
+                )
+                .on_change(move |quality, _, cx| {
+                    settings.update(cx, |settings, cx| {
+                        settings.playback.resample_quality = *quality;
+                        save_settings(cx, settings);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let buffer_frames_dropdown = {
+            let settings = self.settings.clone();
+            let mut dd = dropdown::<usize>("playback-buffer-frames-dropdown")
+                .w(px(250.0))
+                .selected(playback.normalized_buffer_frames())
+                .on_change(move |frames, _, cx| {
+                    settings.update(cx, |settings, cx| {
+                        settings.playback.buffer_frames = *frames;
+                        save_settings(cx, settings);
+                        cx.notify();
+                    });
+                });
+
+            let mut frames = MIN_BUFFER_FRAMES;
+            while frames <= MAX_BUFFER_FRAMES {
+                let label: SharedString = if frames == DEFAULT_BUFFER_FRAMES {
+                    tr!(
+                        "PLAYBACK_BUFFER_FRAMES_DEFAULT",
+                        "{{frames}} frames (default)",
+                        frames = frames
+                    )
+                    .into()
+                } else {
+                    trn!(
+                        "PLAYBACK_BUFFER_FRAMES_OPTION",
+                        "{{count}} frame",
+                        "{{count}} frames",
+                        count = frames
+                    )
+                    .into()
+                };
+                dd = dd.option(frames, label);
+                frames *= 2;
+            }
+
+            dd
+        };
+
+        let release_device_dropdown = {
+            let settings = self.settings.clone();
+            let mut dd = dropdown::<u64>("playback-release-device-dropdown")
+                .w(px(250.0))
+                .selected(playback.release_device_after)
+                .option(0, tr!("PLAYBACK_RELEASE_DEVICE_NEVER", "Never"))
+                .on_change(move |seconds, _, cx| {
+                    settings.update(cx, |settings, cx| {
+                        settings.playback.release_device_after = *seconds;
+                        save_settings(cx, settings);
+                        cx.notify();
+                    });
+                });
+
+            for seconds in [10, 30] {
+                dd = dd.option(
+                    seconds,
+                    trn!(
+                        "PLAYBACK_RELEASE_DEVICE_SECONDS",
+                        "After {{count}} second",
+                        "After {{count}} seconds",
+                        count = seconds
+                    ),
+                );
+            }
+
+            for minutes in [1, 5, 15] {
+                dd = dd.option(
+                    minutes * 60,
+                    trn!(
+                        "PLAYBACK_RELEASE_DEVICE_MINUTES",
+                        "After {{count}} minute",
+                        "After {{count}} minutes",
+                        count = minutes
+                    ),
+                );
+            }
+
+            dd
+        };
+
+        let max_queue_length_dropdown = {
+            let settings = self.settings.clone();
+            let mut dd = dropdown::<Option<usize>>("playback-max-queue-length-dropdown")
+                .w(px(250.0))
+                .selected(playback.max_queue_length)
+                .option(
+                    None,
+                    tr!("PLAYBACK_MAX_QUEUE_LENGTH_UNLIMITED", "Unlimited"),
+                )
+                .on_change(move |length, _, cx| {
+                    settings.update(cx, |settings, cx| {
+                        settings.playback.max_queue_length = *length;
+                        save_settings(cx, settings);
+                        cx.notify();
+                    });
+                });
+
+            for length in [500, 1000, 5000, 10000] {
+                dd = dd.option(
+                    Some(length),
+                    trn!(
+                        "PLAYBACK_MAX_QUEUE_LENGTH_OPTION",
+                        "{{count}} track",
+                        "{{count}} tracks",
+                        count = length
+                    ),
+                );
+            }
+
+            dd
+        };
+
+        let queue_overflow_dropdown = {
+            let settings = self.settings.clone();
+            dropdown::<QueueOverflow>("playback-queue-overflow-dropdown")
+                .w(px(250.0))
+                .selected(playback.queue_overflow)
+                .option(
+                    QueueOverflow::DropPlayed,
+                    tr!(
+                        "PLAYBACK_QUEUE_OVERFLOW_DROP_PLAYED",
+                        "Remove played tracks"
+                    ),
+                )
+                .option(
+                    QueueOverflow::Reject,
+                    tr!("PLAYBACK_QUEUE_OVERFLOW_REJECT", "Don't add more tracks"),
+                )
+                .on_change(move |overflow, _, cx| {
+                    settings.update(cx, |settings, cx| {
+                        settings.playback.queue_overflow = *overflow;
+                        save_settings(cx, settings);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let shuffle_scope_dropdown = {
+            let settings = self.settings.clone();
+            dropdown::<ShuffleScope>("playback-shuffle-scope-dropdown")
+                .w(px(250.0))
+                .selected(playback.shuffle_scope)
+                .option(
+                    ShuffleScope::Upcoming,
+                    tr!("PLAYBACK_SHUFFLE_SCOPE_UPCOMING", "Upcoming tracks"),
+                )
+                .option(
+                    ShuffleScope::Entire,
+                    tr!("PLAYBACK_SHUFFLE_SCOPE_ENTIRE", "Entire queue"),
+                )
+                .on_change(move |scope, _, cx| {
+                    settings.update(cx, |settings, cx| {
+                        settings.playback.shuffle_scope = *scope;
+                        save_settings(cx, settings);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let replay_behavior_dropdown = {
+            let settings = self.settings.clone();
+            dropdown::<ReplayBehavior>("playback-replay-behavior-dropdown")
+                .w(px(250.0))
+                .selected(playback.replay_behavior)
+                .option(
+                    ReplayBehavior::Continue,
+                    tr!("PLAYBACK_REPLAY_BEHAVIOR_CONTINUE", "Keep playing"),
+                )
+                .option(
+                    ReplayBehavior::Restart,
+                    tr!(
+                        "PLAYBACK_REPLAY_BEHAVIOR_RESTART",
+                        "Start from the first track"
+                    ),
+                )
+                .option(
+                    ReplayBehavior::Replace,
+                    tr!("PLAYBACK_REPLAY_BEHAVIOR_REPLACE", "Rebuild the queue"),
+                )
+                .on_change(move |behavior, _, cx| {
+                    settings.update(cx, |settings, cx| {
+                        settings.playback.replay_behavior = *behavior;
+                        save_settings(cx, settings);
+                        cx.notify();
+                    });
+                })
+        };
+
+        let confirm_queue_discard_dropdown = {
+            let settings = self.settings.clone();
+            let mut dd = dropdown::<Option<usize>>("playback-confirm-queue-discard-dropdown")
+                .w(px(250.0))
+                .selected(playback.confirm_queue_discard_over)
+                .option(None, tr!("PLAYBACK_CONFIRM_QUEUE_DISCARD_NEVER", "Never"))
+                .on_change(move |threshold, _, cx| {
+                    settings.update(cx, |settings, cx| {
+                        settings.playback.confirm_queue_discard_over = *threshold;
+                        save_settings(cx, settings);
+                        cx.notify();
+                    });
+                });
+
+            for threshold in [25, 50, 100, 200] {
+                dd = dd.option(
+                    Some(threshold),
+                    trn!(
+                        "PLAYBACK_CONFIRM_QUEUE_DISCARD_OPTION",
+                        "Over {{count}} track",
+                        "Over {{count}} tracks",
+                        count = threshold
+                    ),
+                );
+            }
+
+            dd
+        };
+
         div()
             .flex()
             .flex_col()
@@ -91,6 +370,28 @@ impl Render for PlaybackSettings {
                     playback.prev_track_jump_first,
                 )),
             )
+            .child(
+                label(
+                    "playback-crossfade-on-manual-skip",
+                    tr!("PLAYBACK_CROSSFADE_ON_MANUAL_SKIP", "Fade when skipping"),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_CROSSFADE_ON_MANUAL_SKIP_SUBTEXT",
+                    "Fades out and back in when jumping to another track or seeking, instead of \
+                    cutting abruptly."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.update_playback(cx, |playback| {
+                        playback.crossfade_on_manual_skip = !playback.crossfade_on_manual_skip;
+                    });
+                }))
+                .child(checkbox(
+                    "playback-crossfade-on-manual-skip-check",
+                    playback.crossfade_on_manual_skip,
+                )),
+            )
             .child(
                 label(
                     "playback-keep-current-on-clear",
@@ -116,6 +417,153 @@ impl Render for PlaybackSettings {
                     playback.keep_current_on_queue_clear,
                 )),
             )
+            .child(
+                label(
+                    "playback-resume-on-startup",
+                    tr!("PLAYBACK_RESUME_ON_STARTUP", "Resume playback on startup"),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_RESUME_ON_STARTUP_SUBTEXT",
+                    "Starts playing the last played track when Hummingbird is opened, instead \
+                    of opening it paused."
+                ))
+                .cursor_pointer()
+                .w_full()
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.update_playback(cx, |playback| {
+                        playback.resume_on_startup = !playback.resume_on_startup;
+                    });
+                }))
+                .child(checkbox(
+                    "playback-resume-on-startup-check",
+                    playback.resume_on_startup,
+                )),
+            )
+            .child(
+                label(
+                    "playback-max-queue-length",
+                    tr!("PLAYBACK_MAX_QUEUE_LENGTH", "Queue size limit"),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_MAX_QUEUE_LENGTH_SUBTEXT",
+                    "The most tracks the queue can hold."
+                ))
+                .w_full()
+                .child(max_queue_length_dropdown),
+            )
+            .child(
+                label(
+                    "playback-queue-overflow",
+                    tr!("PLAYBACK_QUEUE_OVERFLOW", "When the queue is full"),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_QUEUE_OVERFLOW_SUBTEXT",
+                    "Removing played tracks starts with the oldest ones. Tracks that still don't \
+                    fit aren't added."
+                ))
+                .w_full()
+                .child(queue_overflow_dropdown),
+            )
+            .child(
+                label(
+                    "playback-shuffle-scope",
+                    tr!("PLAYBACK_SHUFFLE_SCOPE", "When turning on shuffle, shuffle"),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_SHUFFLE_SCOPE_SUBTEXT",
+                    "Shuffling the entire queue moves the current track to the start. Turning \
+                    shuffle off always restores the original order."
+                ))
+                .w_full()
+                .child(shuffle_scope_dropdown),
+            )
+            .child(
+                label(
+                    "playback-replay-behavior",
+                    tr!(
+                        "PLAYBACK_REPLAY_BEHAVIOR",
+                        "When playing what's already in the queue"
+                    ),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_REPLAY_BEHAVIOR_SUBTEXT",
+                    "Applies when you play the same tracks again, such as by clicking Play on \
+                    the album that's already playing."
+                ))
+                .w_full()
+                .child(replay_behavior_dropdown),
+            )
+            .child(
+                label(
+                    "playback-confirm-queue-discard",
+                    tr!(
+                        "PLAYBACK_CONFIRM_QUEUE_DISCARD",
+                        "Ask before replacing or clearing the queue"
+                    ),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_CONFIRM_QUEUE_DISCARD_SUBTEXT",
+                    "Only asks when the queue holds more tracks than this. You can add the new \
+                    tracks to the queue instead."
+                ))
+                .w_full()
+                .child(confirm_queue_discard_dropdown),
+            )
+            .child(
+                label(
+                    "playback-resampler-threading",
+                    tr!("PLAYBACK_RESAMPLER_THREADING", "Resampling thread"),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_RESAMPLER_THREADING_SUBTEXT",
+                    "Resampling on a dedicated thread can prevent stuttering with high sample \
+                    rate files on slower computers. Automatic uses a dedicated thread on systems \
+                    with 4 or more CPU threads. Applies to the next track."
+                ))
+                .w_full()
+                .child(resampler_threading_dropdown),
+            )
+            .child(
+                label(
+                    "playback-resample-quality",
+                    tr!("PLAYBACK_RESAMPLE_QUALITY", "Resampling quality"),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_RESAMPLE_QUALITY_SUBTEXT",
+                    "Used when a track's sample rate doesn't match your device's. Higher quality \
+                    uses more CPU time."
+                ))
+                .w_full()
+                .child(resample_quality_dropdown),
+            )
+            .child(
+                label(
+                    "playback-buffer-frames",
+                    tr!("PLAYBACK_BUFFER_FRAMES", "Buffer size"),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_BUFFER_FRAMES_SUBTEXT",
+                    "Larger buffers can prevent stuttering, but make pausing and seeking slower \
+                    to respond. Smaller buffers respond faster, but may cause dropouts. Applies \
+                    to the next track."
+                ))
+                .w_full()
+                .child(buffer_frames_dropdown),
+            )
+            .child(
+                label(
+                    "playback-release-device",
+                    tr!("PLAYBACK_RELEASE_DEVICE", "Release audio device when idle"),
+                )
+                .subtext(tr!(
+                    "PLAYBACK_RELEASE_DEVICE_SUBTEXT",
+                    "Closes the audio device while playback is stopped or paused, so other \
+                    applications can use it and your computer can sleep. Resuming may take a \
+                    moment longer."
+                ))
+                .w_full()
+                .child(release_device_dropdown),
+            )
             .child({
                 let settings = self.settings.clone();
                 label(
@@ -52,7 +52,10 @@ pub struct Theme {
     pub queue_item: Rgba,
     pub queue_item_hover: Rgba,
     pub queue_item_active: Rgba,
-    pub queue_item_current: Rgba,
+
+    /// The background of the row of the track that is playing, wherever it is listed.
+    #[serde(alias = "queue_item_current")]
+    pub now_playing_highlight: Rgba,
 
     pub button_primary: Rgba,
     pub button_primary_border: Rgba,
@@ -171,7 +174,8 @@ impl Default for Theme {
             queue_item: rgba(0x00000000),
             queue_item_hover: rgb(0x151621),
             queue_item_active: rgb(0x101118),
-            queue_item_current: rgb(0x1B1C28),
+
+            now_playing_highlight: rgb(0x1B1C28),
 
             close_button: rgba(0x00000000),
             close_button_hover: rgb(0x7E2C2C),
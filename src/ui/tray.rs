@@ -0,0 +1,392 @@
+use std::time::Duration;
+
+use cntp_i18n::tr;
+use gpui::{AnyWindowHandle, App, AppContext, AsyncApp, Global, Window};
+use tracing::{error, info};
+use tray_icon::{
+    Icon, MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent,
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+};
+
+use crate::{
+    playback::thread::PlaybackState,
+    settings::{SettingsGlobal, interface::CloseBehavior},
+    ui::{
+        assets::bundled::BundledAssets,
+        global_actions::{Next, PlayPause, Previous, Quit},
+        models::{Models, PlaybackInfo},
+    },
+};
+
+const MENU_SHOW: &str = "show";
+const MENU_NOW_PLAYING: &str = "now_playing";
+const MENU_PLAY_PAUSE: &str = "play_pause";
+const MENU_NEXT: &str = "next";
+const MENU_PREVIOUS: &str = "previous";
+const MENU_QUIT: &str = "quit";
+
+/// How often tray menu and icon events are checked for.
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What the tray menu shows about the current track.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TrayState {
+    title: Option<String>,
+    playing: bool,
+}
+
+impl TrayState {
+    fn read(cx: &App) -> Self {
+        let playing =
+            *cx.global::<PlaybackInfo>().playback_state.read(cx) == PlaybackState::Playing;
+        let metadata = cx.global::<Models>().metadata.read(cx);
+        let title = metadata.name.clone().map(|name| match &metadata.artist {
+            Some(artist) => format!("{name} - {artist}"),
+            None => name,
+        });
+
+        Self { title, playing }
+    }
+}
+
+/// The tray icon and the menu items that change with playback.
+struct TrayMenu {
+    icon: TrayIcon,
+    now_playing: MenuItem,
+    play_pause: MenuItem,
+}
+
+impl TrayMenu {
+    fn new(state: &TrayState) -> anyhow::Result<Self> {
+        let now_playing = MenuItem::with_id(MENU_NOW_PLAYING, "", false, None);
+        let play_pause = MenuItem::with_id(MENU_PLAY_PAUSE, "", true, None);
+
+        let menu = Menu::new();
+        menu.append_items(&[
+            &MenuItem::with_id(
+                MENU_SHOW,
+                tr!("TRAY_SHOW", "Show Hummingbird").to_string(),
+                true,
+                None,
+            ),
+            &PredefinedMenuItem::separator(),
+            &now_playing,
+            &play_pause,
+            &MenuItem::with_id(MENU_NEXT, tr!("TRAY_NEXT", "Next").to_string(), true, None),
+            &MenuItem::with_id(
+                MENU_PREVIOUS,
+                tr!("TRAY_PREVIOUS", "Previous").to_string(),
+                true,
+                None,
+            ),
+            &PredefinedMenuItem::separator(),
+            &MenuItem::with_id(MENU_QUIT, tr!("TRAY_QUIT", "Quit").to_string(), true, None),
+        ])?;
+
+        let icon = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_icon(load_icon()?)
+            .with_tooltip(tr!("APP_NAME").to_string())
+            .build()?;
+
+        let tray = Self {
+            icon,
+            now_playing,
+            play_pause,
+        };
+        tray.update(state);
+
+        Ok(tray)
+    }
+
+    fn update(&self, state: &TrayState) {
+        let title = state
+            .title
+            .clone()
+            .unwrap_or_else(|| tr!("TRAY_NOTHING_PLAYING", "Nothing playing").to_string());
+
+        self.now_playing.set_text(&title);
+        self.play_pause.set_text(if state.playing {
+            tr!("TRAY_PAUSE", "Pause").to_string()
+        } else {
+            tr!("TRAY_PLAY", "Play").to_string()
+        });
+
+        let tooltip = match &state.title {
+            Some(title) => format!("{} - {title}", tr!("APP_NAME")),
+            None => tr!("APP_NAME").to_string(),
+        };
+        if let Err(err) = self.icon.set_tooltip(Some(tooltip)) {
+            error!(?err, "Failed to set tray tooltip: {err}");
+        }
+    }
+}
+
+fn load_icon() -> anyhow::Result<Icon> {
+    let file = BundledAssets::get("images/logo.png")
+        .ok_or_else(|| anyhow::anyhow!("missing bundled logo"))?;
+    let image = image::load_from_memory(&file.data)?.into_rgba8();
+    let (width, height) = image.dimensions();
+
+    Ok(Icon::from_rgba(image.into_raw(), width, height)?)
+}
+
+/// A running tray icon.
+///
+/// On Linux the tray is drawn with GTK, which has to run its own event loop, so the icon lives on
+/// the GTK thread and updates are sent to it. Elsewhere the icon is owned by the main thread.
+struct Tray {
+    #[cfg(not(target_os = "linux"))]
+    menu: TrayMenu,
+    #[cfg(target_os = "linux")]
+    gtk_thread: std::sync::mpsc::Sender<TrayMessage>,
+    state: TrayState,
+}
+
+impl Tray {
+    #[cfg(not(target_os = "linux"))]
+    fn new(state: TrayState) -> anyhow::Result<Self> {
+        Ok(Self {
+            menu: TrayMenu::new(&state)?,
+            state,
+        })
+    }
+
+    #[cfg(target_os = "linux")]
+    fn new(state: TrayState) -> anyhow::Result<Self> {
+        let gtk_thread = gtk_thread()?;
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+        gtk_thread
+            .send(TrayMessage::Create(state.clone(), ready_tx))
+            .map_err(|_| anyhow::anyhow!("the tray thread has stopped"))?;
+        ready_rx.recv()??;
+
+        Ok(Self { gtk_thread, state })
+    }
+
+    fn update(&mut self, state: TrayState) {
+        if self.state == state {
+            return;
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        self.menu.update(&state);
+        #[cfg(target_os = "linux")]
+        let _ = self.gtk_thread.send(TrayMessage::Update(state.clone()));
+
+        self.state = state;
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for Tray {
+    fn drop(&mut self) {
+        let _ = self.gtk_thread.send(TrayMessage::Remove);
+    }
+}
+
+/// A request to the GTK thread.
+#[cfg(target_os = "linux")]
+enum TrayMessage {
+    /// Create the tray icon, replying once it's shown or failed to be created.
+    Create(TrayState, std::sync::mpsc::Sender<anyhow::Result<()>>),
+    Update(TrayState),
+    Remove,
+}
+
+/// The GTK thread, once it has been started.
+///
+/// GTK can only be initialized once per process, and only used from the thread that initialized
+/// it, so a single thread is kept for the life of the app and the tray is created and removed on
+/// it as the setting changes.
+#[cfg(target_os = "linux")]
+static GTK_THREAD: std::sync::Mutex<Option<std::sync::mpsc::Sender<TrayMessage>>> =
+    std::sync::Mutex::new(None);
+
+/// Returns the GTK thread, starting it the first time the tray is shown.
+#[cfg(target_os = "linux")]
+fn gtk_thread() -> anyhow::Result<std::sync::mpsc::Sender<TrayMessage>> {
+    let mut gtk_thread = GTK_THREAD.lock().expect("poisoned GTK thread lock");
+    if let Some(sender) = &*gtk_thread {
+        return Ok(sender.clone());
+    }
+
+    let (sender, messages) = std::sync::mpsc::channel();
+    std::thread::Builder::new()
+        .name("tray".to_string())
+        .spawn(move || run_gtk_thread(messages))?;
+
+    *gtk_thread = Some(sender.clone());
+    Ok(sender)
+}
+
+#[cfg(target_os = "linux")]
+fn run_gtk_thread(messages: std::sync::mpsc::Receiver<TrayMessage>) {
+    use std::sync::mpsc::RecvTimeoutError;
+
+    let initialized = gtk::init().map_err(|err| format!("failed to initialize GTK: {err}"));
+    let mut menu: Option<TrayMenu> = None;
+
+    loop {
+        if initialized.is_ok() {
+            while gtk::events_pending() {
+                gtk::main_iteration_do(false);
+            }
+        }
+
+        // GTK only needs to be polled while the icon is shown
+        let message = if menu.is_some() {
+            match messages.recv_timeout(Duration::from_millis(50)) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            match messages.recv() {
+                Ok(message) => message,
+                Err(_) => break,
+            }
+        };
+
+        match message {
+            TrayMessage::Create(state, ready) => {
+                let result = match &initialized {
+                    Ok(()) => TrayMenu::new(&state).map(|created| menu = Some(created)),
+                    Err(err) => Err(anyhow::anyhow!("{err}")),
+                };
+                let _ = ready.send(result);
+            }
+            TrayMessage::Update(state) => {
+                if let Some(menu) = &menu {
+                    menu.update(&state);
+                }
+            }
+            TrayMessage::Remove => menu = None,
+        }
+    }
+}
+
+/// Tracks the main window and the tray icon, if [`CloseBehavior::MinimizeToTray`] is selected.
+struct TrayGlobal {
+    main_window: AnyWindowHandle,
+    tray: Option<Tray>,
+}
+
+impl Global for TrayGlobal {}
+
+/// Sets up the tray icon for the main window, creating or removing it as the close behavior
+/// setting changes.
+pub fn init(cx: &mut App, main_window: AnyWindowHandle) {
+    cx.set_global(TrayGlobal {
+        main_window,
+        tray: None,
+    });
+    sync_tray(cx);
+
+    let settings = cx.global::<SettingsGlobal>().model.clone();
+    cx.observe(&settings, |_, cx| sync_tray(cx)).detach();
+
+    let playback_state = cx.global::<PlaybackInfo>().playback_state.clone();
+    cx.observe(&playback_state, |_, cx| update_tray(cx))
+        .detach();
+
+    let metadata = cx.global::<Models>().metadata.clone();
+    cx.observe(&metadata, |_, cx| update_tray(cx)).detach();
+
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        loop {
+            while let Ok(event) = MenuEvent::receiver().try_recv() {
+                let _ = cx.update(|cx| handle_menu_event(event.id.0.as_str(), cx));
+            }
+
+            while let Ok(event) = TrayIconEvent::receiver().try_recv() {
+                if let TrayIconEvent::Click {
+                    button: MouseButton::Left,
+                    button_state: MouseButtonState::Up,
+                    ..
+                } = event
+                {
+                    let _ = cx.update(show_main_window);
+                }
+            }
+
+            cx.background_executor().timer(EVENT_POLL_INTERVAL).await;
+        }
+    })
+    .detach();
+}
+
+/// Closes the main window. If the tray icon is shown, the window is minimized and playback
+/// continues, otherwise Hummingbird quits.
+pub fn close_main_window(window: &mut Window, cx: &mut App) {
+    if minimize_to_tray(window, cx) {
+        return;
+    }
+
+    cx.quit();
+}
+
+/// Minimizes the window instead of closing it if the tray icon is shown. Returns whether the
+/// window was minimized.
+pub fn minimize_to_tray(window: &mut Window, cx: &mut App) -> bool {
+    let has_tray = cx
+        .try_global::<TrayGlobal>()
+        .is_some_and(|global| global.tray.is_some());
+
+    if has_tray {
+        info!("Minimizing to tray");
+        window.minimize_window();
+    }
+
+    has_tray
+}
+
+fn sync_tray(cx: &mut App) {
+    let wants_tray = cx
+        .global::<SettingsGlobal>()
+        .model
+        .read(cx)
+        .interface
+        .close_behavior
+        == CloseBehavior::MinimizeToTray;
+    let has_tray = cx.global::<TrayGlobal>().tray.is_some();
+
+    if wants_tray && !has_tray {
+        match Tray::new(TrayState::read(cx)) {
+            Ok(tray) => cx.global_mut::<TrayGlobal>().tray = Some(tray),
+            Err(err) => error!(?err, "Failed to create tray icon: {err}"),
+        }
+    } else if !wants_tray && has_tray {
+        cx.global_mut::<TrayGlobal>().tray = None;
+    }
+}
+
+fn update_tray(cx: &mut App) {
+    if cx.global::<TrayGlobal>().tray.is_none() {
+        return;
+    }
+
+    let state = TrayState::read(cx);
+    if let Some(tray) = &mut cx.global_mut::<TrayGlobal>().tray {
+        tray.update(state);
+    }
+}
+
+fn handle_menu_event(id: &str, cx: &mut App) {
+    match id {
+        MENU_SHOW => show_main_window(cx),
+        MENU_PLAY_PAUSE => cx.dispatch_action(&PlayPause),
+        MENU_NEXT => cx.dispatch_action(&Next),
+        MENU_PREVIOUS => cx.dispatch_action(&Previous),
+        MENU_QUIT => cx.dispatch_action(&Quit),
+        _ => {}
+    }
+}
+
+/// Brings the main window to the front, restoring it if it was minimized to the tray.
+pub fn show_main_window(cx: &mut App) {
+    let main_window = cx.global::<TrayGlobal>().main_window;
+    let _ = main_window.update(cx, |_, window, _| window.activate_window());
+    cx.activate(true);
+}
@@ -1,6 +1,11 @@
 use gpui::{App, AppContext, ClipboardItem, Window, actions};
 use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
 
+use crate::ui::{
+    caching::{image_cache_budget, image_cache_occupancy},
+    format::format_bytes,
+};
+
 actions!(hummingbird, [CopyTroubleshootingInfo, OpenLog]);
 
 pub fn copy_troubleshooting_info(_: &CopyTroubleshootingInfo, cx: &mut App) {
@@ -53,6 +58,8 @@ fn copy_troubleshooting_info_inner(_window: Option<&mut Window>, cx: &mut App) {
         formatted_total_memory(),
     );
 
+    let info = format!("{info}\nImage Caches: {}", image_cache_label(cx));
+
     cx.write_to_clipboard(ClipboardItem::new_string(info));
 }
 
@@ -61,6 +68,14 @@ pub fn open_log(_: &OpenLog, cx: &mut App) {
     cx.open_with_system(&crate::logging::active_log_path());
 }
 
+fn image_cache_label(cx: &App) -> String {
+    let (caches, images) = image_cache_occupancy();
+    format!(
+        "{images} images in {caches} caches (budget: {})",
+        image_cache_budget(cx)
+    )
+}
+
 fn operating_system_label() -> String {
     if let Some(long) = System::long_os_version().filter(|value| !value.trim().is_empty()) {
         return long;
@@ -124,21 +139,5 @@ fn formatted_total_memory() -> String {
     let system = System::new_with_specifics(
         RefreshKind::default().with_memory(MemoryRefreshKind::default().with_ram()),
     );
-    format_memory(system.total_memory() as f64)
-}
-
-fn format_memory(bytes: f64) -> String {
-    const SUFFIX: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
-    const UNIT: f64 = 1024.0;
-    if bytes <= 0.0 {
-        return "0 B".to_string();
-    }
-
-    let power = ((bytes.ln() / UNIT.ln()).floor() as usize).min(SUFFIX.len() - 1);
-    let value = bytes / UNIT.powi(power as i32);
-    if value >= 10.0 || value.fract() == 0.0 {
-        format!("{value:.0} {}", SUFFIX[power])
-    } else {
-        format!("{value:.1} {}", SUFFIX[power])
-    }
+    format_bytes(system.total_memory() as f64)
 }
@@ -1,13 +1,22 @@
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use globwalk::GlobWalkerBuilder;
 use gpui::{
-    AnyElement, App, Bounds, Element, ElementId, Entity, GlobalElementId, InspectorElementId,
-    IntoElement, LayoutId, ParentElement, Pixels, Render, RenderImage, Stateful, StyleRefinement,
-    Styled, Window,
+    AnyElement, App, AppContext, AsyncApp, Bounds, Element, ElementId, Entity, GlobalElementId,
+    InspectorElementId, IntoElement, LayoutId, ParentElement, Pixels, Render, RenderImage,
+    Stateful, StyleRefinement, Styled, Window,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
-use tracing::debug;
+use tracing::{debug, info, warn};
+
+use crate::{
+    media::{lookup_table::can_be_read, traits::MediaProviderFeatures},
+    playback::queue::QueueItemData,
+    ui::drop_notice,
+};
 
 pub fn prune_views<T>(
     views_model: &Entity<FxHashMap<usize, Entity<T>>>,
@@ -247,6 +256,111 @@ where
     }
 }
 
+fn is_playable(path: &Path) -> bool {
+    can_be_read(path, MediaProviderFeatures::PROVIDES_DECODER).unwrap_or(false)
+}
+
+/// Returns the files in `dir` and its subfolders, sorted by path.
+fn files_in_dir(dir: &Path, visited: &mut FxHashSet<PathBuf>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        // symlinks can create loops
+        if !visited.insert(dir.canonicalize().unwrap_or_else(|_| dir.clone())) {
+            continue;
+        }
+
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                warn!(?err, "Failed to read dropped folder {dir:?}: {err}");
+                continue;
+            }
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Expands paths dropped onto the window into the files that can be played. Folders are searched
+/// recursively, and the files in each are sorted by path, so dropping an album or artist folder
+/// queues its tracks in order. Anything that can't be played, like images and text files, is
+/// skipped, and the number of skipped files is returned alongside the playable ones.
+///
+/// This reads the file system, so it should be called off of the main thread.
+pub fn playable_dropped_paths(paths: &[PathBuf]) -> (Vec<PathBuf>, usize) {
+    let mut visited = FxHashSet::default();
+    let mut playable = Vec::new();
+    let mut skipped: usize = 0;
+
+    for path in paths {
+        let files = if path.is_dir() {
+            files_in_dir(path, &mut visited)
+        } else {
+            vec![path.clone()]
+        };
+
+        for file in files {
+            if is_playable(&file) {
+                playable.push(file);
+            } else {
+                skipped += 1;
+            }
+        }
+    }
+
+    if skipped > 0 {
+        info!("Skipped {skipped} dropped files that can't be played");
+    }
+
+    (playable, skipped)
+}
+
+/// Expands dropped paths into playable files in the background, then passes them to `queue` as
+/// queue items. The user is told if any of the files were skipped.
+pub fn queue_dropped_paths(
+    cx: &mut App,
+    paths: Vec<PathBuf>,
+    queue: impl FnOnce(Vec<QueueItemData>, &mut App) + 'static,
+) {
+    let expand = cx
+        .background_executor()
+        .spawn(async move { playable_dropped_paths(&paths) });
+
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        let (paths, skipped) = expand.await;
+
+        let _ = cx.update(|cx| {
+            if skipped > 0 {
+                drop_notice::show_skipped_files(cx, skipped);
+            }
+
+            if paths.is_empty() {
+                return;
+            }
+
+            let items = paths
+                .into_iter()
+                .map(|path| QueueItemData::new(cx, path, None, None))
+                .collect();
+
+            queue(items, cx);
+        });
+    })
+    .detach();
+}
+
 pub fn find_art_file_for_path(path: &Path) -> Option<Arc<Path>> {
     let parent = path.parent()?;
 
@@ -0,0 +1,159 @@
+use cntp_i18n::tr;
+use gpui::{AnyWindowHandle, App, AppContext, Global, Window};
+
+use crate::{
+    playback::thread::PlaybackState,
+    settings::{SettingsGlobal, interface::WindowTitle},
+    ui::{
+        format::format_duration,
+        models::{Models, PlaybackInfo},
+    },
+};
+
+/// The main window and the title it was last given.
+struct WindowTitleGlobal {
+    main_window: AnyWindowHandle,
+    title: String,
+}
+
+impl Global for WindowTitleGlobal {}
+
+/// Keeps the title of the main window up to date with the current track, if the window title
+/// setting asks for it.
+pub fn init(window: &mut Window, cx: &mut App) {
+    let title = read_title(cx);
+    window.set_window_title(&title);
+    cx.set_global(WindowTitleGlobal {
+        main_window: window.window_handle(),
+        title,
+    });
+
+    let settings = cx.global::<SettingsGlobal>().model.clone();
+    cx.observe(&settings, |_, cx| update_title(cx)).detach();
+
+    let metadata = cx.global::<Models>().metadata.clone();
+    cx.observe(&metadata, |_, cx| update_title(cx)).detach();
+
+    let playback = cx.global::<PlaybackInfo>().clone();
+    cx.observe(&playback.playback_state, |_, cx| update_title(cx))
+        .detach();
+    cx.observe(&playback.position, |_, cx| update_title(cx))
+        .detach();
+    cx.observe(&playback.duration, |_, cx| update_title(cx))
+        .detach();
+}
+
+fn read_title(cx: &App) -> String {
+    let mode = cx
+        .global::<SettingsGlobal>()
+        .model
+        .read(cx)
+        .interface
+        .window_title;
+    let app_name = tr!("APP_NAME").to_string();
+
+    if mode == WindowTitle::AppName {
+        return app_name;
+    }
+
+    let playback = cx.global::<PlaybackInfo>();
+    if *playback.playback_state.read(cx) == PlaybackState::Stopped {
+        return app_name;
+    }
+
+    let metadata = cx.global::<Models>().metadata.read(cx);
+    format_title(
+        mode,
+        metadata.name.as_deref(),
+        metadata.artist.as_deref(),
+        *playback.position.read(cx) / 1_000,
+        *playback.duration.read(cx) / 1_000,
+    )
+    .unwrap_or(app_name)
+}
+
+fn update_title(cx: &mut App) {
+    let title = read_title(cx);
+    let global = cx.global::<WindowTitleGlobal>();
+
+    // the position changes many times a second, but the title only once
+    if global.title == title {
+        return;
+    }
+
+    let main_window = global.main_window;
+    let updated = main_window.update(cx, |_, window, _| window.set_window_title(&title));
+    if updated.is_ok() {
+        cx.global_mut::<WindowTitleGlobal>().title = title;
+    }
+}
+
+/// Returns the title for a track, or `None` if the app name should be shown instead.
+fn format_title(
+    mode: WindowTitle,
+    name: Option<&str>,
+    artist: Option<&str>,
+    position_secs: u64,
+    duration_secs: u64,
+) -> Option<String> {
+    let name = name?;
+    let track = match artist {
+        Some(artist) => format!("{name} - {artist}"),
+        None => name.to_string(),
+    };
+
+    match mode {
+        WindowTitle::AppName => None,
+        WindowTitle::Track => Some(track),
+        WindowTitle::TrackAndPosition => Some(format!(
+            "{track} ({} / {})",
+            format_duration(position_secs),
+            format_duration(duration_secs)
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_title;
+    use crate::settings::interface::WindowTitle;
+
+    #[test]
+    fn titles_show_track_and_artist() {
+        assert_eq!(
+            format_title(WindowTitle::Track, Some("Song"), Some("Artist"), 5, 200).as_deref(),
+            Some("Song - Artist")
+        );
+        assert_eq!(
+            format_title(WindowTitle::Track, Some("Song"), None, 5, 200).as_deref(),
+            Some("Song")
+        );
+    }
+
+    #[test]
+    fn titles_can_include_position() {
+        assert_eq!(
+            format_title(
+                WindowTitle::TrackAndPosition,
+                Some("Song"),
+                Some("Artist"),
+                83,
+                296
+            )
+            .as_deref(),
+            Some("Song - Artist (01:23 / 04:56)")
+        );
+    }
+
+    #[test]
+    fn untitled_tracks_use_app_name() {
+        assert_eq!(
+            format_title(WindowTitle::TrackAndPosition, None, Some("Artist"), 0, 0),
+            None
+        );
+        assert_eq!(
+            format_title(WindowTitle::AppName, Some("Song"), None, 0, 0),
+            None
+        );
+    }
+}